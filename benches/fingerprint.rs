@@ -0,0 +1,52 @@
+//! Hashing throughput of `generate_fingerprint` for one large file, with the
+//! default read buffer, a larger one and (with the `mmap` feature) memory
+//! mapping.
+//!
+//! Run with `cargo bench --bench fingerprint --features mmap`.
+
+use std::fs;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use beltic::manifest::fingerprint::{
+    generate_fingerprint, FingerprintOptions, DEFAULT_HASH_BUFFER_SIZE, DEFAULT_MMAP_THRESHOLD,
+};
+
+const FILE_SIZE: usize = 64 * 1024 * 1024;
+
+fn hash_large_file(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let content: Vec<u8> = (0..FILE_SIZE).map(|i| (i % 251) as u8).collect();
+    fs::write(dir.path().join("large.bin"), &content).unwrap();
+
+    let mut modes = vec![
+        ("8 KiB buffer", DEFAULT_HASH_BUFFER_SIZE, None),
+        ("1 MiB buffer", 1024 * 1024, None),
+    ];
+    if cfg!(feature = "mmap") {
+        modes.push((
+            "mmap",
+            DEFAULT_HASH_BUFFER_SIZE,
+            Some(DEFAULT_MMAP_THRESHOLD),
+        ));
+    }
+
+    let mut group = c.benchmark_group("hash_large_file");
+    group.throughput(Throughput::Bytes(FILE_SIZE as u64));
+    group.sample_size(10);
+    for (label, buffer_size, mmap_threshold) in modes {
+        let options = FingerprintOptions {
+            root_path: dir.path().to_path_buf(),
+            buffer_size,
+            mmap_threshold,
+            ..Default::default()
+        };
+        group.bench_function(label, |b| {
+            b.iter(|| generate_fingerprint(&options).unwrap());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, hash_large_file);
+criterion_main!(benches);
@@ -7,7 +7,8 @@ use clap::{Args, Subcommand};
 use console::style;
 use serde::{Deserialize, Serialize};
 
-use crate::config::{load_config, load_credentials};
+use beltic::config::{load_config, load_credentials};
+use beltic::http::{self, request_error};
 
 use super::prompts::CommandPrompts;
 
@@ -171,7 +172,7 @@ fn run_create(args: CreateApiKeyArgs) -> Result<()> {
     let access_token =
         access_token.context("Not logged in. Run 'beltic auth login' first.")?;
 
-    let client = reqwest::blocking::Client::new();
+    let client = http::client()?;
     let response = client
         .post(format!("{}/v1/api-keys", api_url))
         .header("Authorization", format!("Bearer {}", access_token))
@@ -179,6 +180,7 @@ fn run_create(args: CreateApiKeyArgs) -> Result<()> {
         .header("Accept", "application/json")
         .json(&request_body)
         .send()
+        .map_err(|e| request_error(e, "KYA platform API"))
         .context("failed to connect to KYA platform API")?;
 
     if !response.status().is_success() {
@@ -237,12 +239,13 @@ fn run_revoke(args: RevokeApiKeyArgs) -> Result<()> {
 
     let key_id = args.key_id.context("--key-id is required")?;
 
-    let client = reqwest::blocking::Client::new();
+    let client = http::client()?;
     let response = client
         .post(format!("{}/v1/api-keys/{}/revoke", api_url, key_id))
         .header("Authorization", format!("Bearer {}", access_token))
         .header("Accept", "application/json")
         .send()
+        .map_err(|e| request_error(e, "KYA platform API"))
         .context("failed to connect to KYA platform API")?;
 
     if !response.status().is_success() {
@@ -10,19 +10,20 @@ use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::Engine;
 use clap::{Args, Subcommand};
 use console::style;
-use serde::Deserialize;
+use log::debug;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
-use crate::config::{
+use beltic::config::{
     delete_credentials, load_config, load_credentials, save_config, save_credentials,
 };
+use beltic::http::{self, request_error, send_with_retry};
 
 use super::prompts::CommandPrompts;
 
 // WorkOS OAuth configuration
 const WORKOS_CLIENT_ID: &str = "client_01KD6DX6TJ0SVR510DQ5WSTWTR";
 const WORKOS_AUTHORIZE_URL: &str = "https://api.workos.com/user_management/authorize";
-const CALLBACK_PORT: u16 = 8239;
 const CALLBACK_PATH: &str = "/callback";
 const CALLBACK_TIMEOUT_SECS: u64 = 300; // 5 minutes
 
@@ -49,6 +50,10 @@ pub struct LoginArgs {
     /// Skip opening browser automatically (display URL instead)
     #[arg(long)]
     pub no_browser: bool,
+
+    /// Fixed port for the local OAuth callback server (default: OS-assigned free port)
+    #[arg(long)]
+    pub callback_port: Option<u16>,
 }
 
 /// PKCE code verifier and challenge
@@ -61,31 +66,40 @@ struct PkceChallenge {
 #[derive(Debug, Deserialize)]
 struct TokenResponse {
     access_token: String,
+    #[allow(dead_code)]
     #[serde(default)]
     refresh_token: Option<String>,
+    #[allow(dead_code)]
     #[serde(default)]
     token_type: Option<String>,
+    #[allow(dead_code)]
     #[serde(default)]
     expires_in: Option<u64>,
 }
 
 /// Response from GET /api/developers/me
-#[derive(Debug, Deserialize)]
-struct DeveloperMeResponse {
-    data: DeveloperData,
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct DeveloperMeResponse {
+    pub data: DeveloperData,
 }
 
-#[derive(Debug, Deserialize)]
-struct DeveloperData {
-    id: String,
-    attributes: DeveloperAttributes,
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct DeveloperData {
+    pub id: String,
+    #[serde(rename = "type", default)]
+    pub _type: String,
+    pub attributes: DeveloperAttributes,
 }
 
-#[derive(Debug, Deserialize)]
-struct DeveloperAttributes {
-    legal_name: Option<String>,
-    kyb_tier: Option<String>,
-    verification_status: Option<String>,
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct DeveloperAttributes {
+    pub legal_name: Option<String>,
+    pub kyb_tier: Option<String>,
+    pub verification_status: Option<String>,
+    #[serde(default)]
+    pub default_org: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
 }
 
 const SUCCESS_HTML: &str = r#"<!DOCTYPE html>
@@ -178,22 +192,28 @@ fn generate_pkce_challenge() -> PkceChallenge {
     // Base64url encode the challenge
     let challenge = URL_SAFE_NO_PAD.encode(challenge_bytes);
 
-    PkceChallenge { verifier, challenge }
+    PkceChallenge {
+        verifier,
+        challenge,
+    }
 }
 
-/// Build the OAuth authorization URL
-fn build_authorize_url(pkce: &PkceChallenge) -> String {
-    let redirect_uri = format!("http://localhost:{}{}", CALLBACK_PORT, CALLBACK_PATH);
+/// Build the redirect URI for a callback server listening on `port`
+fn callback_redirect_uri(port: u16) -> String {
+    format!("http://localhost:{}{}", port, CALLBACK_PATH)
+}
 
+/// Build the OAuth authorization URL
+fn build_authorize_url(pkce: &PkceChallenge, redirect_uri: &str) -> String {
     // Include provider=authkit to use AuthKit's hosted authentication UI
     // Also include state parameter for additional security
-    let state = urlencoding::encode(&pkce.verifier[..16]); // Use first 16 chars of verifier as state
-    
+    let state = urlencoding::encode(&pkce.verifier[..pkce.verifier.len().min(16)]); // Use first 16 chars of verifier as state
+
     format!(
         "{}?client_id={}&redirect_uri={}&response_type=code&code_challenge={}&code_challenge_method=S256&scope=openid%20email%20profile&provider=authkit&state={}",
         WORKOS_AUTHORIZE_URL,
         WORKOS_CLIENT_ID,
-        urlencoding::encode(&redirect_uri),
+        urlencoding::encode(redirect_uri),
         pkce.challenge,
         state
     )
@@ -202,7 +222,9 @@ fn build_authorize_url(pkce: &PkceChallenge) -> String {
 /// Extract the authorization code from a callback URL
 fn extract_code_from_url(url: &str) -> Result<String> {
     // URL format: /callback?code=xxx or /callback?code=xxx&state=...
-    let query_start = url.find('?').context("no query parameters in callback URL")?;
+    let query_start = url
+        .find('?')
+        .context("no query parameters in callback URL")?;
     let query = &url[query_start + 1..];
 
     for param in query.split('&') {
@@ -220,18 +242,46 @@ fn extract_code_from_url(url: &str) -> Result<String> {
                 .split('&')
                 .find_map(|p| p.strip_prefix("error_description="))
                 .unwrap_or("Unknown error");
-            anyhow::bail!("OAuth error: {} - {}", error, urlencoding::decode(error_desc)?);
+            anyhow::bail!(
+                "OAuth error: {} - {}",
+                error,
+                urlencoding::decode(error_desc)?
+            );
         }
     }
 
     anyhow::bail!("no authorization code in callback URL")
 }
 
-/// Start the local callback server and wait for the OAuth callback
-fn start_callback_server() -> Result<String> {
-    let server = tiny_http::Server::http(format!("127.0.0.1:{}", CALLBACK_PORT))
-        .map_err(|e| anyhow::anyhow!("failed to start callback server on port {}: {}", CALLBACK_PORT, e))?;
+/// Bind the local callback server and return it with the port it listens on.
+///
+/// Port 0 lets the OS pick a free port, so a busy port or a hung earlier
+/// login never blocks a new one.
+fn bind_callback_server(port: Option<u16>) -> Result<(tiny_http::Server, u16)> {
+    let requested = port.unwrap_or(0);
+    let server = tiny_http::Server::http(format!("127.0.0.1:{}", requested)).map_err(|e| {
+        anyhow::anyhow!(
+            "failed to start callback server on port {}: {}",
+            requested,
+            e
+        )
+    })?;
+
+    let bound_port = server
+        .server_addr()
+        .to_ip()
+        .map(|addr| addr.port())
+        .context("callback server is not listening on a TCP port")?;
+    debug!(
+        "OAuth callback server listening on 127.0.0.1:{}",
+        bound_port
+    );
+
+    Ok((server, bound_port))
+}
 
+/// Wait for the OAuth callback on a bound server
+fn wait_for_callback(server: &tiny_http::Server) -> Result<String> {
     // Wait for the callback request with timeout
     let request = server
         .recv_timeout(Duration::from_secs(CALLBACK_TIMEOUT_SECS))
@@ -246,6 +296,14 @@ fn start_callback_server() -> Result<String> {
     // Extract the code from the URL
     let url = request.url().to_string();
     let code_result = extract_code_from_url(&url);
+    debug!(
+        "OAuth callback received ({})",
+        if code_result.is_ok() {
+            "authorization code present"
+        } else {
+            "no authorization code"
+        }
+    );
 
     // Send response to browser
     let (html, status_code) = match &code_result {
@@ -268,13 +326,17 @@ fn start_callback_server() -> Result<String> {
 
 /// Exchange the authorization code for an access token via the console API
 /// The console proxies the token exchange (PKCE doesn't require client_secret)
-fn exchange_code_for_token(code: &str, verifier: &str, api_url: &str) -> Result<TokenResponse> {
-    let redirect_uri = format!("http://localhost:{}{}", CALLBACK_PORT, CALLBACK_PATH);
+fn exchange_code_for_token(
+    code: &str,
+    verifier: &str,
+    redirect_uri: &str,
+    api_url: &str,
+) -> Result<TokenResponse> {
     let api_url_trimmed = api_url.trim_end_matches('/');
     let token_url = format!("{}/api/auth/token", api_url_trimmed);
 
-    let client = reqwest::blocking::Client::new();
-    
+    let client = http::client()?;
+
     // Send JSON to the console's token exchange endpoint
     let body = serde_json::json!({
         "code": code,
@@ -282,15 +344,24 @@ fn exchange_code_for_token(code: &str, verifier: &str, api_url: &str) -> Result<
         "redirect_uri": redirect_uri,
         "client_id": WORKOS_CLIENT_ID,
     });
-    
-    let response = client
-        .post(&token_url)
-        .json(&body)
-        .header("Accept", "application/json")
-        .send()
-        .with_context(|| format!("failed to exchange code for token - is the console running at {}?", api_url_trimmed))?;
+
+    debug!("POST {}", token_url);
+    let response = send_with_retry(
+        client
+            .post(&token_url)
+            .json(&body)
+            .header("Accept", "application/json"),
+    )
+    .map_err(|e| request_error(e, "console"))
+    .with_context(|| {
+        format!(
+            "failed to exchange code for token - is the console running at {}?",
+            api_url_trimmed
+        )
+    })?;
 
     let status = response.status();
+    debug!("POST {} -> {}", token_url, status);
     let response_body = response.text().unwrap_or_default();
 
     if !status.is_success() {
@@ -301,8 +372,9 @@ fn exchange_code_for_token(code: &str, verifier: &str, api_url: &str) -> Result<
         );
     }
 
-    let token_response: TokenResponse = serde_json::from_str(&response_body).context("failed to parse token response")?;
-    
+    let token_response: TokenResponse =
+        serde_json::from_str(&response_body).context("failed to parse token response")?;
+
     Ok(token_response)
 }
 
@@ -315,8 +387,10 @@ fn run_login(args: LoginArgs) -> Result<()> {
     // Step 1: Generate PKCE challenge
     let pkce = generate_pkce_challenge();
 
-    // Step 2: Build authorize URL
-    let authorize_url = build_authorize_url(&pkce);
+    // Step 2: Bind callback server and build authorize URL from its actual port
+    let (server, callback_port) = bind_callback_server(args.callback_port)?;
+    let redirect_uri = callback_redirect_uri(callback_port);
+    let authorize_url = build_authorize_url(&pkce, &redirect_uri);
 
     // Step 3: Open browser or display URL
     prompts.info("Authenticating via browser...")?;
@@ -335,7 +409,10 @@ fn run_login(args: LoginArgs) -> Result<()> {
         println!();
 
         if let Err(e) = open::that(&authorize_url) {
-            prompts.warn(&format!("Failed to open browser: {}. Please open the URL manually.", e))?;
+            prompts.warn(&format!(
+                "Failed to open browser: {}. Please open the URL manually.",
+                e
+            ))?;
         }
     }
 
@@ -348,42 +425,48 @@ fn run_login(args: LoginArgs) -> Result<()> {
         .trim_end_matches('/')
         .to_string();
 
-    // Step 5: Start callback server and wait for code
+    // Step 5: Wait for the callback with the authorization code
     prompts.info("Waiting for authorization (timeout: 5 minutes)...")?;
-    let code = start_callback_server()?;
+    let code = wait_for_callback(&server)?;
 
     // Step 6: Exchange code for token
     prompts.info("Exchanging authorization code...")?;
-    let token_response = exchange_code_for_token(&code, &pkce.verifier, &api_url)?;
+    let token_response = exchange_code_for_token(&code, &pkce.verifier, &redirect_uri, &api_url)?;
 
     // Step 7: Validate token by calling /api/developers/me
 
     prompts.info("Validating token...")?;
 
-    let client = reqwest::blocking::Client::new();
+    let client = http::client()?;
     let auth_header = format!("Bearer {}", token_response.access_token);
     let me_url = format!("{}/api/developers/me", api_url);
-    
-    let response = client
-        .get(&me_url)
-        .header("Authorization", &auth_header)
-        .header("Accept", "application/json")
-        .send()
-        .context("failed to connect to console API")?;
+
+    debug!("GET {}", me_url);
+    let response = send_with_retry(
+        client
+            .get(&me_url)
+            .header("Authorization", &auth_header)
+            .header("Accept", "application/json"),
+    )
+    .map_err(|e| request_error(e, "console"))
+    .context("failed to connect to console API")?;
 
     let status = response.status();
+    debug!("GET {} -> {}", me_url, status);
     let body = response.text().unwrap_or_default();
 
     if !status.is_success() {
         if status.as_u16() == 401 || status.as_u16() == 403 {
-            anyhow::bail!("Token validation failed. Your account may not be linked to the platform.");
+            anyhow::bail!(
+                "Token validation failed. Your account may not be linked to the platform."
+            );
         }
 
         anyhow::bail!("API request failed with status {}: {}", status, body);
     }
 
-    let developer: DeveloperMeResponse = serde_json::from_str(&body)
-        .context("failed to parse developer response")?;
+    let developer: DeveloperMeResponse =
+        serde_json::from_str(&body).context("failed to parse developer response")?;
 
     // Step 7: Save credentials
     save_credentials(&token_response.access_token).context("failed to save credentials")?;
@@ -480,13 +563,21 @@ mod tests {
             challenge: "test_challenge".to_string(),
         };
 
-        let url = build_authorize_url(&pkce);
+        let url = build_authorize_url(&pkce, &callback_redirect_uri(8239));
 
         assert!(url.starts_with(WORKOS_AUTHORIZE_URL));
         assert!(url.contains(&format!("client_id={}", WORKOS_CLIENT_ID)));
         assert!(url.contains("response_type=code"));
         assert!(url.contains("code_challenge=test_challenge"));
         assert!(url.contains("code_challenge_method=S256"));
+        assert!(url.contains("redirect_uri=http%3A%2F%2Flocalhost%3A8239%2Fcallback"));
+    }
+
+    #[test]
+    fn test_bind_callback_server_ephemeral_port() {
+        let (server, port) = bind_callback_server(None).unwrap();
+        assert_ne!(port, 0);
+        assert_eq!(server.server_addr().to_ip().unwrap().port(), port);
     }
 
     #[test]
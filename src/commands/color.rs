@@ -0,0 +1,38 @@
+//! Colored output switch
+//!
+//! All styling goes through the `console` crate (directly, or via dialoguer
+//! and indicatif), which only looks at `CLICOLOR`/`CLICOLOR_FORCE` and whether
+//! the stream is a terminal. `NO_COLOR` (<https://no-color.org>) and the
+//! global `--no-color` flag are applied here by turning styling off for both
+//! stdout and stderr.
+
+use std::ffi::OsStr;
+
+/// Whether colors should be disabled: `--no-color` was passed, or `NO_COLOR`
+/// is set to a non-empty value
+pub fn no_color_requested(flag: bool, no_color_env: Option<&OsStr>) -> bool {
+    flag || no_color_env.is_some_and(|value| !value.is_empty())
+}
+
+/// Disable styling process-wide when `--no-color` or `NO_COLOR` asks for it
+pub fn init(no_color_flag: bool) {
+    let env = std::env::var_os("NO_COLOR");
+    if no_color_requested(no_color_flag, env.as_deref()) {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_color_requested() {
+        assert!(no_color_requested(true, None));
+        assert!(no_color_requested(false, Some(OsStr::new("1"))));
+        // An empty NO_COLOR does not disable colors
+        assert!(!no_color_requested(false, Some(OsStr::new(""))));
+        assert!(!no_color_requested(false, None));
+    }
+}
@@ -0,0 +1,31 @@
+//! Generate shell completion scripts.
+//!
+//! Usage: beltic completions <bash|zsh|fish|powershell|elvish>
+
+use std::io::Write;
+
+use anyhow::Result;
+use clap::Args;
+use clap_complete::Shell;
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    /// Shell to generate completions for: bash, zsh, fish, powershell or elvish
+    pub shell: Shell,
+}
+
+/// Print the completion script for `command` to stdout
+pub fn run(args: CompletionsArgs, command: &mut clap::Command) -> Result<()> {
+    write_completions(args.shell, command, &mut std::io::stdout())
+}
+
+/// Write the completion script for `command` in `shell`'s syntax to `out`
+pub fn write_completions(
+    shell: Shell,
+    command: &mut clap::Command,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, command, name, out);
+    Ok(())
+}
@@ -0,0 +1,392 @@
+//! `.beltic.yaml` configuration commands
+//!
+//! Scaffold or validate a configuration file, or print its JSON Schema.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, Subcommand};
+use console::style;
+use globset::Glob;
+use serde_json::json;
+
+use beltic::manifest::config::{BelticConfig, CONFIG_SCHEMA};
+use beltic::manifest::fingerprint::{matching_files, FingerprintOptions};
+use beltic::manifest::validator::IssueLevel;
+use beltic::status;
+
+use super::exit;
+use super::prompts::CommandPrompts;
+
+#[derive(Args)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+
+    /// The global `--config` file, validated when `config validate` is given
+    /// no path
+    #[arg(skip)]
+    pub global_config: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Write a .beltic.yaml for a deployment profile
+    Init(ConfigInitArgs),
+    /// Check .beltic.yaml for unknown keys, invalid globs and contradictions
+    Validate(ConfigValidateArgs),
+    /// Print the JSON Schema for .beltic.yaml
+    Schema,
+}
+
+#[derive(Args)]
+pub struct ConfigValidateArgs {
+    /// Config file (default: .beltic.yaml in the current or a parent directory)
+    pub path: Option<PathBuf>,
+
+    /// Output the issues as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct ConfigInitArgs {
+    /// Deployment profile: standalone, monorepo, plugin or serverless
+    #[arg(long)]
+    pub profile: Option<ConfigProfile>,
+
+    /// Agent directory within a monorepo (default: current directory name)
+    #[arg(long)]
+    pub agent_path: Option<String>,
+
+    /// Include pattern, replacing the profile's defaults (repeatable)
+    #[arg(long)]
+    pub include: Vec<String>,
+
+    /// Exclude pattern, replacing the profile's defaults (repeatable)
+    #[arg(long)]
+    pub exclude: Vec<String>,
+
+    /// Output file
+    #[arg(short, long, default_value = ".beltic.yaml")]
+    pub out: PathBuf,
+
+    /// Overwrite an existing file
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// Disable interactive mode
+    #[arg(long)]
+    pub non_interactive: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigProfile {
+    Standalone,
+    Monorepo,
+    Plugin,
+    Serverless,
+}
+
+impl ConfigProfile {
+    const ALL: [ConfigProfile; 4] = [
+        ConfigProfile::Standalone,
+        ConfigProfile::Monorepo,
+        ConfigProfile::Plugin,
+        ConfigProfile::Serverless,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigProfile::Standalone => "standalone",
+            ConfigProfile::Monorepo => "monorepo",
+            ConfigProfile::Plugin => "plugin",
+            ConfigProfile::Serverless => "serverless",
+        }
+    }
+}
+
+impl std::str::FromStr for ConfigProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.as_str() == s.to_lowercase())
+            .ok_or_else(|| {
+                format!(
+                    "invalid profile '{}': use standalone, monorepo, plugin or serverless",
+                    s
+                )
+            })
+    }
+}
+
+pub fn run(args: ConfigArgs) -> Result<()> {
+    match args.command {
+        ConfigCommand::Init(init_args) => run_init(init_args),
+        ConfigCommand::Validate(validate_args) => {
+            run_validate(validate_args, args.global_config.as_deref())
+        }
+        ConfigCommand::Schema => {
+            print!("{}", CONFIG_SCHEMA);
+            Ok(())
+        }
+    }
+}
+
+fn run_init(args: ConfigInitArgs) -> Result<()> {
+    if args.out.exists() && !args.force {
+        bail!(
+            "{} already exists. Use --force to overwrite.",
+            args.out.display()
+        );
+    }
+
+    let root = std::env::current_dir().context("failed to get current directory")?;
+    let default_agent_path = root
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("agent")
+        .to_string();
+
+    let config = if args.non_interactive {
+        let profile = args.profile.unwrap_or(ConfigProfile::Standalone);
+        let agent_path = args.agent_path.clone().unwrap_or(default_agent_path);
+        let mut config = BelticConfig::default_for_profile(profile.as_str(), &agent_path);
+        apply_path_overrides(&mut config, &args)?;
+        config
+    } else {
+        prompt_config(&args, &root, default_agent_path)?
+    };
+
+    let count = preview_count(&config, &root)?;
+    config.save_to_file(&args.out)?;
+    status!(
+        "{} Wrote {} ({} file(s) currently match)",
+        style("✓").green(),
+        args.out.display(),
+        count
+    );
+
+    for issue in BelticConfig::lint(&fs::read_to_string(&args.out)?).issues {
+        println!(
+            "  {} {}: {}",
+            style("warning").yellow(),
+            issue.pointer.as_deref().unwrap_or("/"),
+            issue.message
+        );
+    }
+    Ok(())
+}
+
+fn apply_path_overrides(config: &mut BelticConfig, args: &ConfigInitArgs) -> Result<()> {
+    for pattern in args.include.iter().chain(&args.exclude) {
+        check_glob(pattern)?;
+    }
+    if !args.include.is_empty() {
+        config.agent.paths.include = args.include.clone();
+    }
+    if !args.exclude.is_empty() {
+        config.agent.paths.exclude = args.exclude.clone();
+    }
+    Ok(())
+}
+
+fn prompt_config(
+    args: &ConfigInitArgs,
+    root: &Path,
+    default_agent_path: String,
+) -> Result<BelticConfig> {
+    let prompts = CommandPrompts::new();
+    prompts.section_header("Beltic Configuration")?;
+
+    let labels = [
+        "standalone - a single agent project",
+        "monorepo   - one agent among other packages",
+        "plugin     - an extension of a host application",
+        "serverless - functions deployed to a FaaS runtime",
+    ];
+    let default = ConfigProfile::ALL
+        .iter()
+        .position(|p| Some(*p) == args.profile)
+        .unwrap_or(0);
+    let profile =
+        ConfigProfile::ALL[prompts.prompt_select("Deployment profile", &labels, default)?];
+
+    let agent_path = if profile == ConfigProfile::Monorepo {
+        prompts.prompt_string(
+            "Agent directory",
+            Some(args.agent_path.as_deref().unwrap_or(&default_agent_path)),
+        )?
+    } else {
+        default_agent_path
+    };
+
+    let mut config = BelticConfig::default_for_profile(profile.as_str(), &agent_path);
+    apply_path_overrides(&mut config, args)?;
+
+    loop {
+        let paths = &config.agent.paths;
+        prompts.info(&format!("  include: {}", paths.include.join(", ")))?;
+        prompts.info(&format!("  exclude: {}", paths.exclude.join(", ")))?;
+        match preview_count(&config, root) {
+            Ok(count) => prompts.success(&format!("{} file(s) match", count))?,
+            Err(err) => prompts.warn(&format!("Preview failed: {:#}", err))?,
+        }
+
+        let actions = [
+            "Write configuration",
+            "Add include pattern",
+            "Add exclude pattern",
+            "Remove a pattern",
+        ];
+        match prompts.prompt_select("Next", &actions, 0)? {
+            0 => break,
+            choice @ (1 | 2) => {
+                let pattern = prompts.prompt_string("Glob pattern", None)?;
+                if let Err(err) = check_glob(&pattern) {
+                    prompts.warn(&format!("{:#}", err))?;
+                    continue;
+                }
+                let paths = &mut config.agent.paths;
+                if choice == 1 {
+                    paths.include.push(pattern);
+                } else {
+                    paths.exclude.push(pattern);
+                }
+            }
+            _ => {
+                let paths = &mut config.agent.paths;
+                let options: Vec<String> = paths
+                    .include
+                    .iter()
+                    .map(|p| format!("include {}", p))
+                    .chain(paths.exclude.iter().map(|p| format!("exclude {}", p)))
+                    .collect();
+                if options.is_empty() {
+                    continue;
+                }
+                let refs: Vec<&str> = options.iter().map(String::as_str).collect();
+                let index = prompts.prompt_select("Remove", &refs, 0)?;
+                if index < paths.include.len() {
+                    paths.include.remove(index);
+                } else {
+                    paths.exclude.remove(index - paths.include.len());
+                }
+            }
+        }
+    }
+
+    Ok(config)
+}
+
+/// Number of files the config's include/exclude patterns select under `root`
+fn preview_count(config: &BelticConfig, root: &Path) -> Result<usize> {
+    let options = FingerprintOptions::from_path_config(&config.agent.paths, root.to_path_buf());
+    Ok(matching_files(&options)?.len())
+}
+
+fn check_glob(pattern: &str) -> Result<()> {
+    Glob::new(pattern).with_context(|| format!("invalid glob pattern `{}`", pattern))?;
+    Ok(())
+}
+
+fn run_validate(args: ConfigValidateArgs, global_config: Option<&Path>) -> Result<()> {
+    let path = match args.path {
+        Some(path) => path,
+        None => {
+            let cwd = std::env::current_dir().context("failed to get current directory")?;
+            BelticConfig::locate(global_config, &cwd)
+                .context("no .beltic.yaml found in this or any parent directory")?
+        }
+    };
+
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    let result = BelticConfig::lint(&content);
+
+    if args.json {
+        let issues: Vec<_> = result
+            .issues
+            .iter()
+            .map(|issue| {
+                json!({
+                    "ruleId": issue.rule_id,
+                    "level": match issue.level {
+                        IssueLevel::Error => "error",
+                        IssueLevel::Warning => "warning",
+                    },
+                    "pointer": issue.pointer,
+                    "message": issue.message,
+                })
+            })
+            .collect();
+        let output = json!({
+            "path": path.display().to_string(),
+            "valid": result.is_valid,
+            "issues": issues,
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        for issue in &result.issues {
+            let level = match issue.level {
+                IssueLevel::Error => style("error").red().bold(),
+                IssueLevel::Warning => style("warning").yellow().bold(),
+            };
+            println!(
+                "{} {}: {} [{}]",
+                level,
+                issue.pointer.as_deref().unwrap_or("/"),
+                issue.message,
+                issue.rule_id
+            );
+        }
+        if result.is_valid {
+            println!("{} {} is valid", style("✓").green(), path.display());
+        } else {
+            println!(
+                "{} {} has {} error(s)",
+                style("✗").red(),
+                path.display(),
+                result.errors.len()
+            );
+        }
+    }
+
+    if !result.is_valid {
+        return exit::reported(exit::CHECK_FAILED);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_preview_counts_matching_files() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/tests")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.path().join("src/tests/it.rs"), "").unwrap();
+        fs::write(dir.path().join("notes.txt"), "").unwrap();
+
+        let config = BelticConfig::default_standalone();
+        assert_eq!(preview_count(&config, dir.path()).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_profile_parsing() {
+        assert_eq!(
+            "Serverless".parse::<ConfigProfile>(),
+            Ok(ConfigProfile::Serverless)
+        );
+        assert!("lambda".parse::<ConfigProfile>().is_err());
+        assert!(check_glob("src/{a").is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! Convert manifests and credentials between JSON and YAML, and between
+//! each other.
+//!
+//! Usage: beltic convert <input> [output] [--format json|yaml]
+//!        [--from manifest|credential] [--to manifest|credential]
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Args;
+use serde_json::Value;
+
+use beltic::credential::{validate_credential_version, CredentialKind};
+use beltic::document::{read_document, to_document_string, write_document, DocumentFormat};
+use beltic::manifest::convert::{credential_to_manifest, manifest_to_credential, DocumentKind};
+use beltic::manifest::credential::AgentCredential;
+use beltic::manifest::schema::AgentManifest;
+use beltic::manifest::validator::validate_manifest;
+use beltic::schema::active_version;
+
+#[derive(Args)]
+pub struct ConvertArgs {
+    /// Input file (format detected from the extension)
+    pub input: PathBuf,
+
+    /// Output file; prints to stdout when omitted
+    pub output: Option<PathBuf>,
+
+    /// Output format: json or yaml (default: from the output extension, else json)
+    #[arg(long)]
+    pub format: Option<DocumentFormat>,
+
+    /// Input format, when the extension is ambiguous
+    #[arg(long)]
+    pub input_format: Option<DocumentFormat>,
+
+    /// What the input is: manifest or credential (default: detected)
+    #[arg(long, value_name = "KIND", requires = "to")]
+    pub from: Option<DocumentKind>,
+
+    /// Convert to this document: manifest or credential
+    #[arg(long, value_name = "KIND")]
+    pub to: Option<DocumentKind>,
+
+    /// Issuer DID of a credential converted from a manifest
+    /// (default: did:web:self.<agent name>.local)
+    #[arg(long, requires = "to")]
+    pub issuer_did: Option<String>,
+
+    /// Write the converted document even if it fails validation
+    #[arg(long, requires = "to")]
+    pub skip_schema: bool,
+
+    /// Overwrite the output file if it exists
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// The global `--config` file, whose schema pin validation uses
+    #[arg(skip)]
+    pub global_config: Option<PathBuf>,
+}
+
+pub fn run(args: ConvertArgs) -> Result<()> {
+    let mut document = read_document(&args.input, args.input_format)?;
+    if let Some(to) = args.to {
+        document = convert_kind(&args, document, to)?;
+    }
+
+    match &args.output {
+        Some(output) => {
+            if output.exists() && !args.force {
+                bail!(
+                    "{} already exists. Use --force to overwrite.",
+                    output.display()
+                );
+            }
+            write_document(output, &document, args.format)?;
+            eprintln!(
+                "Converted {} → {} ({})",
+                args.input.display(),
+                output.display(),
+                DocumentFormat::resolve(output, args.format)
+            );
+        }
+        None => {
+            let content = to_document_string(&document, args.format.unwrap_or_default())?;
+            println!("{}", content.trim_end());
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert `document` to a `to` document, validating the result
+fn convert_kind(args: &ConvertArgs, document: Value, to: DocumentKind) -> Result<Value> {
+    let from = match args.from {
+        Some(from) => from,
+        None => DocumentKind::detect(&document).ok_or_else(|| {
+            anyhow!(
+                "cannot tell whether {} is a manifest or a credential; pass --from",
+                args.input.display()
+            )
+        })?,
+    };
+    if from == to {
+        return Ok(document);
+    }
+
+    let (converted, errors) = match to {
+        DocumentKind::Credential => {
+            let manifest: AgentManifest = serde_json::from_value(document)
+                .with_context(|| format!("{} is not a valid manifest", args.input.display()))?;
+            let issuer_did = args.issuer_did.clone().unwrap_or_else(|| {
+                format!(
+                    "did:web:self.{}.local",
+                    manifest.agent_name.to_lowercase().replace(' ', "-")
+                )
+            });
+            let credential = serde_json::to_value(manifest_to_credential(&manifest, &issuer_did)?)?;
+            let errors = validate_credential_version(
+                CredentialKind::Agent,
+                &credential,
+                &active_version(args.global_config.as_deref()),
+            )?;
+            (credential, errors)
+        }
+        DocumentKind::Manifest => {
+            let credential: AgentCredential = serde_json::from_value(document)
+                .with_context(|| format!("{} is not a valid credential", args.input.display()))?;
+            let manifest = credential_to_manifest(&credential)?;
+            let errors = validate_manifest(&manifest).errors;
+            (serde_json::to_value(manifest)?, errors)
+        }
+    };
+
+    if !errors.is_empty() {
+        let mut message = format!("converted {} failed validation:\n", to);
+        for error in &errors {
+            message.push_str(&format!("  - {}\n", error));
+        }
+        if !args.skip_schema {
+            message.push_str("Fix the input, or pass --skip-schema to write it anyway");
+            bail!(message);
+        }
+        eprint!("{}", message);
+    }
+    Ok(converted)
+}
@@ -1,47 +1,266 @@
-//! Extract credential ID from a credential JSON or JWT file.
+//! Extract credential ID from a credential JSON, YAML or JWT file.
+//!
+//! With several files or `--all <dir>`, prints an inventory of every
+//! credential found instead.
 
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
 use clap::Args;
+use console::style;
+use ignore::WalkBuilder;
+use serde::Serialize;
 use serde_json::Value;
 
+use beltic::document::{parse_document, DocumentFormat};
+
+use super::exit;
+
 #[derive(Args)]
 pub struct CredentialIdArgs {
-    /// Path to the credential file (JSON or JWT)
-    #[arg()]
-    pub file: PathBuf,
+    /// Path(s) to credential files (JSON, YAML or JWT)
+    #[arg(required_unless_present = "all")]
+    pub files: Vec<PathBuf>,
+
+    /// Scan a directory tree for *.json and *.jwt credentials
+    #[arg(long, value_name = "DIR")]
+    pub all: Option<PathBuf>,
+
+    /// Output the inventory as a JSON array
+    #[arg(long)]
+    pub json: bool,
+
+    /// Only list credentials expiring within this many days (or already
+    /// expired) and exit non-zero if any are found
+    #[arg(long, value_name = "DAYS")]
+    pub expiring_within: Option<i64>,
+}
+
+/// Days left before expiry is highlighted
+pub(crate) const EXPIRY_WARNING_DAYS: i64 = 14;
+
+/// One row of a credential inventory
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CredentialSummary {
+    pub path: String,
+    pub agent_id: Option<String>,
+    pub credential_id: String,
+    pub status: Option<String>,
+    /// `credentialExpirationDate` (or `expirationDate`), else the JWT `exp`
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Whole days until expiry; negative once expired
+    pub days_remaining: Option<i64>,
+}
+
+impl CredentialSummary {
+    /// Whether the credential expires within `days` (or already has)
+    pub fn expires_within(&self, days: i64) -> bool {
+        self.days_remaining
+            .is_some_and(|remaining| remaining < days)
+    }
 }
 
 pub fn run(args: CredentialIdArgs) -> Result<()> {
-    let path = &args.file;
+    let now = Utc::now();
+    if args.all.is_none() && args.files.len() == 1 && !args.json && args.expiring_within.is_none() {
+        return print_credential_id(&args.files[0], now);
+    }
 
+    let mut paths = args.files.clone();
+    if let Some(dir) = &args.all {
+        paths.extend(find_credential_files(dir)?);
+    }
+
+    let mut summaries = Vec::new();
+    for path in &paths {
+        match summarize(path, now) {
+            Ok(summary) => summaries.push(summary),
+            Err(err) => eprintln!("Warning: skipping {}: {:#}", path.display(), err),
+        }
+    }
+
+    if let Some(days) = args.expiring_within {
+        summaries.retain(|summary| summary.expires_within(days));
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&summaries)?);
+    } else {
+        print_table(&summaries);
+    }
+
+    if args.expiring_within.is_some() && !summaries.is_empty() {
+        eprintln!(
+            "{} credential(s) need re-issuance",
+            style(summaries.len()).yellow().bold()
+        );
+        return exit::reported(exit::CHECK_FAILED);
+    }
+    Ok(())
+}
+
+fn print_credential_id(path: &Path, now: DateTime<Utc>) -> Result<()> {
     if !path.exists() {
         return Err(anyhow!("File not found: {}", path.display()));
     }
 
+    let summary = summarize(path, now)?;
+    println!("{}", summary.credential_id);
+    if summary.days_remaining.is_some() {
+        eprintln!("{}", colored_expiry(summary.days_remaining));
+    }
+    Ok(())
+}
+
+/// Find `*.json` and `*.jwt` files under `dir`, honouring `.gitignore`
+pub(crate) fn find_credential_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Err(anyhow!("Not a directory: {}", dir.display()));
+    }
+
+    let mut files = Vec::new();
+    for entry in WalkBuilder::new(dir)
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+    {
+        let entry = entry?;
+        let path = entry.path();
+        let is_candidate = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext == "json" || ext == "jwt");
+        if entry.file_type().is_some_and(|t| t.is_file()) && is_candidate {
+            files.push(path.to_path_buf());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Read a credential file and pull out its identifiers and status
+pub fn summarize(path: &Path, now: DateTime<Utc>) -> Result<CredentialSummary> {
     let content = fs::read_to_string(path)
         .with_context(|| format!("Failed to read file: {}", path.display()))?;
 
-    let credential_id = if is_jwt(&content) {
-        extract_from_jwt(&content)?
+    let (credential_id, credential, jwt_exp) = if is_jwt(&content) {
+        let payload = decode_jwt_payload(&content)?;
+        let credential_id = extract_from_jwt(&content)?;
+        let exp = payload
+            .get("exp")
+            .and_then(Value::as_i64)
+            .and_then(|exp| DateTime::from_timestamp(exp, 0));
+        let credential = payload.get("vc").cloned().unwrap_or(payload);
+        (credential_id, credential, exp)
     } else {
-        extract_from_json(&content)?
+        let credential = parse_document(&content, DocumentFormat::resolve(path, None))
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        (credential_id_from_value(&credential)?, credential, None)
     };
 
-    println!("{}", credential_id);
-    Ok(())
+    let field = |name: &str| {
+        credential
+            .get(name)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    let expires_at = field("credentialExpirationDate")
+        .or_else(|| field("expirationDate"))
+        .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+        .map(|date| date.with_timezone(&Utc))
+        .or(jwt_exp);
+
+    Ok(CredentialSummary {
+        path: path.display().to_string(),
+        agent_id: field("agentId"),
+        credential_id,
+        status: field("credentialStatus"),
+        expires_at,
+        days_remaining: expires_at.map(|expires| days_until(expires, now)),
+    })
+}
+
+/// Whole days from `now` until `expires`, rounding down (so anything already
+/// past is negative)
+fn days_until(expires: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    (expires - now).num_seconds().div_euclid(86_400)
+}
+
+pub(crate) fn expiry_label(days_remaining: Option<i64>) -> String {
+    match days_remaining {
+        None => "-".to_string(),
+        Some(days) if days < 0 => format!("expired {} day(s) ago", -days),
+        Some(days) => format!("{} day(s) left", days),
+    }
 }
 
-fn is_jwt(content: &str) -> bool {
+/// Red when expired, yellow within the warning window
+fn colored_expiry(days_remaining: Option<i64>) -> String {
+    let label = expiry_label(days_remaining);
+    match days_remaining {
+        Some(days) if days < 0 => style(label).red().to_string(),
+        Some(days) if days < EXPIRY_WARNING_DAYS => style(label).yellow().to_string(),
+        _ => label,
+    }
+}
+
+fn print_table(summaries: &[CredentialSummary]) {
+    if summaries.is_empty() {
+        println!("No credentials found");
+        return;
+    }
+
+    let rows: Vec<[&str; 4]> = summaries
+        .iter()
+        .map(|s| {
+            [
+                s.path.as_str(),
+                s.agent_id.as_deref().unwrap_or("-"),
+                s.credential_id.as_str(),
+                s.status.as_deref().unwrap_or("-"),
+            ]
+        })
+        .collect();
+    let header = ["PATH", "AGENT ID", "CREDENTIAL ID", "STATUS"];
+    let expiries = std::iter::once("EXPIRES".to_string())
+        .chain(summaries.iter().map(|s| colored_expiry(s.days_remaining)));
+
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    for (row, expiry) in std::iter::once(&header).chain(&rows).zip(expiries) {
+        println!(
+            "{:<w0$}  {:<w1$}  {:<w2$}  {:<w3$}  {}",
+            row[0],
+            row[1],
+            row[2],
+            row[3],
+            expiry,
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3]
+        );
+    }
+}
+
+pub(crate) fn is_jwt(content: &str) -> bool {
     let trimmed = content.trim();
     // JWT has 3 parts separated by dots
     trimmed.split('.').count() == 3 && !trimmed.contains('{')
 }
 
-fn extract_from_json(content: &str) -> Result<String> {
-    let json: Value = serde_json::from_str(content).context("Invalid JSON")?;
-
+fn credential_id_from_value(json: &Value) -> Result<String> {
     // Try credentialId first (agent credentials)
     if let Some(id) = json.get("credentialId").and_then(|v| v.as_str()) {
         return Ok(id.to_string());
@@ -55,23 +274,58 @@ fn extract_from_json(content: &str) -> Result<String> {
         }
     }
 
-    Err(anyhow!("No credentialId found in JSON"))
+    Err(anyhow!("No credentialId found in credential"))
 }
 
-fn extract_from_jwt(content: &str) -> Result<String> {
+/// Read a credential (JSON, YAML or JWS) from `path`; see
+/// [`parse_credential_value`]
+pub(crate) fn load_credential_value(path: &Path) -> Result<Value> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    parse_credential_value(path, &content)
+}
+
+/// The credential in `content`, read from `path`: the `vc` claim of a JWS
+/// (its signature isn't checked), otherwise the document itself as JSON or
+/// YAML by the file extension
+pub(crate) fn parse_credential_value(path: &Path, content: &str) -> Result<Value> {
+    if is_jwt(content) {
+        decode_jwt_payload(content)
+            .with_context(|| format!("failed to decode token {}", path.display()))?
+            .get("vc")
+            .cloned()
+            .with_context(|| format!("token {} has no vc claim", path.display()))
+    } else {
+        parse_document(content, DocumentFormat::resolve(path, None))
+            .with_context(|| format!("failed to parse {}", path.display()))
+    }
+}
+
+/// Decode a JWT payload without verifying its signature
+pub(crate) fn decode_jwt_payload(content: &str) -> Result<Value> {
+    decode_jwt_segment(content, 1, "payload")
+}
+
+/// Decode a JWT protected header without verifying the signature
+pub(crate) fn decode_jwt_header(content: &str) -> Result<Value> {
+    decode_jwt_segment(content, 0, "header")
+}
+
+fn decode_jwt_segment(content: &str, index: usize, name: &str) -> Result<Value> {
     let parts: Vec<&str> = content.trim().split('.').collect();
     if parts.len() != 3 {
         return Err(anyhow!("Invalid JWT format"));
     }
 
-    // Decode the payload (second part)
-    let payload_b64 = parts[1];
-
     // Handle URL-safe base64
-    let payload_bytes = base64_url_decode(payload_b64).context("Failed to decode JWT payload")?;
+    let bytes = base64_url_decode(parts[index])
+        .with_context(|| format!("Failed to decode JWT {}", name))?;
 
-    let payload: Value =
-        serde_json::from_slice(&payload_bytes).context("Failed to parse JWT payload")?;
+    serde_json::from_slice(&bytes).with_context(|| format!("Failed to parse JWT {}", name))
+}
+
+fn extract_from_jwt(content: &str) -> Result<String> {
+    let payload = decode_jwt_payload(content)?;
 
     // Try jti claim (standard JWT claim for credential ID)
     if let Some(jti) = payload.get("jti").and_then(|v| v.as_str()) {
@@ -107,3 +361,86 @@ fn base64_url_decode(input: &str) -> Result<Vec<u8>> {
         })
         .context("Base64 decode failed")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_scan_skips_non_credentials() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("nested")).unwrap();
+        fs::write(
+            dir.path().join("nested/agent.json"),
+            include_str!("../../tests/fixtures/agent-valid.json"),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("developer.json"),
+            include_str!("../../tests/fixtures/developer-valid.json"),
+        )
+        .unwrap();
+        fs::write(dir.path().join("package.json"), r#"{"name":"x"}"#).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not scanned").unwrap();
+
+        let files = find_credential_files(dir.path()).unwrap();
+        assert_eq!(files.len(), 3);
+
+        let summaries: Vec<_> = files
+            .iter()
+            .filter_map(|f| summarize(f, Utc::now()).ok())
+            .collect();
+        assert_eq!(summaries.len(), 2);
+
+        let agent = summaries
+            .iter()
+            .find(|s| s.path.ends_with("agent.json"))
+            .unwrap();
+        assert_eq!(
+            agent.agent_id.as_deref(),
+            Some("3c9f4e9b-1a2b-4c9d-8f34-7e5c80f3d412")
+        );
+        assert_eq!(agent.credential_id, "a2a1f6a0-7f4f-4aa1-8f6b-5c33c6f9f7e2");
+        assert_eq!(agent.status.as_deref(), Some("active"));
+        assert!(agent.expires_at.is_some());
+    }
+
+    #[test]
+    fn test_days_remaining_and_filter() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("agent.json");
+        fs::write(&path, include_str!("../../tests/fixtures/agent-valid.json")).unwrap();
+
+        let expires = DateTime::parse_from_rfc3339("2030-05-08T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let day = chrono::Duration::days(1);
+
+        let soon = summarize(&path, expires - day * 10).unwrap();
+        assert_eq!(soon.days_remaining, Some(10));
+        assert!(soon.expires_within(14));
+        assert!(!soon.expires_within(7));
+
+        let expired = summarize(&path, expires + chrono::Duration::hours(1)).unwrap();
+        assert_eq!(expired.days_remaining, Some(-1));
+        assert!(expired.expires_within(0));
+        assert_eq!(expiry_label(expired.days_remaining), "expired 1 day(s) ago");
+    }
+
+    #[test]
+    fn test_summarize_yaml_credential() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("agent.yaml");
+        let credential: Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/agent-valid.json")).unwrap();
+        fs::write(&path, serde_yaml::to_string(&credential).unwrap()).unwrap();
+
+        let summary = summarize(&path, Utc::now()).unwrap();
+        assert_eq!(
+            summary.credential_id,
+            "a2a1f6a0-7f4f-4aa1-8f6b-5c33c6f9f7e2"
+        );
+        assert_eq!(summary.status.as_deref(), Some("active"));
+    }
+}
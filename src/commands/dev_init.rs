@@ -3,15 +3,27 @@
 //! Creates a self-attested DeveloperCredential for use with agent credentials.
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::str::FromStr;
 
 use anyhow::{anyhow, Context, Result};
-use chrono::{Duration, Utc};
+use chrono::{NaiveDate, Utc};
 use clap::Args;
 use console::style;
 use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
+use zeroize::Zeroizing;
+
+use beltic::credential::{build_claims, ClaimsOptions, CredentialKind};
+use beltic::crypto::did_key::did_key_from_jwk;
+use beltic::crypto::jwk::{
+    public_jwk_from_pem, public_pem_from_private_pem, thumbprint_from_public_pem,
+};
+use beltic::crypto::{sign_jws, PemSigner, SignatureAlg};
+use beltic::duration::IsoDuration;
+use beltic::{estatus, status};
 
 use super::discovery::find_public_keys;
 use super::prompts::CommandPrompts;
@@ -146,8 +158,9 @@ const ENTITY_TYPES: &[(&str, &str)] = &[
     ),
     ("sole_proprietorship", "Sole Proprietorship"),
     ("partnership", "Partnership"),
-    ("nonprofit", "Nonprofit Organization"),
-    ("government_agency", "Government Agency"),
+    ("nonprofit_organization", "Nonprofit Organization"),
+    ("government_entity", "Government Entity"),
+    ("other", "Other organization"),
 ];
 
 /// Common country codes
@@ -164,6 +177,26 @@ const COMMON_COUNTRIES: &[(&str, &str)] = &[
     ("OTHER", "Other (enter code)"),
 ];
 
+/// Officially assigned ISO 3166-1 alpha-2 country codes
+const ISO_COUNTRY_CODES: &[&str] = &[
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
 #[derive(Args)]
 pub struct DevInitArgs {
     /// Output path for the developer credential (default: ./developer-credential.json)
@@ -174,7 +207,8 @@ pub struct DevInitArgs {
     #[arg(long)]
     pub name: Option<String>,
 
-    /// Entity type (individual, corporation, llc, etc.)
+    /// Entity type (individual, corporation, llc, sole_proprietorship,
+    /// partnership, nonprofit, government, other)
     #[arg(long)]
     pub entity_type: Option<String>,
 
@@ -190,10 +224,50 @@ pub struct DevInitArgs {
     #[arg(long)]
     pub email: Option<String>,
 
-    /// Path to public key (PEM) to embed in credential
+    /// Date of incorporation, YYYY-MM-DD (required for organizations)
+    #[arg(long, value_name = "DATE")]
+    pub incorporation_date: Option<NaiveDate>,
+
+    /// Business registration number (required for organizations). Only its
+    /// SHA-256 hash is stored, unless it is already a hash_, tok_ or hmac_
+    /// token
+    #[arg(long, value_name = "NUMBER")]
+    pub registration_number: Option<String>,
+
+    /// Street of the registered address (required for organizations)
+    #[arg(long)]
+    pub street_address: Option<String>,
+
+    /// City of the registered address (required for organizations)
+    #[arg(long)]
+    pub city: Option<String>,
+
+    /// State or region of the registered address
+    #[arg(long)]
+    pub region: Option<String>,
+
+    /// Postal code of the registered address (required for organizations)
+    #[arg(long)]
+    pub postal_code: Option<String>,
+
+    /// Path to an Ed25519 or P-256 public key (PEM) to embed in credential
     #[arg(long)]
     pub public_key: Option<PathBuf>,
 
+    /// How long the credential is valid, as an ISO 8601 duration (default: P90D)
+    #[arg(long, value_name = "DURATION")]
+    pub valid_for: Option<IsoDuration>,
+
+    /// DID method for the issuer and subject: web (from --website) or key
+    /// (from --public-key)
+    #[arg(long, default_value = "web")]
+    pub did_method: DidMethod,
+
+    /// Sign the credential with this Ed25519 or P-256 private key (PEM),
+    /// writing the JWS next to it and its signature to proof.proofValue
+    #[arg(long, value_name = "KEY")]
+    pub sign_with: Option<PathBuf>,
+
     /// Overwrite existing credential file
     #[arg(short, long)]
     pub force: bool,
@@ -203,6 +277,30 @@ pub struct DevInitArgs {
     pub non_interactive: bool,
 }
 
+/// How the credential's issuer and subject DID is formed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DidMethod {
+    /// `did:web:<website domain>`
+    Web,
+    /// `did:key` of the embedded public key
+    Key,
+}
+
+impl FromStr for DidMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "web" => Ok(DidMethod::Web),
+            "key" => Ok(DidMethod::Key),
+            other => Err(format!(
+                "unknown DID method '{}': expected web or key",
+                other
+            )),
+        }
+    }
+}
+
 pub fn run(args: DevInitArgs) -> Result<()> {
     if args.non_interactive {
         run_non_interactive(args)
@@ -252,6 +350,31 @@ fn run_interactive(mut args: DevInitArgs) -> Result<()> {
         }
     }
 
+    // Organizations state when and where they are registered
+    let entity_type = args.entity_type.as_deref().unwrap_or("individual");
+    if is_organization(entity_type) {
+        if args.incorporation_date.is_none() {
+            let date = prompts.prompt_string("Incorporation date (YYYY-MM-DD)", None)?;
+            args.incorporation_date = Some(
+                NaiveDate::from_str(date.trim())
+                    .with_context(|| format!("invalid incorporation date '{}'", date))?,
+            );
+        }
+        if args.registration_number.is_none() {
+            args.registration_number =
+                Some(prompts.prompt_string("Business registration number (stored hashed)", None)?);
+        }
+        if args.street_address.is_none() {
+            args.street_address = Some(prompts.prompt_string("Registered street address", None)?);
+        }
+        if args.city.is_none() {
+            args.city = Some(prompts.prompt_string("City", None)?);
+        }
+        if args.postal_code.is_none() {
+            args.postal_code = Some(prompts.prompt_string("Postal code", None)?);
+        }
+    }
+
     // 4. Website
     if args.website.is_none() {
         let default_website = git_defaults.website.as_deref().unwrap_or("https://");
@@ -263,14 +386,15 @@ fn run_interactive(mut args: DevInitArgs) -> Result<()> {
         args.email = Some(prompts.prompt_string("Business email", git_defaults.email.as_deref())?);
     }
 
-    // 6. Public key (optional)
+    // 6. Public key (optional, unless the DID is derived from it)
     if args.public_key.is_none() {
         let public_keys = find_public_keys();
-        if !public_keys.is_empty() {
-            if prompts.prompt_confirm("Embed a public key in the credential?", true)? {
-                args.public_key =
-                    Some(prompts.prompt_select_path("Select public key", &public_keys, true)?);
-            }
+        if !public_keys.is_empty()
+            && (args.did_method == DidMethod::Key
+                || prompts.prompt_confirm("Embed a public key in the credential?", true)?)
+        {
+            args.public_key =
+                Some(prompts.prompt_select_path("Select public key", &public_keys, true)?);
         }
     }
 
@@ -281,6 +405,7 @@ fn run_interactive(mut args: DevInitArgs) -> Result<()> {
             Some(&PathBuf::from("developer-credential.json")),
         )?);
     }
+    check_inputs(&mut args)?;
 
     // Generate and save
     let output_path = args.output.as_ref().ok_or_else(|| {
@@ -288,17 +413,19 @@ fn run_interactive(mut args: DevInitArgs) -> Result<()> {
     })?;
 
     // Check for existing file
-    if output_path.exists() && !args.force {
-        if !prompts.prompt_confirm(
+    if output_path.exists()
+        && !args.force
+        && !prompts.prompt_confirm(
             &format!("{} exists. Overwrite?", output_path.display()),
             false,
-        )? {
-            prompts.warn("Aborted.")?;
-            return Ok(());
-        }
+        )?
+    {
+        prompts.warn("Aborted.")?;
+        return Ok(());
     }
 
-    let credential = generate_developer_credential(&args)?;
+    let mut credential = generate_developer_credential(&args)?;
+    let token_path = sign_if_requested(&args, &mut credential, output_path)?;
     let json_str = serde_json::to_string_pretty(&credential)?;
     fs::write(output_path, &json_str)?;
 
@@ -306,6 +433,12 @@ fn run_interactive(mut args: DevInitArgs) -> Result<()> {
         "Developer credential saved to {}",
         output_path.display()
     ))?;
+    if let Some(token_path) = &token_path {
+        prompts.success(&format!(
+            "Signed credential (JWS) saved to {}",
+            token_path.display()
+        ))?;
+    }
     prompts.info("")?;
     prompts.info(&format!(
         "Credential ID: {}",
@@ -317,13 +450,22 @@ fn run_interactive(mut args: DevInitArgs) -> Result<()> {
     ))?;
     prompts.info("")?;
     prompts.info("Next steps:")?;
-    prompts.info("  1. Generate a keypair if you haven't: beltic keygen")?;
-    prompts.info(&format!(
-        "  2. Sign the credential: beltic sign --payload {}",
-        output_path.display()
-    ))?;
-    prompts
-        .info("  3. Use the credential ID in agent manifests: beltic init --developer-id <id>")?;
+    if let Some(token_path) = &token_path {
+        prompts.info(&format!(
+            "  1. Use it in agent manifests: beltic init --developer-credential {} --developer-key <public key>",
+            token_path.display()
+        ))?;
+    } else {
+        prompts.info("  1. Generate a keypair if you haven't: beltic keygen")?;
+        prompts.info(&format!(
+            "  2. Sign the credential: beltic sign --payload {}",
+            output_path.display()
+        ))?;
+        prompts.info(&format!(
+            "  3. Use it in agent manifests: beltic init --developer-credential {}",
+            output_path.display()
+        ))?;
+    }
 
     Ok(())
 }
@@ -334,21 +476,21 @@ fn run_non_interactive(mut args: DevInitArgs) -> Result<()> {
 
     if args.name.is_none() {
         if let Some(name) = git_defaults.name {
-            eprintln!("[info] Using git user.name: {}", name);
+            estatus!("[info] Using git user.name: {}", name);
             args.name = Some(name);
         }
     }
 
     if args.email.is_none() {
         if let Some(email) = git_defaults.email {
-            eprintln!("[info] Using git user.email: {}", email);
+            estatus!("[info] Using git user.email: {}", email);
             args.email = Some(email);
         }
     }
 
     if args.website.is_none() {
         if let Some(website) = git_defaults.website {
-            eprintln!("[info] Using derived website: {}", website);
+            estatus!("[info] Using derived website: {}", website);
             args.website = Some(website);
         }
     }
@@ -363,6 +505,7 @@ fn run_non_interactive(mut args: DevInitArgs) -> Result<()> {
     if args.website.is_none() {
         anyhow::bail!("--website is required (could not derive from git remote)");
     }
+    check_inputs(&mut args)?;
 
     let output_path = args
         .output
@@ -377,11 +520,15 @@ fn run_non_interactive(mut args: DevInitArgs) -> Result<()> {
         );
     }
 
-    let credential = generate_developer_credential(&args)?;
+    let mut credential = generate_developer_credential(&args)?;
+    let token_path = sign_if_requested(&args, &mut credential, &output_path)?;
     let json_str = serde_json::to_string_pretty(&credential)?;
     fs::write(&output_path, &json_str)?;
 
-    println!("Developer credential saved to {}", output_path.display());
+    status!("Developer credential saved to {}", output_path.display());
+    if let Some(token_path) = token_path {
+        status!("Signed credential (JWS) saved to {}", token_path.display());
+    }
     println!(
         "Credential ID: {}",
         credential["credentialId"].as_str().unwrap_or("")
@@ -390,9 +537,231 @@ fn run_non_interactive(mut args: DevInitArgs) -> Result<()> {
     Ok(())
 }
 
+/// Reject malformed `--email`, `--website` and `--country` values and
+/// organization details that don't fit `--entity-type` before generating,
+/// normalizing the country code and entity type
+fn check_inputs(args: &mut DevInitArgs) -> Result<()> {
+    if let Some(email) = &args.email {
+        check_email(email).map_err(|e| anyhow!("--email '{}': {}", email, e))?;
+    }
+    if let Some(website) = &args.website {
+        check_website(website).map_err(|e| anyhow!("--website '{}': {}", website, e))?;
+    }
+    if let Some(country) = &args.country {
+        let code = country.trim().to_uppercase();
+        if !ISO_COUNTRY_CODES.contains(&code.as_str()) {
+            anyhow::bail!(
+                "--country '{}': not an ISO 3166-1 alpha-2 country code (e.g., US, GB, DE)",
+                country
+            );
+        }
+        args.country = Some(code);
+    }
+    if let Some(entity_type) = &args.entity_type {
+        let canonical = normalize_entity_type(entity_type).ok_or_else(|| {
+            let expected: Vec<&str> = ENTITY_TYPES.iter().map(|(value, _)| *value).collect();
+            anyhow!(
+                "--entity-type '{}': expected one of {}",
+                entity_type,
+                expected.join(", ")
+            )
+        })?;
+        args.entity_type = Some(canonical.to_string());
+    }
+    if let Some(number) = &args.registration_number {
+        if number.trim().is_empty() {
+            anyhow::bail!("--registration-number must not be empty");
+        }
+    }
+    if let Some(date) = args.incorporation_date {
+        if date > Utc::now().date_naive() {
+            anyhow::bail!("--incorporation-date {} is in the future", date);
+        }
+    }
+
+    let entity_type = args.entity_type.as_deref().unwrap_or("individual");
+    let organization_flags = [
+        ("--incorporation-date", args.incorporation_date.is_some()),
+        ("--registration-number", args.registration_number.is_some()),
+        ("--street-address", args.street_address.is_some()),
+        ("--city", args.city.is_some()),
+        ("--postal-code", args.postal_code.is_some()),
+    ];
+    let address_flags = [
+        ("--street-address", args.street_address.is_some()),
+        ("--city", args.city.is_some()),
+        ("--postal-code", args.postal_code.is_some()),
+    ];
+    if entity_type == "individual" {
+        let given = organization_flags
+            .into_iter()
+            .chain([("--region", args.region.is_some())])
+            .find(|(_, set)| *set);
+        if let Some((flag, _)) = given {
+            anyhow::bail!("{} does not apply to --entity-type individual", flag);
+        }
+    } else if is_organization(entity_type) {
+        let missing = missing_flags(&organization_flags);
+        if !missing.is_empty() {
+            anyhow::bail!(
+                "--entity-type {} requires {}",
+                entity_type,
+                missing.join(", ")
+            );
+        }
+    } else if address_flags.iter().any(|(_, set)| *set) || args.region.is_some() {
+        let missing = missing_flags(&address_flags);
+        if !missing.is_empty() {
+            anyhow::bail!("a registered address requires {}", missing.join(", "));
+        }
+    }
+    Ok(())
+}
+
+fn missing_flags<'a>(flags: &[(&'a str, bool)]) -> Vec<&'a str> {
+    flags
+        .iter()
+        .filter(|(_, set)| !set)
+        .map(|(flag, _)| *flag)
+        .collect()
+}
+
+/// The schema's entity type for an `--entity-type` value, also accepting
+/// `llc`, `nonprofit` and `government`
+fn normalize_entity_type(value: &str) -> Option<&'static str> {
+    let value = value.trim().to_ascii_lowercase().replace('-', "_");
+    let value = match value.as_str() {
+        "llc" => "limited_liability_company",
+        "nonprofit" => "nonprofit_organization",
+        "government" | "government_agency" => "government_entity",
+        other => other,
+    };
+    ENTITY_TYPES
+        .iter()
+        .map(|(entity_type, _)| *entity_type)
+        .find(|entity_type| *entity_type == value)
+}
+
+/// Entity types the schema requires an incorporation date, registration
+/// number and registered address for
+fn is_organization(entity_type: &str) -> bool {
+    !matches!(entity_type, "individual" | "sole_proprietorship")
+}
+
+/// The credential holds a token for the registration number, never the
+/// number itself: existing `hash_`/`tok_`/`hmac_` tokens are kept, anything
+/// else becomes `hash_<sha256>`
+fn tokenize_registration_number(number: &str) -> String {
+    let number = number.trim();
+    let is_token = ["hash_", "tok_", "hmac_"]
+        .iter()
+        .any(|prefix| number.starts_with(prefix))
+        && (8..=128).contains(&number.len())
+        && number
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if is_token {
+        number.to_string()
+    } else {
+        format!("hash_{:x}", Sha256::digest(number.as_bytes()))
+    }
+}
+
+/// Basic email shape: one `@`, a non-empty local part and a dotted domain
+fn check_email(email: &str) -> std::result::Result<(), String> {
+    if email.chars().any(char::is_whitespace) {
+        return Err("must not contain whitespace".to_string());
+    }
+    let Some((local, domain)) = email.split_once('@') else {
+        return Err("missing '@'".to_string());
+    };
+    if local.is_empty() {
+        return Err("nothing before '@'".to_string());
+    }
+    if domain.contains('@') {
+        return Err("more than one '@'".to_string());
+    }
+    if !domain.contains('.') || domain.starts_with('.') || domain.ends_with('.') {
+        return Err(format!("domain '{}' is not a valid domain name", domain));
+    }
+    Ok(())
+}
+
+/// The website must be an http(s) URL with a host
+fn check_website(website: &str) -> std::result::Result<(), String> {
+    let url = url::Url::parse(website).map_err(|e| format!("not a valid URL ({})", e))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!(
+            "scheme must be http or https, not {}",
+            url.scheme()
+        ));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err("URL has no host".to_string());
+    }
+    Ok(())
+}
+
+/// Algorithm and public key PEM of the `--sign-with` private key
+fn read_signing_public_key(key_path: &Path) -> Result<(SignatureAlg, String)> {
+    let pem = Zeroizing::new(
+        fs::read_to_string(key_path)
+            .with_context(|| format!("Failed to read private key: {}", key_path.display()))?,
+    );
+    public_pem_from_private_pem(&pem)
+        .with_context(|| format!("Invalid private key: {}", key_path.display()))
+}
+
+/// With `--sign-with`, sign the credential as a JWS written next to
+/// `output_path` (`.jwt`) and put the JWS signature in `proof.proofValue`.
+/// Returns the JWS path.
+fn sign_if_requested(
+    args: &DevInitArgs,
+    credential: &mut Value,
+    output_path: &Path,
+) -> Result<Option<PathBuf>> {
+    let Some(key_path) = &args.sign_with else {
+        return Ok(None);
+    };
+    let (alg, _) = read_signing_public_key(key_path)?;
+    let kid = credential["verificationMethod"]
+        .as_str()
+        .map(str::to_string);
+    let signer = PemSigner::from_file(key_path, alg, kid)?;
+
+    let claims = build_claims(
+        credential,
+        CredentialKind::Developer,
+        ClaimsOptions {
+            issuer: None,
+            subject: None,
+            audience: &[],
+            valid_for: None,
+            jti: None,
+            not_before: None,
+        },
+    )?;
+    let token = sign_jws(
+        &claims,
+        &signer,
+        CredentialKind::Developer.media_type(),
+        Some("application/json"),
+    )?;
+    let signature = token.rsplit('.').next().unwrap_or_default();
+    credential["proof"]["proofValue"] = json!(signature);
+
+    let token_path = output_path.with_extension("jwt");
+    fs::write(&token_path, &token)
+        .with_context(|| format!("failed to write token to {}", token_path.display()))?;
+    Ok(Some(token_path))
+}
+
 fn generate_developer_credential(args: &DevInitArgs) -> Result<Value> {
     let now = Utc::now();
-    let expiry = now + Duration::days(90); // 90-day validity for self-attested
+    let valid_for = args.valid_for.unwrap_or_default();
+    let expiry = valid_for
+        .after(now)
+        .ok_or_else(|| anyhow!("--valid-for {} is out of range", valid_for))?;
 
     let credential_id = Uuid::new_v4();
     let name = args.name.as_deref().unwrap_or("Developer");
@@ -401,11 +770,11 @@ fn generate_developer_credential(args: &DevInitArgs) -> Result<Value> {
     let website = args.website.as_deref().unwrap_or("https://example.com");
     let email = args.email.as_deref().unwrap_or("developer@example.com");
 
-    // Determine business registration status based on entity type
-    let registration_status = if entity_type == "individual" {
-        "not_applicable"
-    } else {
+    // Only organizations have a registration status of their own
+    let registration_status = if is_organization(entity_type) {
         "active_good_standing"
+    } else {
+        "not_applicable"
     };
 
     // Extract domain from website for DID
@@ -416,40 +785,87 @@ fn generate_developer_credential(args: &DevInitArgs) -> Result<Value> {
         .next()
         .unwrap_or("example.com");
 
+    // The signing key's public half is embedded when no public key is given
+    let signing_key = args
+        .sign_with
+        .as_deref()
+        .map(read_signing_public_key)
+        .transpose()?;
+
     // Build public key object if provided
-    let public_key = if let Some(key_path) = &args.public_key {
+    let embedded_key = if let Some(key_path) = &args.public_key {
         let pem_content = fs::read_to_string(key_path)
             .with_context(|| format!("Failed to read public key: {}", key_path.display()))?;
-
-        // Detect key type from PEM
-        let key_type = if pem_content.contains("ED25519") {
-            "Ed25519VerificationKey2020"
-        } else if pem_content.contains("EC") || pem_content.contains("P-256") {
-            "JsonWebKey2020"
-        } else {
-            "Ed25519VerificationKey2020" // Default
-        };
-
-        json!({
-            "type": key_type,
-            "publicKeyJwk": {
-                "kty": "OKP",
-                "crv": "Ed25519",
-                "x": "placeholder_base64url_public_key"
+        let (alg, jwk) = public_jwk_from_pem(&pem_content)
+            .with_context(|| format!("Invalid public key: {}", key_path.display()))?;
+        let thumbprint = thumbprint_from_public_pem(&pem_content, alg)?;
+        if let Some((signing_alg, signing_pem)) = &signing_key {
+            if thumbprint_from_public_pem(signing_pem, *signing_alg).ok()
+                != Some(thumbprint.clone())
+            {
+                anyhow::bail!(
+                    "--sign-with key does not match --public-key {}",
+                    key_path.display()
+                );
             }
-        })
+        }
+        Some((alg, jwk, thumbprint))
+    } else if let Some((alg, pem_content)) = &signing_key {
+        let (_, jwk) = public_jwk_from_pem(pem_content)?;
+        Some((*alg, jwk, thumbprint_from_public_pem(pem_content, *alg)?))
     } else {
-        json!({
+        None
+    };
+    let public_key = match &embedded_key {
+        Some((alg, jwk, _)) => {
+            let key_type = match alg {
+                SignatureAlg::EdDsa => "Ed25519VerificationKey2020",
+                _ => "JsonWebKey2020",
+            };
+            json!({
+                "type": key_type,
+                "publicKeyJwk": jwk
+            })
+        }
+        None => json!({
             "type": "Ed25519VerificationKey2020",
             "publicKeyJwk": {
                 "kty": "OKP",
                 "crv": "Ed25519",
                 "x": "placeholder_base64url_public_key"
             }
-        })
+        }),
+    };
+
+    // Self-issued: the developer is both issuer and subject. did:web keys are
+    // named by their JWK thumbprint; a did:key's only key is named by the
+    // multibase value itself
+    let (did, verification_method) = match (args.did_method, &embedded_key) {
+        (DidMethod::Web, key) => {
+            let did = format!("did:web:{}", domain);
+            let fragment = key
+                .as_ref()
+                .map(|(_, _, thumbprint)| thumbprint.as_str())
+                .unwrap_or("key-1");
+            let verification_method = format!("{}#{}", did, fragment);
+            (did, verification_method)
+        }
+        (DidMethod::Key, Some((_, jwk, _))) => {
+            let did = did_key_from_jwk(jwk)?;
+            let verification_method = format!("{}#{}", did, did.trim_start_matches("did:key:"));
+            (did, verification_method)
+        }
+        (DidMethod::Key, None) => {
+            anyhow::bail!("--did-method key needs --public-key to derive the DID from")
+        }
     };
 
-    let credential = json!({
+    let proof_type = match &embedded_key {
+        Some((SignatureAlg::Es256, _, _)) => "JsonWebSignature2020",
+        _ => "Ed25519Signature2020",
+    };
+
+    let mut credential = json!({
         "schemaVersion": "1.0",
         "legalName": name,
         "entityType": entity_type,
@@ -466,17 +882,17 @@ fn generate_developer_credential(args: &DevInitArgs) -> Result<Value> {
         "credentialId": credential_id.to_string(),
         "issuanceDate": now.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
         "expirationDate": expiry.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
-        "issuerDid": "did:web:self",
-        "verificationMethod": "did:web:self#key-1",
+        "issuerDid": did,
+        "verificationMethod": verification_method,
         "credentialStatus": "active",
         "revocationListUrl": format!("https://{}/revocation", domain),
         "lastUpdatedDate": now.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
-        "subjectDid": format!("did:web:{}", domain),
+        "subjectDid": did,
         "publicKey": public_key,
         "proof": {
-            "type": "Ed25519Signature2020",
+            "type": proof_type,
             "created": now.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
-            "verificationMethod": "did:web:self#key-1",
+            "verificationMethod": verification_method,
             "proofPurpose": "assertionMethod",
             "proofValue": "placeholder_will_be_replaced_when_signed"
         },
@@ -493,5 +909,257 @@ fn generate_developer_credential(args: &DevInitArgs) -> Result<Value> {
         }
     });
 
+    if let Some(date) = args.incorporation_date {
+        credential["incorporationDate"] = json!(date.format("%Y-%m-%d").to_string());
+    }
+    if let Some(number) = &args.registration_number {
+        credential["businessRegistrationNumber"] = json!(tokenize_registration_number(number));
+    }
+    if let (Some(street), Some(city), Some(postal_code)) =
+        (&args.street_address, &args.city, &args.postal_code)
+    {
+        let mut address = json!({
+            "streetAddress": street,
+            "city": city,
+            "postalCode": postal_code,
+            "country": country
+        });
+        if let Some(region) = &args.region {
+            address["region"] = json!(region);
+        }
+        credential["registeredAddress"] = address;
+    }
+
     Ok(credential)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use ed25519_dalek::SigningKey;
+    use pkcs8::{EncodePrivateKey, EncodePublicKey, LineEnding};
+
+    fn args(public_key: Option<PathBuf>, did_method: DidMethod) -> DevInitArgs {
+        DevInitArgs {
+            output: None,
+            name: Some("Acme".to_string()),
+            entity_type: None,
+            country: None,
+            website: Some("https://acme.example.com/about".to_string()),
+            email: Some("dev@acme.example.com".to_string()),
+            incorporation_date: None,
+            registration_number: None,
+            street_address: None,
+            city: None,
+            region: None,
+            postal_code: None,
+            public_key,
+            valid_for: None,
+            did_method,
+            sign_with: None,
+            force: false,
+            non_interactive: true,
+        }
+    }
+
+    fn write_public_key(dir: &std::path::Path) -> PathBuf {
+        let path = dir.join("public.pem");
+        let pem = SigningKey::from_bytes(&[7u8; 32])
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        fs::write(&path, pem).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_did_web_uses_key_thumbprint() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = write_public_key(dir.path());
+        let thumbprint =
+            thumbprint_from_public_pem(&fs::read_to_string(&key).unwrap(), SignatureAlg::EdDsa)
+                .unwrap();
+
+        let credential = generate_developer_credential(&args(Some(key), DidMethod::Web)).unwrap();
+        let method = format!("did:web:acme.example.com#{}", thumbprint);
+        assert_eq!(credential["issuerDid"], "did:web:acme.example.com");
+        assert_eq!(credential["subjectDid"], "did:web:acme.example.com");
+        assert_eq!(credential["verificationMethod"], method);
+        assert_eq!(credential["proof"]["verificationMethod"], method);
+
+        let credential = generate_developer_credential(&args(None, DidMethod::Web)).unwrap();
+        assert_eq!(
+            credential["verificationMethod"],
+            "did:web:acme.example.com#key-1"
+        );
+    }
+
+    #[test]
+    fn test_did_key_from_public_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = write_public_key(dir.path());
+
+        let credential = generate_developer_credential(&args(Some(key), DidMethod::Key)).unwrap();
+        let did = did_key_from_jwk(&credential["publicKey"]["publicKeyJwk"]).unwrap();
+        assert!(did.starts_with("did:key:z6Mk"), "{}", did);
+        let method = format!("{}#{}", did, &did["did:key:".len()..]);
+        assert_eq!(credential["issuerDid"], did);
+        assert_eq!(credential["subjectDid"], did);
+        assert_eq!(credential["verificationMethod"], method);
+        assert_eq!(credential["proof"]["verificationMethod"], method);
+
+        assert!(generate_developer_credential(&args(None, DidMethod::Key)).is_err());
+    }
+
+    #[test]
+    fn test_sign_with_writes_verifiable_jws() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("private.pem");
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        fs::write(
+            &key_path,
+            signing_key.to_pkcs8_pem(LineEnding::LF).unwrap().as_bytes(),
+        )
+        .unwrap();
+
+        let mut args = args(None, DidMethod::Key);
+        args.sign_with = Some(key_path);
+        let mut credential = generate_developer_credential(&args).unwrap();
+        let output = dir.path().join("developer-credential.json");
+        let token_path = sign_if_requested(&args, &mut credential, &output)
+            .unwrap()
+            .unwrap();
+
+        let token = fs::read_to_string(&token_path).unwrap();
+        let public_pem = signing_key
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        let verified = beltic::crypto::verify_jws_with_pem(&token, &public_pem, None).unwrap();
+        assert_eq!(verified.alg, SignatureAlg::EdDsa);
+        assert_eq!(
+            verified.header.kid.as_deref(),
+            credential["verificationMethod"].as_str()
+        );
+        assert_eq!(
+            credential["proof"]["proofValue"].as_str(),
+            token.rsplit('.').next()
+        );
+        assert_eq!(
+            credential["publicKey"]["publicKeyJwk"]["x"],
+            URL_SAFE_NO_PAD.encode(signing_key.verifying_key().to_bytes())
+        );
+    }
+
+    #[test]
+    fn test_check_inputs() {
+        let mut valid = args(None, DidMethod::Web);
+        valid.email = Some("dev@acme.example".to_string());
+        valid.website = Some("https://acme.example".to_string());
+        valid.country = Some("nl".to_string());
+        check_inputs(&mut valid).unwrap();
+        assert_eq!(valid.country.as_deref(), Some("NL"));
+
+        let cases = [
+            ("email", "dev.acme.example", "missing '@'"),
+            ("email", "@acme.example", "nothing before '@'"),
+            ("email", "dev@localhost", "not a valid domain"),
+            ("email", "dev@a@b.example", "more than one '@'"),
+            ("website", "acme.example", "not a valid URL"),
+            (
+                "website",
+                "ftp://acme.example",
+                "scheme must be http or https",
+            ),
+            ("country", "XX", "ISO 3166-1 alpha-2"),
+            ("country", "USA", "ISO 3166-1 alpha-2"),
+        ];
+        for (field, value, expected) in cases {
+            let mut bad = args(None, DidMethod::Web);
+            match field {
+                "email" => bad.email = Some(value.to_string()),
+                "website" => bad.website = Some(value.to_string()),
+                _ => bad.country = Some(value.to_string()),
+            }
+            let err = check_inputs(&mut bad).unwrap_err().to_string();
+            assert!(
+                err.starts_with(&format!("--{} '{}'", field, value)),
+                "{}",
+                err
+            );
+            assert!(err.contains(expected), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_entity_type_details() {
+        let mut llc = args(None, DidMethod::Web);
+        llc.entity_type = Some("LLC".to_string());
+        let err = check_inputs(&mut llc).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "--entity-type limited_liability_company requires --incorporation-date, \
+             --registration-number, --street-address, --city, --postal-code"
+        );
+
+        llc.incorporation_date = NaiveDate::from_ymd_opt(2019, 3, 1);
+        llc.registration_number = Some("12-3456789".to_string());
+        llc.street_address = Some("1 Main St".to_string());
+        llc.city = Some("Springfield".to_string());
+        llc.postal_code = Some("12345".to_string());
+        check_inputs(&mut llc).unwrap();
+        let credential = generate_developer_credential(&llc).unwrap();
+        assert_eq!(credential["entityType"], "limited_liability_company");
+        assert_eq!(
+            credential["businessRegistrationStatus"],
+            "active_good_standing"
+        );
+        assert_eq!(credential["incorporationDate"], "2019-03-01");
+        assert_eq!(
+            credential["businessRegistrationNumber"],
+            format!("hash_{:x}", Sha256::digest(b"12-3456789"))
+        );
+        assert_eq!(credential["registeredAddress"]["country"], "US");
+
+        let mut sole = args(None, DidMethod::Web);
+        sole.entity_type = Some("sole_proprietorship".to_string());
+        check_inputs(&mut sole).unwrap();
+        let credential = generate_developer_credential(&sole).unwrap();
+        assert_eq!(credential["businessRegistrationStatus"], "not_applicable");
+        assert!(credential.get("registeredAddress").is_none());
+        sole.city = Some("Springfield".to_string());
+        let err = check_inputs(&mut sole).unwrap_err().to_string();
+        assert!(
+            err.contains("requires --street-address, --postal-code"),
+            "{}",
+            err
+        );
+
+        let mut individual = args(None, DidMethod::Web);
+        individual.registration_number = Some("12-3456789".to_string());
+        let err = check_inputs(&mut individual).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "--registration-number does not apply to --entity-type individual"
+        );
+
+        let mut unknown = args(None, DidMethod::Web);
+        unknown.entity_type = Some("cooperative".to_string());
+        let err = check_inputs(&mut unknown).unwrap_err().to_string();
+        assert!(
+            err.starts_with("--entity-type 'cooperative': expected one of"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_tokenize_registration_number() {
+        assert_eq!(tokenize_registration_number("tok_abc12345"), "tok_abc12345");
+        let hashed = tokenize_registration_number(" HRB 12345 ");
+        assert_eq!(hashed, format!("hash_{:x}", Sha256::digest(b"HRB 12345")));
+        // Too short to be a token: hashed like any raw number
+        assert!(tokenize_registration_number("tok_1").starts_with("hash_"));
+    }
+}
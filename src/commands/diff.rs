@@ -0,0 +1,284 @@
+//! Compare two agent credentials field by field.
+//!
+//! Usage: beltic diff <a> <b> [--json]
+//!
+//! Inputs may be credential JSON files or signed JWS tokens (the `vc` claim
+//! is compared). Exits with status 1 when the credentials differ.
+
+use std::{path::Path, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+use serde::Serialize;
+use serde_json::Value;
+
+use beltic::manifest::credential::AgentCredential;
+
+use super::credential_id::load_credential_value;
+use super::exit;
+
+/// Fields called out in the text output because they matter most in audits
+const HIGHLIGHTED_PATHS: &[&str] = &[
+    "/agentVersion",
+    "/systemConfigFingerprint",
+    "/toolsList",
+    "/dataCategoriesProcessed",
+];
+
+#[derive(Args)]
+pub struct DiffArgs {
+    /// Original credential (JSON or JWS)
+    pub old: PathBuf,
+
+    /// Updated credential (JSON or JWS)
+    pub new: PathBuf,
+
+    /// Output changes as a JSON array of {path, old, new}
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// A single changed field. `old`/`new` are null when the field was added/removed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    pub path: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+pub fn run(args: DiffArgs) -> Result<()> {
+    let old = load_credential(&args.old)?;
+    let new = load_credential(&args.new)?;
+
+    let changes = diff_values(&old, &new);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&changes)?);
+    } else {
+        print_changes(&args.old, &args.new, &changes);
+    }
+
+    if !changes.is_empty() {
+        return exit::reported(exit::CHECK_FAILED);
+    }
+
+    Ok(())
+}
+
+/// Load a credential from JSON or from the `vc` claim of a JWS token
+fn load_credential(path: &Path) -> Result<Value> {
+    let value = load_credential_value(path)?;
+
+    serde_json::from_value::<AgentCredential>(value.clone())
+        .with_context(|| format!("{} is not a valid agent credential", path.display()))?;
+
+    Ok(value)
+}
+
+/// Compute the field-level changes between two JSON documents.
+///
+/// Objects are compared key by key; arrays of tools are matched by `toolId`;
+/// other arrays are compared element by element when they hold objects and
+/// as whole values otherwise.
+pub fn diff_values(old: &Value, new: &Value) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_at("", old, new, &mut changes);
+    changes
+}
+
+fn diff_at(path: &str, old: &Value, new: &Value, changes: &mut Vec<Change>) {
+    if old == new {
+        return;
+    }
+
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            let mut keys: Vec<&String> = old_map.keys().chain(new_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let child = format!("{}/{}", path, escape_pointer(key));
+                diff_at(
+                    &child,
+                    old_map.get(key).unwrap_or(&Value::Null),
+                    new_map.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items))
+            if is_keyed_by_tool_id(old_items) && is_keyed_by_tool_id(new_items) =>
+        {
+            let mut ids: Vec<&str> = old_items
+                .iter()
+                .chain(new_items.iter())
+                .filter_map(tool_id)
+                .collect();
+            ids.sort_unstable();
+            ids.dedup();
+
+            for id in ids {
+                let find = |items: &[Value]| {
+                    items
+                        .iter()
+                        .find(|item| tool_id(item) == Some(id))
+                        .cloned()
+                        .unwrap_or(Value::Null)
+                };
+                let child = format!("{}/{}", path, escape_pointer(id));
+                diff_at(&child, &find(old_items), &find(new_items), changes);
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items))
+            if old_items
+                .iter()
+                .chain(new_items.iter())
+                .all(Value::is_object) =>
+        {
+            for i in 0..old_items.len().max(new_items.len()) {
+                diff_at(
+                    &format!("{}/{}", path, i),
+                    old_items.get(i).unwrap_or(&Value::Null),
+                    new_items.get(i).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ => changes.push(Change {
+            path: path.to_string(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+    }
+}
+
+fn tool_id(item: &Value) -> Option<&str> {
+    item.get("toolId").and_then(Value::as_str)
+}
+
+fn is_keyed_by_tool_id(items: &[Value]) -> bool {
+    items.iter().all(|item| tool_id(item).is_some())
+}
+
+fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn is_highlighted(path: &str) -> bool {
+    HIGHLIGHTED_PATHS
+        .iter()
+        .any(|prefix| path == *prefix || path.starts_with(&format!("{}/", prefix)))
+        || path.ends_with("Score")
+}
+
+fn print_changes(old_path: &Path, new_path: &Path, changes: &[Change]) {
+    println!(
+        "{} {} → {}",
+        style("Comparing").cyan().bold(),
+        old_path.display(),
+        new_path.display()
+    );
+    println!();
+
+    if changes.is_empty() {
+        println!("{}", style("No differences").green());
+        return;
+    }
+
+    for change in changes {
+        let (marker, detail) = match (&change.old, &change.new) {
+            (Value::Null, new) => (style("+").green(), format!("{}", new)),
+            (old, Value::Null) => (style("-").red(), format!("{}", old)),
+            (old, new) => (style("~").yellow(), format!("{} → {}", old, new)),
+        };
+
+        let path = if is_highlighted(&change.path) {
+            style(change.path.as_str()).yellow().bold()
+        } else {
+            style(change.path.as_str())
+        };
+
+        println!("  {} {}: {}", marker, path, detail);
+    }
+
+    println!();
+    println!(
+        "{} difference{}",
+        changes.len(),
+        if changes.len() == 1 { "" } else { "s" }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_identical_documents_have_no_changes() {
+        let doc = json!({"agentVersion": "1.0.0", "toolsList": []});
+        assert!(diff_values(&doc, &doc).is_empty());
+    }
+
+    #[test]
+    fn test_scalar_and_nested_changes() {
+        let old = json!({
+            "agentVersion": "1.0.0",
+            "dataCategoriesProcessed": ["none"],
+            "profile": {"region": "US"}
+        });
+        let new = json!({
+            "agentVersion": "1.1.0",
+            "dataCategoriesProcessed": ["pii"],
+            "profile": {"region": "US", "zone": "a"}
+        });
+
+        let changes = diff_values(&old, &new);
+        assert_eq!(
+            changes,
+            vec![
+                Change {
+                    path: "/agentVersion".to_string(),
+                    old: json!("1.0.0"),
+                    new: json!("1.1.0"),
+                },
+                Change {
+                    path: "/dataCategoriesProcessed".to_string(),
+                    old: json!(["none"]),
+                    new: json!(["pii"]),
+                },
+                Change {
+                    path: "/profile/zone".to_string(),
+                    old: Value::Null,
+                    new: json!("a"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tools_matched_by_id() {
+        let old = json!({"toolsList": [
+            {"toolId": "search", "requiresAuth": false},
+            {"toolId": "pay", "requiresAuth": true}
+        ]});
+        let new = json!({"toolsList": [
+            {"toolId": "pay", "requiresAuth": true},
+            {"toolId": "search", "requiresAuth": true}
+        ]});
+
+        let changes = diff_values(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "/toolsList/search/requiresAuth");
+    }
+
+    #[test]
+    fn test_highlighted_paths() {
+        assert!(is_highlighted("/systemConfigFingerprint"));
+        assert!(is_highlighted("/toolsList/search/requiresAuth"));
+        assert!(is_highlighted("/harmfulContentRefusalScore"));
+        assert!(!is_highlighted("/agentName"));
+    }
+}
@@ -1,8 +1,12 @@
 //! Key Directory Management for Web Bot Auth
 //!
-//! Generate and serve HTTP Message Signatures key directories.
+//! Generate, build, verify and diff HTTP Message Signatures key directories.
 
-use std::{fs, path::PathBuf, time::SystemTime};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
 /// Directory signature validity duration in seconds.
 /// This value is used for both the signature `expires` parameter and Cache-Control max-age
@@ -10,14 +14,36 @@ use std::{fs, path::PathBuf, time::SystemTime};
 const DIRECTORY_SIGNATURE_LIFETIME_SECS: u64 = 300; // 5 minutes
 
 use anyhow::{bail, Context, Result};
-use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use chrono::{DateTime, SecondsFormat};
 use clap::{Args, Subcommand};
 use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
 use pkcs8::{DecodePrivateKey, DecodePublicKey};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
+use serde_json::Value;
 use zeroize::Zeroizing;
 
+use beltic::crypto::http_signature::{
+    signature_base, ComponentId, HttpRequest, ParamValue, SignatureInput,
+};
+use beltic::crypto::jwk::{ed25519_thumbprint, ed25519_thumbprint_from_x};
+use beltic::schema::is_offline;
+
+use super::exit;
+use super::http_verify::{keys_from_jwk_json, verify_request};
+
+/// Media type of a hosted key directory
+const DIRECTORY_MEDIA_TYPE: &str = "application/http-message-signatures-directory+json";
+
+/// Well-known path the directory is served from
+const DIRECTORY_PATH: &str = "/.well-known/http-message-signatures-directory";
+
+/// `tag` parameter of directory response signatures
+const DIRECTORY_SIGNATURE_TAG: &str = "http-message-signatures-directory";
+
 #[derive(Args)]
 pub struct DirectoryArgs {
     #[command(subcommand)]
@@ -29,6 +55,15 @@ pub enum DirectoryCommand {
     /// Generate a key directory JSON from public keys
     Generate(GenerateArgs),
 
+    /// Build a hostable directory with key ids and validity windows
+    Build(BuildArgs),
+
+    /// Check a directory's shape, key ids and validity windows
+    Verify(VerifyDirectoryArgs),
+
+    /// Compare a published directory with the local keys meant to be in it
+    Diff(DiffDirectoryArgs),
+
     /// Compute the JWK thumbprint for a public key
     Thumbprint(ThumbprintArgs),
 }
@@ -64,6 +99,80 @@ pub struct GenerateArgs {
     pub authority: Option<String>,
 }
 
+#[derive(Args)]
+pub struct BuildArgs {
+    /// Ed25519 key to publish (public or private PEM, repeatable)
+    #[arg(long, required = true)]
+    pub key: Vec<PathBuf>,
+
+    /// Output file for the directory JSON
+    #[arg(long)]
+    pub out: PathBuf,
+
+    /// Key validity in days, from --not-before (sets each key's `exp`)
+    #[arg(long, default_value = "90")]
+    pub valid_days: u64,
+
+    /// Start of the key validity window as a Unix timestamp (default: now)
+    #[arg(long)]
+    pub not_before: Option<u64>,
+
+    /// URL to the agent's credential JWT (optional)
+    #[arg(long)]
+    pub credential_url: Option<String>,
+
+    /// Agent metadata JSON
+    #[arg(long)]
+    pub agent_metadata: Option<String>,
+
+    /// Private key to sign the directory response with (must be one of --key)
+    #[arg(long, requires = "authority")]
+    pub signing_key: Option<PathBuf>,
+
+    /// Authority (host) the directory is served from
+    #[arg(long)]
+    pub authority: Option<String>,
+
+    /// Where to write the signed response headers (default: --out with a .headers extension)
+    #[arg(long)]
+    pub headers_out: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct VerifyDirectoryArgs {
+    /// Directory JSON file
+    pub directory: PathBuf,
+
+    /// Signed response headers to check ("Name: Value" per line)
+    #[arg(long, requires = "authority")]
+    pub headers: Option<PathBuf>,
+
+    /// Authority (host) the directory is served from
+    #[arg(long)]
+    pub authority: Option<String>,
+}
+
+#[derive(Args)]
+pub struct DiffDirectoryArgs {
+    /// Ed25519 key (public or private PEM) or directory of `.pem` keys that
+    /// should be published
+    #[arg(long, value_name = "PATH")]
+    pub local: PathBuf,
+
+    /// URL of the published directory
+    #[arg(long, value_name = "URL", required_unless_present = "snapshot")]
+    pub remote: Option<String>,
+
+    /// Saved copy of the published directory to compare instead of fetching
+    /// --remote (for --offline)
+    #[arg(long, value_name = "FILE")]
+    pub snapshot: Option<PathBuf>,
+
+    /// Report published keys expiring within this many days
+    #[arg(long, value_name = "DAYS", default_value = "14")]
+    pub expiring_within: u64,
+}
+
 #[derive(Args)]
 pub struct ThumbprintArgs {
     /// Path to Ed25519 public key (PEM)
@@ -86,11 +195,20 @@ pub struct JwkKey {
     kty: String,
     crv: String,
     x: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nbf: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exp: Option<u64>,
 }
 
 pub fn run(args: DirectoryArgs) -> Result<()> {
     match args.command {
         DirectoryCommand::Generate(gen_args) => run_generate(gen_args),
+        DirectoryCommand::Build(build_args) => run_build(build_args),
+        DirectoryCommand::Verify(verify_args) => run_verify(verify_args),
+        DirectoryCommand::Diff(diff_args) => run_diff(diff_args),
         DirectoryCommand::Thumbprint(thumb_args) => run_thumbprint(thumb_args),
     }
 }
@@ -124,6 +242,9 @@ fn run_generate(args: GenerateArgs) -> Result<()> {
             kty: "OKP".to_string(),
             crv: "Ed25519".to_string(),
             x,
+            kid: None,
+            nbf: None,
+            exp: None,
         });
     }
 
@@ -156,7 +277,7 @@ fn run_generate(args: GenerateArgs) -> Result<()> {
 
     // Output thumbprints
     for (i, key) in directory.keys.iter().enumerate() {
-        let thumbprint = compute_key_thumbprint(&key.x)?;
+        let thumbprint = ed25519_thumbprint_from_x(&key.x);
         println!("  Key {}: thumbprint = {}", i + 1, thumbprint);
     }
 
@@ -186,48 +307,481 @@ fn run_generate(args: GenerateArgs) -> Result<()> {
         })?);
         let signing_key =
             SigningKey::from_pkcs8_pem(&pem).context("failed to parse Ed25519 private key")?;
-        let verifying_key = signing_key.verifying_key();
-        let thumbprint = compute_jwk_thumbprint(&verifying_key)?;
-
-        // Timestamps
-        let now = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .context("system time error")?
-            .as_secs();
-        let created = now;
-        let expires = now + DIRECTORY_SIGNATURE_LIFETIME_SECS;
-
-        // Generate nonce
-        let mut nonce_bytes = [0u8; 32];
-        getrandom::getrandom(&mut nonce_bytes).context("failed to generate nonce")?;
-        let nonce = URL_SAFE_NO_PAD.encode(nonce_bytes);
-
-        // Build signature params
-        let signature_params = format!(
-            "(\"@authority\");alg=\"ed25519\";keyid=\"{}\";nonce=\"{}\";tag=\"http-message-signatures-directory\";created={};expires={}",
-            thumbprint, nonce, created, expires
+        let (signature_input, signature) = sign_directory_response(&signing_key, authority)?;
+
+        println!("\nSigned response headers:");
+        println!("Content-Type: {}", DIRECTORY_MEDIA_TYPE);
+        println!("Signature: {}", signature);
+        println!("Signature-Input: {}", signature_input);
+        println!(
+            "Cache-Control: max-age={}",
+            DIRECTORY_SIGNATURE_LIFETIME_SECS
         );
+    }
 
-        // Build signature base
-        let signature_base = format!(
-            "\"@authority\": {}\n\"@signature-params\": {}",
-            authority, signature_params
+    Ok(())
+}
+
+fn run_build(args: BuildArgs) -> Result<()> {
+    let not_before = match args.not_before {
+        Some(nbf) => nbf,
+        None => now_secs()?,
+    };
+    let expires = not_before + args.valid_days * 86_400;
+
+    let mut keys = Vec::with_capacity(args.key.len());
+    for key_path in &args.key {
+        let verifying_key = load_verifying_key(key_path)?;
+        keys.push(JwkKey {
+            kty: "OKP".to_string(),
+            crv: "Ed25519".to_string(),
+            x: URL_SAFE_NO_PAD.encode(verifying_key.to_bytes()),
+            kid: Some(ed25519_thumbprint(&verifying_key)),
+            nbf: Some(not_before),
+            exp: Some(expires),
+        });
+    }
+
+    let agent_metadata: Option<Value> = args
+        .agent_metadata
+        .as_ref()
+        .map(|m| serde_json::from_str(m))
+        .transpose()
+        .context("failed to parse --agent-metadata as JSON")?;
+
+    let directory = KeyDirectory {
+        keys,
+        agent_credential_url: args.credential_url.clone(),
+        agent_metadata,
+    };
+
+    let value = serde_json::to_value(&directory)?;
+    let errors = validate_directory(&value, not_before);
+    if !errors.is_empty() {
+        bail!("built directory is invalid:\n  - {}", errors.join("\n  - "));
+    }
+
+    write_output(&args.out, &serde_json::to_string_pretty(&directory)?)?;
+    println!("Wrote key directory to {}", args.out.display());
+    for key in &directory.keys {
+        println!(
+            "  kid {} (nbf {}, exp {})",
+            key.kid.as_deref().unwrap_or_default(),
+            not_before,
+            expires
         );
+    }
 
-        // Sign
-        let signature = signing_key.sign(signature_base.as_bytes());
-        let signature_b64 = URL_SAFE_NO_PAD.encode(signature.to_bytes());
+    if let (Some(signing_key_path), Some(authority)) = (&args.signing_key, &args.authority) {
+        let pem = Zeroizing::new(fs::read_to_string(signing_key_path).with_context(|| {
+            format!("failed to read private key {}", signing_key_path.display())
+        })?);
+        let signing_key =
+            SigningKey::from_pkcs8_pem(&pem).context("failed to parse Ed25519 private key")?;
+        let kid = ed25519_thumbprint(&signing_key.verifying_key());
+        if !directory
+            .keys
+            .iter()
+            .any(|key| key.kid.as_deref() == Some(&kid))
+        {
+            bail!("signing key {} is not one of the published keys", kid);
+        }
 
-        println!("\nSigned response headers:");
-        println!("Content-Type: application/http-message-signatures-directory+json");
-        println!("Signature: sig1=:{}:", signature_b64);
-        println!("Signature-Input: sig1={}", signature_params);
-        println!("Cache-Control: max-age={}", DIRECTORY_SIGNATURE_LIFETIME_SECS);
+        let (signature_input, signature) = sign_directory_response(&signing_key, authority)?;
+        let headers = format!(
+            "Content-Type: {}\nSignature: {}\nSignature-Input: {}\nCache-Control: max-age={}\n",
+            DIRECTORY_MEDIA_TYPE, signature, signature_input, DIRECTORY_SIGNATURE_LIFETIME_SECS
+        );
+        let headers_out = args
+            .headers_out
+            .clone()
+            .unwrap_or_else(|| args.out.with_extension("headers"));
+        write_output(&headers_out, &headers)?;
+        println!("Wrote signed response headers to {}", headers_out.display());
     }
 
     Ok(())
 }
 
+fn run_verify(args: VerifyDirectoryArgs) -> Result<()> {
+    let content = fs::read_to_string(&args.directory)
+        .with_context(|| format!("failed to read {}", args.directory.display()))?;
+    let value: Value = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not valid JSON", args.directory.display()))?;
+
+    let now = now_secs()?;
+    let errors = validate_directory(&value, now);
+    if !errors.is_empty() {
+        println!("INVALID: {}", args.directory.display());
+        for error in &errors {
+            println!("  ✗ {}", error);
+        }
+        return exit::reported(exit::CHECK_FAILED);
+    }
+
+    let keys = keys_from_jwk_json(&value)?;
+    println!(
+        "VALID: {} ({} key{})",
+        args.directory.display(),
+        keys.len(),
+        if keys.len() == 1 { "" } else { "s" }
+    );
+
+    if let (Some(headers_path), Some(authority)) = (&args.headers, &args.authority) {
+        let headers = fs::read_to_string(headers_path)
+            .with_context(|| format!("failed to read {}", headers_path.display()))?;
+        let mut request =
+            HttpRequest::new("GET", &format!("https://{}{}", authority, DIRECTORY_PATH))?;
+        for line in headers.lines().filter(|l| !l.trim().is_empty()) {
+            request.add_header_line(line)?;
+        }
+
+        let report = verify_request(&request, None, &keys, None, now, 30)?;
+        let tagged = request
+            .header("signature-input")
+            .is_some_and(|input| input.contains(&format!("tag=\"{}\"", DIRECTORY_SIGNATURE_TAG)));
+        if !report.is_valid() || !tagged {
+            println!("INVALID: response signature ({})", headers_path.display());
+            if !report.signature_valid {
+                println!("  ✗ signature does not verify for {}", authority);
+            }
+            if !tagged {
+                println!("  ✗ signature is not tagged {}", DIRECTORY_SIGNATURE_TAG);
+            }
+            for issue in &report.issues {
+                println!("  ✗ {}", issue);
+            }
+            return exit::reported(exit::CHECK_FAILED);
+        }
+        println!(
+            "VALID: response signature (keyid={})",
+            report.keyid.as_deref().unwrap_or_default()
+        );
+    }
+
+    Ok(())
+}
+
+fn run_diff(args: DiffDirectoryArgs) -> Result<()> {
+    let local = local_keys(&args.local)?;
+    let (source, directory) = match (&args.snapshot, &args.remote) {
+        (Some(snapshot), _) => {
+            let content = fs::read_to_string(snapshot)
+                .with_context(|| format!("failed to read {}", snapshot.display()))?;
+            let value = serde_json::from_str(&content)
+                .with_context(|| format!("{} is not valid JSON", snapshot.display()))?;
+            (snapshot.display().to_string(), value)
+        }
+        (None, Some(url)) => (url.clone(), fetch_directory(url)?),
+        (None, None) => bail!("pass --remote or --snapshot"),
+    };
+    let remote =
+        published_keys(&directory).with_context(|| format!("{} is not a key directory", source))?;
+
+    let now = now_secs()?;
+    let drift = diff_keys(&local, &remote, now, args.expiring_within * 86_400);
+
+    println!(
+        "Comparing {} local key{} with {} published at {}",
+        local.len(),
+        if local.len() == 1 { "" } else { "s" },
+        remote.len(),
+        source
+    );
+    for key in &drift.only_remote {
+        println!("  + only published: {}", key);
+    }
+    for key in &drift.only_local {
+        println!(
+            "  - only local:     {} ({})",
+            key.thumbprint,
+            key.path.display()
+        );
+    }
+    for (key, exp) in &drift.expiring {
+        let verb = if *exp <= now { "expired" } else { "expires" };
+        println!("  ! {}: {} {}", verb, key, format_timestamp(*exp));
+    }
+
+    if drift.is_empty() {
+        println!("IN SYNC");
+        Ok(())
+    } else {
+        println!("DRIFT: {} issue(s)", drift.len());
+        exit::reported(exit::CHECK_FAILED)
+    }
+}
+
+/// A local key that should be published
+#[derive(Debug, Clone, PartialEq)]
+struct LocalKey {
+    thumbprint: String,
+    path: PathBuf,
+}
+
+/// A key in a published directory
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PublishedKey {
+    pub thumbprint: String,
+    pub nbf: Option<u64>,
+    pub exp: Option<u64>,
+}
+
+/// Differences between published and local keys, by thumbprint
+#[derive(Debug, Default, PartialEq)]
+struct KeyDrift {
+    only_remote: Vec<String>,
+    only_local: Vec<LocalKey>,
+    /// Published keys that expire (or have expired) within the warning window
+    expiring: Vec<(String, u64)>,
+}
+
+impl KeyDrift {
+    fn len(&self) -> usize {
+        self.only_remote.len() + self.only_local.len() + self.expiring.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn diff_keys(local: &[LocalKey], remote: &[PublishedKey], now: u64, warn_within: u64) -> KeyDrift {
+    let mut drift = KeyDrift::default();
+    for key in remote {
+        if !local.iter().any(|l| l.thumbprint == key.thumbprint) {
+            drift.only_remote.push(key.thumbprint.clone());
+        }
+        if let Some(exp) = key
+            .exp
+            .filter(|exp| *exp <= now.saturating_add(warn_within))
+        {
+            drift.expiring.push((key.thumbprint.clone(), exp));
+        }
+    }
+    for key in local {
+        if !remote.iter().any(|r| r.thumbprint == key.thumbprint) {
+            drift.only_local.push(key.clone());
+        }
+    }
+    drift
+}
+
+/// Ed25519 keys at `path`: one PEM file, or every `.pem` in a directory.
+/// Files that are not Ed25519 keys are skipped; a private and public key
+/// of the same pair count once.
+fn local_keys(path: &Path) -> Result<Vec<LocalKey>> {
+    let paths = if path.is_dir() {
+        let mut paths: Vec<PathBuf> = fs::read_dir(path)
+            .with_context(|| format!("failed to read {}", path.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "pem"))
+            .collect();
+        paths.sort();
+        paths
+    } else {
+        return Ok(vec![LocalKey {
+            thumbprint: ed25519_thumbprint(&load_verifying_key(path)?),
+            path: path.to_path_buf(),
+        }]);
+    };
+
+    let mut keys: Vec<LocalKey> = Vec::new();
+    for path in paths {
+        let Ok(verifying_key) = load_verifying_key(&path) else {
+            beltic::estatus!("[info] Skipping {}: not an Ed25519 key", path.display());
+            continue;
+        };
+        let thumbprint = ed25519_thumbprint(&verifying_key);
+        if !keys.iter().any(|key| key.thumbprint == thumbprint) {
+            keys.push(LocalKey { thumbprint, path });
+        }
+    }
+    if keys.is_empty() {
+        bail!("no Ed25519 keys found in {}", path.display());
+    }
+    Ok(keys)
+}
+
+/// The Ed25519 keys of a directory document, identified by the thumbprint
+/// of `x` rather than by `kid`, which may be missing or stale
+pub(crate) fn published_keys(directory: &Value) -> Result<Vec<PublishedKey>> {
+    let keys = directory
+        .get("keys")
+        .and_then(Value::as_array)
+        .context("expected an object with a \"keys\" array")?;
+    Ok(keys
+        .iter()
+        .filter(|key| key.get("crv").and_then(Value::as_str) == Some("Ed25519"))
+        .filter_map(|key| {
+            let x = key.get("x").and_then(Value::as_str)?;
+            Some(PublishedKey {
+                thumbprint: ed25519_thumbprint_from_x(x),
+                nbf: key.get("nbf").and_then(Value::as_u64),
+                exp: key.get("exp").and_then(Value::as_u64),
+            })
+        })
+        .collect())
+}
+
+fn fetch_directory(url: &str) -> Result<Value> {
+    if is_offline() {
+        bail!(
+            "offline mode: cannot fetch {}; pass --snapshot with a saved copy",
+            url
+        );
+    }
+    let client = beltic::http::client()?;
+    let response = beltic::http::send_with_retry(client.get(url))
+        .map_err(|err| beltic::http::request_error(err, "key directory host"))
+        .with_context(|| format!("failed to fetch {}", url))?;
+    if !response.status().is_success() {
+        bail!("failed to fetch {}: HTTP {}", url, response.status());
+    }
+    response
+        .json()
+        .with_context(|| format!("{} did not return JSON", url))
+}
+
+pub(crate) fn format_timestamp(secs: u64) -> String {
+    i64::try_from(secs)
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+        .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_else(|| secs.to_string())
+}
+
+/// Check a directory against the Web Bot Auth shape: a non-empty `keys`
+/// array of Ed25519 JWKs whose `kid` (when present) is the RFC 7638
+/// thumbprint and whose `nbf`/`exp` window is well-formed and current.
+pub fn validate_directory(value: &Value, now: u64) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    let Some(keys) = value.get("keys").and_then(Value::as_array) else {
+        errors.push("directory must be an object with a \"keys\" array".to_string());
+        return errors;
+    };
+    if keys.is_empty() {
+        errors.push("\"keys\" must not be empty".to_string());
+    }
+
+    for (i, key) in keys.iter().enumerate() {
+        let field = |name: &str| key.get(name).and_then(Value::as_str);
+        let label = format!("keys[{}]", i);
+
+        if field("kty") != Some("OKP") || field("crv") != Some("Ed25519") {
+            errors.push(format!("{}: expected kty OKP with crv Ed25519", label));
+            continue;
+        }
+        let Some(x) = field("x") else {
+            errors.push(format!("{}: missing x", label));
+            continue;
+        };
+        if URL_SAFE_NO_PAD.decode(x).map(|b| b.len()) != Ok(32) {
+            errors.push(format!(
+                "{}: x is not a base64url Ed25519 public key",
+                label
+            ));
+            continue;
+        }
+        if let Some(kid) = key.get("kid") {
+            let thumbprint = ed25519_thumbprint_from_x(x);
+            if kid.as_str() != Some(thumbprint.as_str()) {
+                errors.push(format!(
+                    "{}: kid {} does not match thumbprint {}",
+                    label, kid, thumbprint
+                ));
+            }
+        }
+
+        let nbf = key.get("nbf").map(Value::as_u64);
+        let exp = key.get("exp").map(Value::as_u64);
+        if nbf == Some(None) || exp == Some(None) {
+            errors.push(format!("{}: nbf/exp must be Unix timestamps", label));
+            continue;
+        }
+        if let (Some(Some(nbf)), Some(Some(exp))) = (nbf, exp) {
+            if nbf >= exp {
+                errors.push(format!("{}: nbf must be before exp", label));
+            }
+        }
+        if let Some(Some(exp)) = exp {
+            if exp <= now {
+                errors.push(format!("{}: key expired at {}", label, exp));
+            }
+        }
+    }
+
+    errors
+}
+
+/// Sign a directory response over `@authority` per
+/// draft-meunier-http-message-signatures-directory.
+/// Returns the `Signature-Input` and `Signature` header values.
+fn sign_directory_response(signing_key: &SigningKey, authority: &str) -> Result<(String, String)> {
+    let created = now_secs()?;
+    let expires = created + DIRECTORY_SIGNATURE_LIFETIME_SECS;
+
+    let mut nonce_bytes = [0u8; 32];
+    getrandom::getrandom(&mut nonce_bytes).context("failed to generate nonce")?;
+    let nonce = URL_SAFE_NO_PAD.encode(nonce_bytes);
+
+    let request = HttpRequest::new("GET", &format!("https://{}{}", authority, DIRECTORY_PATH))?;
+    let input = SignatureInput {
+        label: "sig1".to_string(),
+        components: vec![ComponentId::new("@authority")],
+        params: vec![
+            ("alg".to_string(), ParamValue::String("ed25519".to_string())),
+            (
+                "keyid".to_string(),
+                ParamValue::String(ed25519_thumbprint(&signing_key.verifying_key())),
+            ),
+            ("nonce".to_string(), ParamValue::String(nonce)),
+            (
+                "tag".to_string(),
+                ParamValue::String(DIRECTORY_SIGNATURE_TAG.to_string()),
+            ),
+            ("created".to_string(), ParamValue::Bare(created.to_string())),
+            ("expires".to_string(), ParamValue::Bare(expires.to_string())),
+        ],
+    };
+
+    let base = signature_base(&request, &input)?;
+    let signature = signing_key.sign(base.as_bytes());
+    Ok((
+        input.header_value(),
+        format!("sig1=:{}:", STANDARD.encode(signature.to_bytes())),
+    ))
+}
+
+/// Load an Ed25519 public key from a public or private PEM
+fn load_verifying_key(path: &Path) -> Result<VerifyingKey> {
+    let pem = Zeroizing::new(
+        fs::read_to_string(path)
+            .with_context(|| format!("failed to read key {}", path.display()))?,
+    );
+    VerifyingKey::from_public_key_pem(&pem)
+        .or_else(|_| SigningKey::from_pkcs8_pem(&pem).map(|key| key.verifying_key()))
+        .map_err(|_| anyhow::anyhow!("failed to parse Ed25519 key from {}", path.display()))
+}
+
+fn write_output(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create directory {}", parent.display()))?;
+        }
+    }
+    fs::write(path, content).with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn now_secs() -> Result<u64> {
+    Ok(SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .context("system time error")?
+        .as_secs())
+}
+
 fn run_thumbprint(args: ThumbprintArgs) -> Result<()> {
     let pem = fs::read_to_string(&args.public_key)
         .with_context(|| format!("failed to read public key {}", args.public_key.display()))?;
@@ -239,29 +793,139 @@ fn run_thumbprint(args: ThumbprintArgs) -> Result<()> {
         )
     })?;
 
-    let thumbprint = compute_jwk_thumbprint(&verifying_key)?;
+    let thumbprint = ed25519_thumbprint(&verifying_key);
 
     println!("{}", thumbprint);
 
     Ok(())
 }
 
-/// Compute JWK thumbprint for an Ed25519 public key per RFC 7638.
-fn compute_jwk_thumbprint(verifying_key: &VerifyingKey) -> Result<String> {
-    let public_bytes = verifying_key.to_bytes();
-    let x = URL_SAFE_NO_PAD.encode(public_bytes);
-    compute_key_thumbprint(&x)
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
 
-/// Compute thumbprint from base64url-encoded x coordinate.
-fn compute_key_thumbprint(x: &str) -> Result<String> {
-    // Canonical JWK (keys in lexicographic order)
-    let canonical = format!(r#"{{"crv":"Ed25519","kty":"OKP","x":"{}"}}"#, x);
+    fn directory_for(signing_key: &SigningKey, nbf: u64, exp: u64) -> Value {
+        let verifying_key = signing_key.verifying_key();
+        json!({
+            "keys": [{
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "x": URL_SAFE_NO_PAD.encode(verifying_key.to_bytes()),
+                "kid": ed25519_thumbprint(&verifying_key),
+                "nbf": nbf,
+                "exp": exp,
+            }]
+        })
+    }
+
+    #[test]
+    fn test_validate_directory_shape() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let directory = directory_for(&signing_key, 100, 200);
+        assert!(validate_directory(&directory, 150).is_empty());
 
-    // SHA-256 hash
-    let mut hasher = Sha256::new();
-    hasher.update(canonical.as_bytes());
-    let hash = hasher.finalize();
+        let errors = validate_directory(&directory, 250);
+        assert_eq!(errors, vec!["keys[0]: key expired at 200"]);
 
-    Ok(URL_SAFE_NO_PAD.encode(hash))
+        let mut wrong_kid = directory.clone();
+        wrong_kid["keys"][0]["kid"] = json!("other");
+        assert!(validate_directory(&wrong_kid, 150)[0].contains("does not match thumbprint"));
+
+        let inverted = directory_for(&signing_key, 200, 100);
+        assert!(validate_directory(&inverted, 50)[0].contains("nbf must be before exp"));
+
+        assert!(!validate_directory(&json!({ "keys": [] }), 0).is_empty());
+        assert!(!validate_directory(&json!({ "keys": [{ "kty": "EC" }] }), 0).is_empty());
+    }
+
+    #[test]
+    fn test_diff_keys() {
+        let key = |seed: u8| SigningKey::from_bytes(&[seed; 32]).verifying_key();
+        let local = |seed: u8| LocalKey {
+            thumbprint: ed25519_thumbprint(&key(seed)),
+            path: PathBuf::from(format!("key-{}.pem", seed)),
+        };
+        let day = 86_400;
+        let directory = json!({
+            "keys": [
+                { "kty": "OKP", "crv": "Ed25519", "x": URL_SAFE_NO_PAD.encode(key(1).to_bytes()), "exp": 100 * day },
+                { "kty": "OKP", "crv": "Ed25519", "x": URL_SAFE_NO_PAD.encode(key(2).to_bytes()), "exp": 12 * day },
+                { "kty": "EC", "crv": "P-256", "x": "ignored", "y": "ignored" },
+            ]
+        });
+        let remote = published_keys(&directory).unwrap();
+        assert_eq!(remote.len(), 2);
+
+        // Key 2 was rotated out locally and key 3 not yet published
+        let drift = diff_keys(&[local(1), local(3)], &remote, 0, 14 * day);
+        assert_eq!(drift.only_remote, vec![ed25519_thumbprint(&key(2))]);
+        assert_eq!(drift.only_local, vec![local(3)]);
+        assert_eq!(
+            drift.expiring,
+            vec![(ed25519_thumbprint(&key(2)), 12 * day)]
+        );
+        assert_eq!(drift.len(), 3);
+
+        // Matching sets with no key near expiry are in sync
+        assert!(diff_keys(&[local(1), local(2)], &remote, 0, 7 * day).is_empty());
+        assert!(published_keys(&json!([])).is_err());
+    }
+
+    #[test]
+    fn test_local_keys_dedupes_key_pairs() {
+        use pkcs8::{EncodePrivateKey, EncodePublicKey};
+
+        let dir = tempfile::tempdir().unwrap();
+        let signing_key = SigningKey::from_bytes(&[9u8; 32]);
+        let private = signing_key
+            .to_pkcs8_pem(pkcs8::LineEnding::LF)
+            .unwrap()
+            .to_string();
+        let public = signing_key
+            .verifying_key()
+            .to_public_key_pem(pkcs8::LineEnding::LF)
+            .unwrap();
+        fs::write(dir.path().join("a-private.pem"), private).unwrap();
+        fs::write(dir.path().join("a-public.pem"), public).unwrap();
+        fs::write(dir.path().join("notes.pem"), "not a key").unwrap();
+        fs::write(dir.path().join("readme.txt"), "ignored").unwrap();
+
+        let keys = local_keys(dir.path()).unwrap();
+        assert_eq!(keys.len(), 1);
+        assert_eq!(
+            keys[0].thumbprint,
+            ed25519_thumbprint(&signing_key.verifying_key())
+        );
+    }
+
+    #[test]
+    fn test_signed_directory_round_trip() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let now = now_secs().unwrap();
+        let directory = directory_for(&signing_key, now, now + 86_400);
+        assert!(validate_directory(&directory, now).is_empty());
+
+        let (signature_input, signature) =
+            sign_directory_response(&signing_key, "agent.example.com").unwrap();
+        assert!(signature_input.contains("tag=\"http-message-signatures-directory\""));
+
+        let keys = keys_from_jwk_json(&directory).unwrap();
+        let mut request = HttpRequest::new(
+            "GET",
+            &format!("https://agent.example.com{}", DIRECTORY_PATH),
+        )
+        .unwrap();
+        request.add_header("Signature-Input", &signature_input);
+        request.add_header("Signature", &signature);
+        let report = verify_request(&request, None, &keys, None, now, 30).unwrap();
+        assert!(report.is_valid(), "{:?}", report.issues);
+
+        let mut other_host =
+            HttpRequest::new("GET", &format!("https://evil.example{}", DIRECTORY_PATH)).unwrap();
+        other_host.add_header("Signature-Input", &signature_input);
+        other_host.add_header("Signature", &signature);
+        let report = verify_request(&other_host, None, &keys, None, now, 30).unwrap();
+        assert!(!report.signature_valid);
+    }
 }
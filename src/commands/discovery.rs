@@ -1,15 +1,35 @@
 //! Auto-discovery utilities for keys, tokens, and credentials
 //!
 //! This module provides functions to discover keys, tokens, and credentials
-//! in standard locations, and manage the .beltic directory.
+//! in standard locations, and manage the .beltic directory. `beltic discover`
+//! lists what they find, in the order commands use it.
 
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+use log::debug;
+use serde::Serialize;
 
+use super::credential_id::{decode_jwt_header, is_jwt};
 use super::prompts::beltic_dir;
+use beltic::credential::{credential_kind_from_typ, detect_credential_kind};
+use beltic::crypto::jwk::{
+    public_jwk_from_pem, public_key_alg, public_pem_from_private_pem, thumbprint_from_public_pem,
+};
+use beltic::crypto::SignatureAlg;
+use beltic::document::read_document;
+use beltic::manifest::convert::DocumentKind;
+
+#[derive(Args)]
+pub struct DiscoverArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
 
 /// Ensure the .beltic directory exists
 pub fn ensure_beltic_dir() -> Result<PathBuf> {
@@ -124,8 +144,10 @@ pub fn find_credentials() -> Vec<PathBuf> {
                 let path = entry.path();
                 if path.is_file() {
                     if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                        // Match patterns like *credential*.json or agent-*.json
-                        if name.ends_with(".json")
+                        // Match patterns like *credential*.json or agent-*.yaml
+                        if (name.ends_with(".json")
+                            || name.ends_with(".yaml")
+                            || name.ends_with(".yml"))
                             && (name.contains("credential")
                                 || name.starts_with("agent-")
                                 || name.starts_with("developer-"))
@@ -148,6 +170,34 @@ pub fn find_credentials() -> Vec<PathBuf> {
     credentials
 }
 
+/// The public keys whose type fits `alg`, in the order given; unreadable
+/// keys are skipped too
+pub fn keys_for_alg(keys: Vec<PathBuf>, alg: SignatureAlg) -> Vec<PathBuf> {
+    keys.into_iter()
+        .filter(|path| {
+            let key_alg = fs::read_to_string(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|pem| public_key_alg(&pem));
+            match key_alg {
+                Ok(key_alg) if key_alg == alg => true,
+                Ok(key_alg) => {
+                    debug!(
+                        "skipping {}: {} key, token needs {}",
+                        path.display(),
+                        key_alg.key_type(),
+                        alg.key_type()
+                    );
+                    false
+                }
+                Err(err) => {
+                    debug!("skipping {}: {:#}", path.display(), err);
+                    false
+                }
+            }
+        })
+        .collect()
+}
+
 /// Add private keys pattern to .gitignore
 /// Adds: .beltic/*-private.pem
 pub fn add_to_gitignore(pattern: &str) -> Result<bool> {
@@ -157,12 +207,10 @@ pub fn add_to_gitignore(pattern: &str) -> Result<bool> {
     if gitignore_path.exists() {
         let file = File::open(&gitignore_path)?;
         let reader = BufReader::new(file);
-        for line in reader.lines() {
-            if let Ok(line) = line {
-                if line.trim() == pattern {
-                    // Pattern already exists
-                    return Ok(false);
-                }
+        for line in reader.lines().map_while(Result::ok) {
+            if line.trim() == pattern {
+                // Pattern already exists
+                return Ok(false);
             }
         }
     }
@@ -191,14 +239,167 @@ pub fn ensure_private_keys_gitignored() -> Result<bool> {
     add_to_gitignore(".beltic/*-private.pem")
 }
 
+/// A discovered file and what it was detected to be
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredFile {
+    pub path: PathBuf,
+    /// Key algorithm, credential type or token type; the read or parse
+    /// error when the file couldn't be recognized
+    pub detected_type: String,
+    /// RFC 7638 thumbprint of the (public) key, so key pairs can be matched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbprint: Option<String>,
+}
+
+/// Everything auto-discovery finds, each list in the order commands use it:
+/// without an explicit path, non-interactive commands pick the first entry
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Discovery {
+    pub public_keys: Vec<DiscoveredFile>,
+    pub private_keys: Vec<DiscoveredFile>,
+    pub tokens: Vec<DiscoveredFile>,
+    pub credentials: Vec<DiscoveredFile>,
+}
+
+impl Discovery {
+    pub fn scan() -> Self {
+        Discovery {
+            public_keys: find_public_keys()
+                .into_iter()
+                .map(|path| describe(path, describe_public_key))
+                .collect(),
+            private_keys: find_private_keys()
+                .into_iter()
+                .map(|path| describe(path, describe_private_key))
+                .collect(),
+            tokens: find_tokens()
+                .into_iter()
+                .map(|path| describe(path, describe_token))
+                .collect(),
+            credentials: find_credentials()
+                .into_iter()
+                .map(|path| describe(path, describe_credential))
+                .collect(),
+        }
+    }
+}
+
+pub fn run(args: DiscoverArgs) -> Result<()> {
+    let discovery = Discovery::scan();
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&discovery)?);
+        return Ok(());
+    }
+
+    let sections = [
+        ("Public keys", "verify", &discovery.public_keys),
+        ("Private keys", "sign", &discovery.private_keys),
+        ("Tokens", "verify", &discovery.tokens),
+        ("Credentials", "sign", &discovery.credentials),
+    ];
+    for (index, (title, command, files)) in sections.into_iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        println!("{} ({})", style(title).bold(), files.len());
+        if files.is_empty() {
+            println!("  none found");
+        }
+        for (position, file) in files.iter().enumerate() {
+            let thumbprint = file
+                .thumbprint
+                .as_deref()
+                .map(|thumbprint| format!(" thumbprint={}", thumbprint))
+                .unwrap_or_default();
+            let used = if position == 0 {
+                format!(" {}", style(format!("<- used by {}", command)).green())
+            } else {
+                String::new()
+            };
+            println!(
+                "  {}. {}  {}{}{}",
+                position + 1,
+                file.path.display(),
+                file.detected_type,
+                thumbprint,
+                used
+            );
+        }
+    }
+    Ok(())
+}
+
+type Detected = (String, Option<String>);
+
+fn describe(path: PathBuf, detect: fn(&Path) -> Result<Detected>) -> DiscoveredFile {
+    let (detected_type, thumbprint) =
+        detect(&path).unwrap_or_else(|err| (format!("unrecognized: {:#}", err), None));
+    DiscoveredFile {
+        path,
+        detected_type,
+        thumbprint,
+    }
+}
+
+fn describe_public_key(path: &Path) -> Result<Detected> {
+    let pem = fs::read_to_string(path)?;
+    let (alg, _) = public_jwk_from_pem(&pem)?;
+    let thumbprint = thumbprint_from_public_pem(&pem, alg)?;
+    Ok((format!("{} public key", alg.jws_name()), Some(thumbprint)))
+}
+
+fn describe_private_key(path: &Path) -> Result<Detected> {
+    let pem = fs::read_to_string(path)?;
+    let (alg, public_pem) = public_pem_from_private_pem(&pem)?;
+    let thumbprint = thumbprint_from_public_pem(&public_pem, alg)?;
+    Ok((format!("{} private key", alg.jws_name()), Some(thumbprint)))
+}
+
+fn describe_token(path: &Path) -> Result<Detected> {
+    let content = fs::read_to_string(path)?;
+    if !is_jwt(&content) {
+        anyhow::bail!("not a compact JWS");
+    }
+    let header = decode_jwt_header(&content)?;
+    let field = |name: &str| header.get(name).and_then(|v| v.as_str());
+    let kind = field("typ")
+        .and_then(credential_kind_from_typ)
+        .map(|kind| kind.display_name())
+        .unwrap_or("JWS");
+    Ok((
+        format!("{} token (alg={})", kind, field("alg").unwrap_or("none")),
+        None,
+    ))
+}
+
+fn describe_credential(path: &Path) -> Result<Detected> {
+    let document = read_document(path, None)?;
+    // Manifests carry agentId and agentName too, so rule them out first
+    let detected = if DocumentKind::detect(&document) == Some(DocumentKind::Manifest) {
+        "AgentManifest"
+    } else if let Some(kind) = detect_credential_kind(&document) {
+        kind.display_name()
+    } else {
+        "unknown document"
+    };
+    Ok((detected.to_string(), None))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::env;
+    use std::sync::Mutex;
     use tempfile::TempDir;
 
+    /// The tests change the working directory, which is process-wide
+    static CWD: Mutex<()> = Mutex::new(());
+
     #[test]
     fn test_ensure_beltic_dir() {
+        let _cwd = CWD.lock().unwrap_or_else(|e| e.into_inner());
         let temp = TempDir::new().unwrap();
         env::set_current_dir(temp.path()).unwrap();
 
@@ -209,6 +410,7 @@ mod tests {
 
     #[test]
     fn test_find_keys_empty() {
+        let _cwd = CWD.lock().unwrap_or_else(|e| e.into_inner());
         let temp = TempDir::new().unwrap();
         env::set_current_dir(temp.path()).unwrap();
 
@@ -221,10 +423,69 @@ mod tests {
 
     #[test]
     fn test_find_tokens_empty() {
+        let _cwd = CWD.lock().unwrap_or_else(|e| e.into_inner());
         let temp = TempDir::new().unwrap();
         env::set_current_dir(temp.path()).unwrap();
 
         let tokens = find_tokens();
         assert!(tokens.is_empty());
     }
+
+    #[test]
+    fn test_discovery_detects_types() {
+        let _cwd = CWD.lock().unwrap_or_else(|e| e.into_inner());
+        let temp = TempDir::new().unwrap();
+        env::set_current_dir(temp.path()).unwrap();
+        let beltic = ensure_beltic_dir().unwrap();
+        fs::write(beltic.join("broken-public.pem"), "not a key").unwrap();
+        fs::write(
+            "agent-credential.json",
+            r#"{"credentialId": "c1", "agentId": "a1", "agentName": "x"}"#,
+        )
+        .unwrap();
+        fs::write("agent-manifest.json", r#"{"manifestSchemaVersion": "2.0"}"#).unwrap();
+        // {"alg":"EdDSA","typ":"application/beltic-agent+jwt"}.{}.sig
+        fs::write(
+            "agent.jwt",
+            "eyJhbGciOiJFZERTQSIsInR5cCI6ImFwcGxpY2F0aW9uL2JlbHRpYy1hZ2VudCtqd3QifQ.e30.c2ln",
+        )
+        .unwrap();
+
+        let discovery = Discovery::scan();
+        assert_eq!(discovery.public_keys.len(), 1);
+        assert!(discovery.public_keys[0]
+            .detected_type
+            .starts_with("unrecognized"));
+        assert!(discovery.private_keys.is_empty());
+        assert_eq!(
+            discovery.tokens[0].detected_type,
+            "AgentCredential token (alg=EdDSA)"
+        );
+        let mut credentials: Vec<&str> = discovery
+            .credentials
+            .iter()
+            .map(|file| file.detected_type.as_str())
+            .collect();
+        credentials.sort();
+        assert_eq!(credentials, ["AgentCredential", "AgentManifest"]);
+    }
+
+    #[test]
+    fn test_discovered_keys_filtered_by_alg() {
+        let dir = tempfile::tempdir().unwrap();
+        let ed = dir.path().join("ed-public.pem");
+        fs::write(
+            &ed,
+            "-----BEGIN PUBLIC KEY-----\n\
+             MCowBQYDK2VwAyEA11qYAYKxCrfVS/7TyWQHOg7hcvPapiMlrwIaaPcHURo=\n\
+             -----END PUBLIC KEY-----\n",
+        )
+        .unwrap();
+        let broken = dir.path().join("broken-public.pem");
+        fs::write(&broken, "not a key").unwrap();
+        let keys = vec![broken, ed.clone()];
+
+        assert_eq!(keys_for_alg(keys.clone(), SignatureAlg::EdDsa), [ed]);
+        assert!(keys_for_alg(keys, SignatureAlg::Es256).is_empty());
+    }
 }
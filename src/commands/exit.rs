@@ -0,0 +1,92 @@
+//! Process exit codes
+//!
+//! Every command reports failure by returning an error; `main` maps it to a
+//! code with [`code_for`]. Commands that print their own verdict (`INVALID`,
+//! `DRIFT`, a diff) return [`reported`] so the message isn't repeated.
+//! `sandbox` is the exception: it passes on the exit status of the agent it
+//! ran.
+
+use std::fmt;
+
+use beltic::error::BelticError;
+
+/// The command succeeded
+pub const OK: u8 = 0;
+/// Any failure without a more specific code
+pub const ERROR: u8 = 1;
+/// Bad arguments or unreadable input (clap's own code for usage errors)
+pub const USAGE: u8 = 2;
+/// The command ran, but what it checked failed: a signature, a validation,
+/// an expiry window, a diff
+pub const CHECK_FAILED: u8 = 3;
+/// A network request failed
+pub const NETWORK: u8 = 4;
+
+/// A failure whose message the command already printed; only its exit code
+/// is left to report
+#[derive(Debug)]
+pub struct Reported(pub u8);
+
+impl fmt::Display for Reported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "exit status {}", self.0)
+    }
+}
+
+impl std::error::Error for Reported {}
+
+/// Exit with `code` after the command has printed why
+pub fn reported(code: u8) -> anyhow::Result<()> {
+    Err(Reported(code).into())
+}
+
+/// Whether `err` was already printed by the command
+pub fn is_reported(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<Reported>())
+}
+
+/// The exit code for a command's error, from the first error in its chain
+/// that has one
+pub fn code_for(err: &anyhow::Error) -> u8 {
+    for cause in err.chain() {
+        if let Some(Reported(code)) = cause.downcast_ref::<Reported>() {
+            return *code;
+        }
+        if let Some(err) = cause.downcast_ref::<BelticError>() {
+            return match err {
+                BelticError::Parse(_) => USAGE,
+                BelticError::Validation(_) | BelticError::Signature(_) => CHECK_FAILED,
+                BelticError::Network(_) => NETWORK,
+                BelticError::Io(_) | BelticError::SchemaCache(_) => ERROR,
+            };
+        }
+        if cause.is::<reqwest::Error>() {
+            return NETWORK;
+        }
+    }
+    ERROR
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_code_for() {
+        assert_eq!(code_for(&anyhow::anyhow!("boom")), ERROR);
+        assert_eq!(code_for(&reported(CHECK_FAILED).unwrap_err()), CHECK_FAILED);
+
+        let err = anyhow::Error::new(BelticError::Signature("bad signature".into()))
+            .context("failed to verify token");
+        assert_eq!(code_for(&err), CHECK_FAILED);
+        assert!(!is_reported(&err));
+
+        let err: anyhow::Error = BelticError::Parse("invalid JSON".into()).into();
+        assert_eq!(code_for(&err), USAGE);
+        let err = Err::<(), _>(BelticError::Network("timed out".into()))
+            .context("failed to fetch directory")
+            .unwrap_err();
+        assert_eq!(code_for(&err), NETWORK);
+    }
+}
@@ -1,7 +1,23 @@
-use anyhow::Result;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
 use clap::Parser;
+use console::style;
 
-use crate::manifest::{update_fingerprint, verify_fingerprint};
+use beltic::document::read_document;
+use beltic::estatus;
+use beltic::manifest::fingerprint::{
+    changed_files_since, fingerprint_files, generated_by, matching_files, path_in_scope,
+    watch_fingerprint, FingerprintOptions, COMBINATION_RECIPE, NORMALIZATION,
+};
+use beltic::manifest::{
+    default_manifest_path, resolve_base_dir, update_fingerprint, verify_fingerprint,
+    FingerprintFlags,
+};
 
 #[derive(Parser, Debug)]
 pub struct FingerprintArgs {
@@ -13,19 +29,281 @@ pub struct FingerprintArgs {
     #[arg(short, long)]
     config: Option<String>,
 
+    /// Agent subdirectory to fingerprint; config discovery stays in the base directory
+    #[arg(long, value_name = "DIR")]
+    agent_path: Option<PathBuf>,
+
+    /// Run from this directory instead of the current one: fingerprinting,
+    /// .beltic.yaml discovery and the default manifest path use it
+    #[arg(long, value_name = "DIR")]
+    base_dir: Option<PathBuf>,
+
     /// Include dependency fingerprints
     #[arg(short, long)]
     deps: bool,
 
     /// Verify fingerprint without updating
-    #[arg(short, long)]
+    #[arg(long)]
     verify: bool,
+
+    /// Reuse cached per-file hashes from .beltic/ for unchanged files
+    #[arg(long)]
+    cache: bool,
+
+    /// Skip binary files (images, compiled assets) when hashing
+    #[arg(long)]
+    exclude_binary: bool,
+
+    /// Read buffer for hashing each file, in bytes (default: 8192)
+    #[arg(long, value_name = "BYTES")]
+    hash_buffer_size: Option<usize>,
+
+    /// Memory-map files of 1 MiB or more instead of reading them (needs the
+    /// `mmap` feature; falls back to reads if mapping fails). The
+    /// fingerprint is unchanged.
+    #[arg(long)]
+    mmap: bool,
+
+    /// Print the files that would be hashed, without hashing them
+    #[arg(long, conflicts_with = "verify")]
+    list_files: bool,
+
+    /// Print only the number and total size of the files that would be hashed
+    #[arg(long, conflicts_with = "verify")]
+    count_only: bool,
+
+    /// Print how file hashes are combined into the fingerprint, then exit
+    #[arg(long, alias = "algorithm-list", exclusive = true)]
+    explain: bool,
+
+    /// Fingerprint only in-scope files that changed since a git ref (the manifest is not updated)
+    #[arg(long, value_name = "REF", conflicts_with_all = ["verify", "list_files", "count_only"])]
+    since: Option<String>,
+
+    /// Recompute the fingerprint whenever in-scope files change and compare it
+    /// with the manifest's (read-only; stop with Ctrl-C)
+    #[arg(long, conflicts_with_all = ["verify", "list_files", "count_only", "since"])]
+    watch: bool,
+
+    /// The global `--config` file, used when `--config` isn't given
+    #[arg(skip)]
+    pub global_config: Option<PathBuf>,
 }
 
+/// How often `--watch` polls the scope, and how long it waits for changes
+/// to settle before fingerprinting
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Set by Ctrl-C to end `--watch`
+static STOP_WATCHING: AtomicBool = AtomicBool::new(false);
+
 pub fn run(args: FingerprintArgs) -> Result<()> {
+    if args.mmap && !cfg!(feature = "mmap") {
+        bail!("--mmap needs beltic built with the `mmap` feature (cargo install beltic --features mmap)");
+    }
+    let flags = flags(&args);
+
+    if args.explain {
+        explain();
+        return Ok(());
+    }
+
+    if args.list_files || args.count_only {
+        return list_files(&args);
+    }
+
+    if let Some(git_ref) = &args.since {
+        return fingerprint_since(&args, git_ref);
+    }
+
+    if args.watch {
+        return watch(&args);
+    }
+
     if args.verify {
-        return verify_fingerprint(args.manifest.as_deref());
+        return verify_fingerprint(args.manifest.as_deref(), flags);
+    }
+
+    update_fingerprint(args.manifest.as_deref(), flags)
+}
+
+fn flags(args: &FingerprintArgs) -> FingerprintFlags {
+    FingerprintFlags {
+        use_cache: args.cache,
+        exclude_binary: args.exclude_binary,
+        config_path: config_path(args),
+        agent_path: args.agent_path.clone(),
+        buffer_size: args.hash_buffer_size,
+        mmap: args.mmap,
+        base_dir: args.base_dir.clone(),
     }
+}
+
+/// The subcommand's `--config`, falling back to the global `--config`
+pub(crate) fn config_path(args: &FingerprintArgs) -> Option<PathBuf> {
+    args.config
+        .as_ref()
+        .map(PathBuf::from)
+        .or_else(|| args.global_config.clone())
+}
+
+/// Print the combination recipe recorded as `normalization` in the metadata
+fn explain() {
+    println!("Generated by: {}", generated_by());
+    println!("Algorithm:    sha256");
+    println!("Scheme:       {}", NORMALIZATION);
+    println!();
+    println!("{}", COMBINATION_RECIPE);
+}
+
+/// Resolve fingerprint options the same way a real run does
+fn resolve_options(args: &FingerprintArgs) -> Result<FingerprintOptions> {
+    flags(args).options(&resolve_base_dir(args.base_dir.as_deref())?)
+}
+
+/// Preview the fingerprint scope with the same options a real run uses
+fn list_files(args: &FingerprintArgs) -> Result<()> {
+    let options = resolve_options(args)?;
+    let base_dir = &options.root_path;
+    let files = matching_files(&options)?;
+
+    let mut total_size = 0u64;
+    for file in &files {
+        total_size += file.metadata().map(|m| m.len()).unwrap_or(0);
+        if !args.count_only {
+            println!("{}", relative_path(file, base_dir));
+        }
+    }
+
+    let summary = format!("{} files, {} bytes", files.len(), total_size);
+    if args.count_only {
+        println!("{}", summary);
+    } else {
+        estatus!("{}", summary);
+    }
+    Ok(())
+}
+
+/// Hash the in-scope files that differ from `git_ref`. The full fingerprint
+/// recorded in the manifest is left untouched.
+fn fingerprint_since(args: &FingerprintArgs, git_ref: &str) -> Result<()> {
+    let options = resolve_options(args)?;
+    let base_dir = &options.root_path;
+    let changed = changed_files_since(base_dir, git_ref)?;
+    let in_scope = matching_files(&options)?;
+
+    let (files, others): (Vec<PathBuf>, Vec<PathBuf>) = in_scope
+        .iter()
+        .cloned()
+        .partition(|file| changed.contains(&relative_path(file, base_dir)));
+    let existing: HashSet<String> = files
+        .iter()
+        .chain(&others)
+        .map(|file| relative_path(file, base_dir))
+        .collect();
+    let mut deleted = Vec::new();
+    for path in &changed {
+        if !existing.contains(path)
+            && !base_dir.join(path).exists()
+            && path_in_scope(&options, path)?
+        {
+            deleted.push(path.clone());
+        }
+    }
+
+    if files.is_empty() && deleted.is_empty() {
+        println!(
+            "No in-scope files changed since {} ({} files in scope)",
+            git_ref,
+            in_scope.len()
+        );
+        return Ok(());
+    }
+
+    for file in &files {
+        println!("  {}", relative_path(file, base_dir));
+    }
+    for path in &deleted {
+        println!("  {} (deleted)", path);
+    }
+    println!(
+        "{} of {} in-scope files changed since {}",
+        files.len() + deleted.len(),
+        in_scope.len(),
+        git_ref
+    );
+
+    let result = fingerprint_files(&options, files)?;
+    println!("Scoped fingerprint: {}", result.hash);
+    Ok(())
+}
+
+/// Reprint the fingerprint as files change, saying whether it still matches
+/// the manifest. The manifest is re-read each time and never written.
+fn watch(args: &FingerprintArgs) -> Result<()> {
+    let options = resolve_options(args)?;
+    let manifest_path = match &args.manifest {
+        Some(path) => PathBuf::from(path),
+        None => default_manifest_path(&resolve_base_dir(args.base_dir.as_deref())?),
+    };
+    install_ctrl_c_handler();
+
+    eprintln!(
+        "Watching {} for changes (Ctrl-C to stop)",
+        options.root_path.display()
+    );
+    watch_fingerprint(
+        &options,
+        WATCH_INTERVAL,
+        WATCH_DEBOUNCE,
+        &STOP_WATCHING,
+        |result| {
+            let stored = read_document(&manifest_path, None)
+                .ok()
+                .and_then(|manifest| {
+                    manifest
+                        .get("systemConfigFingerprint")
+                        .and_then(|f| f.as_str())
+                        .map(str::to_string)
+                });
+            let status = match stored {
+                Some(stored) if stored == result.hash => style("matches manifest").green(),
+                Some(_) => style("differs from manifest").yellow(),
+                None => style("no fingerprint in manifest").dim(),
+            };
+            println!(
+                "[{}] {} ({} files) {}",
+                chrono::Local::now().format("%H:%M:%S"),
+                result.hash,
+                result.file_count,
+                status
+            );
+            Ok(())
+        },
+    )?;
+    eprintln!("Stopped watching");
+    Ok(())
+}
+
+/// Let Ctrl-C end the watch loop instead of killing the process
+#[cfg(target_os = "linux")]
+fn install_ctrl_c_handler() {
+    extern "C" fn on_sigint(_: libc::c_int) {
+        STOP_WATCHING.store(true, Ordering::SeqCst);
+    }
+    // SAFETY: the handler only stores to an atomic, which is async-signal-safe
+    unsafe {
+        let handler: extern "C" fn(libc::c_int) = on_sigint;
+        libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+    }
+}
+
+/// Elsewhere Ctrl-C ends the process; the watch never writes, so that is clean
+#[cfg(not(target_os = "linux"))]
+fn install_ctrl_c_handler() {}
 
-    update_fingerprint(args.manifest.as_deref())
+fn relative_path(file: &Path, base_dir: &Path) -> String {
+    let relative = file.strip_prefix(base_dir).unwrap_or(file);
+    relative.to_string_lossy().replace('\\', "/")
 }
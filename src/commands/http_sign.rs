@@ -2,16 +2,25 @@
 //!
 //! Signs HTTP requests per RFC 9421 for Web Bot Auth compatibility.
 
-use std::{collections::HashMap, fs, path::PathBuf, time::SystemTime};
+use std::{fs, path::PathBuf, time::SystemTime};
 
 use anyhow::{bail, Context, Result};
-use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
 use clap::Args;
 use ed25519_dalek::{Signer, SigningKey};
 use pkcs8::DecodePrivateKey;
-use sha2::{Digest, Sha256};
 use zeroize::Zeroizing;
 
+use beltic::crypto::http_signature::{
+    content_digest, signature_base, ComponentId, HttpRequest, ParamValue, SignatureInput,
+};
+use beltic::crypto::jwk::ed25519_thumbprint;
+
+use super::directory::{format_timestamp, published_keys, PublishedKey};
+
 #[derive(Args)]
 pub struct HttpSignArgs {
     /// HTTP method (GET, POST, etc.)
@@ -22,9 +31,19 @@ pub struct HttpSignArgs {
     #[arg(long)]
     pub url: String,
 
-    /// Path to the private key (PEM, Ed25519 only)
-    #[arg(long)]
-    pub key: PathBuf,
+    /// Path to the private key (PEM, Ed25519 only). Repeat to let the
+    /// freshest key that --directory-file lists as valid be chosen.
+    #[arg(long, required = true)]
+    pub key: Vec<PathBuf>,
+
+    /// Local copy of the key directory (e.g. from `directory build`); keys
+    /// outside their nbf/exp window there are not used
+    #[arg(long, value_name = "FILE")]
+    pub directory_file: Option<PathBuf>,
+
+    /// Sign with an expired key when no valid one is available
+    #[arg(long, requires = "directory_file")]
+    pub allow_expired: bool,
 
     /// URL to the agent's key directory
     #[arg(long)]
@@ -34,8 +53,10 @@ pub struct HttpSignArgs {
     #[arg(long)]
     pub header: Vec<String>,
 
-    /// Components to sign (default: @authority, signature-agent)
-    #[arg(long)]
+    /// Components to sign, repeatable or comma-separated, e.g.
+    /// `--cover @authority,@query-param;name=token`
+    /// (default: @method, @authority, @path, signature-agent)
+    #[arg(long, visible_alias = "cover", value_delimiter = ',')]
     pub component: Vec<String>,
 
     /// Request body (for Content-Digest)
@@ -46,9 +67,13 @@ pub struct HttpSignArgs {
     #[arg(long)]
     pub body_file: Option<PathBuf>,
 
-    /// Signature validity in seconds (default: 60)
-    #[arg(long, default_value = "60")]
-    pub expires_in: u64,
+    /// Signature creation time as a Unix timestamp, or `now`
+    #[arg(long, default_value = "now", value_parser = parse_created)]
+    pub created: u64,
+
+    /// Add an `expires` parameter this many seconds after `created`
+    #[arg(long)]
+    pub expires_in: Option<u64>,
 
     /// Output format: headers (default) or curl
     #[arg(long, default_value = "headers")]
@@ -93,42 +118,50 @@ pub fn run(args: HttpSignArgs) -> Result<()> {
         );
     }
 
-    // Load private key
-    let pem = Zeroizing::new(
-        fs::read_to_string(&args.key)
-            .with_context(|| format!("failed to read key file {}", args.key.display()))?,
-    );
-    let signing_key =
-        SigningKey::from_pkcs8_pem(&pem).context("failed to parse Ed25519 private key")?;
-
-    // Compute JWK thumbprint
-    let verifying_key = signing_key.verifying_key();
-    let thumbprint = compute_jwk_thumbprint(&verifying_key)?;
-
-    // Parse URL
-    let parsed_url = url::Url::parse(&args.url).context("invalid URL")?;
-    let authority = parsed_url
-        .host_str()
-        .ok_or_else(|| anyhow::anyhow!("URL must have a host"))?;
-    let authority = if let Some(port) = parsed_url.port() {
-        format!("{}:{}", authority, port)
-    } else {
-        authority.to_string()
+    // Load private keys and pick the one to sign with
+    let mut signing_keys = Vec::with_capacity(args.key.len());
+    for path in &args.key {
+        let pem = Zeroizing::new(
+            fs::read_to_string(path)
+                .with_context(|| format!("failed to read key file {}", path.display()))?,
+        );
+        let signing_key = SigningKey::from_pkcs8_pem(&pem)
+            .with_context(|| format!("failed to parse Ed25519 private key {}", path.display()))?;
+        signing_keys.push(signing_key);
+    }
+    let thumbprints: Vec<String> = signing_keys
+        .iter()
+        .map(|key| ed25519_thumbprint(&key.verifying_key()))
+        .collect();
+    let directory = match &args.directory_file {
+        Some(path) => {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let value = serde_json::from_str(&content)
+                .with_context(|| format!("{} is not valid JSON", path.display()))?;
+            Some(
+                published_keys(&value)
+                    .with_context(|| format!("{} is not a key directory", path.display()))?,
+            )
+        }
+        None => None,
     };
-    let path = parsed_url.path();
-    let query = parsed_url
-        .query()
-        .map(|q| format!("?{}", q))
-        .unwrap_or_default();
-
-    // Parse additional headers
-    let mut headers: HashMap<String, String> = HashMap::new();
+    let selected = select_key(
+        &thumbprints,
+        directory.as_deref(),
+        args.created,
+        args.allow_expired,
+    )?;
+    let signing_key = &signing_keys[selected.index];
+    let thumbprint = &thumbprints[selected.index];
+    if let Some(warning) = &selected.warning {
+        eprintln!("Warning: {}", warning);
+    }
+
+    // Request parts
+    let mut request = HttpRequest::new(&args.method, &args.url)?;
     for h in &args.header {
-        let parts: Vec<&str> = h.splitn(2, ':').collect();
-        if parts.len() != 2 {
-            bail!("invalid header format '{}': use 'Name: Value'", h);
-        }
-        headers.insert(parts[0].trim().to_lowercase(), parts[1].trim().to_string());
+        request.add_header_line(h)?;
     }
 
     // Handle body and Content-Digest
@@ -142,117 +175,74 @@ pub fn run(args: HttpSignArgs) -> Result<()> {
     };
 
     if let Some(ref body_content) = body {
-        let digest = compute_content_digest(body_content.as_bytes());
-        headers.insert("content-digest".to_string(), digest);
+        let digest = content_digest(body_content.as_bytes());
+        request.headers.insert("content-digest".to_string(), digest);
     }
 
+    // Signature-Agent is a Dictionary Structured Header per draft-meunier-http-message-signatures-directory-04
+    let signature_agent_header = format!("{}=\"{}\"", args.signature_agent_key, args.key_directory);
+    request.headers.insert(
+        "signature-agent".to_string(),
+        signature_agent_header.clone(),
+    );
+
     // Build the signature-agent component identifier with ;key parameter per RFC 9421 Section 2.1.2
     // For Dictionary Structured Headers, the component must specify which member to sign
-    let signature_agent_component = format!("signature-agent;key=\"{}\"", args.signature_agent_key);
+    let signature_agent_component = ComponentId {
+        name: "signature-agent".to_string(),
+        params: vec![(
+            "key".to_string(),
+            ParamValue::String(args.signature_agent_key.clone()),
+        )],
+    };
 
     // Determine components to sign
-    let mut components: Vec<String> = if args.component.is_empty() {
+    let mut components: Vec<ComponentId> = if args.component.is_empty() {
         vec![
-            "@method".to_string(),
-            "@authority".to_string(),
-            "@path".to_string(),
+            ComponentId::new("@method"),
+            ComponentId::new("@authority"),
+            ComponentId::new("@path"),
             signature_agent_component.clone(),
         ]
     } else {
-        args.component.clone()
+        args.component
+            .iter()
+            .map(|c| ComponentId::parse(c))
+            .collect::<Result<_>>()?
     };
 
     // Ensure required components
-    if !components.contains(&"@authority".to_string()) {
-        components.insert(0, "@authority".to_string());
+    if !components.iter().any(|c| c.name == "@authority") {
+        components.insert(0, ComponentId::new("@authority"));
     }
     // Check for signature-agent component (with or without key parameter)
-    let has_signature_agent = components
-        .iter()
-        .any(|c| c.starts_with("signature-agent"));
-    if !has_signature_agent {
-        components.push(signature_agent_component.clone());
+    if !components.iter().any(|c| c.name == "signature-agent") {
+        components.push(signature_agent_component);
     }
-    if body.is_some() && !components.contains(&"content-digest".to_string()) {
-        components.push("content-digest".to_string());
+    if body.is_some() && !components.iter().any(|c| c.name == "content-digest") {
+        components.push(ComponentId::new("content-digest"));
     }
 
     // Timestamps
-    let now = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .context("system time error")?
-        .as_secs();
-    let created = now;
-    let expires = now + args.expires_in;
+    let created = args.created;
+    let expires = args.expires_in.map(|secs| created + secs);
 
     // Generate nonce
     let mut nonce_bytes = [0u8; 32];
     getrandom::getrandom(&mut nonce_bytes).context("failed to generate nonce")?;
     let nonce = URL_SAFE_NO_PAD.encode(nonce_bytes);
 
-    // Build signature params
-    let component_list = components
-        .iter()
-        .map(|c| format!("\"{}\"", c))
-        .collect::<Vec<_>>()
-        .join(" ");
-    let signature_params = format!(
-        "({});alg=\"ed25519\";keyid=\"{}\";created={};expires={};nonce=\"{}\";tag=\"web-bot-auth\"",
-        component_list, thumbprint, created, expires, nonce
-    );
-
-    // Build signature base
-    let mut signature_base_lines: Vec<String> = Vec::new();
-    for component in &components {
-        // Extract the base component name (before any parameters like ;key="...")
-        let component_base = component.split(';').next().unwrap_or(component);
-
-        let value = match component_base {
-            "@method" => args.method.to_uppercase(),
-            "@authority" => authority.clone(),
-            "@scheme" => parsed_url.scheme().to_string(),
-            "@path" => path.to_string(),
-            "@query" => {
-                if query.is_empty() {
-                    "?".to_string()
-                } else {
-                    query.clone()
-                }
-            }
-            "@target-uri" => args.url.clone(),
-            "@request-target" => format!("{} {}{}", args.method.to_lowercase(), path, query),
-            "signature-agent" => {
-                // For dictionary members, the value is the String Item (quoted URI)
-                format!("\"{}\"", args.key_directory)
-            }
-            _ => {
-                // It's a header - use base name for lookup
-                headers.get(component_base).cloned().ok_or_else(|| {
-                    anyhow::anyhow!("component '{}' not found in headers", component_base)
-                })?
-            }
-        };
-        signature_base_lines.push(format!("\"{}\": {}", component, value));
-    }
-    signature_base_lines.push(format!("\"@signature-params\": {}", signature_params));
-    let signature_base = signature_base_lines.join("\n");
-
-    // Sign
-    let signature = signing_key.sign(signature_base.as_bytes());
-    let signature_b64 = URL_SAFE_NO_PAD.encode(signature.to_bytes());
-
-    // Output
-    // Signature-Agent is a Dictionary Structured Header per draft-meunier-http-message-signatures-directory-04
-    let signature_agent_header = format!("{}=\"{}\"", args.signature_agent_key, args.key_directory);
-    let signature_input_header = format!("sig1={}", signature_params);
-    let signature_header = format!("sig1=:{}:", signature_b64);
+    // Build signature params and sign
+    let input = signature_input(components, thumbprint, created, expires, nonce);
+    let signature_header = sign_request(&request, &input, signing_key)?;
+    let signature_input_header = input.header_value();
 
     match args.format {
         OutputFormat::Headers => {
             println!("Signature-Agent: {}", signature_agent_header);
             println!("Signature-Input: {}", signature_input_header);
             println!("Signature: {}", signature_header);
-            if let Some(digest) = headers.get("content-digest") {
+            if let Some(digest) = request.headers.get("content-digest") {
                 println!("Content-Digest: {}", digest);
             }
         }
@@ -265,11 +255,11 @@ pub fn run(args: HttpSignArgs) -> Result<()> {
                 signature_input_header,
                 signature_header
             );
-            if let Some(digest) = headers.get("content-digest") {
+            if let Some(digest) = request.headers.get("content-digest") {
                 curl_cmd.push_str(&format!(" \\\n  -H 'Content-Digest: {}'", digest));
             }
-            for (name, value) in &headers {
-                if name != "content-digest" {
+            for (name, value) in &request.headers {
+                if name != "content-digest" && name != "signature-agent" {
                     curl_cmd.push_str(&format!(" \\\n  -H '{}: {}'", name, value));
                 }
             }
@@ -283,36 +273,321 @@ pub fn run(args: HttpSignArgs) -> Result<()> {
         }
     }
 
-    eprintln!("\nKey ID (JWK thumbprint): {}", thumbprint);
     eprintln!(
-        "Signature valid for {} seconds (expires at {})",
-        args.expires_in, expires
+        "\nKey ID (JWK thumbprint): {} ({})",
+        thumbprint,
+        args.key[selected.index].display()
     );
+    if let Some(status) = &selected.status {
+        eprintln!("Key directory: {}", status);
+    }
+    match expires {
+        Some(expires) => eprintln!("Signature created at {}, expires at {}", created, expires),
+        None => eprintln!("Signature created at {} (no expires parameter)", created),
+    }
 
     Ok(())
 }
 
-/// Compute JWK thumbprint for an Ed25519 public key per RFC 7638.
-fn compute_jwk_thumbprint(verifying_key: &ed25519_dalek::VerifyingKey) -> Result<String> {
-    // Get raw public key bytes (32 bytes)
-    let public_bytes = verifying_key.to_bytes();
-    let x = URL_SAFE_NO_PAD.encode(public_bytes);
+/// Where a signing key stands in the key directory at signing time
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum KeyStatus {
+    /// Within its window, which ends at `exp` if set
+    Valid {
+        exp: Option<u64>,
+    },
+    NotYetValid {
+        nbf: u64,
+    },
+    Expired {
+        exp: u64,
+    },
+    /// Not in the directory, so verifiers can't look it up
+    Unlisted,
+}
+
+impl KeyStatus {
+    fn of(thumbprint: &str, directory: &[PublishedKey], at: u64) -> Self {
+        let Some(entry) = directory.iter().find(|key| key.thumbprint == thumbprint) else {
+            return KeyStatus::Unlisted;
+        };
+        match (entry.nbf, entry.exp) {
+            (Some(nbf), _) if nbf > at => KeyStatus::NotYetValid { nbf },
+            (_, Some(exp)) if exp <= at => KeyStatus::Expired { exp },
+            (_, exp) => KeyStatus::Valid { exp },
+        }
+    }
+}
+
+impl std::fmt::Display for KeyStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyStatus::Valid { exp: Some(exp) } => {
+                write!(f, "valid until {}", format_timestamp(*exp))
+            }
+            KeyStatus::Valid { exp: None } => f.write_str("valid, no expiry"),
+            KeyStatus::NotYetValid { nbf } => {
+                write!(f, "not valid until {}", format_timestamp(*nbf))
+            }
+            KeyStatus::Expired { exp } => write!(f, "expired at {}", format_timestamp(*exp)),
+            KeyStatus::Unlisted => f.write_str("not in the key directory"),
+        }
+    }
+}
+
+/// The key [`select_key`] chose
+#[derive(Debug, PartialEq)]
+struct SelectedKey {
+    index: usize,
+    /// The key's directory status, when a directory was given
+    status: Option<KeyStatus>,
+    /// Set when signing with an expired key under `--allow-expired`
+    warning: Option<String>,
+}
+
+/// Pick the key to sign with at time `at`. With a directory, that is the
+/// valid key that stays valid longest (the first on a tie); under
+/// `allow_expired`, the most recently expired key when none is valid.
+/// Without one, there must be a single key.
+fn select_key(
+    thumbprints: &[String],
+    directory: Option<&[PublishedKey]>,
+    at: u64,
+    allow_expired: bool,
+) -> Result<SelectedKey> {
+    let Some(directory) = directory else {
+        if thumbprints.len() > 1 {
+            bail!("several --key given; pass --directory-file so the freshest valid one can be chosen");
+        }
+        return Ok(SelectedKey {
+            index: 0,
+            status: None,
+            warning: None,
+        });
+    };
+
+    let statuses: Vec<KeyStatus> = thumbprints
+        .iter()
+        .map(|thumbprint| KeyStatus::of(thumbprint, directory, at))
+        .collect();
+    let latest = |rank: &dyn Fn(&KeyStatus) -> Option<u64>| {
+        statuses
+            .iter()
+            .enumerate()
+            .filter_map(|(index, status)| rank(status).map(|end| (index, end)))
+            .fold(
+                None,
+                |best: Option<(usize, u64)>, (index, end)| match best {
+                    Some((_, best_end)) if best_end >= end => best,
+                    _ => Some((index, end)),
+                },
+            )
+            .map(|(index, _)| index)
+    };
+
+    if let Some(index) = latest(&|status| match status {
+        KeyStatus::Valid { exp } => Some(exp.unwrap_or(u64::MAX)),
+        _ => None,
+    }) {
+        return Ok(SelectedKey {
+            index,
+            status: Some(statuses[index]),
+            warning: None,
+        });
+    }
+    if allow_expired {
+        if let Some(index) = latest(&|status| match status {
+            KeyStatus::Expired { exp } => Some(*exp),
+            _ => None,
+        }) {
+            return Ok(SelectedKey {
+                index,
+                status: Some(statuses[index]),
+                warning: Some(format!(
+                    "signing with key {}, which {}; verifiers will reject the signature",
+                    thumbprints[index], statuses[index]
+                )),
+            });
+        }
+    }
+
+    let mut message = String::from("no valid signing key in the key directory:");
+    for (thumbprint, status) in thumbprints.iter().zip(&statuses) {
+        message.push_str(&format!("\n  - {}: {}", thumbprint, status));
+    }
+    if !allow_expired
+        && statuses
+            .iter()
+            .any(|s| matches!(s, KeyStatus::Expired { .. }))
+    {
+        message.push_str("\npass --allow-expired to sign with an expired key anyway");
+    }
+    bail!(message)
+}
 
-    // Canonical JWK (keys in lexicographic order)
-    let canonical = format!(r#"{{"crv":"Ed25519","kty":"OKP","x":"{}"}}"#, x);
+/// Web Bot Auth signature parameters. `expires` is only included when set.
+pub fn signature_input(
+    components: Vec<ComponentId>,
+    keyid: &str,
+    created: u64,
+    expires: Option<u64>,
+    nonce: String,
+) -> SignatureInput {
+    let mut params = vec![
+        ("alg".to_string(), ParamValue::String("ed25519".to_string())),
+        ("keyid".to_string(), ParamValue::String(keyid.to_string())),
+        ("created".to_string(), ParamValue::Bare(created.to_string())),
+    ];
+    if let Some(expires) = expires {
+        params.push(("expires".to_string(), ParamValue::Bare(expires.to_string())));
+    }
+    params.push(("nonce".to_string(), ParamValue::String(nonce)));
+    params.push((
+        "tag".to_string(),
+        ParamValue::String("web-bot-auth".to_string()),
+    ));
+
+    SignatureInput {
+        label: "sig1".to_string(),
+        components,
+        params,
+    }
+}
 
-    // SHA-256 hash
-    let mut hasher = Sha256::new();
-    hasher.update(canonical.as_bytes());
-    let hash = hasher.finalize();
+/// Sign the request's signature base, returning the `Signature` header value
+pub fn sign_request(
+    request: &HttpRequest,
+    input: &SignatureInput,
+    signing_key: &SigningKey,
+) -> Result<String> {
+    let base = signature_base(request, input)?;
+    let signature = signing_key.sign(base.as_bytes());
+    Ok(format!(
+        "{}=:{}:",
+        input.label,
+        STANDARD.encode(signature.to_bytes())
+    ))
+}
 
-    Ok(URL_SAFE_NO_PAD.encode(hash))
+/// Parse `--created`: a Unix timestamp or `now`
+fn parse_created(s: &str) -> Result<u64, String> {
+    if s.eq_ignore_ascii_case("now") {
+        return SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .map_err(|e| format!("system time error: {}", e));
+    }
+    s.parse()
+        .map_err(|_| format!("invalid timestamp '{}': use a Unix time or 'now'", s))
 }
 
-/// Compute Content-Digest header value.
-fn compute_content_digest(body: &[u8]) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(body);
-    let hash = hasher.finalize();
-    format!("sha-256=:{}:", URL_SAFE_NO_PAD.encode(hash))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::http_verify::verify_request;
+
+    const CREATED: u64 = 1_700_000_000;
+
+    fn signed(expires: Option<u64>) -> (HttpRequest, SigningKey) {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let mut request = HttpRequest::new("GET", "https://example.com/orders").unwrap();
+        let input = signature_input(
+            vec![ComponentId::new("@method"), ComponentId::new("@authority")],
+            &ed25519_thumbprint(&signing_key.verifying_key()),
+            CREATED,
+            expires,
+            "nonce".to_string(),
+        );
+        let signature = sign_request(&request, &input, &signing_key).unwrap();
+        request.add_header("Signature-Input", &input.header_value());
+        request.add_header("Signature", &signature);
+        (request, signing_key)
+    }
+
+    #[test]
+    fn test_expires_only_when_requested() {
+        let (request, _) = signed(None);
+        let input = request.header("signature-input").unwrap();
+        assert!(input.contains("created=1700000000"));
+        assert!(!input.contains("expires="));
+
+        let (request, _) = signed(Some(CREATED + 30));
+        assert!(request
+            .header("signature-input")
+            .unwrap()
+            .contains("expires=1700000030"));
+    }
+
+    #[test]
+    fn test_expired_signature_rejected_by_verify() {
+        let (request, signing_key) = signed(Some(CREATED + 30));
+        let keys = [signing_key.verifying_key()];
+
+        let fresh = verify_request(&request, None, &keys, None, CREATED + 10, 0).unwrap();
+        assert!(fresh.is_valid(), "{:?}", fresh.issues);
+
+        let expired = verify_request(&request, None, &keys, None, CREATED + 31, 0).unwrap();
+        assert!(expired.signature_valid);
+        assert!(!expired.is_valid());
+    }
+
+    #[test]
+    fn test_select_key_prefers_freshest_valid_key() {
+        let published = |thumbprint: &str, nbf: u64, exp: u64| PublishedKey {
+            thumbprint: thumbprint.to_string(),
+            nbf: Some(nbf),
+            exp: Some(exp),
+        };
+        let directory = [
+            published("old", 0, 100),
+            published("current", 50, 300),
+            published("newer", 50, 400),
+            published("future", 500, 900),
+        ];
+        let keys = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+        let selected = select_key(
+            &keys(&["old", "current", "newer"]),
+            Some(&directory),
+            200,
+            false,
+        )
+        .unwrap();
+        assert_eq!(selected.index, 2);
+        assert_eq!(selected.status, Some(KeyStatus::Valid { exp: Some(400) }));
+        assert_eq!(selected.warning, None);
+
+        // Expired, not yet valid and unlisted keys are refused
+        let err = select_key(
+            &keys(&["old", "future", "unknown"]),
+            Some(&directory),
+            200,
+            false,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("old: expired at"), "{}", err);
+        assert!(err.contains("future: not valid until"), "{}", err);
+        assert!(err.contains("unknown: not in the key directory"), "{}", err);
+        assert!(err.contains("--allow-expired"), "{}", err);
+
+        // ...unless expired keys are explicitly allowed
+        let selected = select_key(&keys(&["future", "old"]), Some(&directory), 200, true).unwrap();
+        assert_eq!(selected.index, 1);
+        assert!(selected.warning.unwrap().contains("expired at"));
+
+        // Without a directory only a single key is accepted
+        assert_eq!(
+            select_key(&keys(&["a"]), None, 200, false).unwrap().index,
+            0
+        );
+        assert!(select_key(&keys(&["a", "b"]), None, 200, false).is_err());
+    }
+
+    #[test]
+    fn test_parse_created() {
+        assert_eq!(parse_created("1700000000"), Ok(1_700_000_000));
+        assert!(parse_created("now").unwrap() > CREATED);
+        assert!(parse_created("yesterday").is_err());
+    }
 }
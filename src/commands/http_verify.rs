@@ -0,0 +1,432 @@
+//! HTTP Request Signature Verification (Web Bot Auth)
+//!
+//! Verifies a signed HTTP request per RFC 9421, for testing the receiving
+//! side locally. The signature base is rebuilt with the same component
+//! canonicalization `http-sign` uses.
+
+use std::{fs, path::PathBuf, time::SystemTime};
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use clap::Args;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde_json::Value;
+
+use beltic::crypto::http_signature::{
+    parse_signature, parse_signature_input, signature_base, verify_content_digest, HttpRequest,
+};
+use beltic::crypto::jwk::ed25519_thumbprint;
+use beltic::estatus;
+use beltic::http::{self, request_error, send_with_retry};
+
+use super::exit;
+
+#[derive(Args)]
+pub struct HttpVerifyArgs {
+    /// HTTP method (GET, POST, etc.)
+    #[arg(long)]
+    pub method: String,
+
+    /// Target URL
+    #[arg(long)]
+    pub url: String,
+
+    /// Request header, including Signature and Signature-Input (format: "Name: Value")
+    #[arg(long)]
+    pub header: Vec<String>,
+
+    /// File with one "Name: Value" header per line (e.g. saved http-sign output)
+    #[arg(long)]
+    pub headers_file: Option<PathBuf>,
+
+    /// Key directory URL (default: the Signature-Agent header)
+    #[arg(long)]
+    pub key_directory: Option<String>,
+
+    /// Local JWK or key directory JSON file
+    #[arg(long, conflicts_with = "key_directory")]
+    pub jwk: Option<PathBuf>,
+
+    /// Request body (checked against Content-Digest)
+    #[arg(long)]
+    pub body: Option<String>,
+
+    /// Path to request body file
+    #[arg(long)]
+    pub body_file: Option<PathBuf>,
+
+    /// Signature label to verify (default: the first in Signature-Input)
+    #[arg(long)]
+    pub label: Option<String>,
+
+    /// Allowed clock skew in seconds for created/expires
+    #[arg(long, default_value = "30")]
+    pub clock_skew: u64,
+}
+
+/// Result of checking one signature
+#[derive(Debug)]
+pub struct VerificationReport {
+    pub label: String,
+    pub keyid: Option<String>,
+    /// (serialized component identifier, canonical value)
+    pub covered: Vec<(String, String)>,
+    pub created: Option<u64>,
+    pub expires: Option<u64>,
+    pub signature_valid: bool,
+    /// Timestamp and digest problems; the signature is only accepted when empty
+    pub issues: Vec<String>,
+}
+
+impl VerificationReport {
+    pub fn is_valid(&self) -> bool {
+        self.signature_valid && self.issues.is_empty()
+    }
+}
+
+pub fn run(args: HttpVerifyArgs) -> Result<()> {
+    let mut request = HttpRequest::new(&args.method, &args.url)?;
+    if let Some(path) = &args.headers_file {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read headers file {}", path.display()))?;
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            request.add_header_line(line)?;
+        }
+    }
+    for h in &args.header {
+        request.add_header_line(h)?;
+    }
+
+    let keys = match (&args.jwk, &args.key_directory) {
+        (Some(path), _) => {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("failed to read JWK file {}", path.display()))?;
+            let value: Value = serde_json::from_str(&content)
+                .with_context(|| format!("{} is not valid JSON", path.display()))?;
+            keys_from_jwk_json(&value)?
+        }
+        (None, Some(url)) => fetch_key_directory(url)?,
+        (None, None) => {
+            let url = signature_agent_url(&request).ok_or_else(|| {
+                anyhow!("no key source: pass --jwk or --key-directory, or include a Signature-Agent header")
+            })?;
+            estatus!("[info] Using key directory from Signature-Agent: {}", url);
+            fetch_key_directory(&url)?
+        }
+    };
+
+    let body = if let Some(body_path) = &args.body_file {
+        Some(
+            fs::read(body_path)
+                .with_context(|| format!("failed to read body file {}", body_path.display()))?,
+        )
+    } else {
+        args.body.as_ref().map(|b| b.as_bytes().to_vec())
+    };
+
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .context("system time error")?
+        .as_secs();
+
+    let report = verify_request(
+        &request,
+        args.label.as_deref(),
+        &keys,
+        body.as_deref(),
+        now,
+        args.clock_skew,
+    )?;
+    print_report(&report, now);
+
+    if !report.is_valid() {
+        return exit::reported(exit::CHECK_FAILED);
+    }
+    Ok(())
+}
+
+/// Verify the signature `label` (or the first one) on a request
+pub fn verify_request(
+    request: &HttpRequest,
+    label: Option<&str>,
+    keys: &[VerifyingKey],
+    body: Option<&[u8]>,
+    now: u64,
+    clock_skew: u64,
+) -> Result<VerificationReport> {
+    let input_header = request
+        .header("signature-input")
+        .ok_or_else(|| anyhow!("request has no Signature-Input header"))?;
+    let signature_header = request
+        .header("signature")
+        .ok_or_else(|| anyhow!("request has no Signature header"))?;
+
+    let inputs = parse_signature_input(input_header)?;
+    let input = match label {
+        Some(label) => inputs
+            .into_iter()
+            .find(|input| input.label == label)
+            .ok_or_else(|| anyhow!("Signature-Input has no '{}' member", label))?,
+        None => inputs
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("Signature-Input is empty"))?,
+    };
+
+    if let Some(alg) = input.param("alg") {
+        if alg != "ed25519" {
+            bail!("unsupported signature algorithm '{}'", alg);
+        }
+    }
+
+    let mut covered = Vec::with_capacity(input.components.len());
+    for component in &input.components {
+        covered.push((component.serialize(), request.component_value(component)?));
+    }
+
+    let base = signature_base(request, &input)?;
+    let signature_bytes = parse_signature(signature_header, &input.label)?;
+    let signature = Signature::from_slice(&signature_bytes).map_err(|_| {
+        anyhow!(
+            "signature '{}' is not a valid Ed25519 signature",
+            input.label
+        )
+    })?;
+
+    let keyid = input.param("keyid").map(str::to_string);
+    let candidates: Vec<&VerifyingKey> = match &keyid {
+        Some(keyid) => keys
+            .iter()
+            .filter(|key| &ed25519_thumbprint(key) == keyid)
+            .collect(),
+        None => keys.iter().collect(),
+    };
+    if candidates.is_empty() {
+        bail!(
+            "no key matches keyid {}",
+            keyid.as_deref().unwrap_or("<none>")
+        );
+    }
+    let signature_valid = candidates
+        .iter()
+        .any(|key| key.verify(base.as_bytes(), &signature).is_ok());
+
+    let created = input.int_param("created")?;
+    let expires = input.int_param("expires")?;
+    let mut issues = Vec::new();
+    match created {
+        Some(created) if created > now + clock_skew => {
+            issues.push(format!("created ({}) is in the future", created))
+        }
+        Some(_) => {}
+        None => issues.push("signature has no created parameter".to_string()),
+    }
+    if let Some(expires) = expires {
+        if expires + clock_skew < now {
+            issues.push(format!("signature expired at {}", expires));
+        }
+        if created.is_some_and(|created| expires < created) {
+            issues.push("expires is before created".to_string());
+        }
+    }
+
+    if let Some(body) = body {
+        match request.header("content-digest") {
+            Some(digest) => {
+                if !verify_content_digest(digest, body)? {
+                    issues.push("Content-Digest does not match the body".to_string());
+                }
+                if !input.components.iter().any(|c| c.name == "content-digest") {
+                    issues.push("Content-Digest is not covered by the signature".to_string());
+                }
+            }
+            None => issues.push("body given but request has no Content-Digest".to_string()),
+        }
+    }
+
+    Ok(VerificationReport {
+        label: input.label,
+        keyid,
+        covered,
+        created,
+        expires,
+        signature_valid,
+        issues,
+    })
+}
+
+/// Ed25519 keys from a single JWK or a key directory (`{"keys": [...]}`)
+pub fn keys_from_jwk_json(value: &Value) -> Result<Vec<VerifyingKey>> {
+    let jwks: Vec<&Value> = match value.get("keys").and_then(Value::as_array) {
+        Some(keys) => keys.iter().collect(),
+        None => vec![value],
+    };
+
+    let keys: Vec<VerifyingKey> = jwks
+        .into_iter()
+        .filter(|jwk| {
+            jwk.get("kty").and_then(Value::as_str) == Some("OKP")
+                && jwk.get("crv").and_then(Value::as_str) == Some("Ed25519")
+        })
+        .filter_map(|jwk| jwk.get("x").and_then(Value::as_str))
+        .map(|x| {
+            let bytes: [u8; 32] = URL_SAFE_NO_PAD
+                .decode(x)
+                .ok()
+                .and_then(|b| b.try_into().ok())
+                .ok_or_else(|| anyhow!("invalid Ed25519 JWK x value '{}'", x))?;
+            VerifyingKey::from_bytes(&bytes).context("invalid Ed25519 public key")
+        })
+        .collect::<Result<_>>()?;
+
+    if keys.is_empty() {
+        bail!("no Ed25519 keys found in JWK input");
+    }
+    Ok(keys)
+}
+
+fn fetch_key_directory(url: &str) -> Result<Vec<VerifyingKey>> {
+    let client = http::client()?;
+    let response = send_with_retry(client.get(url))
+        .map_err(|e| request_error(e, "key directory"))
+        .with_context(|| format!("failed to fetch key directory {}", url))?;
+    if !response.status().is_success() {
+        bail!("key directory {} returned {}", url, response.status());
+    }
+    let value: Value = response
+        .json()
+        .with_context(|| format!("key directory {} is not valid JSON", url))?;
+    keys_from_jwk_json(&value)
+}
+
+/// The URL in the first member of the Signature-Agent dictionary
+fn signature_agent_url(request: &HttpRequest) -> Option<String> {
+    let header = request.header("signature-agent")?;
+    let start = header.find('"')? + 1;
+    let end = start + header[start..].find('"')?;
+    Some(header[start..end].to_string())
+}
+
+fn print_report(report: &VerificationReport, now: u64) {
+    if report.is_valid() {
+        println!("VALID (label={})", report.label);
+    } else if !report.signature_valid {
+        println!(
+            "INVALID: signature does not verify (label={})",
+            report.label
+        );
+    } else {
+        println!("INVALID (label={})", report.label);
+    }
+
+    if let Some(keyid) = &report.keyid {
+        println!("  keyid: {}", keyid);
+    }
+    println!("  covered components:");
+    for (component, value) in &report.covered {
+        println!("    {}: {}", component, value);
+    }
+    if let Some(created) = report.created {
+        println!(
+            "  created: {} ({}s ago)",
+            created,
+            now as i64 - created as i64
+        );
+    }
+    if let Some(expires) = report.expires {
+        println!(
+            "  expires: {} ({}s from now)",
+            expires,
+            expires as i64 - now as i64
+        );
+    }
+    for issue in &report.issues {
+        println!("  ✗ {}", issue);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beltic::crypto::http_signature::{ComponentId, ParamValue, SignatureInput};
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn signed_request(signing_key: &SigningKey, created: u64, expires: u64) -> HttpRequest {
+        let mut request = HttpRequest::new("GET", "https://example.com/orders?id=7").unwrap();
+        request.add_header("Signature-Agent", r#"agent="https://agent.example/dir""#);
+
+        let input = SignatureInput {
+            label: "sig1".to_string(),
+            components: vec![
+                ComponentId::new("@method"),
+                ComponentId::new("@authority"),
+                ComponentId::parse(r#"signature-agent;key="agent""#).unwrap(),
+            ],
+            params: vec![
+                ("alg".to_string(), ParamValue::String("ed25519".to_string())),
+                (
+                    "keyid".to_string(),
+                    ParamValue::String(ed25519_thumbprint(&signing_key.verifying_key())),
+                ),
+                ("created".to_string(), ParamValue::Bare(created.to_string())),
+                ("expires".to_string(), ParamValue::Bare(expires.to_string())),
+            ],
+        };
+        let base = signature_base(&request, &input).unwrap();
+        let signature = signing_key.sign(base.as_bytes());
+
+        request.add_header("Signature-Input", &input.header_value());
+        request.add_header(
+            "Signature",
+            &format!("sig1=:{}:", URL_SAFE_NO_PAD.encode(signature.to_bytes())),
+        );
+        request
+    }
+
+    #[test]
+    fn test_verifies_signed_request() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let request = signed_request(&signing_key, NOW, NOW + 60);
+
+        let report = verify_request(
+            &request,
+            None,
+            &[signing_key.verifying_key()],
+            None,
+            NOW + 10,
+            30,
+        )
+        .unwrap();
+        assert!(report.is_valid(), "{:?}", report);
+        assert_eq!(report.covered.len(), 3);
+        assert_eq!(
+            report.covered[0],
+            ("\"@method\"".to_string(), "GET".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rejects_tampered_request_and_expired_signature() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let mut request = signed_request(&signing_key, NOW, NOW + 60);
+        let keys = [signing_key.verifying_key()];
+
+        let expired = verify_request(&request, None, &keys, None, NOW + 600, 30).unwrap();
+        assert!(expired.signature_valid);
+        assert!(!expired.is_valid());
+
+        request.method = "POST".to_string();
+        let tampered = verify_request(&request, None, &keys, None, NOW, 30).unwrap();
+        assert!(!tampered.signature_valid);
+    }
+
+    #[test]
+    fn test_unknown_keyid_is_an_error() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let other = SigningKey::from_bytes(&[8u8; 32]);
+        let request = signed_request(&signing_key, NOW, NOW + 60);
+        assert!(verify_request(&request, None, &[other.verifying_key()], None, NOW, 30).is_err());
+    }
+}
@@ -1,8 +1,18 @@
-use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
 use clap::Parser;
+use serde_json::Value;
 use uuid::Uuid;
 
-use crate::manifest::{init_manifest, InitOptions};
+use crate::commands::credential_id::{decode_jwt_payload, is_jwt};
+use beltic::crypto::verify_jws;
+use beltic::document::{parse_document, DocumentFormat};
+use beltic::duration::IsoDuration;
+use beltic::estatus;
+use beltic::manifest::schema::AgentStatus;
+use beltic::manifest::{init_manifest, resolve_agent_path, resolve_base_dir, InitOptions};
 
 #[derive(Parser, Debug)]
 pub struct InitArgs {
@@ -30,6 +40,16 @@ pub struct InitArgs {
     #[arg(short, long)]
     developer_id: Option<String>,
 
+    /// Developer credential (JSON, YAML or signed JWS) to take the developer
+    /// credential ID from, instead of --developer-id
+    #[arg(long, value_name = "FILE", conflicts_with = "developer_id")]
+    developer_credential: Option<PathBuf>,
+
+    /// Public key (PEM) the --developer-credential JWS was signed with; when
+    /// it verifies, the developer credential is recorded as verified
+    #[arg(long, value_name = "KEY", requires = "developer_credential")]
+    developer_key: Option<PathBuf>,
+
     /// Overwrite existing manifest
     #[arg(short, long)]
     force: bool,
@@ -47,9 +67,108 @@ pub struct InitArgs {
     #[arg(long)]
     credential: bool,
 
-    /// Issuer DID for self-signed credentials (auto-generated if not provided)
+    /// Issuer DID for self-signed credentials (auto-generated if not provided).
+    /// `${VAR}` references are expanded from the environment
     #[arg(long)]
     issuer_did: Option<String>,
+
+    /// Incident response contact email; `${VAR}` references are expanded
+    #[arg(long)]
+    contact: Option<String>,
+
+    /// Revocation list URL for credentials; `${VAR}` references are expanded
+    #[arg(long)]
+    revocation_list_url: Option<String>,
+
+    /// Preset whose fields pre-populate the credential (see `beltic preset list`)
+    #[arg(long, value_name = "NAME", requires = "credential")]
+    preset: Option<String>,
+
+    /// How long the credential is valid, as an ISO 8601 duration (default: P90D)
+    #[arg(long, value_name = "DURATION", requires = "credential")]
+    valid_for: Option<IsoDuration>,
+
+    /// Output format: json or yaml (default: from --output extension, else json)
+    #[arg(long)]
+    format: Option<DocumentFormat>,
+
+    /// Only ask for agent identity; data handling and operations use template defaults
+    #[arg(long)]
+    minimal: bool,
+
+    /// Skip binary files (images, compiled assets) when fingerprinting
+    #[arg(long)]
+    exclude_binary: bool,
+
+    /// JSON or YAML file with an array of tools (toolsList entries) to declare
+    /// instead of prompting for them
+    #[arg(long, value_name = "FILE")]
+    tools: Option<PathBuf>,
+
+    /// Derive agentId from the issuer DID and agent name, and credentialId from
+    /// agentId and version (UUIDv5), so re-running init reproduces the same IDs
+    #[arg(long, requires = "credential")]
+    deterministic_id: bool,
+
+    /// Agent subdirectory of a monorepo: fingerprinted and detected on its own,
+    /// while .beltic.yaml and git details come from the base directory
+    #[arg(long, value_name = "DIR")]
+    agent_path: Option<PathBuf>,
+
+    /// Run from this directory instead of the current one: detection,
+    /// fingerprinting, .beltic.yaml discovery and the default output path use it
+    #[arg(long, value_name = "DIR")]
+    base_dir: Option<PathBuf>,
+
+    /// Fail when architecture type, model provider/family or deployment type
+    /// could not be detected and were not set by flags or .beltic.yaml,
+    /// instead of falling back to defaults (non-interactive only)
+    #[arg(long)]
+    strict_detection: bool,
+
+    /// Lifecycle status: production, beta, alpha, internal, deprecated or
+    /// retired (default: alpha; interactive init asks instead)
+    #[arg(long)]
+    status: Option<AgentStatus>,
+
+    /// Update the existing manifest: re-run detection and fingerprinting,
+    /// replacing only fields still at their auto-detected values so manual
+    /// edits are kept
+    #[arg(long, conflicts_with_all = ["force", "credential", "minimal"])]
+    update: bool,
+
+    /// Exit non-zero without writing the manifest when validation reports
+    /// any warning (the safety-metrics placeholder note is exempt)
+    #[arg(long, conflicts_with = "no_validate")]
+    fail_on_warning: bool,
+
+    /// Directory with deprecation-policy.txt, fail-safe-behavior.txt and/or
+    /// monitoring-coverage.txt replacing the built-in text for those fields;
+    /// `{agent_name}` and `{oversight_mode}` are substituted
+    #[arg(long, value_name = "DIR")]
+    template_dir: Option<PathBuf>,
+
+    /// Write a manifest into every agent directory under the current one
+    /// (subdirectories with their own package.json, Cargo.toml, pyproject.toml,
+    /// etc.), detecting and fingerprinting them in parallel; never prompts
+    #[arg(
+        long,
+        conflicts_with_all = ["credential", "update", "minimal", "agent_path", "output"]
+    )]
+    monorepo_scan: bool,
+
+    /// Agents scanned at once with --monorepo-scan (default: number of CPUs)
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "monorepo_scan",
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    jobs: Option<u16>,
+
+    /// The global `--config` file, used when `--config` isn't given
+    #[arg(skip)]
+    pub global_config: Option<PathBuf>,
 }
 
 pub fn run(args: InitArgs) -> Result<()> {
@@ -62,6 +181,13 @@ pub fn run(args: InitArgs) -> Result<()> {
     } else {
         None
     };
+    let (developer_id, developer_credential_verified) = match &args.developer_credential {
+        Some(path) => {
+            let (id, verified) = read_developer_credential(path, args.developer_key.as_deref())?;
+            (Some(id), verified)
+        }
+        None => (developer_id, false),
+    };
 
     // Validate deployment type if provided
     if let Some(ref dtype) = args.r#type {
@@ -75,9 +201,27 @@ pub fn run(args: InitArgs) -> Result<()> {
         }
     }
 
+    // Interactive init asks for these fields instead of defaulting them
+    if args.strict_detection && !args.non_interactive && !args.credential && !args.monorepo_scan {
+        anyhow::bail!(
+            "--strict-detection requires --non-interactive, --credential or --monorepo-scan"
+        );
+    }
+
+    let agent_path = match &args.agent_path {
+        Some(path) => Some(resolve_agent_path(
+            &resolve_base_dir(args.base_dir.as_deref())?,
+            path,
+        )?),
+        None => None,
+    };
+
     let options = InitOptions {
         output_path: args.output,
-        config_path: args.config,
+        config_path: args.config.or_else(|| {
+            args.global_config
+                .map(|path| path.to_string_lossy().into_owned())
+        }),
         include_patterns: if args.include.is_empty() {
             None
         } else {
@@ -90,12 +234,139 @@ pub fn run(args: InitArgs) -> Result<()> {
         },
         deployment_type: args.r#type,
         developer_id,
+        developer_credential_verified,
         force: args.force,
         interactive: !args.non_interactive, // Interactive by default
         validate: !args.no_validate,        // Validate by default
         credential: args.credential,        // Schema-compliant credential output
         issuer_did: args.issuer_did,
+        incident_response_contact: args.contact,
+        revocation_list_url: args.revocation_list_url,
+        format: args.format,
+        minimal: args.minimal,
+        exclude_binary: args.exclude_binary,
+        agent_path,
+        valid_for: args.valid_for,
+        preset: args.preset,
+        tools_file: args.tools,
+        deterministic_id: args.deterministic_id,
+        strict_detection: args.strict_detection,
+        status: args.status,
+        update: args.update,
+        fail_on_warning: args.fail_on_warning,
+        template_dir: args.template_dir,
+        monorepo_scan: args.monorepo_scan,
+        jobs: args.jobs.map(usize::from),
+        base_dir: args.base_dir,
     };
 
     init_manifest(&options)
 }
+
+/// The credential ID of a developer credential file, and whether its JWS
+/// signature was verified with `key`
+fn read_developer_credential(path: &Path, key: Option<&Path>) -> Result<(Uuid, bool)> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read developer credential {}", path.display()))?;
+
+    let (credential, verified) = if is_jwt(&content) {
+        let claims = match key {
+            Some(key) => {
+                verify_jws(content.trim(), key, None)
+                    .with_context(|| {
+                        format!(
+                            "developer credential {} did not verify with {}",
+                            path.display(),
+                            key.display()
+                        )
+                    })?
+                    .payload
+            }
+            None => {
+                estatus!(
+                    "[warn] Developer credential signature not checked; pass --developer-key to record it as verified"
+                );
+                decode_jwt_payload(&content)?
+            }
+        };
+        let credential = claims
+            .get("vc")
+            .cloned()
+            .with_context(|| format!("{} has no vc claim", path.display()))?;
+        (credential, key.is_some())
+    } else {
+        if key.is_some() {
+            bail!(
+                "--developer-key needs a signed (JWS) developer credential, but {} is not a JWS",
+                path.display()
+            );
+        }
+        let format = DocumentFormat::resolve(path, None);
+        let credential = parse_document(&content, format)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        (credential, false)
+    };
+
+    if credential.get("agentId").is_some() {
+        bail!(
+            "{} is an agent credential; pass the developer credential from `beltic dev-init`",
+            path.display()
+        );
+    }
+    let id = credential
+        .get("credentialId")
+        .and_then(Value::as_str)
+        .with_context(|| format!("{} has no credentialId", path.display()))?;
+    let id = Uuid::parse_str(id)
+        .with_context(|| format!("credentialId '{}' in {} is not a UUID", id, path.display()))?;
+    Ok((id, verified))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use serde_json::json;
+
+    const ID: &str = "7ee13740-5e91-474a-b943-80298a7c0221";
+
+    #[test]
+    fn test_read_developer_credential() {
+        let dir = tempfile::tempdir().unwrap();
+        let json_path = dir.path().join("developer.json");
+        fs::write(&json_path, json!({ "credentialId": ID }).to_string()).unwrap();
+        let (id, verified) = read_developer_credential(&json_path, None).unwrap();
+        assert_eq!(id.to_string(), ID);
+        assert!(!verified);
+
+        // A JWS read without a key is decoded but not verified
+        let encode = |value: Value| URL_SAFE_NO_PAD.encode(value.to_string());
+        let token = format!(
+            "{}.{}.c2ln",
+            encode(json!({ "alg": "EdDSA" })),
+            encode(json!({ "vc": { "credentialId": ID } }))
+        );
+        let jwt_path = dir.path().join("developer.jwt");
+        fs::write(&jwt_path, &token).unwrap();
+        let (id, verified) = read_developer_credential(&jwt_path, None).unwrap();
+        assert_eq!(id.to_string(), ID);
+        assert!(!verified);
+
+        let key = dir.path().join("key.pem");
+        let err = read_developer_credential(&json_path, Some(&key))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("not a JWS"), "{}", err);
+
+        let agent_path = dir.path().join("agent.json");
+        fs::write(
+            &agent_path,
+            json!({ "credentialId": ID, "agentId": ID }).to_string(),
+        )
+        .unwrap();
+        let err = read_developer_credential(&agent_path, None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("agent credential"), "{}", err);
+    }
+}
@@ -1,20 +1,23 @@
-use std::{fs, io::Write, path::PathBuf};
+use std::{fmt, fs, io::Write, path::PathBuf, str::FromStr};
 
 #[cfg(unix)]
 use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Args;
 use console::style;
 use ed25519_dalek::SigningKey as Ed25519SigningKey;
+use k256::ecdsa::SigningKey as K256SigningKey;
 use p256::ecdsa::SigningKey as P256SigningKey;
 use pkcs8::{EncodePrivateKey, EncodePublicKey, LineEnding};
 use rand_core::OsRng;
 use zeroize::Zeroizing;
 
-use crate::crypto::{parse_signature_alg, SignatureAlg};
+use beltic::crypto::jwk::{private_jwk_from_pem, public_jwk};
+use beltic::crypto::{parse_signature_alg, SignatureAlg};
+use beltic::status;
 
-use super::discovery::{ensure_beltic_dir, ensure_private_keys_gitignored};
+use super::discovery::{add_to_gitignore, ensure_beltic_dir, ensure_private_keys_gitignored};
 use super::prompts::{
     default_private_key_path, default_public_key_path, generate_key_name, CommandPrompts,
 };
@@ -25,14 +28,21 @@ pub struct KeygenArgs {
     #[arg(long, value_parser = parse_signature_alg)]
     pub alg: Option<SignatureAlg>,
 
-    /// Path to write the private key (PEM). Defaults to ./.beltic/{name}-private.pem
+    /// Path to write the private key. Defaults to ./.beltic/{name}-private.pem
+    /// (.jwk with --format jwk)
     #[arg(long)]
     pub out: Option<PathBuf>,
 
-    /// Path to write the public key (PEM). Defaults to ./.beltic/{name}-public.pem
+    /// Path to write the public key. Defaults to ./.beltic/{name}-public.pem
+    /// (.jwk with --format jwk)
     #[arg(long = "pub")]
     pub pub_out: Option<PathBuf>,
 
+    /// Key file format: pem (default), jwk, or both (JWKs written next to
+    /// the PEM files with a .jwk extension)
+    #[arg(long, default_value = "pem")]
+    pub format: KeyFormat,
+
     /// Custom name for the keypair (default: {alg}-{date})
     #[arg(long)]
     pub name: Option<String>,
@@ -42,6 +52,100 @@ pub struct KeygenArgs {
     pub non_interactive: bool,
 }
 
+/// How a generated keypair is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    /// PKCS#8 private key and SPKI public key
+    Pem,
+    /// Private JWK (with `d`) and public JWK, `kid` set to the thumbprint
+    Jwk,
+    /// Both, the JWKs beside the PEM files
+    Both,
+}
+
+impl FromStr for KeyFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "pem" => Ok(KeyFormat::Pem),
+            "jwk" => Ok(KeyFormat::Jwk),
+            "both" => Ok(KeyFormat::Both),
+            _ => Err(format!(
+                "unknown key format '{}', expected pem, jwk or both",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for KeyFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            KeyFormat::Pem => "pem",
+            KeyFormat::Jwk => "jwk",
+            KeyFormat::Both => "both",
+        })
+    }
+}
+
+/// Where each file of a keypair goes: (private, public) per format
+struct KeyPaths {
+    pem: Option<(PathBuf, PathBuf)>,
+    jwk: Option<(PathBuf, PathBuf)>,
+}
+
+impl KeyPaths {
+    /// The --out/--pub paths or the defaults for `name`. With `both`, the
+    /// JWKs go next to the PEM files with a .jwk extension.
+    fn new(
+        format: KeyFormat,
+        out: Option<PathBuf>,
+        pub_out: Option<PathBuf>,
+        name: &str,
+    ) -> Result<Self> {
+        let default = |path: PathBuf| match format {
+            KeyFormat::Jwk => path.with_extension("jwk"),
+            _ => path,
+        };
+        let private = out.unwrap_or_else(|| default(default_private_key_path(name)));
+        let public = pub_out.unwrap_or_else(|| default(default_public_key_path(name)));
+        Ok(match format {
+            KeyFormat::Pem => Self {
+                pem: Some((private, public)),
+                jwk: None,
+            },
+            KeyFormat::Jwk => Self {
+                pem: None,
+                jwk: Some((private, public)),
+            },
+            KeyFormat::Both => {
+                let jwk = (private.with_extension("jwk"), public.with_extension("jwk"));
+                if jwk.0 == private || jwk.1 == public {
+                    bail!("--format both writes JWKs next to the PEM files; give --out and --pub a .pem path");
+                }
+                Self {
+                    pem: Some((private, public)),
+                    jwk: Some(jwk),
+                }
+            }
+        })
+    }
+
+    /// Every file to be written, private keys first
+    fn files(&self) -> Vec<&PathBuf> {
+        let pairs = || self.pem.iter().chain(&self.jwk);
+        pairs()
+            .map(|(private, _)| private)
+            .chain(pairs().map(|(_, public)| public))
+            .collect()
+    }
+
+    fn in_beltic_dir(&self) -> bool {
+        self.files().iter().any(|path| path.starts_with(".beltic"))
+    }
+}
+
 pub fn run(args: KeygenArgs) -> Result<()> {
     // Determine if we need interactive mode
     let needs_interactive = args.out.is_none() && !args.non_interactive;
@@ -74,18 +178,19 @@ fn run_interactive(args: KeygenArgs) -> Result<()> {
     };
 
     // 3. Generate paths
-    let private_path = args.out.unwrap_or_else(|| default_private_key_path(&name));
-    let public_path = args
-        .pub_out
-        .unwrap_or_else(|| default_public_key_path(&name));
+    let paths = KeyPaths::new(args.format, args.out, args.pub_out, &name)?;
 
     // 4. Check for existing files
-    if private_path.exists() || public_path.exists() {
+    let files = paths.files();
+    if files.iter().any(|path| path.exists()) {
+        let listing: Vec<String> = files
+            .iter()
+            .map(|path| format!("  {}", path.display()))
+            .collect();
         let overwrite = prompts.prompt_confirm(
             &format!(
-                "Key files already exist. Overwrite?\n  {}\n  {}",
-                private_path.display(),
-                public_path.display()
+                "Key files already exist. Overwrite?\n{}",
+                listing.join("\n")
             ),
             false,
         )?;
@@ -97,53 +202,74 @@ fn run_interactive(args: KeygenArgs) -> Result<()> {
     }
 
     // 5. Ensure .beltic directory exists
-    if private_path.starts_with(".beltic") || public_path.starts_with(".beltic") {
+    if paths.in_beltic_dir() {
         ensure_beltic_dir()?;
     }
 
     // 6. Generate and write keys
-    let (private_pem, public_pem) = generate_keypair(alg)?;
-
-    write_private_key(&private_path, private_pem.as_bytes())?;
-    write_file(&public_path, public_pem.as_bytes())
-        .with_context(|| format!("failed to write public key to {}", public_path.display()))?;
+    write_keypair(alg, &paths)?;
 
     // 7. Auto-add to .gitignore
-    let gitignore_updated = ensure_private_keys_gitignored()?;
+    let gitignore_updated = gitignore_private_keys(&paths)?;
 
     // 8. Print success message
-    println!();
-    println!(
+    status!();
+    status!(
         "{}",
         style("Key pair generated successfully!").green().bold()
     );
-    println!();
-    println!("  {} {}", style("Algorithm:").dim(), alg);
-    println!(
-        "  {} {}",
-        style("Private key:").dim(),
-        private_path.display()
-    );
-    println!("  {} {}", style("Public key:").dim(), public_path.display());
+    status!();
+    status!("  {} {}", style("Algorithm:").dim(), alg);
+    if let Some((private_path, public_path)) = &paths.pem {
+        status!(
+            "  {} {}",
+            style("Private key:").dim(),
+            private_path.display()
+        );
+        status!("  {} {}", style("Public key:").dim(), public_path.display());
+    }
+    if let Some((private_path, public_path)) = &paths.jwk {
+        status!(
+            "  {} {}",
+            style("Private JWK:").dim(),
+            private_path.display()
+        );
+        status!("  {} {}", style("Public JWK:").dim(), public_path.display());
+    }
 
     if gitignore_updated {
-        println!();
-        println!(
+        status!();
+        status!(
             "{}",
             style("Added .beltic/*-private.pem to .gitignore").dim()
         );
     }
 
-    println!();
-    println!("{}", style("Next steps:").cyan().bold());
-    println!(
-        "  Sign a credential:  beltic sign --payload credential.json --key {}",
-        private_path.display()
-    );
-    println!(
-        "  Verify a token:     beltic verify --token token.jwt --key {}",
-        public_path.display()
-    );
+    status!();
+    status!("{}", style("Next steps:").cyan().bold());
+    match (&paths.pem, &paths.jwk) {
+        (Some((private_path, public_path)), _) => {
+            status!(
+                "  Sign a credential:  beltic sign --payload credential.json --key {}",
+                private_path.display()
+            );
+            status!(
+                "  Verify a token:     beltic verify --token token.jwt --key {}",
+                public_path.display()
+            );
+        }
+        (None, Some((private_path, public_path))) => {
+            status!(
+                "  Sign a credential:  beltic sign --payload credential.json --jwk-key {}",
+                private_path.display()
+            );
+            status!(
+                "  Verify a token:     beltic verify --token token.jwt --jwks {}",
+                public_path.display()
+            );
+        }
+        (None, None) => {}
+    }
 
     Ok(())
 }
@@ -155,36 +281,73 @@ fn run_non_interactive(args: KeygenArgs) -> Result<()> {
     let name = args.name.unwrap_or_else(|| generate_key_name(alg));
 
     // Use provided paths or defaults
-    let private_path = args.out.unwrap_or_else(|| default_private_key_path(&name));
-    let public_path = args
-        .pub_out
-        .unwrap_or_else(|| default_public_key_path(&name));
+    let paths = KeyPaths::new(args.format, args.out, args.pub_out, &name)?;
 
     // Ensure .beltic directory exists
-    if private_path.starts_with(".beltic") || public_path.starts_with(".beltic") {
+    if paths.in_beltic_dir() {
         ensure_beltic_dir()?;
     }
 
     // Generate and write keys
-    let (private_pem, public_pem) = generate_keypair(alg)?;
-
-    write_private_key(&private_path, private_pem.as_bytes())?;
-    write_file(&public_path, public_pem.as_bytes())
-        .with_context(|| format!("failed to write public key to {}", public_path.display()))?;
+    write_keypair(alg, &paths)?;
 
     // Auto-add to .gitignore
-    let _ = ensure_private_keys_gitignored();
-
-    println!(
-        "Generated {} keypair\n  private: {}\n  public: {}",
+    let _ = gitignore_private_keys(&paths);
+
+    let describe = |pair: &Option<(PathBuf, PathBuf)>, label: &str| {
+        pair.as_ref()
+            .map(|(private_path, public_path)| {
+                format!(
+                    "\n  private{}: {}\n  public{}: {}",
+                    label,
+                    private_path.display(),
+                    label,
+                    public_path.display()
+                )
+            })
+            .unwrap_or_default()
+    };
+    status!(
+        "Generated {} keypair{}{}",
         alg,
-        private_path.display(),
-        public_path.display()
+        describe(&paths.pem, ""),
+        describe(&paths.jwk, " JWK")
     );
 
     Ok(())
 }
 
+/// Generate a keypair and write it in every format `paths` asks for
+fn write_keypair(alg: SignatureAlg, paths: &KeyPaths) -> Result<()> {
+    let (private_pem, public_pem) = generate_keypair(alg)?;
+
+    if let Some((private_path, public_path)) = &paths.pem {
+        write_private_key(private_path, private_pem.as_bytes())?;
+        write_file(public_path, public_pem.as_bytes())
+            .with_context(|| format!("failed to write public key to {}", public_path.display()))?;
+    }
+
+    if let Some((private_path, public_path)) = &paths.jwk {
+        let (_, jwk) = private_jwk_from_pem(&private_pem)?;
+        let private_jwk = Zeroizing::new(serde_json::to_string_pretty(&jwk)? + "\n");
+        write_private_key(private_path, private_jwk.as_bytes())?;
+        let public = serde_json::to_string_pretty(&public_jwk(&jwk))? + "\n";
+        write_file(public_path, public.as_bytes())
+            .with_context(|| format!("failed to write public JWK to {}", public_path.display()))?;
+    }
+    Ok(())
+}
+
+/// Add the private key patterns for the formats written to .gitignore;
+/// true if it changed
+fn gitignore_private_keys(paths: &KeyPaths) -> Result<bool> {
+    let mut updated = ensure_private_keys_gitignored()?;
+    if paths.jwk.is_some() {
+        updated |= add_to_gitignore(".beltic/*-private.jwk")?;
+    }
+    Ok(updated)
+}
+
 fn generate_keypair(alg: SignatureAlg) -> Result<(Zeroizing<String>, String)> {
     match alg {
         SignatureAlg::Es256 => {
@@ -202,6 +365,21 @@ fn generate_keypair(alg: SignatureAlg) -> Result<(Zeroizing<String>, String)> {
 
             Ok((private_pem, public_pem))
         }
+        SignatureAlg::Es256k => {
+            let signing_key = K256SigningKey::random(&mut OsRng);
+            let verifying_key = signing_key.verifying_key();
+            let private_pem = Zeroizing::new(
+                signing_key
+                    .to_pkcs8_pem(LineEnding::LF)
+                    .context("failed to encode ES256K private key to PKCS#8 PEM")?
+                    .to_string(),
+            );
+            let public_pem = verifying_key
+                .to_public_key_pem(LineEnding::LF)
+                .context("failed to encode ES256K public key to PEM")?;
+
+            Ok((private_pem, public_pem))
+        }
         SignatureAlg::EdDsa => {
             let signing_key = Ed25519SigningKey::generate(&mut OsRng);
             let verifying_key = signing_key.verifying_key();
@@ -257,7 +435,7 @@ fn write_private_key(path: &PathBuf, contents: &[u8]) -> Result<()> {
         fs::set_permissions(path, fs::Permissions::from_mode(0o600))
             .with_context(|| format!("failed to set permissions on {}", path.display()))?;
 
-        return Ok(());
+        Ok(())
     }
 
     #[cfg(not(unix))]
@@ -272,6 +450,74 @@ fn write_private_key(path: &PathBuf, contents: &[u8]) -> Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_key_paths_per_format() {
+        let pem = KeyPaths::new(KeyFormat::Pem, None, None, "ed").unwrap();
+        assert_eq!(
+            pem.files(),
+            [
+                &PathBuf::from(".beltic/ed-private.pem"),
+                &PathBuf::from(".beltic/ed-public.pem")
+            ]
+        );
+
+        let jwk = KeyPaths::new(KeyFormat::Jwk, None, None, "ed").unwrap();
+        assert!(jwk.pem.is_none());
+        assert_eq!(
+            jwk.jwk,
+            Some((
+                PathBuf::from(".beltic/ed-private.jwk"),
+                PathBuf::from(".beltic/ed-public.jwk")
+            ))
+        );
+
+        let both = KeyPaths::new(
+            KeyFormat::Both,
+            Some(PathBuf::from("keys/k.pem")),
+            Some(PathBuf::from("keys/k.pub.pem")),
+            "ed",
+        )
+        .unwrap();
+        assert_eq!(
+            both.files(),
+            [
+                &PathBuf::from("keys/k.pem"),
+                &PathBuf::from("keys/k.jwk"),
+                &PathBuf::from("keys/k.pub.pem"),
+                &PathBuf::from("keys/k.pub.jwk")
+            ]
+        );
+        assert!(KeyPaths::new(KeyFormat::Both, Some(PathBuf::from("k.jwk")), None, "ed").is_err());
+    }
+
+    #[test]
+    fn test_jwk_keypair_matches_pem() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let paths = KeyPaths::new(
+            KeyFormat::Both,
+            Some(temp_dir.path().join("k-private.pem")),
+            Some(temp_dir.path().join("k-public.pem")),
+            "k",
+        )
+        .unwrap();
+        write_keypair(SignatureAlg::Es256, &paths).unwrap();
+
+        let (private_jwk, public_jwk) = paths.jwk.as_ref().unwrap();
+        let private: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(private_jwk).unwrap()).unwrap();
+        let public: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(public_jwk).unwrap()).unwrap();
+        assert_eq!(private["alg"], "ES256");
+        assert_eq!(public["kid"], private["kid"]);
+        assert!(public.get("d").is_none());
+
+        // The JWK holds the same key as the PEM
+        let (private_pem, _) = paths.pem.as_ref().unwrap();
+        let (_, from_pem) =
+            private_jwk_from_pem(&fs::read_to_string(private_pem).unwrap()).unwrap();
+        assert_eq!(from_pem, private);
+    }
+
     #[cfg(unix)]
     mod unix_tests {
         use super::*;
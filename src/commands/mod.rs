@@ -1,17 +1,32 @@
 pub mod api_key;
+pub mod audit;
 pub mod auth;
+pub mod color;
+pub mod completions;
+pub mod config;
+pub mod convert;
 pub mod credential_id;
 pub mod dev_init;
+pub mod diff;
 pub mod directory;
 pub mod discovery;
+pub mod exit;
 pub mod fingerprint;
 pub mod http_sign;
+pub mod http_verify;
 pub mod init;
 pub mod keygen;
+pub mod preset;
 pub mod prompts;
 pub mod register;
+pub mod rekey;
+pub mod revocation;
+pub mod risk;
 pub mod sandbox;
+pub mod sbom;
 pub mod schema;
 pub mod sign;
+pub mod tools;
+pub mod validate;
 pub mod verify;
 pub mod whoami;
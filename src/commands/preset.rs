@@ -0,0 +1,95 @@
+//! Credential preset commands
+//!
+//! List, show and save the presets that `init --credential --preset` applies.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use console::style;
+
+use beltic::document::read_document;
+use beltic::manifest::preset::{snapshot, PresetScope, PresetStore};
+use beltic::status;
+
+#[derive(Args)]
+pub struct PresetArgs {
+    #[command(subcommand)]
+    pub command: PresetCommand,
+}
+
+#[derive(Subcommand)]
+pub enum PresetCommand {
+    /// List available presets (local ones hide user ones of the same name)
+    List,
+    /// Print a preset's fields
+    Show {
+        /// Preset name
+        name: String,
+    },
+    /// Save the non-identity fields of a credential as a preset
+    Save {
+        /// Preset name (letters, digits, '-' and '_')
+        name: String,
+        /// Credential to snapshot (JSON or YAML)
+        #[arg(long, default_value = "agent-credential.json")]
+        from: PathBuf,
+        /// Save to .beltic/presets/ in the current directory instead of the user config directory
+        #[arg(long)]
+        local: bool,
+        /// Overwrite an existing preset
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+pub fn run(args: PresetArgs) -> Result<()> {
+    let cwd = std::env::current_dir().context("failed to get current directory")?;
+    let store = PresetStore::new(&cwd);
+
+    match args.command {
+        PresetCommand::List => {
+            let entries = store.list()?;
+            if entries.is_empty() {
+                println!("No presets found. Create one with: beltic preset save <name>");
+                return Ok(());
+            }
+            for entry in entries {
+                println!(
+                    "{:<24} {:<6} {}",
+                    style(&entry.name).cyan(),
+                    entry.scope.as_str(),
+                    entry.path.display()
+                );
+            }
+        }
+        PresetCommand::Show { name } => {
+            let fields = store.load(&name)?;
+            println!("{}", serde_json::to_string_pretty(&fields)?);
+        }
+        PresetCommand::Save {
+            name,
+            from,
+            local,
+            force,
+        } => {
+            let credential = read_document(&from, None)
+                .with_context(|| format!("failed to read credential {}", from.display()))?;
+            let fields = snapshot(&credential)?;
+            let scope = if local {
+                PresetScope::Local
+            } else {
+                PresetScope::User
+            };
+            let path = store.save(&name, scope, &fields, force)?;
+            status!(
+                "{} Saved preset '{}' ({} fields) to {}",
+                style("✓").green(),
+                name,
+                fields.len(),
+                path.display()
+            );
+        }
+    }
+    Ok(())
+}
@@ -9,7 +9,8 @@ use chrono::Local;
 use console::{style, Term};
 use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
 
-use crate::crypto::SignatureAlg;
+use beltic::crypto::SignatureAlg;
+use beltic::output::is_quiet;
 
 /// Interactive prompts for CLI commands
 pub struct CommandPrompts {
@@ -41,15 +42,21 @@ impl CommandPrompts {
         Ok(())
     }
 
-    /// Display a success message
+    /// Display a success message (suppressed by `--quiet`)
     pub fn success(&self, message: &str) -> Result<()> {
+        if is_quiet() {
+            return Ok(());
+        }
         self.term
             .write_line(&format!("{} {}", style("").green(), message))?;
         Ok(())
     }
 
-    /// Display an info message
+    /// Display an info message (suppressed by `--quiet`)
     pub fn info(&self, message: &str) -> Result<()> {
+        if is_quiet() {
+            return Ok(());
+        }
         self.term.write_line(&format!("{}", style(message).dim()))?;
         Ok(())
     }
@@ -63,14 +70,19 @@ impl CommandPrompts {
 
     /// Prompt for algorithm selection
     pub fn prompt_algorithm(&self, default: Option<SignatureAlg>) -> Result<SignatureAlg> {
-        let options = vec![
+        let options = [
             ("EdDSA (Ed25519) - recommended", SignatureAlg::EdDsa),
             ("ES256 (P-256) - NIST compliant", SignatureAlg::Es256),
+            (
+                "ES256K (secp256k1) - for secp256k1 proofs",
+                SignatureAlg::Es256k,
+            ),
         ];
 
         let default_idx = match default.unwrap_or(SignatureAlg::EdDsa) {
             SignatureAlg::EdDsa => 0,
             SignatureAlg::Es256 => 1,
+            SignatureAlg::Es256k => 2,
         };
 
         let idx = Select::with_theme(&self.theme)
@@ -163,6 +175,7 @@ pub fn generate_key_name(alg: SignatureAlg) -> String {
     let alg_str = match alg {
         SignatureAlg::EdDsa => "eddsa",
         SignatureAlg::Es256 => "es256",
+        SignatureAlg::Es256k => "es256k",
     };
     format!("{}-{}", alg_str, date)
 }
@@ -7,7 +7,8 @@ use clap::Args;
 use console::style;
 use serde::{Deserialize, Serialize};
 
-use crate::config::{load_config, save_config};
+use beltic::config::{load_config, save_config};
+use beltic::http::{self, request_error};
 
 use super::prompts::CommandPrompts;
 
@@ -149,13 +150,14 @@ pub fn run(args: RegisterArgs) -> Result<()> {
         }
     });
 
-    let client = reqwest::blocking::Client::new();
+    let client = http::client()?;
     let response = client
         .post(format!("{}/api/developers", api_url))
         .header("Content-Type", "application/json")
         .header("Accept", "application/json")
         .json(&request_body)
         .send()
+        .map_err(|e| request_error(e, "console"))
         .context("failed to connect to console API")?;
 
     if !response.status().is_success() {
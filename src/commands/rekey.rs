@@ -0,0 +1,309 @@
+//! Rotate the signing key of a credential.
+//!
+//! Usage: beltic rekey --credential <file> --new-key <pem> [--kid <id>]
+//!
+//! Points `verificationMethod`, `proof.verificationMethod` and (when present)
+//! `httpSigningKeyJwkThumbprint` at the new key, validates the result and
+//! re-signs it into a new JWS.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Args;
+use serde_json::Value;
+use zeroize::Zeroizing;
+
+use beltic::credential::{
+    build_claims, check_proof_type, detect_credential_kind, validate_credential_version,
+    ClaimsOptions, CredentialKind,
+};
+use beltic::crypto::jwk::thumbprint_from_private_pem;
+use beltic::crypto::{parse_signature_alg, sign_jws, PemSigner, SignatureAlg};
+use beltic::document::{parse_document, write_document, DocumentFormat};
+use beltic::schema::active_version;
+use beltic::status;
+
+use super::credential_id::{decode_jwt_payload, is_jwt};
+
+#[derive(Args)]
+pub struct RekeyArgs {
+    /// Credential to rekey (JSON or YAML; a signed JWS requires --force)
+    #[arg(long)]
+    pub credential: PathBuf,
+
+    /// New private key (PEM)
+    #[arg(long)]
+    pub new_key: PathBuf,
+
+    /// Key identifier for the new key (default: its JWK thumbprint)
+    #[arg(long)]
+    pub kid: Option<String>,
+
+    /// Algorithm of the new key (default: EdDSA)
+    #[arg(long, default_value = "EdDSA", value_parser = parse_signature_alg)]
+    pub alg: SignatureAlg,
+
+    /// Output file for the new JWS. Defaults to {credential}.jwt, or
+    /// {credential}.rekeyed.jwt when the credential is already a .jwt
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+
+    /// Rekey the `vc` claim of an already-signed JWS
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// The global `--config` file, whose schema pin validation uses
+    #[arg(skip)]
+    pub global_config: Option<PathBuf>,
+}
+
+/// Outcome of rewriting the key references in a credential
+#[derive(Debug, PartialEq, Eq)]
+pub struct RekeyedFields {
+    pub verification_method: String,
+    pub http_thumbprint_updated: bool,
+}
+
+pub fn run(args: RekeyArgs) -> Result<()> {
+    let content = fs::read_to_string(&args.credential)
+        .with_context(|| format!("failed to read {}", args.credential.display()))?;
+
+    let signed = is_jwt(&content);
+    let mut credential = if signed {
+        if !args.force {
+            bail!(
+                "{} is already a signed JWS. Use --force to rekey its vc claim.",
+                args.credential.display()
+            );
+        }
+        decode_jwt_payload(&content)
+            .with_context(|| format!("failed to decode token {}", args.credential.display()))?
+            .get("vc")
+            .cloned()
+            .with_context(|| format!("token {} has no vc claim", args.credential.display()))?
+    } else {
+        parse_document(&content, DocumentFormat::resolve(&args.credential, None))
+            .with_context(|| format!("failed to parse {}", args.credential.display()))?
+    };
+
+    let kind = detect_credential_kind(&credential)
+        .ok_or_else(|| anyhow!("unable to detect credential type"))?;
+
+    let pem = Zeroizing::new(
+        fs::read_to_string(&args.new_key)
+            .with_context(|| format!("failed to read key {}", args.new_key.display()))?,
+    );
+    let thumbprint = thumbprint_from_private_pem(&pem, args.alg)?;
+    let kid = args.kid.clone().unwrap_or_else(|| thumbprint.clone());
+
+    let fields = rewrite_key_fields(&mut credential, kind, &kid, &thumbprint, args.alg)?;
+
+    let errors = validate_credential_version(
+        kind,
+        &credential,
+        &active_version(args.global_config.as_deref()),
+    )?;
+    if !errors.is_empty() {
+        let mut message = String::from("rekeyed credential failed validation:\n");
+        for err in errors {
+            message.push_str(&format!("  - {err}\n"));
+        }
+        bail!(message);
+    }
+    check_proof_type(&credential, args.alg)?;
+
+    let claims = build_claims(
+        &credential,
+        kind,
+        ClaimsOptions {
+            issuer: None,
+            subject: None,
+            audience: &[],
+            valid_for: None,
+            jti: None,
+            not_before: None,
+        },
+    )?;
+    let signer = PemSigner::from_file(&args.new_key, args.alg, Some(kid.clone()))?;
+    let token = sign_jws(
+        &claims,
+        &signer,
+        kind.media_type(),
+        Some("application/json"),
+    )?;
+
+    if !signed {
+        write_document(&args.credential, &credential, None)?;
+        status!("Updated {}", args.credential.display());
+    }
+
+    let out = args
+        .out
+        .clone()
+        .unwrap_or_else(|| default_out(&args.credential));
+    fs::write(&out, &token)
+        .with_context(|| format!("failed to write token to {}", out.display()))?;
+
+    status!("  verificationMethod: {}", fields.verification_method);
+    if fields.http_thumbprint_updated {
+        status!("  httpSigningKeyJwkThumbprint: {}", thumbprint);
+    }
+    status!(
+        "Wrote {} JWS (alg={}, kid={}) to {}",
+        kind.display_name(),
+        args.alg,
+        kid,
+        out.display()
+    );
+    Ok(())
+}
+
+/// Where the new JWS goes without `--out`, never the input itself
+fn default_out(credential: &Path) -> PathBuf {
+    let out = credential.with_extension("jwt");
+    if out == credential {
+        credential.with_extension("rekeyed.jwt")
+    } else {
+        out
+    }
+}
+
+/// Point the credential's key references at `kid`.
+///
+/// `verificationMethod` and `proof.verificationMethod` become `{issuerDid}#{kid}`.
+/// The HTTP signing thumbprint is only rewritten when the credential already
+/// carries one, since it must be paired with a key directory URL.
+pub fn rewrite_key_fields(
+    credential: &mut Value,
+    kind: CredentialKind,
+    kid: &str,
+    thumbprint: &str,
+    alg: SignatureAlg,
+) -> Result<RekeyedFields> {
+    let issuer = credential
+        .get("issuerDid")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("credential has no issuerDid"))?;
+    let verification_method = format!("{}#{}", issuer, kid);
+
+    let object = credential
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("credential is not a JSON object"))?;
+    object.insert(
+        "verificationMethod".to_string(),
+        Value::String(verification_method.clone()),
+    );
+
+    match object.get_mut("proof").and_then(Value::as_object_mut) {
+        Some(proof) => {
+            proof.insert(
+                "verificationMethod".to_string(),
+                Value::String(verification_method.clone()),
+            );
+        }
+        None => bail!("credential has no proof object"),
+    }
+
+    let mut http_thumbprint_updated = false;
+    if kind == CredentialKind::Agent {
+        if let Some(existing) = find_http_thumbprint(object) {
+            if alg == SignatureAlg::EdDsa {
+                *existing = Value::String(thumbprint.to_string());
+                http_thumbprint_updated = true;
+            } else {
+                eprintln!(
+                    "Warning: httpSigningKeyJwkThumbprint left unchanged (HTTP signing keys must be Ed25519)"
+                );
+            }
+        }
+    }
+
+    Ok(RekeyedFields {
+        verification_method,
+        http_thumbprint_updated,
+    })
+}
+
+/// The HTTP signing thumbprint sits at the top level (v1) or in a nested section (v2)
+fn find_http_thumbprint(object: &mut serde_json::Map<String, Value>) -> Option<&mut Value> {
+    const FIELD: &str = "httpSigningKeyJwkThumbprint";
+    if object.contains_key(FIELD) {
+        return object.get_mut(FIELD);
+    }
+    object
+        .values_mut()
+        .filter_map(Value::as_object_mut)
+        .find(|section| section.contains_key(FIELD))
+        .and_then(|section| section.get_mut(FIELD))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const THUMBPRINT: &str = "kPrK_qmxVWaYVA9wwBF6Iuo3vVzz7TxHCTwXBygrS4k";
+
+    #[test]
+    fn test_rewrites_verification_methods() {
+        let mut credential: Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/agent-valid.json")).unwrap();
+        credential["httpSigningKeyJwkThumbprint"] = json!("old");
+
+        let fields = rewrite_key_fields(
+            &mut credential,
+            CredentialKind::Agent,
+            "key-2",
+            THUMBPRINT,
+            SignatureAlg::EdDsa,
+        )
+        .unwrap();
+
+        assert_eq!(fields.verification_method, "did:web:beltic.com#key-2");
+        assert!(fields.http_thumbprint_updated);
+        assert_eq!(credential["verificationMethod"], "did:web:beltic.com#key-2");
+        assert_eq!(
+            credential["proof"]["verificationMethod"],
+            "did:web:beltic.com#key-2"
+        );
+        assert_eq!(credential["httpSigningKeyJwkThumbprint"], THUMBPRINT);
+    }
+
+    #[test]
+    fn test_does_not_add_http_thumbprint() {
+        let mut credential: Value =
+            serde_json::from_str(include_str!("../../tests/fixtures/developer-valid.json"))
+                .unwrap();
+
+        let fields = rewrite_key_fields(
+            &mut credential,
+            CredentialKind::Developer,
+            THUMBPRINT,
+            THUMBPRINT,
+            SignatureAlg::EdDsa,
+        )
+        .unwrap();
+
+        assert!(!fields.http_thumbprint_updated);
+        assert!(credential.get("httpSigningKeyJwkThumbprint").is_none());
+        assert_eq!(
+            credential["proof"]["verificationMethod"],
+            format!("did:web:beltic.com#{}", THUMBPRINT)
+        );
+    }
+
+    #[test]
+    fn test_default_out_keeps_signed_input() {
+        assert_eq!(
+            default_out(Path::new("agent.json")),
+            PathBuf::from("agent.jwt")
+        );
+        assert_eq!(
+            default_out(Path::new("dir/credential.jwt")),
+            PathBuf::from("dir/credential.rekeyed.jwt")
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Revocation list commands
+//!
+//! Build and edit the StatusList2021 credential an issuer hosts at its
+//! credentials' status list URL; `verify --check-revocation` reads it back.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use clap::{ArgGroup, Args, Subcommand};
+use console::style;
+use serde_json::{json, Map, Value};
+
+use beltic::revocation::{
+    encode_bitstring, set_status_bit, status_list_credential, RevocationList, DEFAULT_LIST_SIZE,
+};
+use beltic::status;
+
+#[derive(Args)]
+pub struct RevocationArgs {
+    #[command(subcommand)]
+    pub command: RevocationCommand,
+}
+
+#[derive(Subcommand)]
+pub enum RevocationCommand {
+    /// Build a status list credential with the given entries set
+    Build(BuildArgs),
+
+    /// Set or clear one entry of an existing status list credential
+    Set(SetArgs),
+}
+
+#[derive(Args)]
+pub struct BuildArgs {
+    /// Status list indices (or credential ids, with --index-map) to mark
+    #[arg(value_name = "INDEX|ID")]
+    pub entries: Vec<String>,
+
+    /// URL the list will be hosted at (the credentials' statusListCredential)
+    #[arg(long)]
+    pub url: String,
+
+    /// Issuer DID of the list
+    #[arg(long)]
+    pub issuer: String,
+
+    /// What a set bit means: revocation or suspension
+    #[arg(long, default_value = "revocation")]
+    pub purpose: StatusPurpose,
+
+    /// Number of entries in the list (a multiple of 8)
+    #[arg(long, default_value_t = DEFAULT_LIST_SIZE)]
+    pub size: usize,
+
+    /// JSON object mapping credential ids to status list indices
+    #[arg(long, value_name = "FILE")]
+    pub index_map: Option<PathBuf>,
+
+    /// Output file for the status list credential
+    #[arg(long, default_value = "status-list.json")]
+    pub out: PathBuf,
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("status").required(true).args(["revoked", "suspended", "active"])))]
+pub struct SetArgs {
+    /// Status list index (or credential id, with --index-map) to change
+    #[arg(value_name = "INDEX|ID")]
+    pub entry: String,
+
+    /// Mark the entry revoked (revocation lists)
+    #[arg(long)]
+    pub revoked: bool,
+
+    /// Mark the entry suspended (suspension lists)
+    #[arg(long)]
+    pub suspended: bool,
+
+    /// Clear the entry
+    #[arg(long)]
+    pub active: bool,
+
+    /// Status list credential to edit
+    #[arg(long, default_value = "status-list.json")]
+    pub list: PathBuf,
+
+    /// JSON object mapping credential ids to status list indices
+    #[arg(long, value_name = "FILE")]
+    pub index_map: Option<PathBuf>,
+
+    /// Write the updated list here instead of over --list
+    #[arg(long)]
+    pub out: Option<PathBuf>,
+}
+
+/// What a set bit in a status list means
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusPurpose {
+    Revocation,
+    Suspension,
+}
+
+impl StatusPurpose {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StatusPurpose::Revocation => "revocation",
+            StatusPurpose::Suspension => "suspension",
+        }
+    }
+}
+
+impl FromStr for StatusPurpose {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "revocation" => Ok(StatusPurpose::Revocation),
+            "suspension" => Ok(StatusPurpose::Suspension),
+            other => Err(format!(
+                "unknown status purpose '{}': expected revocation or suspension",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for StatusPurpose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+pub fn run(args: RevocationArgs) -> Result<()> {
+    match args.command {
+        RevocationCommand::Build(args) => run_build(&args),
+        RevocationCommand::Set(args) => run_set(&args),
+    }
+}
+
+fn run_build(args: &BuildArgs) -> Result<()> {
+    if args.size == 0 || !args.size.is_multiple_of(8) {
+        bail!("--size must be a positive multiple of 8, got {}", args.size);
+    }
+    let index_map = args.index_map.as_deref().map(read_index_map).transpose()?;
+    let indices = args
+        .entries
+        .iter()
+        .map(|entry| resolve_index(entry, index_map.as_ref()))
+        .collect::<Result<BTreeSet<_>>>()?;
+
+    let mut bits = vec![0u8; args.size / 8];
+    for index in &indices {
+        set_status_bit(&mut bits, *index, true)?;
+    }
+    let credential = status_list_credential(&args.url, &args.issuer, args.purpose.as_str(), &bits)?;
+    write_list(&args.out, &credential)?;
+
+    status!(
+        "{} Wrote {} list with {} of {} entries set to {}",
+        style("✓").green(),
+        args.purpose,
+        indices.len(),
+        args.size,
+        args.out.display()
+    );
+    Ok(())
+}
+
+fn run_set(args: &SetArgs) -> Result<()> {
+    let content = fs::read_to_string(&args.list)
+        .with_context(|| format!("failed to read status list {}", args.list.display()))?;
+    let mut credential: Value = serde_json::from_str(&content)
+        .with_context(|| format!("status list {} is not valid JSON", args.list.display()))?;
+    let RevocationList::Bitstring { purpose, mut bits } = RevocationList::parse(&credential)
+        .with_context(|| format!("invalid status list {}", args.list.display()))?
+    else {
+        bail!(
+            "{} is an id list, not a status list credential; edit it directly",
+            args.list.display()
+        );
+    };
+
+    if args.revoked && purpose != "revocation" {
+        bail!(
+            "--revoked needs a revocation list, but this list is for {}",
+            purpose
+        );
+    }
+    if args.suspended && purpose != "suspension" {
+        bail!(
+            "--suspended needs a suspension list, but this list is for {}",
+            purpose
+        );
+    }
+
+    let index_map = args.index_map.as_deref().map(read_index_map).transpose()?;
+    let index = resolve_index(&args.entry, index_map.as_ref())?;
+    set_status_bit(&mut bits, index, !args.active)?;
+
+    credential["credentialSubject"]["encodedList"] = json!(encode_bitstring(&bits)?);
+    let now = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    for field in ["issuanceDate", "validFrom"] {
+        if credential.get(field).is_some() {
+            credential[field] = json!(now);
+        }
+    }
+
+    let out = args.out.as_ref().unwrap_or(&args.list);
+    write_list(out, &credential)?;
+    let state = if args.revoked {
+        "revoked"
+    } else if args.suspended {
+        "suspended"
+    } else {
+        "active"
+    };
+    status!(
+        "{} Marked entry {} {} in {}",
+        style("✓").green(),
+        index,
+        state,
+        out.display()
+    );
+    Ok(())
+}
+
+/// An entry's status list index: the entry itself when it is a number,
+/// otherwise its value in the index map
+fn resolve_index(entry: &str, index_map: Option<&Map<String, Value>>) -> Result<usize> {
+    if let Ok(index) = entry.parse::<usize>() {
+        return Ok(index);
+    }
+    let Some(index_map) = index_map else {
+        bail!(
+            "'{}' is not an index; pass --index-map to look up credential ids",
+            entry
+        );
+    };
+    index_map
+        .get(entry)
+        .and_then(Value::as_u64)
+        .map(|index| index as usize)
+        .with_context(|| format!("credential id '{}' is not in the index map", entry))
+}
+
+fn read_index_map(path: &Path) -> Result<Map<String, Value>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read index map {}", path.display()))?;
+    match serde_json::from_str(&content)
+        .with_context(|| format!("index map {} is not valid JSON", path.display()))?
+    {
+        Value::Object(map) => Ok(map),
+        _ => bail!(
+            "index map {} must be a JSON object of credential id to index",
+            path.display()
+        ),
+    }
+}
+
+fn write_list(path: &Path, credential: &Value) -> Result<()> {
+    let content = serde_json::to_string_pretty(credential)?;
+    fs::write(path, content + "\n")
+        .with_context(|| format!("failed to write status list {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beltic::revocation::{check_revocation, RevocationStatus};
+
+    #[test]
+    fn test_build_and_set_round_trip_through_verify() {
+        let dir = tempfile::tempdir().unwrap();
+        let list = dir.path().join("status-list.json");
+        let index_map = dir.path().join("index-map.json");
+        fs::write(&index_map, r#"{"cred-a": 5, "cred-b": 6}"#).unwrap();
+        let url = "https://issuer.example.com/status/1";
+
+        run_build(&BuildArgs {
+            entries: vec!["3".to_string(), "cred-a".to_string()],
+            url: url.to_string(),
+            issuer: "did:web:issuer.example.com".to_string(),
+            purpose: StatusPurpose::Revocation,
+            size: DEFAULT_LIST_SIZE,
+            index_map: Some(index_map.clone()),
+            out: list.clone(),
+        })
+        .unwrap();
+
+        let status = |index: usize| {
+            let vc = json!({"credentialStatus": {
+                "id": format!("{}#{}", url, index),
+                "type": "StatusList2021Entry",
+                "statusPurpose": "revocation",
+                "statusListIndex": index.to_string(),
+                "statusListCredential": url
+            }});
+            check_revocation(&vc, None, Some(&list)).unwrap().status
+        };
+        assert_eq!(status(3), RevocationStatus::Revoked);
+        assert_eq!(status(5), RevocationStatus::Revoked);
+        assert_eq!(status(6), RevocationStatus::Active);
+
+        let set = |entry: &str, revoked: bool| SetArgs {
+            entry: entry.to_string(),
+            revoked,
+            suspended: false,
+            active: !revoked,
+            list: list.clone(),
+            index_map: Some(index_map.clone()),
+            out: None,
+        };
+        run_set(&set("cred-b", true)).unwrap();
+        run_set(&set("3", false)).unwrap();
+        assert_eq!(status(6), RevocationStatus::Revoked);
+        assert_eq!(status(3), RevocationStatus::Active);
+        assert_eq!(status(5), RevocationStatus::Revoked);
+
+        let mut suspend = set("4", false);
+        suspend.suspended = true;
+        suspend.active = false;
+        assert!(run_set(&suspend).is_err());
+    }
+
+    #[test]
+    fn test_resolve_index() {
+        let map: Map<String, Value> = serde_json::from_str(r#"{"cred-a": 9}"#).unwrap();
+        assert_eq!(resolve_index("12", None).unwrap(), 12);
+        assert_eq!(resolve_index("cred-a", Some(&map)).unwrap(), 9);
+        assert!(resolve_index("cred-a", None).is_err());
+        assert!(resolve_index("cred-z", Some(&map)).is_err());
+    }
+}
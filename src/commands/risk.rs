@@ -0,0 +1,102 @@
+//! Suggest a KYB tier and safety rating from an agent credential's tools and data.
+//!
+//! Usage: beltic risk <credential> [--json]
+//!
+//! Advisory only: the credential is never modified.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+use serde_json::json;
+
+use beltic::manifest::credential::AgentCredential;
+use beltic::manifest::risk::{assess_credential, label, rating_below, tier_below};
+
+use super::credential_id::load_credential_value;
+
+#[derive(Args)]
+pub struct RiskArgs {
+    /// Agent credential (JSON, YAML, or JWS)
+    pub credential: PathBuf,
+
+    /// Output the assessment as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run(args: RiskArgs) -> Result<()> {
+    let path = &args.credential;
+    let value = load_credential_value(path)?;
+    let credential: AgentCredential = serde_json::from_value(value)
+        .with_context(|| format!("{} is not a valid agent credential", path.display()))?;
+
+    let assessment = assess_credential(&credential);
+
+    if args.json {
+        let output = json!({
+            "suggested": assessment,
+            "declared": {
+                "kybTier": credential.kyb_tier_required,
+                "safetyRating": credential.overall_safety_rating,
+            },
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} {}",
+        style("Risk assessment for").cyan().bold(),
+        path.display()
+    );
+    println!();
+
+    let tier_low = tier_below(&credential.kyb_tier_required, &assessment.kyb_tier);
+    let rating_low = rating_below(&credential.overall_safety_rating, &assessment.safety_rating);
+    let mark = |low: bool| {
+        if low {
+            style("below suggestion").yellow().to_string()
+        } else {
+            style("ok").green().to_string()
+        }
+    };
+
+    println!(
+        "  KYB tier:      {} suggested, {} declared ({})",
+        label(&assessment.kyb_tier),
+        label(&credential.kyb_tier_required),
+        mark(tier_low)
+    );
+    println!(
+        "  Safety rating: {} suggested, {} declared ({})",
+        label(&assessment.safety_rating),
+        label(&credential.overall_safety_rating),
+        mark(rating_low)
+    );
+    println!();
+
+    if assessment.findings.is_empty() {
+        println!("No risk rules matched");
+        return Ok(());
+    }
+
+    for finding in &assessment.findings {
+        println!(
+            "  • [{}] {}: {} → at least {}, {}",
+            finding.rule,
+            finding.subject,
+            finding.reason,
+            label(&finding.kyb_tier),
+            label(&finding.safety_rating)
+        );
+    }
+    println!();
+    println!(
+        "{}",
+        style("Advisory only; the credential was not modified.").dim()
+    );
+
+    Ok(())
+}
@@ -1,49 +1,128 @@
+//! Run an agent under the sandbox monitor
+//!
+//! Usage: beltic sandbox run --policy <manifest-or-credential> -- <command...>
+//!
+//! The policy is derived from an agent manifest or credential; the agent's
+//! output is checked against it and a report is written.
+
 use anyhow::{Context, Result};
-use clap::Args;
+use clap::{Args, Subcommand};
 use console::style;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use beltic::estatus;
+use beltic::sandbox::monitor::Severity;
+use beltic::sandbox::report::EXIT_POLICY_VIOLATED;
+use beltic::sandbox::{extract_policy, SandboxMonitor, SandboxPolicy, SandboxReport};
 
-use crate::manifest::schema::AgentManifest;
-use crate::sandbox::{extract_policy, SandboxMonitor, SandboxReport};
+use super::credential_id::load_credential_value;
 
 #[derive(Args)]
 pub struct SandboxArgs {
-    /// Path to agent manifest
-    #[arg(short, long, default_value = "./agent-manifest.json")]
-    pub manifest: PathBuf,
+    #[command(subcommand)]
+    pub command: SandboxCommand,
+}
 
-    /// Command to run the agent (e.g., "node index.js", "python agent.py")
-    #[arg(short, long)]
-    pub command: String,
+#[derive(Subcommand)]
+pub enum SandboxCommand {
+    /// Run an agent command and check its behavior against the declared policy
+    Run(SandboxRunArgs),
+    /// Run a command inside the sandbox network namespace (used by --enforce-network)
+    #[cfg(target_os = "linux")]
+    #[command(name = "netns-exec", hide = true)]
+    NetnsExec(NetnsExecArgs),
+}
 
-    /// Output path for sandbox report
-    #[arg(short, long, default_value = "./sandbox-report.json")]
-    pub output: PathBuf,
+#[derive(Args)]
+pub struct SandboxRunArgs {
+    /// Agent manifest or credential (JSON, YAML, or JWS) to derive the policy from
+    #[arg(
+        short,
+        long,
+        visible_alias = "manifest",
+        default_value = "./agent-manifest.json"
+    )]
+    pub policy: PathBuf,
+
+    /// Output path for sandbox report (default: ./sandbox-report.json or .md)
+    #[arg(short, long, visible_alias = "output")]
+    pub report: Option<PathBuf>,
+
+    /// Report format: json or md
+    #[arg(long, value_name = "FORMAT", default_value = "json")]
+    pub report_format: ReportFormat,
 
     /// Timeout in seconds (optional)
     #[arg(short, long)]
     pub timeout: Option<u64>,
 
+    /// Exit non-zero on violations of at least this severity: low, medium, high or critical
+    #[arg(long, value_name = "SEVERITY", default_value = "low")]
+    pub fail_on: Severity,
+
     /// Show detailed policy information
     #[arg(long)]
     pub show_policy: bool,
+
+    /// Block network access to undeclared domains instead of only detecting
+    /// it (Linux only: needs unshare and user namespaces, or root)
+    #[arg(long)]
+    pub enforce_network: bool,
+
+    /// Command to run the agent, after `--` (e.g. `-- node index.js`)
+    #[arg(last = true, required = true, value_name = "COMMAND")]
+    pub command: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Markdown,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(ReportFormat::Json),
+            "md" | "markdown" => Ok(ReportFormat::Markdown),
+            _ => Err(format!("invalid report format '{}': use json or md", s)),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Args)]
+pub struct NetnsExecArgs {
+    /// Egress proxy socket in the host namespace
+    #[arg(long)]
+    pub socket: PathBuf,
+
+    #[arg(last = true, required = true)]
+    pub command: Vec<String>,
 }
 
 pub fn run(args: SandboxArgs) -> Result<()> {
-    // Load manifest
-    let manifest_content = std::fs::read_to_string(&args.manifest)
-        .with_context(|| format!("failed to read manifest at {}", args.manifest.display()))?;
-    let manifest: AgentManifest =
-        serde_json::from_str(&manifest_content).context("failed to parse manifest JSON")?;
+    match args.command {
+        SandboxCommand::Run(run_args) => run_sandbox(run_args),
+        #[cfg(target_os = "linux")]
+        SandboxCommand::NetnsExec(exec_args) => {
+            let code = beltic::sandbox::enforce::run_helper(&exec_args.socket, &exec_args.command)?;
+            std::process::exit(code)
+        }
+    }
+}
 
-    // Extract policy from manifest
-    let policy = extract_policy(&manifest)?;
+fn run_sandbox(args: SandboxRunArgs) -> Result<()> {
+    let policy = load_policy(&args.policy)?;
 
-    eprintln!(
+    estatus!(
         "[info] Testing agent: {} v{}",
-        manifest.agent_name, manifest.agent_version
+        policy.agent_name,
+        policy.agent_version
     );
-    eprintln!(
+    estatus!(
         "[info] Policy: {} tools, {} file paths, {} prohibited domains",
         policy.tools.len(),
         policy.filesystem.allowed_read_paths.len(),
@@ -54,35 +133,97 @@ pub fn run(args: SandboxArgs) -> Result<()> {
         print_detailed_policy(&policy);
     }
 
+    #[cfg(target_os = "linux")]
+    let proxy = if args.enforce_network {
+        beltic::sandbox::enforce::preflight()?;
+        let proxy = beltic::sandbox::enforce::EgressProxy::start(policy.network.clone())?;
+        estatus!("[info] Network enforcement: egress only to allowed domains, through a proxy");
+        Some(proxy)
+    } else {
+        None
+    };
+    #[cfg(target_os = "linux")]
+    let command = match &proxy {
+        Some(proxy) => proxy.wrap_command(&args.command)?,
+        None => args.command.clone(),
+    };
+    #[cfg(not(target_os = "linux"))]
+    let command = {
+        if args.enforce_network {
+            eprintln!(
+                "[warn] --enforce-network is only supported on Linux; detecting violations from output only"
+            );
+        }
+        args.command.clone()
+    };
+
     // Run agent and monitor
     let mut monitor = SandboxMonitor::new(policy.clone());
-    let exit_code = monitor.run_agent(&args.command, args.timeout)?;
+    let exit_code = monitor.run_agent(&command, args.timeout)?;
+    #[cfg(target_os = "linux")]
+    if let Some(proxy) = proxy {
+        monitor.record(proxy.take_violations(), proxy.take_observations());
+        // Removes the socket directory; the exit below would skip the drop
+        drop(proxy);
+    }
 
     // Generate compliance report
     let violations = monitor.get_violations().to_vec();
     let observations = monitor.get_observations().to_vec();
-    let report = SandboxReport::new(policy, violations, observations, exit_code);
+    let mut report =
+        SandboxReport::new(policy, violations, observations, exit_code).with_fail_on(args.fail_on);
+    report.summary.duration_secs = monitor.get_duration().map(|d| d.as_secs_f64());
 
-    report.save(&args.output)?;
+    let report_path = args.report.clone().unwrap_or_else(|| {
+        PathBuf::from(match args.report_format {
+            ReportFormat::Json => "./sandbox-report.json",
+            ReportFormat::Markdown => "./sandbox-report.md",
+        })
+    });
+    match args.report_format {
+        ReportFormat::Json => report.save(&report_path)?,
+        ReportFormat::Markdown => report.save_markdown(&report_path)?,
+    }
     report.print_summary();
 
-    println!("\nWrote sandbox report to {}", args.output.display());
+    println!("\nWrote sandbox report to {}", report_path.display());
 
-    if report.summary.compliant {
-        println!(
-            "{}",
-            style("Agent is compliant with declared policies")
-                .green()
-                .bold()
-        );
-        Ok(())
-    } else {
-        println!("{}", style("Agent has policy violations").red().bold());
-        std::process::exit(1);
+    let message = match report.exit_code() {
+        0 if report.violations.is_empty() => {
+            style("Agent is compliant with declared policies".to_string()).green()
+        }
+        0 => style(format!(
+            "No violations at or above {}",
+            args.fail_on.as_str()
+        ))
+        .green(),
+        EXIT_POLICY_VIOLATED => style(format!(
+            "Policy violated: {} violation(s) at or above {}",
+            report.violations_at_or_above(args.fail_on),
+            args.fail_on.as_str()
+        ))
+        .red(),
+        _ => style(format!(
+            "Agent failed with exit code {} (no policy violation)",
+            exit_code
+        ))
+        .red(),
+    };
+    println!("{}", message.bold());
+
+    match report.exit_code() {
+        0 => Ok(()),
+        code => std::process::exit(code),
     }
 }
 
-fn print_detailed_policy(policy: &crate::sandbox::SandboxPolicy) {
+/// Build the policy from a manifest, or from a credential (JSON, YAML or JWS)
+fn load_policy(path: &Path) -> Result<SandboxPolicy> {
+    let value = load_credential_value(path)?;
+    extract_policy(&value).with_context(|| format!("failed to load policy from {}", path.display()))
+}
+
+fn print_detailed_policy(policy: &SandboxPolicy) {
     println!();
     println!("{}", style("Security Policy").bold().cyan());
     println!("{}", style("-".repeat(40)).dim());
@@ -0,0 +1,78 @@
+//! Emit an SBOM for an agent, cross-linked with its credential.
+//!
+//! Usage: beltic sbom <credential> [--format cyclonedx|spdx] [--output <path>]
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use console::style;
+
+use beltic::estatus;
+use beltic::manifest::config::BelticConfig;
+use beltic::manifest::fingerprint::fingerprint_external_dependencies;
+use beltic::manifest::resolve_agent_path;
+use beltic::manifest::sbom::{generate, SbomFormat, SbomSubject};
+
+use super::credential_id::load_credential_value;
+
+#[derive(Args)]
+pub struct SbomArgs {
+    /// Agent credential or manifest (JSON, YAML, or JWS) whose agentId and
+    /// systemConfigFingerprint the SBOM references
+    pub credential: PathBuf,
+
+    /// SBOM format: cyclonedx or spdx
+    #[arg(long, default_value = "cyclonedx")]
+    pub format: SbomFormat,
+
+    /// Write the SBOM to a file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Agent subdirectory whose package manifests are read (default: current directory)
+    #[arg(long, value_name = "DIR")]
+    pub agent_path: Option<PathBuf>,
+
+    /// The global `--config` file, read for dependencies instead of the
+    /// nearest .beltic.yaml
+    #[arg(skip)]
+    pub global_config: Option<PathBuf>,
+}
+
+pub fn run(args: SbomArgs) -> Result<()> {
+    let path = &args.credential;
+    let document = load_credential_value(path)?;
+    let subject = SbomSubject::from_document(&document)
+        .with_context(|| format!("{} cannot be referenced by an SBOM", path.display()))?;
+
+    let cwd = std::env::current_dir().context("failed to get current directory")?;
+    let base_dir = match &args.agent_path {
+        Some(agent_path) => cwd.join(resolve_agent_path(&cwd, agent_path)?),
+        None => cwd.clone(),
+    };
+    // Dependencies listed in .beltic.yaml are added to the declared ones
+    let configured = BelticConfig::load(args.global_config.as_deref(), &cwd)?
+        .and_then(|config| config.agent.dependencies)
+        .and_then(|deps| deps.external)
+        .unwrap_or_default();
+    let deps = fingerprint_external_dependencies(&configured, &base_dir)?;
+
+    let sbom = generate(args.format, &subject, &deps);
+    let json = serde_json::to_string_pretty(&sbom)?;
+    match &args.output {
+        Some(output) => {
+            fs::write(output, json + "\n")
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            estatus!(
+                "{} Wrote {} SBOM with {} dependencies to {}",
+                style("✓").green(),
+                args.format,
+                deps.len(),
+                output.display()
+            );
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
@@ -2,23 +2,43 @@
 //!
 //! Provides CLI commands for managing schema caching and updates.
 
-use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
 use console::style;
 
-use crate::schema::{self, SchemaType};
+use beltic::manifest::config::{BelticConfig, SchemaConfig};
+use beltic::schema::{self, Refreshed, SchemaType};
+
+use super::exit;
 
 #[derive(Args)]
 pub struct SchemaArgs {
     #[command(subcommand)]
     pub command: SchemaCommand,
+
+    /// The global `--config` file, which holds the schema pin instead of the
+    /// nearest .beltic.yaml
+    #[arg(skip)]
+    pub global_config: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
 pub enum SchemaCommand {
     /// Show schema cache status
     Status,
+    /// List cached schemas with their versions, timestamps and sizes
+    List,
+    /// Print a cached schema
+    Show {
+        /// Schema to print: `agent` or `developer`, optionally with a version
+        /// (`agent@v2`; default: the active version)
+        #[arg(value_name = "NAME[@VERSION]")]
+        name: String,
+    },
     /// Refresh schemas from GitHub
+    #[command(alias = "update")]
     Refresh {
         /// Refresh only agent schema
         #[arg(long)]
@@ -26,33 +46,95 @@ pub enum SchemaCommand {
         /// Refresh only developer schema
         #[arg(long)]
         developer: bool,
+        /// Fetch this schema version and bump the pin in .beltic.yaml (e.g. v2)
+        #[arg(long, value_name = "VERSION")]
+        to: Option<String>,
+        /// Fetch the latest version even if .beltic.yaml pins an older one
+        #[arg(long)]
+        force: bool,
     },
     /// Clear schema cache
     Clear,
+    /// Import schemas from local files into the cache (for air-gapped machines)
+    Import {
+        /// Schema files to import
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// Treat the files as agent schemas
+        #[arg(long, conflicts_with = "developer")]
+        agent: bool,
+        /// Treat the files as developer schemas
+        #[arg(long)]
+        developer: bool,
+        /// Schema version to store the files under (default: from `$id`)
+        #[arg(long)]
+        version: Option<String>,
+        /// Expected SHA-256 of the files, required for versions this CLI
+        /// has no pinned checksum for
+        #[arg(long, value_name = "HEX")]
+        sha256: Option<String>,
+    },
 }
 
 pub fn run(args: SchemaArgs) -> Result<()> {
+    let config = args.global_config.as_deref();
     match args.command {
-        SchemaCommand::Status => run_status(),
-        SchemaCommand::Refresh { agent, developer } => run_refresh(agent, developer),
+        SchemaCommand::Status => run_status(config),
+        SchemaCommand::List => run_list(config),
+        SchemaCommand::Show { name } => run_show(&name, config),
+        SchemaCommand::Refresh {
+            agent,
+            developer,
+            to,
+            force,
+        } => run_refresh(agent, developer, to.as_deref(), force, config),
         SchemaCommand::Clear => run_clear(),
+        SchemaCommand::Import {
+            files,
+            agent,
+            developer,
+            version,
+            sha256,
+        } => run_import(
+            &files,
+            agent,
+            developer,
+            version.as_deref(),
+            sha256.as_deref(),
+            config,
+        ),
     }
 }
 
-fn run_status() -> Result<()> {
+fn run_status(config: Option<&Path>) -> Result<()> {
     println!("{}", style("Schema Cache Status").cyan().bold());
     println!();
 
-    print_cache_status("Agent", SchemaType::Agent);
-    print_cache_status("Developer", SchemaType::Developer);
+    let version = schema::active_version(config);
+    match schema::pinned_version(config) {
+        Some(pinned) => println!("  Pinned version: {} (.beltic.yaml)", style(&pinned).bold()),
+        None => println!(
+            "  Pinned version: {} (using default {})",
+            style("none").dim(),
+            version
+        ),
+    }
+    println!(
+        "  Latest available: {}",
+        style(schema::latest_available_version()).bold()
+    );
+    println!();
+
+    print_cache_status("Agent", SchemaType::Agent, &version);
+    print_cache_status("Developer", SchemaType::Developer, &version);
 
     Ok(())
 }
 
-fn print_cache_status(name: &str, schema_type: SchemaType) {
-    match schema::cache_status(schema_type) {
+fn print_cache_status(name: &str, schema_type: SchemaType, version: &str) {
+    match schema::cache_status(schema_type, version) {
         Some(status) => {
-            println!("  {}:", style(name).bold());
+            println!("  {} ({}):", style(name).bold(), version);
             println!("    Path: {}", status.path.display());
             if status.exists {
                 let age_str = format_duration(status.age);
@@ -62,6 +144,14 @@ fn print_cache_status(name: &str, schema_type: SchemaType) {
                     style("⚠").yellow()
                 };
                 println!("    Status: {} Cached ({})", valid_icon, age_str);
+                if let Some(sha256) = &status.sha256 {
+                    let note = match schema::pinned_checksum(schema_type, version) {
+                        Some(pinned) if pinned == *sha256 => style("matches pinned").green(),
+                        Some(_) => style("differs from pinned").yellow(),
+                        None => style("no pinned checksum").dim(),
+                    };
+                    println!("    SHA-256: {} ({})", sha256, note);
+                }
                 if !status.valid {
                     println!(
                         "    {}",
@@ -83,6 +173,90 @@ fn print_cache_status(name: &str, schema_type: SchemaType) {
     println!();
 }
 
+fn run_list(config: Option<&Path>) -> Result<()> {
+    let cached = schema::cached_schemas();
+    if cached.is_empty() {
+        println!("No schemas cached.");
+        println!(
+            "{}",
+            style("Run 'beltic schema refresh' to download them; embedded copies are used until then.").dim()
+        );
+        return Ok(());
+    }
+
+    println!("{}", style("Cached Schemas").cyan().bold());
+    println!();
+
+    let active = schema::active_version(config);
+    for entry in &cached {
+        let modified = entry
+            .modified
+            .map(|time| {
+                chrono::DateTime::<chrono::Utc>::from(time)
+                    .format("%Y-%m-%d %H:%M:%S UTC")
+                    .to_string()
+            })
+            .unwrap_or_else(|| "unknown".to_string());
+        let age = entry
+            .modified
+            .and_then(|time| std::time::SystemTime::now().duration_since(time).ok());
+        let marker = if entry.version == active {
+            style("(active)").green().to_string()
+        } else {
+            String::new()
+        };
+        let line = format!(
+            "  {:<10} {:<4} {}  {:>9}  {:>8}  {}",
+            entry.schema_type.name(),
+            entry.version,
+            modified,
+            format_duration(age),
+            format_size(entry.size),
+            marker
+        );
+        println!("{}", line.trim_end());
+    }
+
+    Ok(())
+}
+
+fn run_show(name: &str, config: Option<&Path>) -> Result<()> {
+    let (schema_type, version) = parse_schema_name(name, config)?;
+    let Some(cached) = schema::cached_schema(schema_type, &version)? else {
+        anyhow::bail!(
+            "no cached {} schema for {}; run 'beltic schema refresh' or 'beltic schema import <file>'",
+            schema_type.name(),
+            version
+        );
+    };
+    println!("{}", serde_json::to_string_pretty(&cached)?);
+    Ok(())
+}
+
+/// Parse `agent`, `developer`, or either with `@<version>` (default: the
+/// active version of `config`)
+fn parse_schema_name(name: &str, config: Option<&Path>) -> Result<(SchemaType, String)> {
+    let (name, version) = match name.split_once('@') {
+        Some((name, version)) => (name, Some(version)),
+        None => (name, None),
+    };
+    let schema_type = SchemaType::from_name(name)
+        .with_context(|| format!("unknown schema '{}': expected 'agent' or 'developer'", name))?;
+    let version = match version {
+        Some(version) => schema::normalize_version(version)?,
+        None => schema::active_version(config),
+    };
+    Ok((schema_type, version))
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
 fn format_duration(duration: Option<std::time::Duration>) -> String {
     match duration {
         Some(d) => {
@@ -101,22 +275,135 @@ fn format_duration(duration: Option<std::time::Duration>) -> String {
     }
 }
 
-fn run_refresh(agent_only: bool, developer_only: bool) -> Result<()> {
+fn run_refresh(
+    agent_only: bool,
+    developer_only: bool,
+    to: Option<&str>,
+    force: bool,
+    config: Option<&Path>,
+) -> Result<()> {
+    if schema::is_offline() {
+        anyhow::bail!(
+            "offline mode, cannot fetch schemas. Use 'beltic schema import <file>' to load them from disk."
+        );
+    }
+
+    let pinned = schema::pinned_version(config);
+    let latest = schema::latest_available_version();
+
+    let version = match to {
+        Some(to) => schema::normalize_version(to)?,
+        None if force => latest,
+        None => {
+            if let Some(pinned) = &pinned {
+                if *pinned != latest {
+                    println!(
+                        "{}",
+                        style(format!(
+                            "Schema pinned to {} in .beltic.yaml ({} available). Use --to {} to bump the pin or --force to fetch it anyway.",
+                            pinned, latest, latest
+                        ))
+                        .dim()
+                    );
+                }
+            }
+            schema::active_version(config)
+        }
+    };
+
     let refresh_both = !agent_only && !developer_only;
 
-    if refresh_both || agent_only {
-        print!("Refreshing agent schema... ");
-        match schema::refresh_schema(SchemaType::Agent) {
-            Ok(_) => println!("{}", style("done").green()),
-            Err(e) => println!("{} ({})", style("failed").red(), e),
+    let mut failure = None;
+    for (schema_type, selected) in [
+        (SchemaType::Agent, refresh_both || agent_only),
+        (SchemaType::Developer, refresh_both || developer_only),
+    ] {
+        if !selected {
+            continue;
         }
+        print!("Refreshing {} schema ({})... ", schema_type.name(), version);
+        match schema::refresh_schema(schema_type, &version) {
+            Ok(Refreshed::Updated) => println!("{}", style("done").green()),
+            Ok(Refreshed::UpToDate) => println!("{}", style("already up to date").green()),
+            Err(e) => {
+                println!("{} ({})", style("failed").red(), e);
+                failure.get_or_insert(e);
+            }
+        }
+    }
+
+    // Don't bump the pin to a version that couldn't be fetched
+    if let Some(err) = failure {
+        return exit::reported(exit::code_for(&err));
     }
 
-    if refresh_both || developer_only {
-        print!("Refreshing developer schema... ");
-        match schema::refresh_schema(SchemaType::Developer) {
-            Ok(_) => println!("{}", style("done").green()),
-            Err(e) => println!("{} ({})", style("failed").red(), e),
+    if to.is_some() && pinned.as_deref() != Some(version.as_str()) {
+        println!();
+        match update_pin(&version, config)? {
+            Some(_) => println!(
+                "Pinned schema version updated to {}",
+                style(&version).bold()
+            ),
+            None => println!(
+                "{}",
+                style("No .beltic.yaml found, so the schema version was not pinned.").dim()
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// Write a new `schema.version` pin to `config` or the nearest .beltic.yaml,
+/// returning the file written, or `None` if there is none
+fn update_pin(version: &str, config: Option<&Path>) -> Result<Option<PathBuf>> {
+    let located = match config {
+        Some(path) => Some(path.to_path_buf()),
+        None => BelticConfig::find_path(&std::env::current_dir()?),
+    };
+    let Some(config_path) = located else {
+        return Ok(None);
+    };
+    let mut config = BelticConfig::from_file(&config_path)?;
+
+    config
+        .schema
+        .get_or_insert_with(SchemaConfig::default)
+        .version = Some(version.to_string());
+    config
+        .save_to_file(&config_path)
+        .with_context(|| format!("failed to update {}", config_path.display()))?;
+    Ok(Some(config_path))
+}
+
+fn run_import(
+    files: &[PathBuf],
+    agent: bool,
+    developer: bool,
+    version: Option<&str>,
+    sha256: Option<&str>,
+    config: Option<&Path>,
+) -> Result<()> {
+    let schema_type = if agent {
+        Some(SchemaType::Agent)
+    } else if developer {
+        Some(SchemaType::Developer)
+    } else {
+        None
+    };
+
+    for file in files {
+        print!("Importing {}... ", file.display());
+        match schema::import_schema(file, schema_type, version, sha256, config) {
+            Ok((imported, version)) => println!(
+                "{} ({})",
+                style("done").green(),
+                imported.cache_name(&version)
+            ),
+            Err(e) => {
+                println!("{}", style("failed").red());
+                return Err(e);
+            }
         }
     }
 
@@ -138,3 +425,34 @@ fn run_clear() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_schema_name() {
+        let (schema_type, version) = parse_schema_name("developer@2", None).unwrap();
+        assert_eq!(schema_type, SchemaType::Developer);
+        assert_eq!(version, "v2");
+
+        let (schema_type, _) = parse_schema_name("Agent", None).unwrap();
+        assert_eq!(schema_type, SchemaType::Agent);
+
+        assert!(parse_schema_name("issuer@v1", None).is_err());
+        assert!(parse_schema_name("agent@latest", None).is_err());
+    }
+
+    #[test]
+    fn test_update_pin_adds_missing_pin() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".beltic.yaml");
+        let mut config = BelticConfig::default_standalone();
+        config.schema = None;
+        config.save_to_file(&path).unwrap();
+
+        assert_eq!(update_pin("v2", Some(&path)).unwrap(), Some(path.clone()));
+        let config = BelticConfig::from_file(&path).unwrap();
+        assert_eq!(config.schema_version(), Some("v2"));
+    }
+}
@@ -1,33 +1,94 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fmt, fs,
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
 
 use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
 use clap::Args;
 use console::style;
-use serde_json::Value;
+use serde_json::{json, Value};
+use zeroize::Zeroizing;
 
-use crate::credential::{
-    build_claims, detect_credential_kind, parse_credential_kind, validate_credential,
-    ClaimsOptions, CredentialKind,
+use beltic::credential::{
+    build_claims, check_proof_type, detect_credential_kind, parse_credential_kind,
+    validate_credential_version, ClaimsOptions, CredentialKind, SigningMetadata,
 };
-use crate::crypto::{parse_signature_alg, sign_jws, SignatureAlg};
-
+#[cfg(feature = "azure-kv")]
+use beltic::crypto::azure_kv::AzureKeyVaultSigner;
+#[cfg(feature = "gcp-kms")]
+use beltic::crypto::gcp_kms::GcpKmsSigner;
+use beltic::crypto::jwk::{
+    private_pem_from_jwk, thumbprint_from_private_pem, thumbprint_from_public_pem,
+};
+#[cfg(feature = "kms")]
+use beltic::crypto::kms::KmsSigner;
+use beltic::crypto::{parse_signature_alg, sign_jws, PemSigner, SignatureAlg, Signer};
+use beltic::document::{read_document_with_limit, DocumentFormat, DEFAULT_MAX_PAYLOAD_SIZE};
+use beltic::duration::IsoDuration;
+use beltic::schema::active_version;
+use beltic::{estatus, status};
+
+use super::credential_id::{decode_jwt_header, decode_jwt_payload};
 use super::discovery::{find_credentials, find_private_keys};
 use super::prompts::CommandPrompts;
 
 #[derive(Args)]
 pub struct SignArgs {
     /// Path to the private key (PEM). Auto-discovered if omitted.
-    #[arg(long)]
+    #[arg(long, conflicts_with_all = ["kms_key_id", "azure_key_id", "gcp_key_version"])]
     pub key: Option<PathBuf>,
 
-    /// Algorithm to use for signing (default: EdDSA)
+    /// Path to a private JWK (with "d") to sign with instead of a PEM key,
+    /// e.g. from `keygen --format jwk`. Its kid is used unless --kid is given.
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["key", "kms_key_id", "azure_key_id", "gcp_key_version"]
+    )]
+    pub jwk_key: Option<PathBuf>,
+
+    /// Where the signing key lives: file (default), aws, azure or gcp.
+    /// Implied by the key identifier option given.
+    #[arg(long, value_name = "BACKEND")]
+    pub signer: Option<SignerBackend>,
+
+    /// Sign with this AWS KMS key (key ID, ARN or alias) instead of a key
+    /// file; needs the `kms` feature and an ES256 or ES256K key
+    #[arg(long, value_name = "ARN", conflicts_with_all = ["azure_key_id", "gcp_key_version"])]
+    pub kms_key_id: Option<String>,
+
+    /// Sign with this Azure Key Vault key
+    /// (https://<vault>.vault.azure.net/keys/<name>[/<version>]); needs the
+    /// `azure-kv` feature and an ES256 or ES256K key
+    #[arg(long, value_name = "URL", conflicts_with = "gcp_key_version")]
+    pub azure_key_id: Option<String>,
+
+    /// Sign with this Google Cloud KMS key version
+    /// (projects/.../cryptoKeys/<key>/cryptoKeyVersions/<n>); needs the
+    /// `gcp-kms` feature
+    #[arg(long, value_name = "NAME")]
+    pub gcp_key_version: Option<String>,
+
+    /// Algorithm to use for signing: EdDSA (default), ES256 or ES256K
     #[arg(long, default_value = "EdDSA", value_parser = parse_signature_alg)]
     pub alg: SignatureAlg,
 
-    /// JSON payload file to sign. Prompted if omitted.
+    /// JSON or YAML payload file to sign. Prompted if omitted.
     #[arg(long)]
     pub payload: Option<PathBuf>,
 
+    /// Payload format: json or yaml (default: from the payload extension)
+    #[arg(long)]
+    pub format: Option<DocumentFormat>,
+
+    /// Refuse payload files larger than this many bytes
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_PAYLOAD_SIZE)]
+    pub max_payload_size: u64,
+
     /// Output file for the resulting JWS token. Defaults to {payload}.jwt
     #[arg(long)]
     pub out: Option<PathBuf>,
@@ -48,6 +109,25 @@ pub struct SignArgs {
     #[arg(long, value_name = "AUDIENCE")]
     pub audience: Vec<String>,
 
+    /// Set the JWT exp claim this long after nbf (ISO 8601 duration, e.g.
+    /// P30D) instead of from the credential's expiration date
+    #[arg(long, visible_alias = "expires-in", value_name = "DURATION")]
+    pub valid_for: Option<IsoDuration>,
+
+    /// Set the JWT nbf and iat claims (RFC 3339) instead of from the
+    /// credential's issuance date, or the current time when it has none
+    #[arg(long, value_name = "TIME")]
+    pub not_before: Option<DateTime<Utc>>,
+
+    /// JWT ID (jti claim); defaults to the credential's credentialId, or a
+    /// new UUID when it has none. `verify` requires a jti.
+    #[arg(long)]
+    pub jti: Option<String>,
+
+    /// Record the jti in this ledger file and warn when it was already issued
+    #[arg(long, value_name = "FILE")]
+    pub jti_ledger: Option<PathBuf>,
+
     /// Credential type (agent|developer). Auto-detected when omitted.
     #[arg(long, value_parser = parse_credential_kind)]
     pub credential_type: Option<CredentialKind>,
@@ -56,15 +136,153 @@ pub struct SignArgs {
     #[arg(long)]
     pub skip_schema: bool,
 
+    /// Add a signingMetadata claim (tool version, time, key thumbprint, git commit)
+    #[arg(long)]
+    pub embed_provenance: bool,
+
+    /// Also write the decoded header and payload as pretty JSON for review
+    /// (non-authoritative; verification always uses the compact token)
+    #[arg(long, value_name = "PATH")]
+    pub emit_decoded: Option<PathBuf>,
+
     /// Disable interactive mode
     #[arg(long)]
     pub non_interactive: bool,
+
+    /// The global `--config` file, whose schema pin validation uses
+    #[arg(skip)]
+    pub global_config: Option<PathBuf>,
+}
+
+/// Where the signing key lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignerBackend {
+    /// A PEM key file (`--key`)
+    File,
+    /// AWS KMS (`--kms-key-id`)
+    Aws,
+    /// Azure Key Vault (`--azure-key-id`)
+    Azure,
+    /// Google Cloud KMS (`--gcp-key-version`)
+    Gcp,
+}
+
+impl SignerBackend {
+    /// The option naming the key for this backend
+    fn key_flag(self) -> &'static str {
+        match self {
+            SignerBackend::File => "--key",
+            SignerBackend::Aws => "--kms-key-id",
+            SignerBackend::Azure => "--azure-key-id",
+            SignerBackend::Gcp => "--gcp-key-version",
+        }
+    }
+
+    /// The cargo feature that builds this backend in
+    fn feature(self) -> &'static str {
+        match self {
+            SignerBackend::File => "default",
+            SignerBackend::Aws => "kms",
+            SignerBackend::Azure => "azure-kv",
+            SignerBackend::Gcp => "gcp-kms",
+        }
+    }
+}
+
+impl FromStr for SignerBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "file" | "pem" => Ok(SignerBackend::File),
+            "aws" | "kms" => Ok(SignerBackend::Aws),
+            "azure" => Ok(SignerBackend::Azure),
+            "gcp" => Ok(SignerBackend::Gcp),
+            _ => Err(format!(
+                "unknown signer '{}', expected file, aws, azure or gcp",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SignerBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SignerBackend::File => "file",
+            SignerBackend::Aws => "aws",
+            SignerBackend::Azure => "azure",
+            SignerBackend::Gcp => "gcp",
+        })
+    }
+}
+
+impl SignArgs {
+    /// The backend from `--signer`, or the one implied by the key
+    /// identifier option given
+    fn backend(&self) -> Result<SignerBackend> {
+        let implied = if self.kms_key_id.is_some() {
+            Some(SignerBackend::Aws)
+        } else if self.azure_key_id.is_some() {
+            Some(SignerBackend::Azure)
+        } else if self.gcp_key_version.is_some() {
+            Some(SignerBackend::Gcp)
+        } else {
+            None
+        };
+        match (self.signer, implied) {
+            (Some(signer), Some(implied)) if signer != implied => bail!(
+                "{} cannot be used with --signer {}",
+                implied.key_flag(),
+                signer
+            ),
+            (Some(signer), _) => Ok(signer),
+            (None, implied) => Ok(implied.unwrap_or(SignerBackend::File)),
+        }
+    }
+
+    /// The key identifier for a cloud key service, if one was given
+    fn remote_key_id(&self) -> Option<&str> {
+        self.kms_key_id
+            .as_deref()
+            .or(self.azure_key_id.as_deref())
+            .or(self.gcp_key_version.as_deref())
+    }
+
+    /// Whether a key file (PEM or JWK) was given
+    fn has_key_file(&self) -> bool {
+        self.key.is_some() || self.jwk_key.is_some()
+    }
+
+    /// The `kid` of the --jwk-key JWK, if it has one
+    fn jwk_kid(&self) -> Result<Option<String>> {
+        let Some(path) = &self.jwk_key else {
+            return Ok(None);
+        };
+        let jwk = read_private_jwk(path)?;
+        Ok(jwk.get("kid").and_then(Value::as_str).map(str::to_string))
+    }
+
+    /// Whether the key is held by a cloud key service
+    fn uses_remote_key(&self) -> bool {
+        self.remote_key_id().is_some()
+            || self
+                .signer
+                .is_some_and(|signer| signer != SignerBackend::File)
+    }
 }
 
 pub fn run(args: SignArgs) -> Result<()> {
+    // Reject a --signer without its key before prompting or reading files
+    let backend = args.backend()?;
+    if backend != SignerBackend::File && args.remote_key_id().is_none() {
+        bail!("--signer {} needs {}", backend, backend.key_flag());
+    }
+
     // Determine if we need interactive mode
-    let needs_interactive = (args.key.is_none() || args.payload.is_none() || args.kid.is_none())
-        && !args.non_interactive;
+    let has_key = args.has_key_file() || args.uses_remote_key();
+    let needs_interactive =
+        (!has_key || args.payload.is_none() || args.kid.is_none()) && !args.non_interactive;
 
     if needs_interactive {
         run_interactive(args)
@@ -79,7 +297,7 @@ fn run_interactive(mut args: SignArgs) -> Result<()> {
     prompts.section_header("Beltic Credential Signer")?;
 
     // 1. Key selection (with auto-discovery)
-    if args.key.is_none() {
+    if !args.has_key_file() && !args.uses_remote_key() {
         let private_keys = find_private_keys();
         if private_keys.is_empty() {
             prompts.warn("No private keys found. Generate one with: beltic keygen")?;
@@ -104,14 +322,19 @@ fn run_interactive(mut args: SignArgs) -> Result<()> {
 
     // 3. Key identifier (kid)
     if args.kid.is_none() {
-        // Suggest kid based on key filename
+        // Suggest the JWK's kid, or one based on the key filename
+        let jwk_kid = args.jwk_kid()?;
         let suggested_kid = args
-            .key
-            .as_ref()
-            .and_then(|p| p.file_stem())
-            .and_then(|s| s.to_str())
-            .map(|s| s.trim_end_matches("-private"))
-            .unwrap_or("my-key");
+            .remote_key_id()
+            .or(jwk_kid.as_deref())
+            .unwrap_or_else(|| {
+                args.key
+                    .as_ref()
+                    .and_then(|p| p.file_stem())
+                    .and_then(|s| s.to_str())
+                    .map(|s| s.trim_end_matches("-private"))
+                    .unwrap_or("my-key")
+            });
 
         args.kid = Some(prompts.prompt_string("Key identifier (kid)", Some(suggested_kid))?);
     }
@@ -134,14 +357,16 @@ fn run_interactive(mut args: SignArgs) -> Result<()> {
 fn run_non_interactive(args: SignArgs) -> Result<()> {
     // Auto-discover key if not provided
     let key = if let Some(k) = args.key.as_ref() {
-        k.clone()
+        Some(k.clone())
+    } else if args.uses_remote_key() || args.jwk_key.is_some() {
+        None
     } else {
         let keys = find_private_keys();
         if keys.is_empty() {
             bail!("No private keys found. Generate one with: beltic keygen");
         }
-        eprintln!("[info] Using auto-discovered key: {}", keys[0].display());
-        keys[0].clone()
+        estatus!("[info] Using auto-discovered key: {}", keys[0].display());
+        Some(keys[0].clone())
     };
 
     // Auto-discover payload if not provided
@@ -152,24 +377,31 @@ fn run_non_interactive(args: SignArgs) -> Result<()> {
         if credentials.is_empty() {
             bail!("No credential files found. Create one with: beltic init --credential");
         }
-        eprintln!(
+        estatus!(
             "[info] Using auto-discovered payload: {}",
             credentials[0].display()
         );
         credentials[0].clone()
     };
 
-    // Auto-derive kid from key filename if not provided
+    // Auto-derive kid from the remote key identifier or key filename if not provided
     let kid = if let Some(k) = args.kid.as_ref() {
         k.clone()
+    } else if let Some(key_id) = args.remote_key_id() {
+        estatus!("[info] Using key identifier as kid: {}", key_id);
+        key_id.to_string()
+    } else if let Some(jwk_kid) = args.jwk_kid()? {
+        estatus!("[info] Using the JWK's kid: {}", jwk_kid);
+        jwk_kid
     } else {
         let kid_str = key
-            .file_stem()
+            .as_deref()
+            .and_then(|k| k.file_stem())
             .and_then(|s| s.to_str())
             .map(|s| s.trim_end_matches("-private"))
             .unwrap_or("key-1")
             .to_string();
-        eprintln!("[info] Using auto-derived kid: {}", kid_str);
+        estatus!("[info] Using auto-derived kid: {}", kid_str);
         kid_str
     };
 
@@ -179,10 +411,8 @@ fn run_non_interactive(args: SignArgs) -> Result<()> {
         .clone()
         .unwrap_or_else(|| payload.with_extension("jwt"));
 
-    let payload_content = fs::read_to_string(&payload)
-        .with_context(|| format!("failed to read payload file {}", payload.display()))?;
-    let payload_json: Value =
-        serde_json::from_str(&payload_content).context("payload is not valid JSON")?;
+    let payload_json = read_document_with_limit(&payload, args.format, args.max_payload_size)
+        .with_context(|| format!("failed to load payload file {}", payload.display()))?;
 
     let kind = if let Some(kind) = args.credential_type {
         kind
@@ -193,7 +423,11 @@ fn run_non_interactive(args: SignArgs) -> Result<()> {
     };
 
     if !args.skip_schema {
-        let errors = validate_credential(kind, &payload_json)?;
+        let errors = validate_credential_version(
+            kind,
+            &payload_json,
+            &active_version(args.global_config.as_deref()),
+        )?;
         if !errors.is_empty() {
             let mut message = String::from("schema validation failed:\n");
             for err in errors {
@@ -203,21 +437,28 @@ fn run_non_interactive(args: SignArgs) -> Result<()> {
         }
     }
 
-    let claims = build_claims(
+    check_proof_type(&payload_json, args.alg)?;
+
+    let mut claims = build_claims(
         &payload_json,
         kind,
         ClaimsOptions {
             issuer: args.issuer.as_deref(),
             subject: args.subject.as_deref(),
             audience: &args.audience,
+            valid_for: args.valid_for,
+            jti: args.jti.as_deref(),
+            not_before: args.not_before,
         },
     )?;
+    let (signer, thumbprint) = open_signer(&args, key.as_deref(), &kid)?;
+    if let Some(thumbprint) = thumbprint {
+        SigningMetadata::new(thumbprint, git_commit(&payload)).embed(&mut claims)?;
+    }
 
     let token = sign_jws(
         &claims,
-        &key,
-        args.alg,
-        Some(kid.clone()),
+        signer.as_ref(),
         kind.media_type(),
         Some("application/json"),
     )?;
@@ -230,8 +471,15 @@ fn run_non_interactive(args: SignArgs) -> Result<()> {
     }
     fs::write(&out, &token)
         .with_context(|| format!("failed to write token to {}", out.display()))?;
+    if let Some(ledger) = &args.jti_ledger {
+        record_jti(ledger, &claims, &out)?;
+    }
+    if let Some(decoded) = &args.emit_decoded {
+        write_decoded(&token, &out, decoded)?;
+        estatus!("[info] Wrote decoded view to {}", decoded.display());
+    }
 
-    println!(
+    status!(
         "Wrote {} JWS (alg={}, typ={}) to {}",
         kind.display_name(),
         args.alg,
@@ -242,9 +490,9 @@ fn run_non_interactive(args: SignArgs) -> Result<()> {
 }
 
 fn do_sign(args: &SignArgs, prompts: &CommandPrompts) -> Result<()> {
-    let key = args.key.as_ref().ok_or_else(|| {
-        anyhow!("private key is required; rerun without --non-interactive to select one")
-    })?;
+    if !args.has_key_file() && !args.uses_remote_key() {
+        bail!("private key is required; rerun without --non-interactive to select one");
+    }
     let payload_path = args.payload.as_ref().ok_or_else(|| {
         anyhow!("payload path is required; rerun without --non-interactive to select one")
     })?;
@@ -255,10 +503,9 @@ fn do_sign(args: &SignArgs, prompts: &CommandPrompts) -> Result<()> {
         anyhow!("key identifier (kid) is required; rerun without --non-interactive to set one")
     })?;
 
-    let payload_content = fs::read_to_string(payload_path)
-        .with_context(|| format!("failed to read payload file {}", payload_path.display()))?;
-    let payload_json: Value =
-        serde_json::from_str(&payload_content).context("payload is not valid JSON")?;
+    let payload_json =
+        read_document_with_limit(payload_path, args.format, args.max_payload_size)
+            .with_context(|| format!("failed to load payload file {}", payload_path.display()))?;
 
     let kind = if let Some(kind) = args.credential_type {
         kind
@@ -275,7 +522,11 @@ fn do_sign(args: &SignArgs, prompts: &CommandPrompts) -> Result<()> {
 
     if !args.skip_schema {
         prompts.info("Validating credential schema...")?;
-        let errors = validate_credential(kind, &payload_json)?;
+        let errors = validate_credential_version(
+            kind,
+            &payload_json,
+            &active_version(args.global_config.as_deref()),
+        )?;
         if !errors.is_empty() {
             let mut message = String::from("schema validation failed:\n");
             for err in errors {
@@ -286,27 +537,46 @@ fn do_sign(args: &SignArgs, prompts: &CommandPrompts) -> Result<()> {
         prompts.info("Schema validation passed")?;
     }
 
-    let claims = build_claims(
+    check_proof_type(&payload_json, args.alg)?;
+
+    let mut claims = build_claims(
         &payload_json,
         kind,
         ClaimsOptions {
             issuer: args.issuer.as_deref(),
             subject: args.subject.as_deref(),
             audience: &args.audience,
+            valid_for: args.valid_for,
+            jti: args.jti.as_deref(),
+            not_before: args.not_before,
         },
     )?;
+    let (signer, thumbprint) = open_signer(args, args.key.as_deref(), kid)?;
+    if let Some(thumbprint) = thumbprint {
+        let metadata = SigningMetadata::new(thumbprint, git_commit(payload_path));
+        prompts.info(&format!(
+            "Embedding provenance (key thumbprint {})",
+            metadata.signer_key_thumbprint
+        ))?;
+        metadata.embed(&mut claims)?;
+    }
 
+    let key_label = match (
+        args.remote_key_id(),
+        args.key.as_ref().or(args.jwk_key.as_ref()),
+    ) {
+        (Some(key_id), _) => format!("{} ({})", key_id, args.backend()?),
+        (None, Some(key)) => key.display().to_string(),
+        (None, None) => format!("none ({})", args.backend()?),
+    };
     prompts.info(&format!(
         "Signing with {} using key: {}",
-        args.alg,
-        key.display()
+        args.alg, key_label
     ))?;
 
     let token = sign_jws(
         &claims,
-        key,
-        args.alg,
-        Some(kid.clone()),
+        signer.as_ref(),
         kind.media_type(),
         Some("application/json"),
     )?;
@@ -319,14 +589,257 @@ fn do_sign(args: &SignArgs, prompts: &CommandPrompts) -> Result<()> {
     }
     fs::write(out, &token)
         .with_context(|| format!("failed to write token to {}", out.display()))?;
+    if let Some(ledger) = &args.jti_ledger {
+        record_jti(ledger, &claims, out)?;
+    }
+    if let Some(decoded) = &args.emit_decoded {
+        write_decoded(&token, out, decoded)?;
+    }
 
-    println!();
-    println!("{}", style("Signed successfully!").green().bold());
-    println!();
-    println!("  {} {}", style("Type:").dim(), kind.display_name());
-    println!("  {} {}", style("Algorithm:").dim(), args.alg);
-    println!("  {} {}", style("Key ID:").dim(), kid);
-    println!("  {} {}", style("Output:").dim(), out.display());
+    status!();
+    status!("{}", style("Signed successfully!").green().bold());
+    status!();
+    status!("  {} {}", style("Type:").dim(), kind.display_name());
+    status!("  {} {}", style("Algorithm:").dim(), args.alg);
+    status!("  {} {}", style("Key ID:").dim(), kid);
+    status!("  {} {}", style("Output:").dim(), out.display());
+    if let Some(decoded) = &args.emit_decoded {
+        status!(
+            "  {} {} (for review only)",
+            style("Decoded:").dim(),
+            decoded.display()
+        );
+    }
 
     Ok(())
 }
+
+/// Append the token's `jti` to the ledger at `path` (one JSON object per
+/// line), warning when an earlier entry already used it
+fn record_jti(path: &Path, claims: &Value, out: &Path) -> Result<()> {
+    let jti = claims
+        .get("jti")
+        .and_then(Value::as_str)
+        .context("claims have no jti")?;
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => {
+            return Err(err)
+                .with_context(|| format!("failed to read jti ledger {}", path.display()))
+        }
+    };
+    for (number, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: Value = serde_json::from_str(line).with_context(|| {
+            format!(
+                "jti ledger {} line {} is not JSON",
+                path.display(),
+                number + 1
+            )
+        })?;
+        if entry.get("jti").and_then(Value::as_str) == Some(jti) {
+            estatus!(
+                "[warn] jti {} was already issued at {} for {} (jti ledger {})",
+                jti,
+                entry
+                    .get("signedAt")
+                    .and_then(Value::as_str)
+                    .unwrap_or("<unknown>"),
+                entry
+                    .get("token")
+                    .and_then(Value::as_str)
+                    .unwrap_or("<unknown>"),
+                path.display()
+            );
+        }
+    }
+
+    let entry = json!({
+        "jti": jti,
+        "signedAt": Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        "token": out.display().to_string(),
+    });
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open jti ledger {}", path.display()))?;
+    writeln!(file, "{}", entry)
+        .with_context(|| format!("failed to write jti ledger {}", path.display()))
+}
+
+/// The signer for the selected backend, and the key's JWK thumbprint when
+/// `--embed-provenance` needs it
+fn open_signer(
+    args: &SignArgs,
+    key: Option<&Path>,
+    kid: &str,
+) -> Result<(Box<dyn Signer>, Option<String>)> {
+    let backend = args.backend()?;
+    if backend != SignerBackend::File {
+        let key_id = args
+            .remote_key_id()
+            .with_context(|| format!("--signer {} needs {}", backend, backend.key_flag()))?;
+        let signer = remote_signer(backend, key_id, args.alg, kid)?;
+        let thumbprint = if args.embed_provenance {
+            let pem = signer
+                .public_key_pem()?
+                .with_context(|| format!("{} signer did not provide its public key", backend))?;
+            Some(thumbprint_from_public_pem(&pem, args.alg)?)
+        } else {
+            None
+        };
+        return Ok((signer, thumbprint));
+    }
+
+    if let Some(path) = &args.jwk_key {
+        let (alg, pem) = private_pem_from_jwk(&read_private_jwk(path)?)
+            .with_context(|| format!("invalid private JWK {}", path.display()))?;
+        if alg != args.alg {
+            bail!(
+                "{} is an {} key; pass --alg {}",
+                path.display(),
+                alg.key_type(),
+                alg
+            );
+        }
+        let signer = PemSigner::from_pem(&pem, alg, Some(kid.to_string()))?;
+        let thumbprint = if args.embed_provenance {
+            Some(thumbprint_from_private_pem(&pem, alg)?)
+        } else {
+            None
+        };
+        return Ok((Box::new(signer), thumbprint));
+    }
+
+    let key = key.context("private key is required")?;
+    let signer = PemSigner::from_file(key, args.alg, Some(kid.to_string()))?;
+    let thumbprint = if args.embed_provenance {
+        let pem = Zeroizing::new(
+            fs::read_to_string(key)
+                .with_context(|| format!("failed to read private key {}", key.display()))?,
+        );
+        Some(thumbprint_from_private_pem(&pem, args.alg)?)
+    } else {
+        None
+    };
+    Ok((Box::new(signer), thumbprint))
+}
+
+fn read_private_jwk(path: &Path) -> Result<Value> {
+    let content = Zeroizing::new(
+        fs::read_to_string(path)
+            .with_context(|| format!("failed to read private JWK {}", path.display()))?,
+    );
+    serde_json::from_str(&content)
+        .with_context(|| format!("private JWK {} is not valid JSON", path.display()))
+}
+
+/// A signer backed by a cloud key service, if this build includes it
+#[cfg_attr(
+    not(all(feature = "kms", feature = "azure-kv", feature = "gcp-kms")),
+    allow(unused_variables)
+)]
+fn remote_signer(
+    backend: SignerBackend,
+    key_id: &str,
+    alg: SignatureAlg,
+    kid: &str,
+) -> Result<Box<dyn Signer>> {
+    let kid = Some(kid.to_string());
+    match backend {
+        #[cfg(feature = "kms")]
+        SignerBackend::Aws => Ok(Box::new(KmsSigner::new(key_id, alg, kid)?)),
+        #[cfg(feature = "azure-kv")]
+        SignerBackend::Azure => Ok(Box::new(AzureKeyVaultSigner::new(key_id, alg, kid)?)),
+        #[cfg(feature = "gcp-kms")]
+        SignerBackend::Gcp => Ok(Box::new(GcpKmsSigner::new(key_id, alg, kid)?)),
+        _ => bail!(
+            "--signer {} needs beltic built with the `{}` feature (cargo install beltic --features {})",
+            backend,
+            backend.feature(),
+            backend.feature()
+        ),
+    }
+}
+
+/// Commit of the git repository containing the payload, for
+/// `--embed-provenance`
+fn git_commit(payload: &Path) -> Option<String> {
+    let dir = payload
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!commit.is_empty()).then_some(commit)
+}
+
+/// Notice at the top of `--emit-decoded` files
+const DECODED_NOTICE: &str =
+    "NON-AUTHORITATIVE: decoded view of a signed token, for human review. \
+    It is not signed and may be edited without detection; verify the compact token instead.";
+
+/// Write the token's header and payload as pretty JSON next to the token.
+/// The compact token stays the only thing that gets verified.
+fn write_decoded(token: &str, token_path: &Path, decoded_path: &Path) -> Result<()> {
+    if decoded_path == token_path {
+        bail!(
+            "--emit-decoded must not overwrite the token at {}",
+            token_path.display()
+        );
+    }
+
+    let decoded = serde_json::json!({
+        "_notice": DECODED_NOTICE,
+        "token": token_path.display().to_string(),
+        "header": decode_jwt_header(token)?,
+        "payload": decode_jwt_payload(token)?,
+    });
+
+    if let Some(parent) = decoded_path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create directory {}", parent.display()))?;
+        }
+    }
+    fs::write(decoded_path, serde_json::to_string_pretty(&decoded)? + "\n")
+        .with_context(|| format!("failed to write decoded view to {}", decoded_path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_jti_appends_to_ledger() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = dir.path().join("jti-ledger.jsonl");
+        let claims = json!({"jti": "urn:jti:1"});
+        record_jti(&ledger, &claims, Path::new("a.jwt")).unwrap();
+        // Reuse only warns
+        record_jti(&ledger, &claims, Path::new("b.jwt")).unwrap();
+
+        let content = fs::read_to_string(&ledger).unwrap();
+        let entries: Vec<Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["jti"], "urn:jti:1");
+        assert_eq!(entries[1]["token"], "b.jwt");
+
+        fs::write(&ledger, "not json\n").unwrap();
+        assert!(record_jti(&ledger, &claims, Path::new("c.jwt")).is_err());
+    }
+}
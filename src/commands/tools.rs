@@ -0,0 +1,230 @@
+//! Revise the tools declared in an existing agent credential.
+//!
+//! Usage: beltic tools edit <credential> [--output <path>] [--force]
+
+use std::{fs, path::Path, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, Subcommand};
+use console::style;
+use serde_json::{json, Value};
+
+use beltic::credential::{detect_credential_kind, schema_violations_version, CredentialKind};
+use beltic::document::write_document;
+use beltic::manifest::credential::AssuranceSource;
+use beltic::manifest::prompts::InteractivePrompts;
+use beltic::manifest::schema::Tool;
+use beltic::schema::active_version;
+use beltic::status;
+
+use super::credential_id::{is_jwt, parse_credential_value};
+
+#[derive(Args)]
+pub struct ToolsArgs {
+    #[command(subcommand)]
+    pub command: ToolsCommand,
+
+    /// The global `--config` file, whose schema pin validation uses
+    #[arg(skip)]
+    pub global_config: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+pub enum ToolsCommand {
+    /// Add, modify or remove tools in an agent credential interactively
+    Edit {
+        /// Agent credential (JSON or YAML; a signed JWS needs --force)
+        credential: PathBuf,
+
+        /// Write the edited credential here instead of over the input
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Edit the payload of a signed JWS, discarding its signature
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+pub fn run(args: ToolsArgs) -> Result<()> {
+    match args.command {
+        ToolsCommand::Edit {
+            credential,
+            output,
+            force,
+        } => edit(&credential, output, force, args.global_config.as_deref()),
+    }
+}
+
+fn edit(path: &Path, output: Option<PathBuf>, force: bool, config: Option<&Path>) -> Result<()> {
+    let (mut value, signed) = load_credential(path, force)?;
+    let tools = current_tools(&value)?;
+
+    // A JWS is never overwritten with JSON unless asked to
+    let output = match output {
+        Some(output) => output,
+        None if signed => path.with_extension("json"),
+        None => path.to_path_buf(),
+    };
+
+    let prompts = InteractivePrompts::new();
+    let Some(tools) = prompts.edit_tools(tools)? else {
+        println!("No changes saved");
+        return Ok(());
+    };
+
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    set_tools(&mut value, &tools, &today)?;
+
+    let violations =
+        schema_violations_version(CredentialKind::Agent, &value, &active_version(config))?;
+    if !violations.is_empty() {
+        for violation in &violations {
+            let location = if violation.pointer.is_empty() {
+                "<root>"
+            } else {
+                violation.pointer.as_str()
+            };
+            eprintln!("  {} {}: {}", style("✗").red(), location, violation.message);
+        }
+        bail!(
+            "edited credential failed schema validation ({} errors); {} was not written",
+            violations.len(),
+            output.display()
+        );
+    }
+
+    write_document(&output, &value, None)?;
+    status!(
+        "{} Saved {} tools to {}",
+        style("✓").green(),
+        tools.len(),
+        output.display()
+    );
+    if signed {
+        println!(
+            "{}",
+            style("The signature no longer applies; sign the credential again with `beltic sign`.")
+                .yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// Read an agent credential, returning it and whether it came from a JWS
+fn load_credential(path: &Path, force: bool) -> Result<(Value, bool)> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+
+    let signed = is_jwt(&content);
+    if signed && !force {
+        bail!(
+            "{} is a signed credential; editing its tools invalidates the signature. Use --force to edit the payload and re-sign it afterwards.",
+            path.display()
+        );
+    }
+    let value = parse_credential_value(path, &content)?;
+
+    if detect_credential_kind(&value) != Some(CredentialKind::Agent) {
+        bail!("{} is not an agent credential", path.display());
+    }
+    Ok((value, signed))
+}
+
+fn current_tools(credential: &Value) -> Result<Vec<Tool>> {
+    match credential.get("toolsList") {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(tools) => serde_json::from_value(tools.clone()).context("invalid toolsList"),
+    }
+}
+
+/// Replace `toolsList` and stamp `toolsLastAudited`; an empty list removes both.
+/// Missing tool abuse metrics, which the schema requires once tools are
+/// declared, get the self-attested placeholders `init` uses.
+fn set_tools(credential: &mut Value, tools: &[Tool], today: &str) -> Result<()> {
+    let fields = credential
+        .as_object_mut()
+        .context("credential must be a JSON object")?;
+    if tools.is_empty() {
+        fields.remove("toolsList");
+        fields.remove("toolsLastAudited");
+    } else {
+        fields.insert("toolsList".to_string(), serde_json::to_value(tools)?);
+        fields.insert(
+            "toolsLastAudited".to_string(),
+            Value::String(today.to_string()),
+        );
+        let placeholders = [
+            ("toolAbuseRobustnessScore", json!(0.0)),
+            ("toolAbuseBenchmarkName", json!("self-evaluation")),
+            ("toolAbuseBenchmarkVersion", json!("0.0.0")),
+            ("toolAbuseEvaluationDate", json!(today)),
+            (
+                "toolAbuseAssuranceSource",
+                serde_json::to_value(AssuranceSource::SelfAttested)?,
+            ),
+        ];
+        for (key, value) in placeholders {
+            fields.entry(key).or_insert(value);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beltic::credential::schema_violations;
+    use beltic::manifest::schema::RiskCategory;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_set_tools_stamps_audit_date() {
+        let fixture = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/agent-valid.json"
+        );
+        let (mut value, signed) = load_credential(Path::new(fixture), false).unwrap();
+        assert!(!signed);
+        let fields = value.as_object_mut().unwrap();
+        fields.retain(|key, _| !key.starts_with("toolAbuse"));
+        fields.remove("toolsList");
+
+        let mut tools = current_tools(&value).unwrap();
+        tools.push(Tool {
+            tool_id: "refund".to_string(),
+            tool_name: "Refund".to_string(),
+            tool_description: "Issue a refund to a customer".to_string(),
+            risk_category: RiskCategory::Financial,
+            risk_subcategory: "financial_transaction".to_string(),
+            requires_auth: true,
+            requires_human_approval: true,
+            mitigations: None,
+        });
+        set_tools(&mut value, &tools, "2025-06-01").unwrap();
+        assert_eq!(value["toolsLastAudited"], "2025-06-01");
+        assert_eq!(value["toolAbuseAssuranceSource"], "self");
+        assert_eq!(current_tools(&value).unwrap().len(), tools.len());
+        assert!(schema_violations(CredentialKind::Agent, &value)
+            .unwrap()
+            .is_empty());
+
+        set_tools(&mut value, &[], "2025-06-01").unwrap();
+        assert!(value.get("toolsList").is_none());
+        assert!(value.get("toolsLastAudited").is_none());
+    }
+
+    #[test]
+    fn test_signed_credential_requires_force() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("agent.jwt");
+        fs::write(&path, "eyJhbGciOiJFZERTQSJ9.eyJ2YyI6e319.c2ln").unwrap();
+
+        let err = load_credential(&path, false).unwrap_err();
+        assert!(err.to_string().contains("--force"));
+        // With --force the payload is read, but `{}` is no agent credential
+        let err = load_credential(&path, true).unwrap_err();
+        assert!(err.to_string().contains("not an agent credential"));
+    }
+}
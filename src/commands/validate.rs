@@ -0,0 +1,107 @@
+//! Validate an agent manifest or credential file.
+//!
+//! Usage: beltic validate [agent-manifest.json] [--format text|sarif] [--output report.sarif]
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use serde_json::Value;
+
+use beltic::credential::{detect_credential_kind, schema_violations_version};
+use beltic::document::{parse_document, DocumentFormat};
+use beltic::manifest::sarif::to_sarif;
+use beltic::manifest::schema::AgentManifest;
+use beltic::manifest::validator::{
+    format_validation_summary, validate_manifest, ValidationResult, RULE_SCHEMA_VIOLATION,
+};
+use beltic::schema::active_version;
+
+use super::exit;
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    /// Path to the manifest or credential (JSON or YAML)
+    #[arg(default_value = "agent-manifest.json")]
+    pub file: PathBuf,
+
+    /// Output format: text (default) or sarif
+    #[arg(long, default_value = "text")]
+    pub format: ValidateFormat,
+
+    /// Write the report to a file instead of stdout
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
+
+    /// The global `--config` file, whose schema pin validation uses
+    #[arg(skip)]
+    pub global_config: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidateFormat {
+    Text,
+    Sarif,
+}
+
+impl std::str::FromStr for ValidateFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(ValidateFormat::Text),
+            "sarif" => Ok(ValidateFormat::Sarif),
+            _ => Err(format!("invalid format '{}': use 'text' or 'sarif'", s)),
+        }
+    }
+}
+
+pub fn run(args: ValidateArgs) -> Result<()> {
+    let source = fs::read_to_string(&args.file)
+        .with_context(|| format!("failed to read {}", args.file.display()))?;
+    let value = parse_document(&source, DocumentFormat::resolve(&args.file, None))
+        .with_context(|| format!("failed to parse {}", args.file.display()))?;
+
+    let result = validate_document(&value, &active_version(args.global_config.as_deref()))?;
+
+    let report = match args.format {
+        ValidateFormat::Text => format_validation_summary(&result),
+        ValidateFormat::Sarif => {
+            let uri = args.file.to_string_lossy().replace('\\', "/");
+            let sarif = to_sarif(&result, &uri, Some(&source));
+            format!("{}\n", serde_json::to_string_pretty(&sarif)?)
+        }
+    };
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, &report)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            eprintln!("Wrote validation report to {}", path.display());
+        }
+        None => print!("{}", report),
+    }
+
+    if !result.is_valid {
+        return exit::reported(exit::CHECK_FAILED);
+    }
+
+    Ok(())
+}
+
+/// Validate a manifest with the manifest rules, or a credential against its
+/// schema `version`
+fn validate_document(value: &Value, version: &str) -> Result<ValidationResult> {
+    if let Ok(manifest) = serde_json::from_value::<AgentManifest>(value.clone()) {
+        return Ok(validate_manifest(&manifest));
+    }
+
+    let kind = detect_credential_kind(value)
+        .context("file is neither an agent manifest nor a recognized credential")?;
+
+    let mut result = ValidationResult::new();
+    for violation in schema_violations_version(kind, value, version)? {
+        result.add_error_at(RULE_SCHEMA_VIOLATION, &violation.pointer, violation.message);
+    }
+    Ok(result)
+}
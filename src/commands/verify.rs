@@ -1,17 +1,34 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
 use clap::Args;
 use console::style;
 use serde_json::Value;
 
-use crate::credential::{
-    credential_kind_from_typ, detect_credential_kind, parse_credential_kind, validate_credential,
-    CredentialKind,
+use beltic::credential::{
+    check_proof_type, credential_kind_from_typ, detect_credential_kind, parse_credential_kind,
+    validate_credential_version, CredentialKind, SigningMetadata, AGENT_TYP, DEVELOPER_TYP,
+    SIGNING_METADATA_CLAIM,
+};
+use beltic::crypto::jwk::{public_pem_from_jwk, select_jwk, thumbprint_from_public_pem};
+use beltic::crypto::verifier::LEEWAY_SECS;
+use beltic::crypto::{
+    decode_jws_header, parse_signature_alg, verify_jws_signature, verify_jws_with_allowed_algs,
+    verify_jws_with_pem, JwsHeader, SignatureAlg, VerifiedToken,
 };
-use crate::crypto::{verify_jws, VerifiedToken};
+use beltic::document::{check_payload_size, DEFAULT_MAX_PAYLOAD_SIZE};
+use beltic::estatus;
+use beltic::revocation::{check_revocation, RevocationCheck, RevocationStatus};
+use beltic::schema::{active_version, is_offline};
 
-use super::discovery::{find_public_keys, find_tokens};
+use super::credential_id::decode_jwt_payload;
+use super::discovery::{find_public_keys, find_tokens, keys_for_alg};
+use super::exit;
 use super::prompts::CommandPrompts;
 
 #[derive(Args)]
@@ -20,7 +37,12 @@ pub struct VerifyArgs {
     #[arg(long)]
     pub key: Option<PathBuf>,
 
-    /// Path to the JWS token or the token string itself. Auto-discovered if omitted.
+    /// JWK Set (or single JWK) file; the key matching the token's kid is used
+    #[arg(long, value_name = "FILE", conflicts_with = "key")]
+    pub jwks: Option<PathBuf>,
+
+    /// Path or http(s) URL of the JWS token, or the token string itself.
+    /// Auto-discovered if omitted.
     #[arg(long)]
     pub token: Option<String>,
 
@@ -36,18 +58,83 @@ pub struct VerifyArgs {
     #[arg(long, value_parser = parse_credential_kind)]
     pub credential_type: Option<CredentialKind>,
 
+    /// Fail when the typ header is missing; by default only an unknown or
+    /// mismatched typ fails. The typ must be the Beltic media type for the
+    /// credential kind (application/beltic-agent+jwt or
+    /// application/beltic-developer+jwt).
+    #[arg(long)]
+    pub require_typ: bool,
+
+    /// Only accept tokens signed with these algorithms (e.g. ES256,EdDSA;
+    /// repeatable). Others are rejected before the signature is checked.
+    #[arg(
+        long,
+        value_name = "ALG",
+        value_delimiter = ',',
+        value_parser = parse_signature_alg
+    )]
+    pub allow_alg: Vec<SignatureAlg>,
+
+    /// Refuse tokens whose decoded payload is larger than this many bytes
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_PAYLOAD_SIZE)]
+    pub max_payload_size: u64,
+
     /// Skip JSON Schema validation
     #[arg(long)]
     pub skip_schema: bool,
 
+    /// After successful verification, print only the standard JWT claims
+    /// (iss, sub, jti, nbf, exp, aud) as JSON instead of the credential
+    #[arg(long, alias = "print-claims-only")]
+    pub claims_only: bool,
+
+    /// Look up the credential in its revocation list (credentialStatus entry
+    /// or revocationListUrl) and fail if it is revoked or suspended. With
+    /// --offline the cached copy of the list is used.
+    #[arg(long)]
+    pub check_revocation: bool,
+
+    /// Check revocation against this local list instead of fetching it
+    /// (implies --check-revocation)
+    #[arg(long, value_name = "FILE")]
+    pub revocation_list: Option<PathBuf>,
+
+    /// Developer credential (JWS file or URL) that the agent credential's
+    /// developerCredentialId must name; its signature and expiry are checked too
+    #[arg(long, value_name = "FILE|URL")]
+    pub developer_credential: Option<String>,
+
+    /// Public key (PEM) the developer credential was signed with. Without
+    /// it the developer credential's signature isn't checked and the link is
+    /// reported as unverified.
+    #[arg(long, value_name = "KEY", requires = "developer_credential")]
+    pub developer_key: Option<PathBuf>,
+
+    /// After the result, print a review summary: algorithm strength, key
+    /// size, validity window and whether schema validation ran. Exit codes
+    /// are unchanged.
+    #[arg(long, conflicts_with = "claims_only")]
+    pub summary: bool,
+
+    /// When verification fails, run every check anyway (signature, typ,
+    /// claims, validity window, audience, schema, ...) and print a
+    /// pass/fail checklist instead of stopping at the first failure
+    #[arg(long)]
+    pub explain_failure: bool,
+
     /// Disable interactive mode
     #[arg(long)]
     pub non_interactive: bool,
+
+    /// The global `--config` file, whose schema pin validation uses
+    #[arg(skip)]
+    pub global_config: Option<PathBuf>,
 }
 
 pub fn run(args: VerifyArgs) -> Result<()> {
     // Determine if we need interactive mode
-    let needs_interactive = (args.key.is_none() || args.token.is_none()) && !args.non_interactive;
+    let has_key = args.key.is_some() || args.jwks.is_some();
+    let needs_interactive = (!has_key || args.token.is_none()) && !args.non_interactive;
 
     if needs_interactive {
         run_interactive(args)
@@ -75,8 +162,21 @@ fn run_interactive(mut args: VerifyArgs) -> Result<()> {
     }
 
     // 2. Public key selection (with auto-discovery)
-    if args.key.is_none() {
-        let public_keys = find_public_keys();
+    if args.key.is_none() && args.jwks.is_none() {
+        let mut public_keys = find_public_keys();
+        // Only offer keys that fit the token's alg, when it can be read
+        let token_alg = args
+            .token
+            .as_deref()
+            .and_then(|token| load_token(token, args.max_payload_size).ok())
+            .and_then(|token| token_alg(&token).ok());
+        if let Some(alg) = token_alg {
+            let discovered = public_keys.len();
+            public_keys = keys_for_alg(public_keys, alg);
+            if discovered > 0 && public_keys.is_empty() {
+                prompts.warn(&no_key_for_alg(alg))?;
+            }
+        }
         if public_keys.is_empty() {
             prompts.warn("No public keys found.")?;
             let path = prompts.prompt_path("Enter public key path", None)?;
@@ -99,27 +199,34 @@ fn run_non_interactive(args: VerifyArgs) -> Result<()> {
         if tokens.is_empty() {
             bail!("No token files (.jwt) found.");
         }
-        eprintln!(
+        estatus!(
             "[info] Using auto-discovered token: {}",
             tokens[0].display()
         );
         tokens[0].display().to_string()
     };
 
+    let token = load_token(&token_input, args.max_payload_size)?;
+    check_token_payload_size(token.trim(), args.max_payload_size)?;
+
     // Auto-discover public key if not provided
-    let key = if let Some(k) = args.key.as_ref() {
-        k.clone()
+    let key = if let Some(jwks) = args.jwks.as_ref() {
+        VerificationKey::from_jwks(jwks, token.trim())?
+    } else if let Some(k) = args.key.as_ref() {
+        VerificationKey::from_pem_file(k)?
     } else {
         let keys = find_public_keys();
         if keys.is_empty() {
             bail!("No public keys found.");
         }
-        eprintln!("[info] Using auto-discovered key: {}", keys[0].display());
-        keys[0].clone()
+        let alg = token_alg(token.trim())?;
+        let Some(path) = keys_for_alg(keys, alg).into_iter().next() else {
+            bail!("{}", no_key_for_alg(alg));
+        };
+        estatus!("[info] Using auto-discovered key: {}", path.display());
+        VerificationKey::from_pem_file(&path)?
     };
 
-    let token = load_token(&token_input)?;
-
     // Pass audience to verify_jws for RFC 7519 compliant validation
     let expected_audience = if args.audience.is_empty() {
         None
@@ -127,33 +234,43 @@ fn run_non_interactive(args: VerifyArgs) -> Result<()> {
         Some(args.audience.as_slice())
     };
 
-    match verify_jws(token.trim(), &key, expected_audience) {
-        Ok(verified) => {
-            if let Err(err) = validate_verified(verified, &args) {
-                eprintln!("INVALID: {err}");
-                std::process::exit(1);
-            }
-            Ok(())
-        }
-        Err(err) => {
-            eprintln!("INVALID: {err}");
-            std::process::exit(1);
+    let mut schema = SchemaCheck::initial(&args);
+    let outcome =
+        verify_jws_with_allowed_algs(token.trim(), &key.pem, expected_audience, &args.allow_alg)
+            .map_err(anyhow::Error::from)
+            .and_then(|verified| validate_verified(verified, &key.pem, &args, &mut schema));
+    if let Err(err) = &outcome {
+        eprintln!("INVALID: {err}");
+        if args.explain_failure {
+            print_checklist(&explain_failure(token.trim(), &key.pem, &args));
         }
     }
+    if args.summary {
+        print_summary(outcome.is_ok(), token.trim(), schema);
+    }
+    if outcome.is_err() {
+        return exit::reported(exit::CHECK_FAILED);
+    }
+    Ok(())
 }
 
 fn do_verify(args: &VerifyArgs, prompts: &CommandPrompts) -> Result<()> {
-    let key = args.key.as_ref().ok_or_else(|| {
-        anyhow!("public key is required; rerun without --non-interactive to select one")
-    })?;
     let token_input = args.token.as_ref().ok_or_else(|| {
         anyhow!("token input is required; rerun without --non-interactive to select one")
     })?;
 
     prompts.info(&format!("Loading token from: {}", token_input))?;
-    let token = load_token(token_input)?;
+    let token = load_token(token_input, args.max_payload_size)?;
+    check_token_payload_size(token.trim(), args.max_payload_size)?;
 
-    prompts.info(&format!("Verifying with key: {}", key.display()))?;
+    let key = match (&args.jwks, &args.key) {
+        (Some(jwks), _) => VerificationKey::from_jwks(jwks, token.trim())?,
+        (None, Some(key)) => VerificationKey::from_pem_file(key)?,
+        (None, None) => {
+            bail!("public key is required; rerun without --non-interactive to select one")
+        }
+    };
+    prompts.info(&format!("Verifying with key: {}", key.source))?;
 
     // Pass audience to verify_jws for RFC 7519 compliant validation
     let expected_audience = if args.audience.is_empty() {
@@ -162,227 +279,1089 @@ fn do_verify(args: &VerifyArgs, prompts: &CommandPrompts) -> Result<()> {
         Some(args.audience.as_slice())
     };
 
-    match verify_jws(token.trim(), key, expected_audience) {
+    let mut schema = SchemaCheck::initial(args);
+    let valid = match verify_jws_with_allowed_algs(
+        token.trim(),
+        &key.pem,
+        expected_audience,
+        &args.allow_alg,
+    ) {
         Ok(verified) => {
             println!();
-            println!("{}", style("Verification successful!").green().bold());
+            println!("{}", success_line());
 
-            if let Err(err) = validate_verified_interactive(verified, args, prompts) {
-                println!();
-                println!("{}", style("Validation failed:").red().bold());
-                println!("  {}", err);
-                std::process::exit(1);
+            match validate_verified_interactive(verified, &key.pem, args, prompts, &mut schema) {
+                Ok(()) => true,
+                Err(err) => {
+                    println!();
+                    println!("{}", style("Validation failed:").red().bold());
+                    println!("  {}", err);
+                    false
+                }
             }
-            Ok(())
         }
         Err(err) => {
             println!();
             println!("{}", style("Verification failed:").red().bold());
             println!("  {}", err);
-            std::process::exit(1);
+            false
         }
+    };
+    if !valid && args.explain_failure {
+        print_checklist(&explain_failure(token.trim(), &key.pem, args));
+    }
+    if args.summary {
+        print_summary(valid, token.trim(), schema);
+    }
+    if !valid {
+        return exit::reported(exit::CHECK_FAILED);
     }
+    Ok(())
+}
+
+fn success_line() -> String {
+    style("Verification successful!").green().bold().to_string()
 }
 
-fn load_token(token_input: &str) -> Result<String> {
+/// The public key a token is verified with, as PEM
+struct VerificationKey {
+    pem: String,
+    /// Where the key came from, for display
+    source: String,
+}
+
+impl VerificationKey {
+    fn from_pem_file(path: &Path) -> Result<Self> {
+        let pem = fs::read_to_string(path)
+            .with_context(|| format!("failed to read key {}", path.display()))?;
+        Ok(Self {
+            pem,
+            source: path.display().to_string(),
+        })
+    }
+
+    /// The key in a JWKS file for the token's `kid`, checked against its `alg`
+    fn from_jwks(path: &Path, token: &str) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read JWKS {}", path.display()))?;
+        let jwks: Value = serde_json::from_str(&content)
+            .with_context(|| format!("JWKS {} is not valid JSON", path.display()))?;
+
+        let header = decode_jws_header(token)?;
+        let alg = SignatureAlg::from_jws_name(&header.alg)?;
+        let jwk = select_jwk(&jwks, header.kid.as_deref())
+            .with_context(|| format!("no usable key in JWKS {}", path.display()))?;
+        let pem = public_pem_from_jwk(jwk, alg)?;
+
+        let source = match jwk.get("kid").and_then(Value::as_str) {
+            Some(kid) => format!("{} (kid {})", path.display(), kid),
+            None => path.display().to_string(),
+        };
+        Ok(Self { pem, source })
+    }
+}
+
+fn token_alg(token: &str) -> Result<SignatureAlg> {
+    SignatureAlg::from_jws_name(&decode_jws_header(token)?.alg)
+}
+
+fn no_key_for_alg(alg: SignatureAlg) -> String {
+    format!(
+        "no key of type {} for alg {} among the discovered public keys; pass --key",
+        alg.key_type(),
+        alg
+    )
+}
+
+/// The token at an http(s) URL, in a file, or `token_input` itself. Files
+/// and bodies are read only up to the size a token whose payload is within
+/// `limit` can have.
+fn load_token(token_input: &str, limit: u64) -> Result<String> {
+    if is_url(token_input) {
+        return fetch_token(token_input, "token", limit);
+    }
     let candidate = PathBuf::from(token_input);
     if candidate.exists() {
-        fs::read_to_string(&candidate)
-            .with_context(|| format!("failed to read token file {}", candidate.display()))
+        read_token_file(&candidate, "token file", limit)
     } else {
         Ok(token_input.to_string())
     }
 }
 
+/// Room for the header, signature and any JSON wrapper around a token, on
+/// top of its encoded payload
+const TOKEN_OVERHEAD_BYTES: u64 = 64 * 1024;
+
+/// Longest token (or token body) read for a payload of at most `limit`
+/// bytes: the payload base64url-encoded, plus [`TOKEN_OVERHEAD_BYTES`]
+fn encoded_token_limit(limit: u64) -> u64 {
+    (limit.saturating_mul(4) / 3).saturating_add(TOKEN_OVERHEAD_BYTES)
+}
+
+/// Read `reader` (`what`, for messages) as UTF-8, stopping as soon as it is
+/// longer than [`encoded_token_limit`]
+fn read_token_bounded(reader: impl Read, what: &str, limit: u64) -> Result<String> {
+    let max = encoded_token_limit(limit);
+    let mut bytes = Vec::new();
+    reader
+        .take(max.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("failed to read {}", what))?;
+    if bytes.len() as u64 > max {
+        bail!(
+            "payload exceeds limit: {} is over {} bytes, more than a token with a {}-byte payload can be (--max-payload-size)",
+            what,
+            max,
+            limit
+        );
+    }
+    String::from_utf8(bytes).with_context(|| format!("{} is not valid UTF-8", what))
+}
+
+fn read_token_file(path: &Path, what: &str, limit: u64) -> Result<String> {
+    let what = format!("{} {}", what, path.display());
+    let file = fs::File::open(path).with_context(|| format!("failed to read {}", what))?;
+    read_token_bounded(file, &what, limit)
+}
+
+/// Refuse a token whose payload would decode to more than `limit` bytes,
+/// judged from the length of its base64url segment before decoding it
+fn check_token_payload_size(token: &str, limit: u64) -> Result<()> {
+    let encoded = token.split('.').nth(1).unwrap_or_default();
+    check_payload_size(encoded.len() as u64 * 3 / 4, limit)
+}
+
 fn validate_verified_interactive(
     verified: VerifiedToken,
+    key_pem: &str,
     args: &VerifyArgs,
     prompts: &CommandPrompts,
+    schema: &mut SchemaCheck,
 ) -> Result<()> {
-    let header_typ = verified.header.typ.clone();
-    if let Some(ref typ) = header_typ {
-        if credential_kind_from_typ(typ).is_none() {
-            bail!("unexpected typ header '{}'", typ);
+    if !args.skip_schema {
+        prompts.info("Validating claims and credential schema...")?;
+    }
+    let mut checks = Checklist::fail_fast();
+    let checked = check_claims(
+        &mut checks,
+        &verified.header,
+        &verified.payload,
+        Some(verified.alg),
+        key_pem,
+        args,
+        schema,
+    );
+    checks.into_result()?;
+    let Some(checked) = checked else {
+        bail!("verification did not complete");
+    };
+    if *schema == SchemaCheck::Passed {
+        prompts.info("Schema validation passed")?;
+    }
+
+    println!();
+    println!("  {} {}", style("Type:").dim(), checked.kind.display_name());
+    println!("  {} {}", style("Algorithm:").dim(), verified.alg);
+    println!(
+        "  {} {}",
+        style("Key ID:").dim(),
+        verified.header.kid.as_deref().unwrap_or("<none>")
+    );
+    println!("  {} {}", style("Issuer:").dim(), checked.iss);
+    println!("  {} {}", style("Subject:").dim(), checked.sub);
+    println!("  {} {}", style("JTI:").dim(), checked.jti);
+    if let Some(check) = &checked.revocation {
+        println!(
+            "  {} {} {}",
+            style("Status:").dim(),
+            style(check.status).green(),
+            style(format!("({})", check.source)).dim()
+        );
+    }
+    if let Some(link) = &checked.developer {
+        println!(
+            "  {} {} {}",
+            style("Developer credential:").dim(),
+            style(&link.credential_id).green(),
+            style(format!(
+                "(issuer {}, expires {}, key {})",
+                link.issuer,
+                link.expires,
+                link.key_label()
+            ))
+            .dim()
+        );
+    }
+
+    if let Some(metadata) = &checked.signing_metadata {
+        println!();
+        println!("{}", style("Signing provenance:").cyan().bold());
+        println!(
+            "  {} {} {}",
+            style("Signed by:").dim(),
+            metadata.tool,
+            metadata.tool_version
+        );
+        println!("  {} {}", style("Signed at:").dim(), metadata.signed_at);
+        println!(
+            "  {} {}",
+            style("Key thumbprint:").dim(),
+            metadata.signer_key_thumbprint
+        );
+        if let Some(commit) = &metadata.git_commit {
+            println!("  {} {}", style("Git commit:").dim(), commit);
         }
     }
 
-    let claims = verified.payload;
-    let vc = claims
-        .get("vc")
-        .ok_or_else(|| anyhow!("vc claim missing from JWT payload"))?;
-    if !vc.is_object() {
-        bail!("vc claim must be an object");
-    }
-
-    let header_kind = header_typ.as_deref().and_then(credential_kind_from_typ);
-    let detected_kind = detect_credential_kind(vc);
-    let kind = resolve_kind(args.credential_type, header_kind, detected_kind)?;
-
-    let iss = claims
-        .get("iss")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("iss claim missing"))?;
-    let sub = claims
-        .get("sub")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("sub claim missing"))?;
-    let jti = claims
-        .get("jti")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("jti claim missing"))?;
-
-    if claims.get("nbf").is_none() || claims.get("exp").is_none() {
-        bail!("nbf and exp claims are required");
+    println!();
+    if args.claims_only {
+        println!("{}", style("Claims:").cyan().bold());
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&standard_claims(&verified.payload))?
+        );
+    } else {
+        println!("{}", style("Credential payload:").cyan().bold());
+        let pretty = serde_json::to_string_pretty(checked.vc)?;
+        println!("{pretty}");
     }
 
-    if let Some(expected_issuer) = &args.issuer {
-        if iss != expected_issuer {
-            bail!(
-                "issuer mismatch: expected '{}', got '{}'",
-                expected_issuer,
-                iss
-            );
+    Ok(())
+}
+
+fn validate_verified(
+    verified: VerifiedToken,
+    key_pem: &str,
+    args: &VerifyArgs,
+    schema: &mut SchemaCheck,
+) -> Result<()> {
+    let mut checks = Checklist::fail_fast();
+    let checked = check_claims(
+        &mut checks,
+        &verified.header,
+        &verified.payload,
+        Some(verified.alg),
+        key_pem,
+        args,
+        schema,
+    );
+    checks.into_result()?;
+    let Some(checked) = checked else {
+        bail!("verification did not complete");
+    };
+
+    if args.claims_only {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&standard_claims(&verified.payload))?
+        );
+        return Ok(());
+    }
+
+    println!(
+        "VALID (type={}, alg={}, kid={}, typ={}, iss={}, sub={}, jti={})",
+        checked.kind.display_name(),
+        verified.alg,
+        verified.header.kid.as_deref().unwrap_or("<none>"),
+        verified.header.typ.as_deref().unwrap_or("<missing>"),
+        checked.iss,
+        checked.sub,
+        checked.jti,
+    );
+    if let Some(check) = &checked.revocation {
+        println!(
+            "REVOCATION (status={}, list={})",
+            check.status, check.source
+        );
+    }
+    if let Some(link) = &checked.developer {
+        println!(
+            "DEVELOPER (credentialId={}, iss={}, exp={}, key={})",
+            link.credential_id,
+            link.issuer,
+            link.expires,
+            link.key_label()
+        );
+    }
+    if let Some(metadata) = &checked.signing_metadata {
+        println!(
+            "SIGNED (tool={}, version={}, signedAt={}, keyThumbprint={}, gitCommit={})",
+            metadata.tool,
+            metadata.tool_version,
+            metadata
+                .signed_at
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+            metadata.signer_key_thumbprint,
+            metadata.git_commit.as_deref().unwrap_or("<none>"),
+        );
+    }
+    let pretty = serde_json::to_string_pretty(checked.vc)?;
+    println!("{pretty}");
+    Ok(())
+}
+
+/// The outcome of each verification stage, in the order they ran. Plain
+/// `verify` stops at the first failure and reports only that one;
+/// `--explain-failure` keeps going so every stage gets a verdict.
+struct Checklist {
+    keep_going: bool,
+    stages: Vec<(&'static str, Stage)>,
+}
+
+enum Stage {
+    Passed,
+    Failed(anyhow::Error),
+    /// Not run: not requested, or a stage it needs failed
+    Skipped(&'static str),
+}
+
+impl Checklist {
+    fn fail_fast() -> Self {
+        Self {
+            keep_going: false,
+            stages: Vec::new(),
         }
     }
 
-    if !args.audience.is_empty() {
-        let actual_aud = extract_audience(&claims)?;
-        let missing: Vec<String> = args
-            .audience
+    fn keep_going() -> Self {
+        Self {
+            keep_going: true,
+            stages: Vec::new(),
+        }
+    }
+
+    fn failures(&self) -> usize {
+        self.stages
+            .iter()
+            .filter(|(_, stage)| matches!(stage, Stage::Failed(_)))
+            .count()
+    }
+
+    fn stopped(&self) -> bool {
+        !self.keep_going && self.failures() > 0
+    }
+
+    /// Run `check` as stage `name`, unless an earlier stage already failed
+    /// in fail-fast mode
+    fn check<T>(&mut self, name: &'static str, check: impl FnOnce() -> Result<T>) -> Option<T> {
+        if self.stopped() {
+            return None;
+        }
+        match check() {
+            Ok(value) => {
+                self.stages.push((name, Stage::Passed));
+                Some(value)
+            }
+            Err(err) => {
+                self.stages.push((name, Stage::Failed(err)));
+                None
+            }
+        }
+    }
+
+    fn skip(&mut self, name: &'static str, reason: &'static str) {
+        if !self.stopped() {
+            self.stages.push((name, Stage::Skipped(reason)));
+        }
+    }
+
+    /// The first failure, which is what plain `verify` reports
+    fn into_result(self) -> Result<()> {
+        for (_, stage) in self.stages {
+            if let Stage::Failed(err) = stage {
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// One `PASS`/`FAIL`/`SKIP` line per stage; continuation lines of a
+    /// multi-line failure (schema errors) are indented under it
+    fn lines(&self) -> Vec<String> {
+        self.stages
             .iter()
-            .filter(|expected| !actual_aud.contains(&expected.to_string()))
-            .cloned()
-            .collect();
-        if !missing.is_empty() {
+            .map(|(name, stage)| match stage {
+                Stage::Passed => format!("PASS {}", name),
+                Stage::Failed(err) => {
+                    let message = format!("{:#}", err);
+                    format!(
+                        "FAIL {}: {}",
+                        name,
+                        message.trim_end().replace('\n', "\n       ")
+                    )
+                }
+                Stage::Skipped(reason) => format!("SKIP {} ({})", name, reason),
+            })
+            .collect()
+    }
+}
+
+/// What the claim checks established, for printing a verified credential
+struct CheckedClaims<'a> {
+    kind: CredentialKind,
+    vc: &'a Value,
+    iss: &'a str,
+    sub: &'a str,
+    jti: &'a str,
+    signing_metadata: Option<SigningMetadata>,
+    revocation: Option<RevocationCheck>,
+    developer: Option<DeveloperLink>,
+}
+
+/// Check a token's header and claims, one stage each, recording the
+/// outcomes in `checks`. `alg` is `None` when the header names an algorithm
+/// beltic doesn't support; stages that need it are skipped. Returns the
+/// checked claims only if every stage passed.
+fn check_claims<'a>(
+    checks: &mut Checklist,
+    header: &JwsHeader,
+    claims: &'a Value,
+    alg: Option<SignatureAlg>,
+    key_pem: &str,
+    args: &VerifyArgs,
+    schema: &mut SchemaCheck,
+) -> Option<CheckedClaims<'a>> {
+    let header_kind = header.typ.as_deref().and_then(credential_kind_from_typ);
+    checks.check("typ header", || match &header.typ {
+        Some(typ) if header_kind.is_none() => bail!("unexpected typ header '{}'", typ),
+        None if args.require_typ => {
+            let expected = match args
+                .credential_type
+                .or_else(|| claims.get("vc").and_then(detect_credential_kind))
+            {
+                Some(kind) => kind.media_type().to_string(),
+                None => format!("{} or {}", AGENT_TYP, DEVELOPER_TYP),
+            };
+            bail!("typ header missing (--require-typ expects {})", expected)
+        }
+        _ => Ok(()),
+    });
+
+    let vc = checks.check("vc claim", || {
+        let vc = claims
+            .get("vc")
+            .ok_or_else(|| anyhow!("vc claim missing from JWT payload"))?;
+        if !vc.is_object() {
+            bail!("vc claim must be an object");
+        }
+        Ok(vc)
+    });
+    let kind = checks.check("credential type", || {
+        resolve_kind(
+            args.credential_type,
+            header_kind,
+            vc.and_then(detect_credential_kind),
+        )
+    });
+
+    let string_claim = |name: &str| {
+        claims
+            .get(name)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("{} claim missing", name))
+    };
+    let iss = checks.check("iss claim", || string_claim("iss"));
+    let sub = checks.check("sub claim", || string_claim("sub"));
+    let jti = checks.check("jti claim", || string_claim("jti"));
+    checks.check("validity window", || check_time_claims(claims, Utc::now()));
+
+    let signing_metadata = match alg {
+        Some(alg) => checks.check("signing metadata", || {
+            check_signing_metadata(claims, key_pem, alg)
+        }),
+        None => {
+            checks.skip("signing metadata", "unsupported alg");
+            None
+        }
+    };
+    match (vc, alg) {
+        (Some(vc), Some(alg)) => {
+            checks.check("proof type", || check_proof_type(vc, alg));
+        }
+        _ => checks.skip("proof type", "needs the vc claim and a supported alg"),
+    }
+
+    if let Some(expected_issuer) = &args.issuer {
+        match iss {
+            Some(iss) => {
+                checks.check("issuer", || {
+                    if iss != expected_issuer {
+                        bail!(
+                            "issuer mismatch: expected '{}', got '{}'",
+                            expected_issuer,
+                            iss
+                        );
+                    }
+                    Ok(())
+                });
+            }
+            None => checks.skip("issuer", "no iss claim"),
+        }
+    }
+    checks.check("audience", || check_audience(claims, &args.audience));
+
+    if args.skip_schema {
+        checks.skip("schema", "--skip-schema");
+    } else {
+        match (kind, vc) {
+            (Some(kind), Some(vc)) => {
+                let version = active_version(args.global_config.as_deref());
+                checks.check("schema", || check_schema(kind, vc, &version, schema));
+            }
+            _ => checks.skip("schema", "needs the vc claim and credential type"),
+        }
+    }
+
+    let revocation = if args.check_revocation || args.revocation_list.is_some() {
+        match (vc, jti) {
+            (Some(vc), Some(jti)) => checks.check("revocation", || check_status(args, vc, jti)),
+            _ => {
+                checks.skip("revocation", "needs the vc and jti claims");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let developer = if args.developer_credential.is_some() {
+        match (kind, vc) {
+            (Some(kind), Some(vc)) => checks.check("developer credential", || {
+                check_developer_credential(args, kind, vc)
+            }),
+            _ => {
+                checks.skip(
+                    "developer credential",
+                    "needs the vc claim and credential type",
+                );
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    if checks.failures() > 0 {
+        return None;
+    }
+    Some(CheckedClaims {
+        kind: kind?,
+        vc: vc?,
+        iss: iss?,
+        sub: sub?,
+        jti: jti?,
+        signing_metadata: signing_metadata.flatten(),
+        revocation: revocation.flatten(),
+        developer: developer.flatten(),
+    })
+}
+
+/// `nbf` and `exp` must be present, and `now` within them give or take the
+/// verifier's clock skew leeway
+fn check_time_claims(claims: &Value, now: DateTime<Utc>) -> Result<()> {
+    let (Some(nbf), Some(exp)) = (claims.get("nbf"), claims.get("exp")) else {
+        bail!("nbf and exp claims are required");
+    };
+    let now = now.timestamp();
+    if let Some(exp) = exp.as_i64() {
+        if exp < now - LEEWAY_SECS {
+            bail!("token expired at {}", exp);
+        }
+    }
+    if let Some(nbf) = nbf.as_i64() {
+        if nbf > now + LEEWAY_SECS {
+            bail!("token is not valid before {}", nbf);
+        }
+    }
+    Ok(())
+}
+
+/// Every `--audience` must be in `aud`; without `--audience`, a token that
+/// names an audience is refused (RFC 7519 section 4.1.3)
+fn check_audience(claims: &Value, expected: &[String]) -> Result<()> {
+    let actual_aud = extract_audience(claims)?;
+    if expected.is_empty() {
+        if !actual_aud.is_empty() {
             bail!(
-                "audience mismatch: missing {:?} from aud claim ({:?})",
-                missing,
+                "token contains audience claim {:?} but no expected audience was provided",
                 actual_aud
             );
         }
+        return Ok(());
+    }
+    let missing: Vec<String> = expected
+        .iter()
+        .filter(|expected| !actual_aud.contains(&expected.to_string()))
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "audience mismatch: missing {:?} from aud claim ({:?})",
+            missing,
+            actual_aud
+        );
     }
+    Ok(())
+}
 
-    if !args.skip_schema {
-        prompts.info("Validating credential schema...")?;
-        let errors = validate_credential(kind, vc)?;
-        if !errors.is_empty() {
-            let mut message = String::from("schema validation failed:\n");
-            for err in errors {
-                message.push_str(&format!("  - {err}\n"));
+/// Run every stage on `token` regardless of earlier failures, for
+/// `--explain-failure`. The signature is checked on its own, and the claim
+/// stages read the payload without trusting it.
+fn explain_failure(token: &str, key_pem: &str, args: &VerifyArgs) -> Checklist {
+    let mut checks = Checklist::keep_going();
+    let header = checks.check("header", || decode_jws_header(token));
+    let claims = checks.check("payload", || decode_jwt_payload(token));
+
+    let alg = match &header {
+        Some(header) => checks.check("algorithm", || {
+            let alg = SignatureAlg::from_jws_name(&header.alg)?;
+            if !args.allow_alg.is_empty() && !args.allow_alg.contains(&alg) {
+                let names: Vec<&str> = args.allow_alg.iter().map(|alg| alg.jws_name()).collect();
+                bail!(
+                    "token alg {} is not allowed; accepted algorithms: {}",
+                    alg,
+                    names.join(", ")
+                );
             }
-            bail!(message);
+            Ok(alg)
+        }),
+        None => {
+            checks.skip("algorithm", "header unreadable");
+            None
         }
-        prompts.info("Schema validation passed")?;
+    };
+    checks.check("signature", || {
+        verify_jws_signature(token, key_pem)?;
+        Ok(())
+    });
+
+    match (&header, &claims) {
+        (Some(header), Some(claims)) => {
+            let mut schema = SchemaCheck::initial(args);
+            check_claims(&mut checks, header, claims, alg, key_pem, args, &mut schema);
+        }
+        _ => checks.skip("claims", "header or payload unreadable"),
     }
+    checks
+}
 
+/// Print the `--explain-failure` checklist
+fn print_checklist(checks: &Checklist) {
     println!();
-    println!("  {} {}", style("Type:").dim(), kind.display_name());
-    println!("  {} {}", style("Algorithm:").dim(), verified.alg);
+    println!("{}", style("Checks:").cyan().bold());
+    for line in checks.lines() {
+        println!("  {}", line);
+    }
     println!(
-        "  {} {}",
-        style("Key ID:").dim(),
-        verified.header.kid.as_deref().unwrap_or("<none>")
+        "  {} of {} stage(s) failed",
+        checks.failures(),
+        checks.stages.len()
     );
-    println!("  {} {}", style("Issuer:").dim(), iss);
-    println!("  {} {}", style("Subject:").dim(), sub);
-    println!("  {} {}", style("JTI:").dim(), jti);
+}
+
+/// Validate `vc` against its JSON Schema `version`, recording the outcome
+/// in `schema`
+fn check_schema(
+    kind: CredentialKind,
+    vc: &Value,
+    version: &str,
+    schema: &mut SchemaCheck,
+) -> Result<()> {
+    let errors = validate_credential_version(kind, vc, version)?;
+    if !errors.is_empty() {
+        *schema = SchemaCheck::Failed;
+        let mut message = String::from("schema validation failed:\n");
+        for err in errors {
+            message.push_str(&format!("  - {err}\n"));
+        }
+        bail!(message);
+    }
+    *schema = SchemaCheck::Passed;
+    Ok(())
+}
+
+/// How far JSON Schema validation got, for `--summary`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SchemaCheck {
+    Skipped,
+    /// Verification failed before the schema step
+    NotReached,
+    Passed,
+    Failed,
+}
 
+impl SchemaCheck {
+    fn initial(args: &VerifyArgs) -> Self {
+        if args.skip_schema {
+            SchemaCheck::Skipped
+        } else {
+            SchemaCheck::NotReached
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            SchemaCheck::Skipped => "skipped (--skip-schema)",
+            SchemaCheck::NotReached => "not run (verification failed first)",
+            SchemaCheck::Passed => "ran, passed",
+            SchemaCheck::Failed => "ran, failed",
+        }
+    }
+}
+
+/// Print the `--summary` block for `token`
+fn print_summary(valid: bool, token: &str, schema: SchemaCheck) {
     println!();
-    println!("{}", style("Credential payload:").cyan().bold());
-    let pretty = serde_json::to_string_pretty(vc)?;
-    println!("{pretty}");
+    println!("{}", style("Summary:").cyan().bold());
+    for (label, value) in summary_lines(valid, token, schema, Utc::now()) {
+        println!("  {:<11} {}", format!("{}:", label), value);
+    }
+}
 
-    Ok(())
+/// The `--summary` lines as (label, value), read from the token's header
+/// and claims whether or not it verified
+fn summary_lines(
+    valid: bool,
+    token: &str,
+    schema: SchemaCheck,
+    now: DateTime<Utc>,
+) -> Vec<(&'static str, String)> {
+    let mut lines = vec![(
+        "Result",
+        if valid { "VALID" } else { "INVALID" }.to_string(),
+    )];
+
+    match decode_jws_header(token) {
+        Ok(header) => {
+            let (rating, key_size) = alg_strength(&header.alg);
+            lines.push(("Algorithm", format!("{} \u{2014} {}", header.alg, rating)));
+            if let Some(key_size) = key_size {
+                lines.push(("Key size", key_size.to_string()));
+            }
+        }
+        Err(_) => lines.push(("Algorithm", "unreadable header".to_string())),
+    }
+
+    let validity = match decode_jwt_payload(token) {
+        Ok(claims) => validity_window(&claims, now),
+        Err(_) => "unreadable payload".to_string(),
+    };
+    lines.push(("Validity", validity));
+    lines.push(("Schema", schema.describe().to_string()));
+    lines
 }
 
-fn validate_verified(verified: VerifiedToken, args: &VerifyArgs) -> Result<()> {
-    let header_typ = verified.header.typ.clone();
-    if let Some(ref typ) = header_typ {
-        if credential_kind_from_typ(typ).is_none() {
-            bail!("unexpected typ header '{}'", typ);
+/// A reviewer's rating of a JWS `alg`, and the key size it implies. Only
+/// the algorithms `verify` accepts rate OK; RSA is acceptable elsewhere but
+/// not supported here, and shared-secret or unsigned tokens are rejected.
+fn alg_strength(alg: &str) -> (&'static str, Option<&'static str>) {
+    match alg {
+        "EdDSA" => ("OK", Some("256-bit (Ed25519)")),
+        "ES256" => ("OK", Some("256-bit (P-256)")),
+        "ES256K" => ("OK", Some("256-bit (secp256k1)")),
+        "RS256" | "RS384" | "RS512" | "PS256" | "PS384" | "PS512" => {
+            ("acceptable (not supported by beltic)", None)
         }
+        "ES384" | "ES512" => ("OK (not supported by beltic)", None),
+        "HS256" | "HS384" | "HS512" => ("REJECTED (shared secret)", None),
+        alg if alg.eq_ignore_ascii_case("none") => ("REJECTED", None),
+        _ => ("unknown", None),
     }
+}
+
+/// Where `now` falls in the claims' `nbf`/`exp` window
+fn validity_window(claims: &Value, now: DateTime<Utc>) -> String {
+    let timestamp = |name: &str| {
+        claims
+            .get(name)
+            .and_then(Value::as_i64)
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+    };
+    let rfc3339 = |time: DateTime<Utc>| time.to_rfc3339_opts(SecondsFormat::Secs, true);
 
-    let claims = verified.payload;
-    let vc = claims
+    if let Some(nbf) = timestamp("nbf").filter(|nbf| *nbf > now) {
+        return format!(
+            "not yet valid, starts in {} ({})",
+            format_span(nbf - now),
+            rfc3339(nbf)
+        );
+    }
+    match timestamp("exp") {
+        Some(exp) if exp <= now => {
+            format!("expired {} ago ({})", format_span(now - exp), rfc3339(exp))
+        }
+        Some(exp) => format!(
+            "within window, {} remaining (until {})",
+            format_span(exp - now),
+            rfc3339(exp)
+        ),
+        None => "no exp claim".to_string(),
+    }
+}
+
+/// A duration as its two largest units, e.g. `29d 3h` or `4m 10s`
+fn format_span(span: chrono::Duration) -> String {
+    let seconds = span.num_seconds().max(0);
+    let (days, hours, minutes) = (seconds / 86_400, seconds / 3600 % 24, seconds / 60 % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m {}s", minutes, seconds % 60)
+    }
+}
+
+/// Look up the credential's revocation status when asked to; anything but
+/// active fails verification
+fn check_status(args: &VerifyArgs, vc: &Value, jti: &str) -> Result<Option<RevocationCheck>> {
+    if !args.check_revocation && args.revocation_list.is_none() {
+        return Ok(None);
+    }
+    let check = check_revocation(vc, Some(jti), args.revocation_list.as_deref())?;
+    if check.status != RevocationStatus::Active {
+        bail!(
+            "credential is {} according to revocation list {}",
+            check.status,
+            check.source
+        );
+    }
+    Ok(Some(check))
+}
+
+/// The developer credential an agent credential was checked against
+struct DeveloperLink {
+    credential_id: String,
+    issuer: String,
+    expires: String,
+    /// The key its signature was checked with; `None` when no
+    /// `--developer-key` was given and the link is unverified
+    key_source: Option<String>,
+}
+
+impl DeveloperLink {
+    fn key_label(&self) -> &str {
+        self.key_source
+            .as_deref()
+            .unwrap_or("UNVERIFIED: signature not checked, pass --developer-key")
+    }
+}
+
+/// Check that the developer credential given with `--developer-credential`
+/// is the one the agent credential's `developerCredentialId` names, and that
+/// it is unexpired and not revoked. Its signature is only verified against
+/// `--developer-key`: a self-issued credential's embedded key proves nothing,
+/// so without one the link is reported as unverified.
+fn check_developer_credential(
+    args: &VerifyArgs,
+    kind: CredentialKind,
+    vc: &Value,
+) -> Result<Option<DeveloperLink>> {
+    let Some(source) = &args.developer_credential else {
+        return Ok(None);
+    };
+    if kind != CredentialKind::Agent {
+        bail!(
+            "--developer-credential checks agent credentials, not a {}",
+            kind.display_name()
+        );
+    }
+    let expected = vc
+        .get("developerCredentialId")
+        .and_then(Value::as_str)
+        .context("agent credential has no developerCredentialId")?;
+
+    let token = load_developer_token(source, args.max_payload_size)?;
+    let (claims, key_source) = match &args.developer_key {
+        Some(path) => {
+            let key = VerificationKey::from_pem_file(path)?;
+            let verified = verify_jws_with_pem(&token, &key.pem, None).map_err(|err| {
+                anyhow!(
+                    "developer credential {} failed verification: {:#}",
+                    source,
+                    err
+                )
+            })?;
+            (verified.payload, Some(key.source))
+        }
+        None => {
+            estatus!(
+                "[warn] developer credential {} signature not checked; pass --developer-key",
+                source
+            );
+            (decode_jwt_payload(&token)?, None)
+        }
+    };
+    let now = Utc::now();
+    check_time_claims(&claims, now)
+        .with_context(|| format!("developer credential {} is not valid", source))?;
+
+    let developer = claims
         .get("vc")
-        .ok_or_else(|| anyhow!("vc claim missing from JWT payload"))?;
-    if !vc.is_object() {
-        bail!("vc claim must be an object");
-    }
-
-    let header_kind = header_typ.as_deref().and_then(credential_kind_from_typ);
-    let detected_kind = detect_credential_kind(vc);
-    let kind = resolve_kind(args.credential_type, header_kind, detected_kind)?;
-
-    let iss = claims
-        .get("iss")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("iss claim missing"))?;
-    let sub = claims
-        .get("sub")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("sub claim missing"))?;
-    let jti = claims
-        .get("jti")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("jti claim missing"))?;
-
-    if claims.get("nbf").is_none() || claims.get("exp").is_none() {
-        bail!("nbf and exp claims are required");
+        .filter(|vc| vc.is_object())
+        .with_context(|| format!("developer credential {} has no vc claim", source))?;
+    if detect_credential_kind(developer) == Some(CredentialKind::Agent) {
+        bail!(
+            "{} is an agent credential, not a developer credential",
+            source
+        );
+    }
+    let credential_id = developer
+        .get("credentialId")
+        .or_else(|| claims.get("jti"))
+        .and_then(Value::as_str)
+        .with_context(|| format!("developer credential {} has no credentialId", source))?;
+    if credential_id != expected {
+        bail!(
+            "agent credential names developer credential {}, but {} is {}",
+            expected,
+            source,
+            credential_id
+        );
     }
 
-    if let Some(expected_issuer) = &args.issuer {
-        if iss != expected_issuer {
+    if let Some(expiration) = developer.get("expirationDate").and_then(Value::as_str) {
+        let expiration = DateTime::parse_from_rfc3339(expiration).with_context(|| {
+            format!(
+                "developer credential {} has an invalid expirationDate",
+                source
+            )
+        })?;
+        if expiration < now - chrono::Duration::seconds(LEEWAY_SECS) {
+            bail!("developer credential {} expired on {}", source, expiration);
+        }
+    }
+    if developer.get("credentialStatus").is_some() {
+        let jti = claims.get("jti").and_then(Value::as_str);
+        let check = check_revocation(developer, jti, None)
+            .with_context(|| format!("failed to check developer credential {} status", source))?;
+        if check.status != RevocationStatus::Active {
             bail!(
-                "issuer mismatch: expected '{}', got '{}'",
-                expected_issuer,
-                iss
+                "developer credential {} is {} according to revocation list {}",
+                source,
+                check.status,
+                check.source
             );
         }
     }
 
-    if !args.audience.is_empty() {
-        let actual_aud = extract_audience(&claims)?;
-        let missing: Vec<String> = args
-            .audience
+    let expires = claims
+        .get("exp")
+        .and_then(Value::as_i64)
+        .and_then(|exp| DateTime::from_timestamp(exp, 0))
+        .map(|exp| exp.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_else(|| "<none>".to_string());
+    Ok(Some(DeveloperLink {
+        credential_id: credential_id.to_string(),
+        issuer: claims
+            .get("iss")
+            .and_then(Value::as_str)
+            .unwrap_or("<none>")
+            .to_string(),
+        expires,
+        key_source,
+    }))
+}
+
+/// A developer credential JWS from a file or an http(s) URL, read up to
+/// the size allowed for `limit` (see [`load_token`])
+fn load_developer_token(source: &str, limit: u64) -> Result<String> {
+    let content = if is_url(source) {
+        fetch_token(source, "developer credential", limit)?
+    } else {
+        read_token_file(Path::new(source), "developer credential", limit)?
+    };
+
+    let token = content.trim().to_string();
+    if token.split('.').count() != 3 || token.contains('{') {
+        bail!(
+            "developer credential {} is not a signed JWS; sign it with `beltic sign` or `beltic dev-init --sign-with`",
+            source
+        );
+    }
+    Ok(token)
+}
+
+fn is_url(input: &str) -> bool {
+    input.starts_with("https://") || input.starts_with("http://")
+}
+
+/// Fetch a token (`what`, for messages) from an http(s) URL. Whatever the
+/// Content-Type, the body may be the compact JWS itself or a JSON wrapper:
+/// a string, or an object with a `token`, `jwt`, `jws` or `credential`
+/// string member. The body is read only up to the size allowed for `limit`
+/// (see [`load_token`]).
+fn fetch_token(url: &str, what: &str, limit: u64) -> Result<String> {
+    if is_offline() {
+        bail!("offline mode: cannot fetch {} {}", what, url);
+    }
+    let client = beltic::http::client()?;
+    let response = beltic::http::send_with_retry(client.get(url))
+        .map_err(|err| beltic::http::request_error(err, &format!("{} host", what)))
+        .with_context(|| format!("failed to fetch {} {}", what, url))?;
+    if !response.status().is_success() {
+        bail!(
+            "failed to fetch {} {}: HTTP {}",
+            what,
+            url,
+            response.status()
+        );
+    }
+    let body = read_token_bounded(response, &format!("{} {}", what, url), limit)?;
+    unwrap_token_body(&body).with_context(|| format!("unexpected {} body from {}", what, url))
+}
+
+fn unwrap_token_body(body: &str) -> Result<String> {
+    let body = body.trim();
+    if !body.starts_with('{') && !body.starts_with('"') {
+        return Ok(body.to_string());
+    }
+    let wrapper: Value = serde_json::from_str(body).context("invalid JSON")?;
+    let token = match &wrapper {
+        Value::String(token) => Some(token.as_str()),
+        Value::Object(object) => ["token", "jwt", "jws", "credential"]
             .iter()
-            .filter(|expected| !actual_aud.contains(&expected.to_string()))
-            .cloned()
-            .collect();
-        if !missing.is_empty() {
+            .find_map(|name| object.get(*name).and_then(Value::as_str)),
+        _ => None,
+    };
+    token
+        .map(|token| token.trim().to_string())
+        .context("expected a compact JWS, or JSON with a \"token\", \"jwt\", \"jws\" or \"credential\" string")
+}
+
+/// Read `signingMetadata`, if present, and check that it names the key the
+/// token was verified with
+fn check_signing_metadata(
+    claims: &Value,
+    key_pem: &str,
+    alg: SignatureAlg,
+) -> Result<Option<SigningMetadata>> {
+    let Some(metadata) = SigningMetadata::from_claims(claims)? else {
+        return Ok(None);
+    };
+
+    if let Ok(thumbprint) = thumbprint_from_public_pem(key_pem, alg) {
+        if thumbprint != metadata.signer_key_thumbprint {
             bail!(
-                "audience mismatch: missing {:?} from aud claim ({:?})",
-                missing,
-                actual_aud
+                "{} names signer key {}, but the token was verified with key {}",
+                SIGNING_METADATA_CLAIM,
+                metadata.signer_key_thumbprint,
+                thumbprint
             );
         }
     }
+    Ok(Some(metadata))
+}
 
-    if !args.skip_schema {
-        let errors = validate_credential(kind, vc)?;
-        if !errors.is_empty() {
-            let mut message = String::from("schema validation failed:\n");
-            for err in errors {
-                message.push_str(&format!("  - {err}\n"));
-            }
-            bail!(message);
+/// The registered JWT claims worth logging; absent ones (e.g. `aud`) are
+/// left out
+fn standard_claims(claims: &Value) -> Value {
+    let mut selected = serde_json::Map::new();
+    for name in ["iss", "sub", "jti", "nbf", "exp", "aud"] {
+        if let Some(value) = claims.get(name) {
+            selected.insert(name.to_string(), value.clone());
         }
     }
-
-    println!(
-        "VALID (type={}, alg={}, kid={}, typ={}, iss={}, sub={}, jti={})",
-        kind.display_name(),
-        verified.alg,
-        verified.header.kid.as_deref().unwrap_or("<none>"),
-        header_typ.as_deref().unwrap_or("<missing>"),
-        iss,
-        sub,
-        jti,
-    );
-    let pretty = serde_json::to_string_pretty(vc)?;
-    println!("{pretty}");
-    Ok(())
+    Value::Object(selected)
 }
 
 fn resolve_kind(
@@ -444,3 +1423,478 @@ fn extract_audience(claims: &Value) -> Result<Vec<String>> {
         None => Ok(Vec::new()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_no_key_for_alg_message() {
+        assert_eq!(
+            no_key_for_alg(SignatureAlg::Es256),
+            "no key of type EC (P-256) for alg ES256 among the discovered public keys; pass --key"
+        );
+    }
+
+    #[test]
+    fn test_summary_lines() {
+        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use base64::Engine;
+
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let token = |alg: &str, claims: Value| {
+            format!(
+                "{}.{}.sig",
+                URL_SAFE_NO_PAD.encode(json!({ "alg": alg }).to_string()),
+                URL_SAFE_NO_PAD.encode(claims.to_string())
+            )
+        };
+
+        let valid = token(
+            "EdDSA",
+            json!({ "nbf": 1_699_000_000, "exp": 1_700_000_000 + 86_400 * 29 + 3 * 3600 }),
+        );
+        assert_eq!(
+            summary_lines(true, &valid, SchemaCheck::Passed, now),
+            vec![
+                ("Result", "VALID".to_string()),
+                ("Algorithm", "EdDSA \u{2014} OK".to_string()),
+                ("Key size", "256-bit (Ed25519)".to_string()),
+                (
+                    "Validity",
+                    "within window, 29d 3h remaining (until 2023-12-14T01:13:20Z)".to_string()
+                ),
+                ("Schema", "ran, passed".to_string()),
+            ]
+        );
+
+        let unsigned = token("none", json!({ "exp": 1_700_000_000 - 90 }));
+        let lines = summary_lines(false, &unsigned, SchemaCheck::NotReached, now);
+        assert_eq!(lines[0].1, "INVALID");
+        assert_eq!(lines[1].1, "none \u{2014} REJECTED");
+        assert_eq!(lines[2].1, "expired 1m 30s ago (2023-11-14T22:11:50Z)");
+        assert_eq!(lines[3].1, "not run (verification failed first)");
+
+        let future = token("RS256", json!({ "nbf": 1_700_000_000 + 7200 }));
+        let lines = summary_lines(false, &future, SchemaCheck::Skipped, now);
+        assert_eq!(
+            lines[1].1,
+            "RS256 \u{2014} acceptable (not supported by beltic)"
+        );
+        assert!(lines[2].1.starts_with("not yet valid, starts in 2h 0m"));
+        assert_eq!(lines[3].1, "skipped (--skip-schema)");
+    }
+
+    #[test]
+    fn test_check_token_payload_size() {
+        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use base64::Engine;
+
+        let token = |payload_len: usize| {
+            format!(
+                "eyJhbGciOiJFZERTQSJ9.{}.sig",
+                URL_SAFE_NO_PAD.encode("x".repeat(payload_len))
+            )
+        };
+        assert!(check_token_payload_size(&token(1000), 1000).is_ok());
+        assert!(check_token_payload_size(&token(999), 1000).is_ok());
+        let err = check_token_payload_size(&token(1001), 1000).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("payload exceeds limit: 1001 bytes"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_load_token_stops_at_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("big.jwt");
+        let limit = 1000;
+        let oversized = encoded_token_limit(limit) as usize + 1;
+        fs::write(&path, format!("e30.{}.sig", "x".repeat(oversized))).unwrap();
+        let err = load_token(path.to_str().unwrap(), limit).unwrap_err();
+        assert!(
+            err.to_string().starts_with("payload exceeds limit"),
+            "{}",
+            err
+        );
+
+        fs::write(&path, "eyJhbGciOiJFZERTQSJ9.e30.c2ln\n").unwrap();
+        assert_eq!(
+            load_token(path.to_str().unwrap(), limit).unwrap().trim(),
+            "eyJhbGciOiJFZERTQSJ9.e30.c2ln"
+        );
+    }
+
+    #[test]
+    fn test_unwrap_token_body() {
+        assert_eq!(unwrap_token_body(" a.b.c\n").unwrap(), "a.b.c");
+        assert_eq!(unwrap_token_body(r#""a.b.c""#).unwrap(), "a.b.c");
+        assert_eq!(unwrap_token_body(r#"{"jwt": "a.b.c"}"#).unwrap(), "a.b.c");
+        assert_eq!(
+            unwrap_token_body(r#"{"credential": "a.b.c", "format": "jwt"}"#).unwrap(),
+            "a.b.c"
+        );
+        assert!(unwrap_token_body(r#"{"agentId": "1"}"#).is_err());
+    }
+
+    #[test]
+    fn test_load_token_from_url() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/.well-known/agent-credential",
+            listener.local_addr().unwrap()
+        );
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let read = stream.read(&mut request).unwrap();
+            assert!(String::from_utf8_lossy(&request[..read])
+                .starts_with("GET /.well-known/agent-credential "));
+            let body = r#"{"token": "eyJhbGciOiJFZERTQSJ9.e30.c2ln"}"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+
+        assert_eq!(
+            load_token(&url, DEFAULT_MAX_PAYLOAD_SIZE).unwrap(),
+            "eyJhbGciOiJFZERTQSJ9.e30.c2ln"
+        );
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_success_line_honors_no_color() {
+        console::set_colors_enabled(true);
+        assert!(success_line().contains('\x1b'));
+
+        std::env::set_var("NO_COLOR", "1");
+        crate::commands::color::init(false);
+        std::env::remove_var("NO_COLOR");
+        assert!(!success_line().contains('\x1b'));
+        assert_eq!(success_line(), "Verification successful!");
+    }
+
+    #[test]
+    fn test_standard_claims_drop_the_credential() {
+        let claims = json!({
+            "iss": "did:web:example.com",
+            "sub": "did:web:example.com:agents:1",
+            "jti": "urn:uuid:1",
+            "nbf": 1,
+            "iat": 1,
+            "exp": 2,
+            "vc": {"agentName": "support-agent"}
+        });
+        assert_eq!(
+            standard_claims(&claims),
+            json!({
+                "iss": "did:web:example.com",
+                "sub": "did:web:example.com:agents:1",
+                "jti": "urn:uuid:1",
+                "nbf": 1,
+                "exp": 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_explain_failure_reports_every_stage() {
+        use clap::Parser;
+        use ed25519_dalek::pkcs8::{EncodePrivateKey, EncodePublicKey};
+        use ed25519_dalek::SigningKey;
+        use pkcs8::LineEnding;
+
+        use beltic::crypto::{sign_jws, PemSigner};
+
+        #[derive(Parser)]
+        struct Cli {
+            #[command(flatten)]
+            args: VerifyArgs,
+        }
+
+        let private_pem = SigningKey::from_bytes(&[7u8; 32])
+            .to_pkcs8_pem(LineEnding::LF)
+            .unwrap();
+        let other_pem = SigningKey::from_bytes(&[8u8; 32])
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        // Expired, no jti, and an audience nobody asked for
+        let claims = json!({
+            "iss": "did:web:acme.example.com",
+            "sub": "did:web:acme.example.com",
+            "nbf": 1,
+            "exp": 2,
+            "aud": "did:web:verifier.example",
+            "vc": {"agentId": "a"}
+        });
+        let signer = PemSigner::from_pem(&private_pem, SignatureAlg::EdDsa, None).unwrap();
+        let token = sign_jws(&claims, &signer, "application/beltic-agent+jwt", None).unwrap();
+
+        let args = Cli::parse_from(["verify", "--skip-schema", "--explain-failure"]).args;
+        let checks = explain_failure(&token, &other_pem, &args);
+        let lines = checks.lines();
+        for expected in [
+            "PASS header",
+            "PASS algorithm",
+            "FAIL signature: signature verification failed for alg EdDSA",
+            "PASS typ header",
+            "FAIL jti claim: jti claim missing",
+            "FAIL validity window: token expired at 2",
+            "FAIL audience: token contains audience claim",
+            "SKIP schema (--skip-schema)",
+        ] {
+            assert!(
+                lines.iter().any(|line| line.starts_with(expected)),
+                "{} not in {:#?}",
+                expected,
+                lines
+            );
+        }
+        assert_eq!(checks.failures(), 4);
+
+        // Fail-fast stops at the first failing claim, which is the error
+        // plain verify reports
+        let mut checks = Checklist::fail_fast();
+        let header = decode_jws_header(&token).unwrap();
+        let mut schema = SchemaCheck::initial(&args);
+        assert!(check_claims(
+            &mut checks,
+            &header,
+            &claims,
+            Some(SignatureAlg::EdDsa),
+            &other_pem,
+            &args,
+            &mut schema,
+        )
+        .is_none());
+        assert_eq!(
+            checks.lines().last().unwrap(),
+            "FAIL jti claim: jti claim missing"
+        );
+        assert_eq!(
+            checks.into_result().unwrap_err().to_string(),
+            "jti claim missing"
+        );
+    }
+
+    #[test]
+    fn test_require_typ() {
+        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use base64::Engine;
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct Cli {
+            #[command(flatten)]
+            args: VerifyArgs,
+        }
+
+        let agent: Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/agent-valid.json"
+        )))
+        .unwrap();
+        let developer: Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/developer-valid.json"
+        )))
+        .unwrap();
+
+        // Run the claim stages fail-fast and return the first failure
+        let verify = |vc: &Value, typ: Option<&str>, strict: bool| {
+            let mut header = json!({ "alg": "EdDSA" });
+            if let Some(typ) = typ {
+                header["typ"] = typ.into();
+            }
+            let now = Utc::now().timestamp();
+            let claims = json!({
+                "iss": "did:web:acme.example.com",
+                "sub": "did:web:acme.example.com",
+                "jti": "urn:uuid:1",
+                "nbf": now - 60,
+                "exp": now + 3600,
+                "vc": vc,
+            });
+            let token = format!(
+                "{}.{}.sig",
+                URL_SAFE_NO_PAD.encode(header.to_string()),
+                URL_SAFE_NO_PAD.encode(claims.to_string())
+            );
+            let mut argv = vec!["verify", "--skip-schema"];
+            if strict {
+                argv.push("--require-typ");
+            }
+            let args = Cli::parse_from(argv).args;
+
+            let mut checks = Checklist::fail_fast();
+            let mut schema = SchemaCheck::initial(&args);
+            check_claims(
+                &mut checks,
+                &decode_jws_header(&token).unwrap(),
+                &claims,
+                None,
+                "",
+                &args,
+                &mut schema,
+            );
+            checks.into_result().err().map(|err| err.to_string())
+        };
+
+        for (vc, typ, other) in [
+            (&agent, AGENT_TYP, DEVELOPER_TYP),
+            (&developer, DEVELOPER_TYP, AGENT_TYP),
+        ] {
+            for strict in [false, true] {
+                // Present and matching
+                assert_eq!(verify(vc, Some(typ), strict), None);
+                // The other kind's typ, or an unknown one
+                let err = verify(vc, Some(other), strict).unwrap();
+                assert!(err.starts_with("credential type conflict"), "{}", err);
+                let err = verify(vc, Some("JWT"), strict).unwrap();
+                assert_eq!(err, "unexpected typ header 'JWT'");
+            }
+
+            // Absent: accepted unless required
+            assert_eq!(verify(vc, None, false), None);
+            assert_eq!(
+                verify(vc, None, true).unwrap(),
+                format!("typ header missing (--require-typ expects {})", typ)
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_developer_credential() {
+        use clap::Parser;
+        use ed25519_dalek::pkcs8::{EncodePrivateKey, EncodePublicKey};
+        use ed25519_dalek::SigningKey;
+        use pkcs8::LineEnding;
+
+        use beltic::crypto::jwk::public_jwk_from_pem;
+        use beltic::crypto::{sign_jws, PemSigner};
+
+        #[derive(Parser)]
+        struct Cli {
+            #[command(flatten)]
+            args: VerifyArgs,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let private_pem = signing_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+        let public_pem = signing_key
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        let (_, jwk) = public_jwk_from_pem(&public_pem).unwrap();
+        let developer_id = "7ee13740-5e91-474a-b943-80298a7c0221";
+        let claims = json!({
+            "iss": "did:web:acme.example.com",
+            "sub": "did:web:acme.example.com",
+            "jti": developer_id,
+            "nbf": 1,
+            "exp": 4102444800i64,
+            "vc": {
+                "credentialId": developer_id,
+                "legalName": "Acme",
+                "publicKey": {"publicKeyJwk": jwk}
+            }
+        });
+        let signer = PemSigner::from_pem(&private_pem, SignatureAlg::EdDsa, None).unwrap();
+        let write_token = |name: &str, claims: &Value| {
+            let path = dir.path().join(name);
+            fs::write(&path, sign_jws(claims, &signer, "JWT", None).unwrap()).unwrap();
+            path.to_str().unwrap().to_string()
+        };
+        let token_path = write_token("developer.jwt", &claims);
+        let key_path = dir.path().join("developer.pem");
+        fs::write(&key_path, &public_pem).unwrap();
+        let key_path = key_path.to_str().unwrap();
+
+        // Without --developer-key the embedded key isn't trusted
+        let args = Cli::parse_from(["verify", "--developer-credential", &token_path]).args;
+        let agent = json!({"agentId": "a", "developerCredentialId": developer_id});
+        let link = check_developer_credential(&args, CredentialKind::Agent, &agent)
+            .unwrap()
+            .unwrap();
+        assert_eq!(link.credential_id, developer_id);
+        assert!(link.key_source.is_none());
+        assert!(link.key_label().starts_with("UNVERIFIED"));
+
+        let verify_with_key = |token_path: &str| {
+            let args = Cli::parse_from([
+                "verify",
+                "--developer-credential",
+                token_path,
+                "--developer-key",
+                key_path,
+            ])
+            .args;
+            check_developer_credential(&args, CredentialKind::Agent, &agent)
+        };
+        let link = verify_with_key(&token_path).unwrap().unwrap();
+        assert_eq!(link.issuer, "did:web:acme.example.com");
+        assert_eq!(link.expires, "2100-01-01T00:00:00Z");
+        assert_eq!(link.key_source.as_deref(), Some(key_path));
+
+        // exp is required, and an expired expirationDate fails the link
+        let mut no_exp = claims.clone();
+        no_exp.as_object_mut().unwrap().remove("exp");
+        let err = verify_with_key(&write_token("no-exp.jwt", &no_exp))
+            .err()
+            .unwrap();
+        assert!(
+            format!("{:#}", err).contains("exp claims are required"),
+            "{:#}",
+            err
+        );
+        let mut expired = claims.clone();
+        expired["vc"]["expirationDate"] = json!("2020-01-01T00:00:00Z");
+        let err = verify_with_key(&write_token("expired.jwt", &expired))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("expired on"), "{}", err);
+
+        let other = json!({"developerCredentialId": "00000000-0000-0000-0000-000000000001"});
+        let err = check_developer_credential(&args, CredentialKind::Agent, &other)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("names developer credential"), "{}", err);
+
+        // A key that did not sign it fails verification
+        let other_key = dir.path().join("other.pem");
+        let other_pem = SigningKey::from_bytes(&[8u8; 32])
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        fs::write(&other_key, other_pem).unwrap();
+        let args = Cli::parse_from([
+            "verify",
+            "--developer-credential",
+            &token_path,
+            "--developer-key",
+            other_key.to_str().unwrap(),
+        ])
+        .args;
+        let err = check_developer_credential(&args, CredentialKind::Agent, &agent)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("failed verification"), "{}", err);
+    }
+}
@@ -5,9 +5,11 @@
 use anyhow::{Context, Result};
 use clap::Args;
 use console::style;
-use serde::{Deserialize, Serialize};
 
-use crate::config::{load_config, load_credentials};
+use beltic::config::{load_config, load_credentials};
+use beltic::http::{self, request_error};
+
+use super::auth::DeveloperMeResponse;
 
 #[derive(Args)]
 pub struct WhoamiArgs {
@@ -16,29 +18,6 @@ pub struct WhoamiArgs {
     pub json: bool,
 }
 
-/// Response from GET /api/developers/me
-#[derive(Debug, Deserialize, Serialize)]
-struct DeveloperMeResponse {
-    data: DeveloperData,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct DeveloperData {
-    id: String,
-    #[serde(rename = "type")]
-    _type: String,
-    attributes: DeveloperAttributes,
-}
-
-#[derive(Debug, Deserialize, Serialize)]
-struct DeveloperAttributes {
-    legal_name: Option<String>,
-    kyb_tier: Option<String>,
-    verification_status: Option<String>,
-    default_org: Option<String>,
-    created_at: Option<String>,
-}
-
 pub fn run(args: WhoamiArgs) -> Result<()> {
     // Load credentials
     let access_token =
@@ -48,7 +27,7 @@ pub fn run(args: WhoamiArgs) -> Result<()> {
     let config = load_config().unwrap_or_default();
 
     // Call API
-    let client = reqwest::blocking::Client::new();
+    let client = http::client()?;
     let response = client
         .get(format!(
             "{}/api/developers/me",
@@ -57,6 +36,7 @@ pub fn run(args: WhoamiArgs) -> Result<()> {
         .header("Authorization", format!("Bearer {}", access_token))
         .header("Accept", "application/json")
         .send()
+        .map_err(|e| request_error(e, "console"))
         .context("failed to connect to console API")?;
 
     if !response.status().is_success() {
@@ -117,7 +117,7 @@ pub fn save_credentials(access_token: &str) -> Result<()> {
         fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
             .with_context(|| format!("failed to set permissions on {}", path.display()))?;
 
-        return Ok(());
+        Ok(())
     }
 
     #[cfg(not(unix))]
@@ -1,15 +1,22 @@
 use anyhow::{anyhow, Result};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SubsecRound, Utc};
 use jsonschema::{Draft, JSONSchema};
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::sync::{Mutex, OnceLock};
 
+use crate::crypto::SignatureAlg;
+use crate::duration::IsoDuration;
 use crate::schema::{self, SchemaType};
 
 /// Media type for DeveloperCredential JWTs.
 pub const DEVELOPER_TYP: &str = "application/beltic-developer+jwt";
 /// Media type for AgentCredential JWTs.
 pub const AGENT_TYP: &str = "application/beltic-agent+jwt";
+/// Top-level JWT claim carrying signing provenance. It sits next to `vc`,
+/// so the credential itself still validates against its schema.
+pub const SIGNING_METADATA_CLAIM: &str = "signingMetadata";
 
 /// Supported credential types for signing/verification.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -55,9 +62,9 @@ impl CredentialKind {
     }
 }
 
-// Use mutex-protected boxes for dynamic schema storage
-static AGENT_SCHEMA: OnceLock<Mutex<Option<Value>>> = OnceLock::new();
-static DEVELOPER_SCHEMA: OnceLock<Mutex<Option<Value>>> = OnceLock::new();
+// Schemas loaded so far, by version
+static AGENT_SCHEMA: OnceLock<Mutex<HashMap<String, Value>>> = OnceLock::new();
+static DEVELOPER_SCHEMA: OnceLock<Mutex<HashMap<String, Value>>> = OnceLock::new();
 
 /// Parse a credential type string (for CLI value parsers).
 pub fn parse_credential_kind(value: &str) -> Result<CredentialKind, String> {
@@ -103,35 +110,76 @@ pub fn detect_credential_kind(value: &Value) -> Option<CredentialKind> {
     None
 }
 
+/// A schema violation located by JSON pointer.
+#[derive(Debug, Clone)]
+pub struct SchemaViolation {
+    /// JSON pointer into the credential; empty for the document root
+    pub pointer: String,
+    pub message: String,
+}
+
 /// Validate the credential JSON against the schema.
 /// Uses dynamic schema fetching with caching and embedded fallback.
 pub fn validate_credential(kind: CredentialKind, value: &Value) -> Result<Vec<String>> {
+    validate_credential_version(kind, value, &schema::active_version(None))
+}
+
+/// [`validate_credential`] against a given schema version, e.g. the one
+/// pinned in a `--config` file
+pub fn validate_credential_version(
+    kind: CredentialKind,
+    value: &Value,
+    version: &str,
+) -> Result<Vec<String>> {
+    let violations = schema_violations_version(kind, value, version)?;
+
+    Ok(violations
+        .into_iter()
+        .map(|v| {
+            let location = if v.pointer.is_empty() {
+                "<root>"
+            } else {
+                v.pointer.as_str()
+            };
+            format!("{location}: {}", v.message)
+        })
+        .collect())
+}
+
+/// Validate the credential JSON against the schema, keeping error locations.
+pub fn schema_violations(kind: CredentialKind, value: &Value) -> Result<Vec<SchemaViolation>> {
+    schema_violations_version(kind, value, &schema::active_version(None))
+}
+
+/// [`schema_violations`] against a given schema version
+pub fn schema_violations_version(
+    kind: CredentialKind,
+    value: &Value,
+    version: &str,
+) -> Result<Vec<SchemaViolation>> {
     // Ensure schema is loaded
-    let schema = ensure_schema_loaded(kind);
+    let schema = ensure_schema_loaded(kind, version);
 
     // Compile the schema (we compile fresh each time to use latest fetched schema)
     let compiled = compile_schema(&schema);
 
-    let mut errors = Vec::new();
+    let mut violations = Vec::new();
     if let Err(iter) = compiled.validate(value) {
         for err in iter {
-            let path = err.instance_path.to_string();
-            let location = if path.is_empty() {
-                "<root>"
-            } else {
-                path.as_str()
-            };
-            errors.push(format!("{location}: {err}"));
+            violations.push(SchemaViolation {
+                pointer: err.instance_path.to_string(),
+                message: err.to_string(),
+            });
         }
     }
 
-    Ok(errors)
+    Ok(violations)
 }
 
 /// Get or fetch the schema for a credential kind.
 /// Uses dynamic fetching with caching and embedded fallback.
-fn get_or_fetch_schema(kind: CredentialKind) -> Value {
-    schema::get_schema(kind.schema_type()).unwrap_or_else(|_| {
+fn get_or_fetch_schema(kind: CredentialKind, version: &str) -> Value {
+    schema::get_schema_version(kind.schema_type(), version).unwrap_or_else(|_| {
         // Ultimate fallback: use embedded schema
         match kind {
             CredentialKind::Agent => serde_json::from_str(include_str!(
@@ -146,17 +194,17 @@ fn get_or_fetch_schema(kind: CredentialKind) -> Value {
     })
 }
 
-fn ensure_schema_loaded(kind: CredentialKind) -> Value {
+fn ensure_schema_loaded(kind: CredentialKind, version: &str) -> Value {
     let schema_lock = match kind {
-        CredentialKind::Agent => AGENT_SCHEMA.get_or_init(|| Mutex::new(None)),
-        CredentialKind::Developer => DEVELOPER_SCHEMA.get_or_init(|| Mutex::new(None)),
+        CredentialKind::Agent => AGENT_SCHEMA.get_or_init(|| Mutex::new(HashMap::new())),
+        CredentialKind::Developer => DEVELOPER_SCHEMA.get_or_init(|| Mutex::new(HashMap::new())),
     };
 
     let mut guard = schema_lock.lock().unwrap();
-    if guard.is_none() {
-        *guard = Some(get_or_fetch_schema(kind));
-    }
-    guard.as_ref().unwrap().clone()
+    guard
+        .entry(version.to_string())
+        .or_insert_with(|| get_or_fetch_schema(kind, version))
+        .clone()
 }
 
 fn compile_schema(schema: &Value) -> JSONSchema {
@@ -171,6 +219,14 @@ pub struct ClaimsOptions<'a> {
     pub issuer: Option<&'a str>,
     pub subject: Option<&'a str>,
     pub audience: &'a [String],
+    /// Set `exp` this long after issuance instead of from the credential's
+    /// expiration date
+    pub valid_for: Option<IsoDuration>,
+    /// `jti` to use instead of the credential's `credentialId`
+    pub jti: Option<&'a str>,
+    /// Set `nbf` and `iat` to this time instead of the credential's issuance
+    /// date
+    pub not_before: Option<DateTime<Utc>>,
 }
 
 /// Build JWT claims following the Beltic signing profile.
@@ -181,8 +237,12 @@ pub fn build_claims(
 ) -> Result<Value> {
     let issuer = if let Some(override_issuer) = options.issuer {
         override_issuer.to_string()
+    } else if let Some(issuer) = credential.get("issuerDid").and_then(|v| v.as_str()) {
+        issuer.to_string()
     } else {
-        extract_string(credential, "issuerDid")?
+        return Err(anyhow!(
+            "issuer DID is required (pass --issuer or include issuerDid in the credential)"
+        ));
     };
 
     let subject = if let Some(subject) = options.subject {
@@ -208,9 +268,40 @@ pub fn build_claims(
         ));
     };
 
-    let credential_id = extract_string(credential, "credentialId")?;
-    let nbf = parse_rfc3339_seconds(credential, kind.issuance_field())?;
-    let exp = parse_rfc3339_seconds(credential, kind.expiration_field())?;
+    // The verifier requires a jti: fall back to a fresh UUID when the
+    // credential has no credentialId
+    let jti = match options.jti {
+        Some(jti) => jti.to_string(),
+        None => credential
+            .get("credentialId")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+    };
+    // Hand-written payloads may lack the dates: nbf then defaults to now,
+    // and exp must come from --valid-for
+    let nbf = match options.not_before {
+        Some(not_before) => not_before.timestamp(),
+        None if credential.get(kind.issuance_field()).is_some() => {
+            parse_rfc3339_seconds(credential, kind.issuance_field())?
+        }
+        None => Utc::now().timestamp(),
+    };
+    let exp = match options.valid_for {
+        Some(valid_for) => DateTime::from_timestamp(nbf, 0)
+            .and_then(|issued| valid_for.after(issued))
+            .ok_or_else(|| anyhow!("--valid-for {} is out of range", valid_for))?
+            .timestamp(),
+        None if credential.get(kind.expiration_field()).is_some() => {
+            parse_rfc3339_seconds(credential, kind.expiration_field())?
+        }
+        None => {
+            return Err(anyhow!(
+                "credential has no {}; pass --expires-in to set exp",
+                kind.expiration_field()
+            ))
+        }
+    };
 
     if exp <= nbf {
         return Err(anyhow!(
@@ -223,7 +314,7 @@ pub fn build_claims(
     let mut claims = Map::new();
     claims.insert("iss".to_string(), Value::String(issuer));
     claims.insert("sub".to_string(), Value::String(subject));
-    claims.insert("jti".to_string(), Value::String(credential_id));
+    claims.insert("jti".to_string(), Value::String(jti));
     claims.insert("nbf".to_string(), Value::Number(nbf.into()));
     claims.insert("exp".to_string(), Value::Number(exp.into()));
     claims.insert("iat".to_string(), Value::Number(nbf.into()));
@@ -252,6 +343,94 @@ pub fn build_claims(
     Ok(Value::Object(claims))
 }
 
+/// Check that the credential's `proof.type` fits the JWS algorithm.
+///
+/// `EcdsaSecp256k1Signature2019` needs an ES256K key, and an ES256K key needs
+/// that proof type or the algorithm-agnostic `JsonWebSignature2020`. Other
+/// combinations are left as they are. Credentials without a proof pass.
+pub fn check_proof_type(credential: &Value, alg: SignatureAlg) -> Result<()> {
+    let Some(proof_type) = credential
+        .get("proof")
+        .and_then(|proof| proof.get("type"))
+        .and_then(Value::as_str)
+    else {
+        return Ok(());
+    };
+
+    let secp256k1_proof = proof_type == "EcdsaSecp256k1Signature2019";
+    let fits = match alg {
+        SignatureAlg::Es256k => secp256k1_proof || proof_type == "JsonWebSignature2020",
+        SignatureAlg::Es256 | SignatureAlg::EdDsa => !secp256k1_proof,
+    };
+    if !fits {
+        let expected = if secp256k1_proof {
+            "use an ES256K (secp256k1) key".to_string()
+        } else {
+            format!(
+                "set proof.type to EcdsaSecp256k1Signature2019 or JsonWebSignature2020, \
+                 or sign with a key matching {}",
+                proof_type
+            )
+        };
+        return Err(anyhow!(
+            "proof type {} does not match algorithm {}: {}",
+            proof_type,
+            alg,
+            expected
+        ));
+    }
+    Ok(())
+}
+
+/// Who and what signed a credential, embedded with `sign --embed-provenance`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SigningMetadata {
+    pub tool: String,
+    pub tool_version: String,
+    pub signed_at: DateTime<Utc>,
+    /// RFC 7638 thumbprint of the signing key
+    pub signer_key_thumbprint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+}
+
+impl SigningMetadata {
+    /// Metadata for a signature made now by this build of the CLI
+    pub fn new(signer_key_thumbprint: String, git_commit: Option<String>) -> Self {
+        Self {
+            tool: env!("CARGO_PKG_NAME").to_string(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            signed_at: Utc::now().trunc_subsecs(0),
+            signer_key_thumbprint,
+            git_commit,
+        }
+    }
+
+    /// Add the metadata to JWT claims built by [`build_claims`].
+    pub fn embed(&self, claims: &mut Value) -> Result<()> {
+        let object = claims
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("JWT claims must be an object"))?;
+        object.insert(
+            SIGNING_METADATA_CLAIM.to_string(),
+            serde_json::to_value(self)?,
+        );
+        Ok(())
+    }
+
+    /// Read the metadata from verified JWT claims, if present.
+    pub fn from_claims(claims: &Value) -> Result<Option<Self>> {
+        claims
+            .get(SIGNING_METADATA_CLAIM)
+            .map(|value| {
+                serde_json::from_value(value.clone())
+                    .map_err(|e| anyhow!("invalid {} claim: {}", SIGNING_METADATA_CLAIM, e))
+            })
+            .transpose()
+    }
+}
+
 fn extract_string(value: &Value, field: &str) -> Result<String> {
     value
         .get(field)
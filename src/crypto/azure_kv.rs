@@ -0,0 +1,173 @@
+//! Signing with a key held in Azure Key Vault (`azure-kv` feature).
+//!
+//! The SHA-256 digest of the JWS signing input is sent to the Key Vault
+//! `sign` operation, which returns the signature already in JWS `r || s`
+//! form. Key Vault has EC keys on P-256 (ES256) and P-256K (ES256K) but no
+//! Ed25519. The access token comes from `AZURE_ACCESS_TOKEN` or the Azure CLI.
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use super::jwk::public_pem_from_jwk;
+use super::remote::{access_token, low_s_es256k, send_json};
+use super::signer::Signer;
+use super::SignatureAlg;
+
+/// Environment variable holding a Key Vault access token; without it the
+/// token is requested from `az account get-access-token`
+pub const AZURE_TOKEN_ENV: &str = "AZURE_ACCESS_TOKEN";
+
+const API_VERSION: &str = "7.4";
+const SERVICE: &str = "Azure Key Vault";
+
+/// Signs with an Azure Key Vault EC key
+pub struct AzureKeyVaultSigner {
+    http: Client,
+    token: String,
+    /// Versioned key identifier, `https://<vault>.vault.azure.net/keys/<name>/<version>`
+    key_id: String,
+    alg: SignatureAlg,
+    kid: Option<String>,
+    /// The key's public JWK as returned by Key Vault
+    jwk: Value,
+}
+
+impl AzureKeyVaultSigner {
+    /// Look up `key_id` (a key identifier URL, with or without version) and
+    /// check that it is an EC key for `alg`. Without a version the key's
+    /// current version is used.
+    pub fn new(key_id: &str, alg: SignatureAlg, kid: Option<String>) -> Result<Self> {
+        let curve = curve_for(alg)?;
+        let key_id = key_id.trim_end_matches('/');
+        if !key_id.starts_with("https://") || !key_id.contains("/keys/") {
+            bail!(
+                "Azure key ID must look like https://<vault>.vault.azure.net/keys/<name>[/<version>], got {}",
+                key_id
+            );
+        }
+
+        let http = crate::http::client()?;
+        let token = access_token(
+            AZURE_TOKEN_ENV,
+            "az",
+            &[
+                "account",
+                "get-access-token",
+                "--resource",
+                "https://vault.azure.net",
+                "--query",
+                "accessToken",
+                "--output",
+                "tsv",
+            ],
+        )?;
+        let bundle = send_json(
+            http.get(format!("{}?api-version={}", key_id, API_VERSION))
+                .bearer_auth(&token),
+            SERVICE,
+        )?;
+        let jwk = bundle
+            .get("key")
+            .cloned()
+            .context("Azure Key Vault returned no key")?;
+        let crv = jwk.get("crv").and_then(Value::as_str);
+        if jwk.get("kty").and_then(Value::as_str) != Some("EC") || crv != Some(curve) {
+            bail!(
+                "Azure key {} is not an EC {} key, which {} needs (crv: {})",
+                key_id,
+                curve,
+                alg,
+                crv.unwrap_or("<none>")
+            );
+        }
+        let versioned = jwk
+            .get("kid")
+            .and_then(Value::as_str)
+            .unwrap_or(key_id)
+            .to_string();
+
+        Ok(Self {
+            http,
+            token,
+            key_id: versioned,
+            alg,
+            kid,
+            jwk,
+        })
+    }
+}
+
+/// Key Vault curve name for `alg`
+fn curve_for(alg: SignatureAlg) -> Result<&'static str> {
+    match alg {
+        SignatureAlg::Es256 => Ok("P-256"),
+        SignatureAlg::Es256k => Ok("P-256K"),
+        SignatureAlg::EdDsa => {
+            bail!("Azure Key Vault has no Ed25519 keys; use --alg ES256 or ES256K")
+        }
+    }
+}
+
+impl Signer for AzureKeyVaultSigner {
+    fn alg(&self) -> SignatureAlg {
+        self.alg
+    }
+
+    fn kid(&self) -> Option<&str> {
+        self.kid.as_deref()
+    }
+
+    fn sign(&self, signing_input: &[u8]) -> Result<Vec<u8>> {
+        let digest = Sha256::digest(signing_input);
+        let result = send_json(
+            self.http
+                .post(format!("{}/sign?api-version={}", self.key_id, API_VERSION))
+                .bearer_auth(&self.token)
+                .json(&json!({
+                    "alg": self.alg.jws_name(),
+                    "value": URL_SAFE_NO_PAD.encode(digest),
+                })),
+            SERVICE,
+        )?;
+        let signature = result
+            .get("value")
+            .and_then(Value::as_str)
+            .context("Azure Key Vault returned no signature")?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .context("Azure Key Vault returned a signature that is not base64url")?;
+        match self.alg {
+            SignatureAlg::Es256k => low_s_es256k(&signature),
+            _ => Ok(signature),
+        }
+    }
+
+    fn public_key_pem(&self) -> Result<Option<String>> {
+        // JOSE names the P-256K curve secp256k1
+        let mut jwk = self.jwk.clone();
+        if jwk["crv"] == "P-256K" {
+            jwk["crv"] = json!("secp256k1");
+        }
+        public_pem_from_jwk(&jwk, self.alg).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_for_alg() {
+        assert_eq!(curve_for(SignatureAlg::Es256).unwrap(), "P-256");
+        assert_eq!(curve_for(SignatureAlg::Es256k).unwrap(), "P-256K");
+        assert!(curve_for(SignatureAlg::EdDsa).is_err());
+    }
+
+    #[test]
+    fn test_rejects_non_key_vault_ids() {
+        assert!(AzureKeyVaultSigner::new("my-key", SignatureAlg::Es256, None).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! `did:key` identifiers for Ed25519 and P-256 public keys: the multicodec
+//! key type prefix and key bytes, base58btc-encoded as a multibase string.
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde_json::Value;
+
+/// Multicodec prefix of an Ed25519 public key (`ed25519-pub`, varint 0xed)
+const ED25519_PUB: [u8; 2] = [0xed, 0x01];
+/// Multicodec prefix of a compressed P-256 public key (`p256-pub`, varint 0x1200)
+const P256_PUB: [u8; 2] = [0x80, 0x24];
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// The `did:key` DID of an OKP/Ed25519 or EC/P-256 public JWK
+pub fn did_key_from_jwk(jwk: &Value) -> Result<String> {
+    let member = |name: &str| -> Result<Vec<u8>> {
+        let value = jwk
+            .get(name)
+            .and_then(Value::as_str)
+            .with_context(|| format!("JWK has no \"{}\"", name))?;
+        URL_SAFE_NO_PAD
+            .decode(value)
+            .with_context(|| format!("JWK \"{}\" is not base64url", name))
+    };
+    let field = |name: &str| jwk.get(name).and_then(Value::as_str);
+
+    let key = match (field("kty"), field("crv")) {
+        (Some("OKP"), Some("Ed25519")) => {
+            let x = member("x")?;
+            if x.len() != 32 {
+                bail!("Ed25519 JWK \"x\" must be 32 bytes");
+            }
+            [&ED25519_PUB[..], &x].concat()
+        }
+        (Some("EC"), Some("P-256")) => {
+            let (x, y) = (member("x")?, member("y")?);
+            if x.len() != 32 || y.len() != 32 {
+                bail!("P-256 JWK coordinates must be 32 bytes each");
+            }
+            // Compressed SEC1 point: the parity of y, then x
+            let parity = if y[31] & 1 == 0 { 0x02 } else { 0x03 };
+            [&P256_PUB[..], &[parity], &x].concat()
+        }
+        (kty, crv) => bail!(
+            "did:key supports Ed25519 and P-256 keys, not kty {} with crv {}",
+            kty.unwrap_or("<missing>"),
+            crv.unwrap_or("<missing>")
+        ),
+    };
+    Ok(format!("did:key:z{}", base58btc(&key)))
+}
+
+fn base58btc(bytes: &[u8]) -> String {
+    // Base-58 digits, least significant first
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in bytes {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    // Each leading zero byte is a leading '1'
+    let zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+    let digits = digits
+        .iter()
+        .rev()
+        .map(|&digit| BASE58_ALPHABET[digit as usize]);
+    std::iter::repeat_n(b'1', zeros)
+        .chain(digits)
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_base58btc() {
+        assert_eq!(base58btc(b"Hello World!"), "2NEpo7TZRRrLZSi2U");
+        assert_eq!(base58btc(&[0, 0, 1]), "112");
+        assert_eq!(base58btc(&[]), "");
+    }
+
+    #[test]
+    fn test_did_key_prefixes() {
+        // RFC 8037 Appendix A.2
+        let ed = json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo"
+        });
+        let did = did_key_from_jwk(&ed).unwrap();
+        assert!(did.starts_with("did:key:z6Mk"), "{}", did);
+
+        let ec = json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "x": URL_SAFE_NO_PAD.encode([1u8; 32]),
+            "y": URL_SAFE_NO_PAD.encode([2u8; 32])
+        });
+        let did = did_key_from_jwk(&ec).unwrap();
+        assert!(did.starts_with("did:key:zDn"), "{}", did);
+
+        let err = did_key_from_jwk(&json!({ "kty": "RSA" }))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("kty RSA"), "{}", err);
+    }
+}
@@ -0,0 +1,155 @@
+//! Signing with a key held in Google Cloud KMS (`gcp-kms` feature).
+//!
+//! EC keys sign the SHA-256 digest of the JWS signing input and return a DER
+//! signature, converted to the `r || s` form JWS uses; Ed25519 keys sign the
+//! signing input itself. The access token comes from
+//! `GOOGLE_OAUTH_ACCESS_TOKEN` or the gcloud CLI.
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use super::remote::{access_token, raw_ecdsa_signature, send_json};
+use super::signer::Signer;
+use super::SignatureAlg;
+
+/// Environment variable holding a Cloud KMS access token; without it the
+/// token is requested from `gcloud auth print-access-token`
+pub const GCP_TOKEN_ENV: &str = "GOOGLE_OAUTH_ACCESS_TOKEN";
+
+const API: &str = "https://cloudkms.googleapis.com/v1";
+const SERVICE: &str = "Cloud KMS";
+
+/// Signs with a Cloud KMS asymmetric signing key version
+pub struct GcpKmsSigner {
+    http: Client,
+    token: String,
+    /// `projects/<p>/locations/<l>/keyRings/<r>/cryptoKeys/<k>/cryptoKeyVersions/<n>`
+    key_version: String,
+    alg: SignatureAlg,
+    kid: Option<String>,
+    public_key_pem: String,
+}
+
+impl GcpKmsSigner {
+    /// Look up `key_version` and check that its algorithm matches `alg`
+    pub fn new(key_version: &str, alg: SignatureAlg, kid: Option<String>) -> Result<Self> {
+        let key_version = key_version.trim_matches('/');
+        if !key_version.starts_with("projects/") || !key_version.contains("/cryptoKeyVersions/") {
+            bail!(
+                "GCP key must be a key version name (projects/<p>/locations/<l>/keyRings/<r>/cryptoKeys/<k>/cryptoKeyVersions/<n>), got {}",
+                key_version
+            );
+        }
+
+        let http = crate::http::client()?;
+        let token = access_token(GCP_TOKEN_ENV, "gcloud", &["auth", "print-access-token"])?;
+        let public_key = send_json(
+            http.get(format!("{}/{}/publicKey", API, key_version))
+                .bearer_auth(&token),
+            SERVICE,
+        )?;
+        let algorithm = public_key
+            .get("algorithm")
+            .and_then(Value::as_str)
+            .unwrap_or("<unknown>");
+        if algorithm_alg(algorithm) != Some(alg) {
+            bail!(
+                "Cloud KMS key {} uses {}, which cannot sign {}",
+                key_version,
+                algorithm,
+                alg
+            );
+        }
+        let public_key_pem = public_key
+            .get("pem")
+            .and_then(Value::as_str)
+            .context("Cloud KMS returned no public key")?
+            .to_string();
+
+        Ok(Self {
+            http,
+            token,
+            key_version: key_version.to_string(),
+            alg,
+            kid,
+            public_key_pem,
+        })
+    }
+}
+
+/// The JWS algorithm for a Cloud KMS key algorithm, if it has one
+fn algorithm_alg(algorithm: &str) -> Option<SignatureAlg> {
+    match algorithm {
+        "EC_SIGN_P256_SHA256" => Some(SignatureAlg::Es256),
+        "EC_SIGN_SECP256K1_SHA256" => Some(SignatureAlg::Es256k),
+        "EC_SIGN_ED25519" => Some(SignatureAlg::EdDsa),
+        _ => None,
+    }
+}
+
+impl Signer for GcpKmsSigner {
+    fn alg(&self) -> SignatureAlg {
+        self.alg
+    }
+
+    fn kid(&self) -> Option<&str> {
+        self.kid.as_deref()
+    }
+
+    fn sign(&self, signing_input: &[u8]) -> Result<Vec<u8>> {
+        let body = match self.alg {
+            SignatureAlg::EdDsa => json!({ "data": STANDARD.encode(signing_input) }),
+            _ => json!({ "digest": { "sha256": STANDARD.encode(Sha256::digest(signing_input)) } }),
+        };
+        let result = send_json(
+            self.http
+                .post(format!("{}/{}:asymmetricSign", API, self.key_version))
+                .bearer_auth(&self.token)
+                .json(&body),
+            SERVICE,
+        )?;
+        let signature = result
+            .get("signature")
+            .and_then(Value::as_str)
+            .context("Cloud KMS returned no signature")?;
+        let signature = STANDARD
+            .decode(signature)
+            .context("Cloud KMS returned a signature that is not base64")?;
+        match self.alg {
+            SignatureAlg::EdDsa => Ok(signature),
+            alg => raw_ecdsa_signature(alg, &signature),
+        }
+    }
+
+    fn public_key_pem(&self) -> Result<Option<String>> {
+        Ok(Some(self.public_key_pem.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_algorithm_alg() {
+        assert_eq!(
+            algorithm_alg("EC_SIGN_P256_SHA256"),
+            Some(SignatureAlg::Es256)
+        );
+        assert_eq!(
+            algorithm_alg("EC_SIGN_SECP256K1_SHA256"),
+            Some(SignatureAlg::Es256k)
+        );
+        assert_eq!(algorithm_alg("EC_SIGN_ED25519"), Some(SignatureAlg::EdDsa));
+        assert_eq!(algorithm_alg("RSA_SIGN_PSS_2048_SHA256"), None);
+    }
+
+    #[test]
+    fn test_rejects_key_names_without_version() {
+        let key = "projects/p/locations/global/keyRings/r/cryptoKeys/k";
+        assert!(GcpKmsSigner::new(key, SignatureAlg::Es256, None).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! Signing with a key held in AWS KMS (`kms` feature).
+//!
+//! The private key never leaves KMS: the SHA-256 digest of the JWS signing
+//! input is sent to the `Sign` API and the DER signature it returns is
+//! converted to the `r || s` form JWS uses. Credentials and region come from
+//! the standard AWS environment (variables, profile, instance role).
+
+use anyhow::{bail, Context, Result};
+use aws_sdk_kms::primitives::Blob;
+use aws_sdk_kms::types::{KeySpec, MessageType, SigningAlgorithmSpec};
+use aws_sdk_kms::Client;
+use pkcs8::der::Document;
+use pkcs8::LineEnding;
+use sha2::{Digest, Sha256};
+use tokio::runtime::Runtime;
+
+use super::remote::raw_ecdsa_signature;
+use super::signer::Signer;
+use super::SignatureAlg;
+
+/// Signs with an asymmetric AWS KMS key (`ECC_NIST_P256` for ES256,
+/// `ECC_SECG_P256K1` for ES256K)
+pub struct KmsSigner {
+    runtime: Runtime,
+    client: Client,
+    key_id: String,
+    alg: SignatureAlg,
+    kid: Option<String>,
+}
+
+impl KmsSigner {
+    /// Connect to KMS and check that `key_id` (key ID, ARN or alias) is a
+    /// signing key for `alg`
+    pub fn new(key_id: &str, alg: SignatureAlg, kid: Option<String>) -> Result<Self> {
+        let expected = match alg {
+            SignatureAlg::Es256 => KeySpec::EccNistP256,
+            SignatureAlg::Es256k => KeySpec::EccSecgP256K1,
+            SignatureAlg::EdDsa => {
+                bail!("AWS KMS has no Ed25519 signing keys; use --alg ES256 or ES256K")
+            }
+        };
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("failed to start the AWS KMS client runtime")?;
+        let config = runtime.block_on(aws_config::load_from_env());
+        let signer = Self {
+            client: Client::new(&config),
+            runtime,
+            key_id: key_id.to_string(),
+            alg,
+            kid,
+        };
+
+        let key = signer
+            .runtime
+            .block_on(signer.client.describe_key().key_id(key_id).send())
+            .with_context(|| format!("failed to describe KMS key {}", key_id))?;
+        let spec = key.key_metadata().and_then(|metadata| metadata.key_spec());
+        if spec != Some(&expected) {
+            bail!(
+                "KMS key {} has key spec {}, but {} needs {}",
+                key_id,
+                spec.map(KeySpec::as_str).unwrap_or("<unknown>"),
+                alg,
+                expected.as_str()
+            );
+        }
+        Ok(signer)
+    }
+}
+
+impl Signer for KmsSigner {
+    fn alg(&self) -> SignatureAlg {
+        self.alg
+    }
+
+    fn kid(&self) -> Option<&str> {
+        self.kid.as_deref()
+    }
+
+    fn sign(&self, signing_input: &[u8]) -> Result<Vec<u8>> {
+        let digest = Sha256::digest(signing_input);
+        let output = self
+            .runtime
+            .block_on(
+                self.client
+                    .sign()
+                    .key_id(&self.key_id)
+                    .message(Blob::new(digest.to_vec()))
+                    .message_type(MessageType::Digest)
+                    .signing_algorithm(SigningAlgorithmSpec::EcdsaSha256)
+                    .send(),
+            )
+            .with_context(|| format!("KMS failed to sign with key {}", self.key_id))?;
+        let der = output
+            .signature()
+            .context("KMS returned no signature")?
+            .as_ref();
+        raw_ecdsa_signature(self.alg, der)
+    }
+
+    fn public_key_pem(&self) -> Result<Option<String>> {
+        let output = self
+            .runtime
+            .block_on(self.client.get_public_key().key_id(&self.key_id).send())
+            .with_context(|| format!("failed to get public key of KMS key {}", self.key_id))?;
+        let der = output
+            .public_key()
+            .context("KMS returned no public key")?
+            .as_ref();
+        Document::try_from(der)
+            .and_then(|document| document.to_pem("PUBLIC KEY", LineEnding::LF))
+            .map(Some)
+            .map_err(|err| anyhow::anyhow!("KMS returned an invalid public key: {}", err))
+    }
+}
@@ -3,23 +3,71 @@ use std::{fmt, str::FromStr};
 use anyhow::anyhow;
 use jsonwebtoken::Algorithm;
 
+#[cfg(feature = "azure-kv")]
+pub mod azure_kv;
+pub mod did_key;
+#[cfg(feature = "gcp-kms")]
+pub mod gcp_kms;
+pub mod http_signature;
+pub mod jwk;
+#[cfg(feature = "kms")]
+pub mod kms;
+#[cfg(any(feature = "kms", feature = "azure-kv", feature = "gcp-kms"))]
+mod remote;
+pub mod secp256k1;
 pub mod signer;
 pub mod verifier;
 
-pub use signer::sign_jws;
-pub use verifier::{verify_jws, VerifiedToken};
+pub use signer::{sign_jws, PemSigner, Signer};
+pub use verifier::{
+    decode_jws_header, verify_jws, verify_jws_signature, verify_jws_with_allowed_algs,
+    verify_jws_with_pem, JwsHeader, VerifiedToken,
+};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum SignatureAlg {
     Es256,
+    /// ECDSA over secp256k1 (RFC 8812), implemented with `k256` since
+    /// jsonwebtoken has no ES256K support
+    Es256k,
     EdDsa,
 }
 
 impl SignatureAlg {
-    pub fn as_jwt_alg(self) -> Algorithm {
+    /// The jsonwebtoken algorithm, or `None` for ES256K
+    pub fn as_jwt_alg(self) -> Option<Algorithm> {
         match self {
-            SignatureAlg::Es256 => Algorithm::ES256,
-            SignatureAlg::EdDsa => Algorithm::EdDSA,
+            SignatureAlg::Es256 => Some(Algorithm::ES256),
+            SignatureAlg::Es256k => None,
+            SignatureAlg::EdDsa => Some(Algorithm::EdDSA),
+        }
+    }
+
+    /// The JWS `alg` header value
+    pub fn jws_name(self) -> &'static str {
+        match self {
+            SignatureAlg::Es256 => "ES256",
+            SignatureAlg::Es256k => "ES256K",
+            SignatureAlg::EdDsa => "EdDSA",
+        }
+    }
+
+    /// The JWK key type and curve this algorithm signs with
+    pub fn key_type(self) -> &'static str {
+        match self {
+            SignatureAlg::Es256 => "EC (P-256)",
+            SignatureAlg::Es256k => "EC (secp256k1)",
+            SignatureAlg::EdDsa => "OKP (Ed25519)",
+        }
+    }
+
+    /// Parse a JWS `alg` header value (case-sensitive, unlike `FromStr`)
+    pub fn from_jws_name(name: &str) -> anyhow::Result<Self> {
+        match name {
+            "ES256" => Ok(SignatureAlg::Es256),
+            "ES256K" => Ok(SignatureAlg::Es256k),
+            "EdDSA" => Ok(SignatureAlg::EdDsa),
+            other => Err(anyhow!("unsupported JWS alg: {}", other)),
         }
     }
 
@@ -34,10 +82,7 @@ impl SignatureAlg {
 
 impl fmt::Display for SignatureAlg {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            SignatureAlg::Es256 => write!(f, "ES256"),
-            SignatureAlg::EdDsa => write!(f, "EdDSA"),
-        }
+        f.write_str(self.jws_name())
     }
 }
 
@@ -47,9 +92,10 @@ impl FromStr for SignatureAlg {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_ascii_uppercase().as_str() {
             "ES256" => Ok(SignatureAlg::Es256),
+            "ES256K" => Ok(SignatureAlg::Es256k),
             "EDDSA" => Ok(SignatureAlg::EdDsa),
             _ => Err(format!(
-                "unknown algorithm '{}', expected ES256 or EdDSA",
+                "unknown algorithm '{}', expected ES256, ES256K or EdDSA",
                 s
             )),
         }
@@ -0,0 +1,126 @@
+//! Helpers shared by the remote signers (AWS KMS, Azure Key Vault, GCP KMS)
+
+#[cfg(any(feature = "azure-kv", feature = "gcp-kms"))]
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+#[cfg(any(feature = "azure-kv", feature = "gcp-kms"))]
+use reqwest::blocking::RequestBuilder;
+#[cfg(any(feature = "azure-kv", feature = "gcp-kms"))]
+use serde_json::Value;
+
+#[cfg(any(feature = "kms", feature = "gcp-kms"))]
+use super::SignatureAlg;
+
+/// OAuth access token from `env`, or else printed by a cloud CLI
+/// (`program args...`) the user is logged in with
+#[cfg(any(feature = "azure-kv", feature = "gcp-kms"))]
+pub(crate) fn access_token(env: &str, program: &str, args: &[&str]) -> Result<String> {
+    if let Some(token) = std::env::var(env).ok().filter(|t| !t.trim().is_empty()) {
+        return Ok(token.trim().to_string());
+    }
+    let output = Command::new(program).args(args).output().with_context(|| {
+        format!(
+            "no access token: set {} or install and log in with `{}`",
+            env, program
+        )
+    })?;
+    if !output.status.success() {
+        bail!(
+            "`{} {}` failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if token.is_empty() {
+        bail!("`{} {}` printed no access token", program, args.join(" "));
+    }
+    Ok(token)
+}
+
+/// Send an authenticated request to a key service and parse its JSON
+/// response, turning error responses into messages naming `service`
+#[cfg(any(feature = "azure-kv", feature = "gcp-kms"))]
+pub(crate) fn send_json(request: RequestBuilder, service: &str) -> Result<Value> {
+    let response = crate::http::send_with_retry(request)
+        .map_err(|err| crate::http::request_error(err, service))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .with_context(|| format!("failed to read {} response", service))?;
+    if !status.is_success() {
+        bail!("{} returned {}: {}", service, status, body.trim());
+    }
+    serde_json::from_str(&body).with_context(|| format!("{} returned invalid JSON", service))
+}
+
+/// DER-encoded ECDSA signature to the 64-byte `r || s` form (low `s` for
+/// ES256K, as JWS requires)
+#[cfg(any(feature = "kms", feature = "gcp-kms"))]
+pub(crate) fn raw_ecdsa_signature(alg: SignatureAlg, der: &[u8]) -> Result<Vec<u8>> {
+    match alg {
+        SignatureAlg::Es256 => {
+            let signature = p256::ecdsa::Signature::from_der(der)
+                .context("invalid ES256 signature from the key service")?;
+            Ok(signature.to_bytes().to_vec())
+        }
+        SignatureAlg::Es256k => {
+            let signature = k256::ecdsa::Signature::from_der(der)
+                .context("invalid ES256K signature from the key service")?;
+            Ok(low_s(signature).to_bytes().to_vec())
+        }
+        SignatureAlg::EdDsa => bail!("EdDSA signatures are not DER-encoded"),
+    }
+}
+
+/// Raw `r || s` ES256K signature with a low `s`
+#[cfg(feature = "azure-kv")]
+pub(crate) fn low_s_es256k(raw: &[u8]) -> Result<Vec<u8>> {
+    let signature = k256::ecdsa::Signature::from_slice(raw)
+        .context("ES256K signature must be 64 bytes (r || s)")?;
+    Ok(low_s(signature).to_bytes().to_vec())
+}
+
+fn low_s(signature: k256::ecdsa::Signature) -> k256::ecdsa::Signature {
+    signature.normalize_s().unwrap_or(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::signature::Signer as _;
+
+    #[cfg(any(feature = "kms", feature = "gcp-kms"))]
+    #[test]
+    fn test_der_signature_becomes_raw() {
+        let signing_key = p256::ecdsa::SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let signature: p256::ecdsa::Signature = signing_key.sign(b"header.payload");
+        let raw = raw_ecdsa_signature(SignatureAlg::Es256, signature.to_der().as_bytes()).unwrap();
+        assert_eq!(raw, signature.to_bytes().to_vec());
+        assert!(raw_ecdsa_signature(SignatureAlg::Es256, b"not der").is_err());
+    }
+
+    #[cfg(feature = "azure-kv")]
+    #[test]
+    fn test_es256k_signature_gets_low_s() {
+        let signing_key = k256::ecdsa::SigningKey::from_slice(&[9u8; 32]).unwrap();
+        let signature: k256::ecdsa::Signature = signing_key.sign(b"header.payload");
+        let (r, s) = signature.split_scalars();
+        let flipped = k256::ecdsa::Signature::from_scalars(r.to_bytes(), (-*s).to_bytes()).unwrap();
+        // Exactly one of the two has a high s
+        let high = if signature.normalize_s().is_some() {
+            signature
+        } else {
+            flipped
+        };
+
+        let raw = low_s_es256k(&high.to_bytes()).unwrap();
+        assert_ne!(raw, high.to_bytes().to_vec());
+        assert!(k256::ecdsa::Signature::from_slice(&raw)
+            .unwrap()
+            .normalize_s()
+            .is_none());
+    }
+}
@@ -0,0 +1,232 @@
+//! Reading and writing manifests and credentials as JSON or YAML.
+//!
+//! JSON stays the canonical form (it is what gets signed); YAML is accepted
+//! for authoring. The format is taken from an explicit flag when given,
+//! otherwise from the file extension, defaulting to JSON.
+
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Serialization format for manifests and credentials
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DocumentFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+impl DocumentFormat {
+    /// Detect the format from a file extension (`.json`, `.yaml`, `.yml`)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "json" => Some(DocumentFormat::Json),
+            "yaml" | "yml" => Some(DocumentFormat::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Use the explicit format if given, else the extension, else JSON
+    pub fn resolve(path: &Path, explicit: Option<Self>) -> Self {
+        explicit
+            .or_else(|| Self::from_path(path))
+            .unwrap_or_default()
+    }
+
+    /// Preferred file extension for the format
+    pub fn extension(self) -> &'static str {
+        match self {
+            DocumentFormat::Json => "json",
+            DocumentFormat::Yaml => "yaml",
+        }
+    }
+}
+
+impl FromStr for DocumentFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(DocumentFormat::Json),
+            "yaml" | "yml" => Ok(DocumentFormat::Yaml),
+            _ => Err(format!("invalid format '{}': use 'json' or 'yaml'", s)),
+        }
+    }
+}
+
+impl fmt::Display for DocumentFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocumentFormat::Json => f.write_str("json"),
+            DocumentFormat::Yaml => f.write_str("yaml"),
+        }
+    }
+}
+
+/// Parse document text in the given format
+pub fn parse_document(content: &str, format: DocumentFormat) -> Result<Value> {
+    match format {
+        DocumentFormat::Json => serde_json::from_str(content).context("document is not valid JSON"),
+        DocumentFormat::Yaml => serde_yaml::from_str(content).context("document is not valid YAML"),
+    }
+}
+
+/// Read and parse a document, detecting the format from the extension if not given
+pub fn read_document(path: &Path, format: Option<DocumentFormat>) -> Result<Value> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    parse_document(&content, DocumentFormat::resolve(path, format))
+        .with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// Default for `sign` and `verify --max-payload-size`: 4 MiB, far above
+/// any real credential
+pub const DEFAULT_MAX_PAYLOAD_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Fail if a payload of `size` bytes is over `limit`
+pub fn check_payload_size(size: u64, limit: u64) -> Result<()> {
+    if size > limit {
+        bail!(
+            "payload exceeds limit: {} bytes is over the maximum of {} bytes (--max-payload-size)",
+            size,
+            limit
+        );
+    }
+    Ok(())
+}
+
+/// [`read_document`] that refuses files over `limit` bytes before parsing
+/// them. The read itself is bounded too, for files whose size is not known
+/// up front (pipes, `/dev/stdin`).
+pub fn read_document_with_limit(
+    path: &Path,
+    format: Option<DocumentFormat>,
+    limit: u64,
+) -> Result<Value> {
+    let file =
+        fs::File::open(path).with_context(|| format!("failed to read {}", path.display()))?;
+    if let Ok(metadata) = file.metadata() {
+        if metadata.is_file() {
+            check_payload_size(metadata.len(), limit)?;
+        }
+    }
+
+    let mut bytes = Vec::new();
+    file.take(limit.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    check_payload_size(bytes.len() as u64, limit)?;
+
+    let content = String::from_utf8(bytes)
+        .with_context(|| format!("failed to read {}: not valid UTF-8", path.display()))?;
+    parse_document(&content, DocumentFormat::resolve(path, format))
+        .with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// Serialize a value in the given format (pretty-printed JSON or YAML)
+pub fn to_document_string<T: Serialize>(value: &T, format: DocumentFormat) -> Result<String> {
+    match format {
+        DocumentFormat::Json => Ok(serde_json::to_string_pretty(value)?),
+        DocumentFormat::Yaml => Ok(serde_yaml::to_string(value)?),
+    }
+}
+
+/// Serialize and write a document, detecting the format from the extension if not given
+pub fn write_document<T: Serialize>(
+    path: &Path,
+    value: &T,
+    format: Option<DocumentFormat>,
+) -> Result<()> {
+    let content = to_document_string(value, DocumentFormat::resolve(path, format))?;
+    fs::write(path, content).with_context(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::schema::AgentManifest;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            DocumentFormat::from_path(&PathBuf::from("agent-manifest.yaml")),
+            Some(DocumentFormat::Yaml)
+        );
+        assert_eq!(
+            DocumentFormat::from_path(&PathBuf::from("agent.YML")),
+            Some(DocumentFormat::Yaml)
+        );
+        assert_eq!(
+            DocumentFormat::from_path(&PathBuf::from("agent.json")),
+            Some(DocumentFormat::Json)
+        );
+        assert_eq!(DocumentFormat::from_path(&PathBuf::from("agent")), None);
+        assert_eq!(
+            DocumentFormat::resolve(&PathBuf::from("agent"), None),
+            DocumentFormat::Json
+        );
+    }
+
+    #[test]
+    fn test_read_document_with_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("payload.json");
+        // `{"a":"xxxx"}`: 8 bytes of structure plus the string
+        let document = |len: usize| format!(r#"{{"a":"{}"}}"#, "x".repeat(len));
+        let limit = 64;
+
+        fs::write(&path, document(limit as usize - 8)).unwrap();
+        let value = read_document_with_limit(&path, None, limit).unwrap();
+        assert_eq!(value["a"].as_str().unwrap().len(), limit as usize - 8);
+
+        fs::write(&path, document(limit as usize - 7)).unwrap();
+        let err = read_document_with_limit(&path, None, limit).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "payload exceeds limit: 65 bytes is over the maximum of 64 bytes (--max-payload-size)"
+        );
+    }
+
+    #[test]
+    fn test_manifest_yaml_round_trip() {
+        let manifest = AgentManifest::new_with_defaults();
+        let original = serde_json::to_value(&manifest).unwrap();
+
+        let yaml = to_document_string(&manifest, DocumentFormat::Yaml).unwrap();
+        let from_yaml = parse_document(&yaml, DocumentFormat::Yaml).unwrap();
+        let parsed: AgentManifest = serde_json::from_value(from_yaml).unwrap();
+        let round_tripped = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(original, round_tripped);
+
+        let json = to_document_string(&parsed, DocumentFormat::Json).unwrap();
+        let yaml_again = to_document_string(
+            &parse_document(&json, DocumentFormat::Json).unwrap(),
+            DocumentFormat::Yaml,
+        )
+        .unwrap();
+        assert_eq!(
+            parse_document(&yaml_again, DocumentFormat::Yaml).unwrap(),
+            original
+        );
+    }
+
+    #[test]
+    fn test_credential_fixture_yaml_round_trip() {
+        let json = include_str!("../tests/fixtures/agent-valid.json");
+        let original = parse_document(json, DocumentFormat::Json).unwrap();
+
+        let yaml = to_document_string(&original, DocumentFormat::Yaml).unwrap();
+        assert_eq!(
+            parse_document(&yaml, DocumentFormat::Yaml).unwrap(),
+            original
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! ISO 8601 durations for credential validity windows (`--valid-for`)
+//!
+//! Supports `PnYnMnWnD` with an optional `TnHnMnS` time part, in whole
+//! numbers. Years and months are added on the calendar (`P1Y` from
+//! 2025-03-01 ends on 2026-03-01); weeks, days and the time part are exact.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Duration, Months, Utc};
+
+/// A positive ISO 8601 duration such as `P30D`, `P1Y` or `PT12H`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IsoDuration {
+    months: u32,
+    exact: Duration,
+}
+
+impl IsoDuration {
+    /// Whole days, as in `P90D`
+    pub fn days(days: u32) -> Self {
+        Self {
+            months: 0,
+            exact: Duration::days(days as i64),
+        }
+    }
+
+    /// The end of a window of this length starting at `start`, or `None`
+    /// when it falls outside the supported date range
+    pub fn after(&self, start: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        start
+            .checked_add_months(Months::new(self.months))?
+            .checked_add_signed(self.exact)
+    }
+}
+
+/// 90 days, the validity of credentials when no `--valid-for` is given
+impl Default for IsoDuration {
+    fn default() -> Self {
+        Self::days(90)
+    }
+}
+
+impl FromStr for IsoDuration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "invalid duration '{}': use ISO 8601 such as P30D, P6M, P1Y or PT12H",
+                s
+            )
+        };
+
+        let input = s.trim();
+        if input.starts_with('-') {
+            return Err(format!("invalid duration '{}': must not be negative", s));
+        }
+        let rest = input
+            .strip_prefix('P')
+            .or_else(|| input.strip_prefix('p'))
+            .ok_or_else(invalid)?;
+
+        let mut months: u64 = 0;
+        let mut exact = Duration::zero();
+        let mut in_time = false;
+        let mut number = String::new();
+        let mut components = 0;
+        let mut time_components = 0;
+
+        for c in rest.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let c = c.to_ascii_uppercase();
+            if c == 'T' {
+                if in_time || !number.is_empty() {
+                    return Err(invalid());
+                }
+                in_time = true;
+                continue;
+            }
+            if number.is_empty() {
+                return Err(invalid());
+            }
+            let value: u32 = number.parse().map_err(|_| invalid())?;
+            number.clear();
+            components += 1;
+            if in_time {
+                time_components += 1;
+            }
+
+            let value_i64 = value as i64;
+            match (in_time, c) {
+                (false, 'Y') => months += value as u64 * 12,
+                (false, 'M') => months += value as u64,
+                (false, 'W') => exact += Duration::weeks(value_i64),
+                (false, 'D') => exact += Duration::days(value_i64),
+                (true, 'H') => exact += Duration::hours(value_i64),
+                (true, 'M') => exact += Duration::minutes(value_i64),
+                (true, 'S') => exact += Duration::seconds(value_i64),
+                _ => return Err(invalid()),
+            }
+        }
+
+        if !number.is_empty() || components == 0 || (in_time && time_components == 0) {
+            return Err(invalid());
+        }
+        let months = u32::try_from(months).map_err(|_| invalid())?;
+        if months == 0 && exact.is_zero() {
+            return Err(format!(
+                "invalid duration '{}': must be longer than zero",
+                s
+            ));
+        }
+
+        Ok(Self { months, exact })
+    }
+}
+
+impl fmt::Display for IsoDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "P")?;
+        let (years, months) = (self.months / 12, self.months % 12);
+        if years > 0 {
+            write!(f, "{}Y", years)?;
+        }
+        if months > 0 {
+            write!(f, "{}M", months)?;
+        }
+        let seconds = self.exact.num_seconds();
+        let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+        if days > 0 {
+            write!(f, "{}D", days)?;
+        }
+        if seconds > 0 {
+            write!(f, "T")?;
+            let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+            if hours > 0 {
+                write!(f, "{}H", hours)?;
+            }
+            if minutes > 0 {
+                write!(f, "{}M", minutes)?;
+            }
+            if seconds > 0 {
+                write!(f, "{}S", seconds)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parses_calendar_and_exact_parts() {
+        let start = at("2025-01-31T12:00:00Z");
+        let end = |d: &str| d.parse::<IsoDuration>().unwrap().after(start).unwrap();
+
+        assert_eq!(end("P30D"), at("2025-03-02T12:00:00Z"));
+        assert_eq!(end("P1Y"), at("2026-01-31T12:00:00Z"));
+        assert_eq!(end("P1M"), at("2025-02-28T12:00:00Z"));
+        assert_eq!(end("P2W"), at("2025-02-14T12:00:00Z"));
+        assert_eq!(end("P1DT12H30M"), at("2025-02-02T00:30:00Z"));
+        assert_eq!(end("PT90S"), at("2025-01-31T12:01:30Z"));
+        assert_eq!(
+            "P1Y2M3DT4H".parse::<IsoDuration>().unwrap().to_string(),
+            "P1Y2M3DT4H"
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_zero_and_negative() {
+        for input in [
+            "", "P", "30D", "PT", "P1H", "PT1D", "P1.5D", "P1DT", "PD", "P1Y1",
+        ] {
+            assert!(input.parse::<IsoDuration>().is_err(), "{}", input);
+        }
+        let zero = "P0D".parse::<IsoDuration>().unwrap_err();
+        assert!(zero.contains("longer than zero"));
+        let negative = "-P30D".parse::<IsoDuration>().unwrap_err();
+        assert!(negative.contains("negative"));
+    }
+}
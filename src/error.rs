@@ -0,0 +1,120 @@
+//! Error type for the library API.
+//!
+//! The functions other programs embed ([`generate_fingerprint`], the
+//! [`verify_jws`] family, config loading and schema lookup) return
+//! [`BelticError`] so a caller can match on the kind of failure. Inside the
+//! CLI they convert to `anyhow::Error` with `?` like any other error.
+//!
+//! [`generate_fingerprint`]: crate::manifest::fingerprint::generate_fingerprint
+//! [`verify_jws`]: crate::crypto::verify_jws
+
+use thiserror::Error;
+
+/// Failure kinds of the library API. Each carries the full message,
+/// including the context the CLI would print.
+#[derive(Debug, Error)]
+pub enum BelticError {
+    /// Reading or writing a file failed
+    #[error("{0}")]
+    Io(String),
+
+    /// Input could not be parsed: JSON, YAML, base64url, keys, glob patterns
+    #[error("{0}")]
+    Parse(String),
+
+    /// Input parsed but breaks a rule: an invalid manifest, a token outside
+    /// its validity window or for another audience, colliding paths
+    #[error("{0}")]
+    Validation(String),
+
+    /// A signature did not verify, or its algorithm is refused
+    #[error("{0}")]
+    Signature(String),
+
+    /// A network request failed
+    #[error("{0}")]
+    Network(String),
+
+    /// No usable schema in the cache, the embedded copies or upstream
+    #[error("{0}")]
+    SchemaCache(String),
+}
+
+/// `Result` with [`BelticError`] as the default error
+pub type Result<T, E = BelticError> = std::result::Result<T, E>;
+
+impl BelticError {
+    /// Classify an `anyhow` error by the first error in its chain that has
+    /// a kind of its own (a `BelticError`, or an I/O, network or parse
+    /// error), else as `fallback`. The message keeps the whole chain.
+    pub(crate) fn classify(err: anyhow::Error, fallback: fn(String) -> Self) -> Self {
+        let message = format!("{:#}", err);
+        for cause in err.chain() {
+            if let Some(err) = cause.downcast_ref::<BelticError>() {
+                return err.with_message(message);
+            }
+            if cause.is::<std::io::Error>() {
+                return BelticError::Io(message);
+            }
+            if cause.is::<reqwest::Error>() {
+                return BelticError::Network(message);
+            }
+            if cause.is::<serde_json::Error>()
+                || cause.is::<serde_yaml::Error>()
+                || cause.is::<base64::DecodeError>()
+                || cause.is::<globset::Error>()
+            {
+                return BelticError::Parse(message);
+            }
+        }
+        fallback(message)
+    }
+
+    /// The same kind of error with another message
+    fn with_message(&self, message: String) -> Self {
+        match self {
+            BelticError::Io(_) => BelticError::Io(message),
+            BelticError::Parse(_) => BelticError::Parse(message),
+            BelticError::Validation(_) => BelticError::Validation(message),
+            BelticError::Signature(_) => BelticError::Signature(message),
+            BelticError::Network(_) => BelticError::Network(message),
+            BelticError::SchemaCache(_) => BelticError::SchemaCache(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_classify_by_cause() {
+        let io = std::fs::read("/nonexistent/beltic")
+            .context("failed to read key")
+            .unwrap_err();
+        let err = BelticError::classify(io, BelticError::Signature);
+        assert!(matches!(err, BelticError::Io(_)));
+        assert!(err.to_string().starts_with("failed to read key: "));
+
+        let json = serde_json::from_str::<serde_json::Value>("{")
+            .context("bad header")
+            .unwrap_err();
+        assert!(matches!(
+            BelticError::classify(json, BelticError::Signature),
+            BelticError::Parse(_)
+        ));
+
+        let nested = anyhow::Error::new(BelticError::Validation("token expired".into()))
+            .context("verification failed");
+        let err = BelticError::classify(nested, BelticError::Signature);
+        assert!(matches!(err, BelticError::Validation(_)));
+        assert_eq!(err.to_string(), "verification failed: token expired");
+
+        let other = anyhow::anyhow!("bad signature");
+        assert!(matches!(
+            BelticError::classify(other, BelticError::Signature),
+            BelticError::Signature(_)
+        ));
+    }
+}
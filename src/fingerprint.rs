@@ -0,0 +1,11 @@
+//! Code fingerprinting: a SHA-256 over the files an agent ships, the same
+//! value `beltic fingerprint` writes into `systemConfigFingerprint`.
+//!
+//! [`generate_fingerprint`] walks `root_path` with the include and exclude
+//! patterns in [`FingerprintOptions`]; [`fingerprint_files`] hashes a list
+//! of files chosen by the caller.
+
+pub use crate::manifest::fingerprint::{
+    fingerprint_files, generate_fingerprint, FingerprintOptions, FingerprintResult,
+};
+pub use crate::manifest::schema::FingerprintMetadata;
@@ -0,0 +1,287 @@
+//! Shared HTTP helpers for blocking reqwest calls
+//!
+//! Every client is built by [`client`] so that connecting and waiting for a
+//! response are bounded. Requests to the console and to key directories are
+//! retried on transient failures: connection errors, timeouts and 5xx
+//! responses. 4xx responses are returned immediately.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use log::debug;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+
+use crate::error::BelticError;
+
+/// Environment variable overriding the request timeout in seconds (default 30)
+pub const TIMEOUT_ENV: &str = "BELTIC_HTTP_TIMEOUT_SECS";
+
+/// Environment variable overriding the connect timeout in seconds (default 10)
+pub const CONNECT_TIMEOUT_ENV: &str = "BELTIC_HTTP_CONNECT_TIMEOUT_SECS";
+
+/// Environment variable overriding the number of attempts (default 3)
+pub const MAX_ATTEMPTS_ENV: &str = "BELTIC_HTTP_MAX_ATTEMPTS";
+
+/// Environment variable overriding the first backoff delay in milliseconds
+/// (default 500); each later retry waits twice as long
+pub const BACKOFF_MS_ENV: &str = "BELTIC_HTTP_BACKOFF_MS";
+
+/// Upper bounds for establishing a connection and for a whole request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    pub connect: Duration,
+    pub request: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(10),
+            request: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Timeouts {
+    /// Default timeouts with overrides from [`CONNECT_TIMEOUT_ENV`] and
+    /// [`TIMEOUT_ENV`]. Unparseable or zero values are ignored.
+    pub fn from_env() -> Self {
+        let mut timeouts = Self::default();
+        if let Some(secs) = env_u64(CONNECT_TIMEOUT_ENV).filter(|s| *s > 0) {
+            timeouts.connect = Duration::from_secs(secs);
+        }
+        if let Some(secs) = env_u64(TIMEOUT_ENV).filter(|s| *s > 0) {
+            timeouts.request = Duration::from_secs(secs);
+        }
+        timeouts
+    }
+}
+
+/// Blocking client with the timeouts from [`Timeouts::from_env`]
+pub fn client() -> Result<Client> {
+    client_with(&Timeouts::from_env())
+}
+
+/// Blocking client with explicit timeouts
+pub fn client_with(timeouts: &Timeouts) -> Result<Client> {
+    Client::builder()
+        .connect_timeout(timeouts.connect)
+        .timeout(timeouts.request)
+        .user_agent(concat!("beltic-cli/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .context("failed to create HTTP client")
+}
+
+/// Turn a send error into an actionable message, naming `service` (e.g.
+/// "console") when it timed out
+pub fn request_error(err: reqwest::Error, service: &str) -> anyhow::Error {
+    if !err.is_timeout() {
+        return err.into();
+    }
+    let timeouts = Timeouts::from_env();
+    if err.is_connect() {
+        BelticError::Network(format!(
+            "could not connect to {} within {}s (set {} to wait longer)",
+            service,
+            timeouts.connect.as_secs(),
+            CONNECT_TIMEOUT_ENV
+        ))
+        .into()
+    } else {
+        BelticError::Network(format!(
+            "{} did not respond within {}s (set {} to wait longer)",
+            service,
+            timeouts.request.as_secs(),
+            TIMEOUT_ENV
+        ))
+        .into()
+    }
+}
+
+/// How often and how patiently to retry a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first (at least 1)
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Default policy with overrides from [`MAX_ATTEMPTS_ENV`] and
+    /// [`BACKOFF_MS_ENV`]. Unparseable values are ignored.
+    pub fn from_env() -> Self {
+        let mut policy = Self::default();
+        if let Some(attempts) = env_u64(MAX_ATTEMPTS_ENV) {
+            policy.max_attempts = attempts.clamp(1, u32::MAX as u64) as u32;
+        }
+        if let Some(ms) = env_u64(BACKOFF_MS_ENV) {
+            policy.initial_backoff = Duration::from_millis(ms);
+        }
+        policy
+    }
+
+    /// Delay before retry number `retry` (1-based)
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+    }
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok()?.trim().parse().ok()
+}
+
+/// Send a request, retrying transient failures per [`RetryPolicy::from_env`]
+pub fn send_with_retry(request: RequestBuilder) -> reqwest::Result<Response> {
+    send_with_policy(request, &RetryPolicy::from_env())
+}
+
+/// Send a request, retrying transient failures per `policy`.
+///
+/// After the last attempt the final 5xx response or error is returned as is.
+/// Requests whose body can't be cloned (streams) are sent once.
+pub fn send_with_policy(
+    request: RequestBuilder,
+    policy: &RetryPolicy,
+) -> reqwest::Result<Response> {
+    let mut attempt = 1;
+    let mut request = request;
+    loop {
+        let retry = if attempt < policy.max_attempts {
+            request.try_clone()
+        } else {
+            None
+        };
+
+        let result = request.send();
+        let Some(next) = retry else {
+            return result;
+        };
+        match &result {
+            Ok(response) if response.status().is_server_error() => {
+                debug!(
+                    "{} returned {}, retrying (attempt {}/{})",
+                    response.url(),
+                    response.status(),
+                    attempt + 1,
+                    policy.max_attempts
+                );
+            }
+            Err(err) if err.is_connect() || err.is_timeout() => {
+                debug!(
+                    "request failed: {}, retrying (attempt {}/{})",
+                    err,
+                    attempt + 1,
+                    policy.max_attempts
+                );
+            }
+            _ => return result,
+        }
+
+        std::thread::sleep(policy.backoff(attempt));
+        attempt += 1;
+        request = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    /// Serve `statuses` in order, one request each, and return the base URL
+    fn mock_server(statuses: Vec<u16>) -> (String, thread::JoinHandle<usize>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let handle = thread::spawn(move || {
+            let mut served = 0;
+            for status in statuses {
+                let request = server.recv().unwrap();
+                let response = tiny_http::Response::from_string(format!("status {}", status))
+                    .with_status_code(status);
+                request.respond(response).unwrap();
+                served += 1;
+            }
+            served
+        });
+        (url, handle)
+    }
+
+    fn fast_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn test_retries_503_then_succeeds() {
+        let (url, server) = mock_server(vec![503, 503, 200]);
+        let client = reqwest::blocking::Client::new();
+
+        let response = send_with_policy(client.post(&url).body("{}"), &fast_policy(3)).unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.text().unwrap(), "status 200");
+        assert_eq!(server.join().unwrap(), 3);
+    }
+
+    #[test]
+    fn test_returns_last_5xx_when_attempts_run_out() {
+        let (url, server) = mock_server(vec![503, 502]);
+        let client = reqwest::blocking::Client::new();
+
+        let response = send_with_policy(client.get(&url), &fast_policy(2)).unwrap();
+        assert_eq!(response.status(), 502);
+        assert_eq!(server.join().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_does_not_retry_4xx() {
+        let (url, server) = mock_server(vec![404]);
+        let client = reqwest::blocking::Client::new();
+
+        let response = send_with_policy(client.get(&url), &fast_policy(3)).unwrap();
+        assert_eq!(response.status(), 404);
+        assert_eq!(server.join().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_timeout_is_reported_with_service() {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let hold = thread::spawn(move || {
+            // Accept the request but never answer it
+            let request = server.recv().unwrap();
+            thread::sleep(Duration::from_millis(1500));
+            drop(request);
+        });
+
+        let client = client_with(&Timeouts {
+            connect: Duration::from_secs(1),
+            request: Duration::from_secs(1),
+        })
+        .unwrap();
+        let err = send_with_policy(client.get(&url), &fast_policy(1)).unwrap_err();
+        assert!(err.is_timeout());
+        let message = request_error(err, "console").to_string();
+        assert!(message.starts_with("console did not respond within"));
+        hold.join().unwrap();
+    }
+
+    #[test]
+    fn test_backoff_doubles() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_millis(1000));
+    }
+}
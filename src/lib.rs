@@ -1,7 +1,33 @@
-pub mod commands;
+//! Beltic credentials for AI agents, as a library.
+//!
+//! The `beltic` binary is a thin clap front end over this crate. A service
+//! that fingerprints, signs or verifies credentials itself can use:
+//!
+//! - [`fingerprint`] - [`generate_fingerprint`](fingerprint::generate_fingerprint)
+//!   over an agent's source tree
+//! - [`sign`] - [`sign_jws`](sign::sign_jws) with a PEM or KMS-backed signer
+//! - [`verify`] - [`verify_jws`](verify::verify_jws) with signature, time and
+//!   audience checks
+//! - [`manifest`] - [`validate_manifest`](manifest::validate_manifest) and
+//!   the manifest types
+//!
+//! These functions return [`error::BelticError`], whose variants separate
+//! I/O, parse, validation, signature, network and schema cache failures.
+//! The other modules are public for the CLI and may change between minor
+//! versions.
+
 pub mod config;
 pub mod credential;
 pub mod crypto;
+pub mod document;
+pub mod duration;
+pub mod error;
+pub mod fingerprint;
+pub mod http;
 pub mod manifest;
+pub mod output;
+pub mod revocation;
 pub mod sandbox;
 pub mod schema;
+pub mod sign;
+pub mod verify;
@@ -1,12 +1,20 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
 use anyhow::Result;
-use beltic::commands::{
-    self, api_key::ApiKeyArgs, auth::AuthArgs, credential_id::CredentialIdArgs,
-    dev_init::DevInitArgs, directory::DirectoryArgs, fingerprint::FingerprintArgs,
-    http_sign::HttpSignArgs, init::InitArgs, keygen::KeygenArgs, register::RegisterArgs,
-    sandbox::SandboxArgs, schema::SchemaArgs, sign::SignArgs, verify::VerifyArgs,
-    whoami::WhoamiArgs,
+use clap::{CommandFactory, Parser, Subcommand};
+use commands::{
+    api_key::ApiKeyArgs, audit::AuditArgs, auth::AuthArgs, completions::CompletionsArgs,
+    config::ConfigArgs, convert::ConvertArgs, credential_id::CredentialIdArgs,
+    dev_init::DevInitArgs, diff::DiffArgs, directory::DirectoryArgs, discovery::DiscoverArgs, exit,
+    fingerprint::FingerprintArgs, http_sign::HttpSignArgs, http_verify::HttpVerifyArgs,
+    init::InitArgs, keygen::KeygenArgs, preset::PresetArgs, register::RegisterArgs,
+    rekey::RekeyArgs, revocation::RevocationArgs, risk::RiskArgs, sandbox::SandboxArgs,
+    sbom::SbomArgs, schema::SchemaArgs, sign::SignArgs, tools::ToolsArgs, validate::ValidateArgs,
+    verify::VerifyArgs, whoami::WhoamiArgs,
 };
-use clap::{Parser, Subcommand};
+
+mod commands;
 
 #[derive(Parser)]
 #[command(
@@ -15,6 +23,26 @@ use clap::{Parser, Subcommand};
     about = "Beltic CLI for signing and verifying credentials"
 )]
 struct Cli {
+    /// Never fetch schemas or revocation lists from the network; use cached or embedded copies
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Log what detection and fingerprinting are doing to stderr (-v: debug, -vv: per-file trace, -vvv: also dependencies)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Suppress progress and status output; errors, warnings and requested results are still printed
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Disable colored output (also disabled when NO_COLOR is set)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Use this .beltic.yaml for every command instead of searching the current and parent directories (a command's own --config wins)
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Command,
 }
@@ -33,12 +61,38 @@ enum Command {
     Sign(SignArgs),
     /// Verify a JWS token and print its payload
     Verify(VerifyArgs),
+    /// List the keys, tokens and credentials auto-discovery finds, in the order commands use them
+    Discover(DiscoverArgs),
+    /// Report expiry, signature and fingerprint freshness of every credential in a directory
+    Audit(AuditArgs),
+    /// Re-sign a credential with a new key, updating its key references
+    Rekey(RekeyArgs),
+    /// Build or update a StatusList2021 revocation list
+    Revocation(RevocationArgs),
+    /// Validate an agent manifest or credential (text or SARIF report)
+    Validate(ValidateArgs),
     /// Sign an HTTP request (Web Bot Auth)
     HttpSign(HttpSignArgs),
+    /// Verify a signed HTTP request (Web Bot Auth)
+    HttpVerify(HttpVerifyArgs),
     /// Manage HTTP Message Signatures key directories
     Directory(DirectoryArgs),
     /// Extract credential ID from a credential JSON or JWT file
     CredentialId(CredentialIdArgs),
+    /// Compare two agent credentials field by field
+    Diff(DiffArgs),
+    /// Suggest a KYB tier and safety rating from tools and data categories
+    Risk(RiskArgs),
+    /// Convert a manifest or credential between JSON and YAML
+    Convert(ConvertArgs),
+    /// List, show or save credential presets for `init --credential --preset`
+    Preset(PresetArgs),
+    /// Add, modify or remove the tools of an existing agent credential
+    Tools(ToolsArgs),
+    /// Emit a CycloneDX or SPDX SBOM of the agent's external dependencies
+    Sbom(SbomArgs),
+    /// Create or validate .beltic.yaml, or print its schema
+    Config(ConfigArgs),
     /// Manage schema caching and updates
     Schema(SchemaArgs),
     /// Run agent in sandboxed environment for testing
@@ -51,28 +105,208 @@ enum Command {
     Auth(AuthArgs),
     /// Display current authenticated developer info
     Whoami(WhoamiArgs),
+    /// Print a shell completion script
+    Completions(CompletionsArgs),
 }
 
-fn main() -> Result<()> {
+fn main() -> ExitCode {
     let cli = Cli::parse();
+    init_logging(cli.verbose);
+    match run(cli) {
+        Ok(()) => ExitCode::from(exit::OK),
+        Err(err) => {
+            if !exit::is_reported(&err) {
+                eprintln!("Error: {:?}", err);
+            }
+            ExitCode::from(exit::code_for(&err))
+        }
+    }
+}
+
+/// Run the selected command; [`main`] maps its error to an exit code
+fn run(cli: Cli) -> Result<()> {
+    commands::color::init(cli.no_color);
+    beltic::output::set_quiet(cli.quiet);
 
+    if cli.offline {
+        beltic::schema::set_offline(true);
+    }
+
+    if let Some(path) = &cli.config {
+        if !path.is_file() {
+            eprintln!("Error: Config file not found: {}", path.display());
+            return exit::reported(exit::USAGE);
+        }
+    }
+
+    // Commands that load .beltic.yaml get the global --config explicitly
+    let global_config = cli.config;
     match cli.command {
-        Command::Init(args) => commands::init::run(args)?,
+        Command::Init(mut args) => {
+            args.global_config = global_config;
+            commands::init::run(args)?
+        }
         Command::DevInit(args) => commands::dev_init::run(args)?,
-        Command::Fingerprint(args) => commands::fingerprint::run(args)?,
+        Command::Fingerprint(mut args) => {
+            args.global_config = global_config;
+            commands::fingerprint::run(args)?
+        }
         Command::Keygen(args) => commands::keygen::run(args)?,
-        Command::Sign(args) => commands::sign::run(args)?,
-        Command::Verify(args) => commands::verify::run(args)?,
+        Command::Sign(mut args) => {
+            args.global_config = global_config;
+            commands::sign::run(args)?
+        }
+        Command::Verify(mut args) => {
+            args.global_config = global_config;
+            commands::verify::run(args)?
+        }
+        Command::Discover(args) => commands::discovery::run(args)?,
+        Command::Audit(args) => commands::audit::run(args)?,
+        Command::Rekey(mut args) => {
+            args.global_config = global_config;
+            commands::rekey::run(args)?
+        }
+        Command::Revocation(args) => commands::revocation::run(args)?,
+        Command::Validate(mut args) => {
+            args.global_config = global_config;
+            commands::validate::run(args)?
+        }
         Command::HttpSign(args) => commands::http_sign::run(args)?,
+        Command::HttpVerify(args) => commands::http_verify::run(args)?,
         Command::Directory(args) => commands::directory::run(args)?,
         Command::CredentialId(args) => commands::credential_id::run(args)?,
-        Command::Schema(args) => commands::schema::run(args)?,
+        Command::Diff(args) => commands::diff::run(args)?,
+        Command::Risk(args) => commands::risk::run(args)?,
+        Command::Convert(mut args) => {
+            args.global_config = global_config;
+            commands::convert::run(args)?
+        }
+        Command::Preset(args) => commands::preset::run(args)?,
+        Command::Tools(mut args) => {
+            args.global_config = global_config;
+            commands::tools::run(args)?
+        }
+        Command::Sbom(mut args) => {
+            args.global_config = global_config;
+            commands::sbom::run(args)?
+        }
+        Command::Config(mut args) => {
+            args.global_config = global_config;
+            commands::config::run(args)?
+        }
+        Command::Schema(mut args) => {
+            args.global_config = global_config;
+            commands::schema::run(args)?
+        }
         Command::Sandbox(args) => commands::sandbox::run(args)?,
         Command::Register(args) => commands::register::run(args)?,
         Command::ApiKey(args) => commands::api_key::run(args)?,
         Command::Auth(args) => commands::auth::run(args)?,
         Command::Whoami(args) => commands::whoami::run(args)?,
+        Command::Completions(args) => commands::completions::run(args, &mut Cli::command())?,
     };
 
     Ok(())
 }
+
+/// Without `-v` only `RUST_LOG` enables logging, so default output is unchanged
+fn init_logging(verbose: u8) {
+    let mut builder = env_logger::Builder::new();
+    builder.format_timestamp(None);
+    match verbose {
+        0 => {
+            builder.parse_env("RUST_LOG");
+        }
+        1 => {
+            builder.filter_module("beltic", log::LevelFilter::Debug);
+        }
+        2 => {
+            builder.filter_module("beltic", log::LevelFilter::Trace);
+        }
+        _ => {
+            builder
+                .filter_level(log::LevelFilter::Debug)
+                .filter_module("beltic", log::LevelFilter::Trace);
+        }
+    }
+    builder.init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap_complete::Shell;
+
+    fn completions(shell: Shell) -> String {
+        let mut out = Vec::new();
+        commands::completions::write_completions(shell, &mut Cli::command(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn assert_lists_subcommands(script: &str) {
+        for name in [
+            "init",
+            "dev-init",
+            "fingerprint",
+            "sign",
+            "verify",
+            "sandbox",
+        ] {
+            assert!(script.contains(name), "missing subcommand {}", name);
+        }
+    }
+
+    #[test]
+    fn test_bash_completions() {
+        assert_lists_subcommands(&completions(Shell::Bash));
+    }
+
+    #[test]
+    fn test_zsh_completions() {
+        assert_lists_subcommands(&completions(Shell::Zsh));
+    }
+
+    #[test]
+    fn test_fish_completions() {
+        assert_lists_subcommands(&completions(Shell::Fish));
+    }
+
+    #[test]
+    fn test_powershell_completions() {
+        assert_lists_subcommands(&completions(Shell::PowerShell));
+    }
+
+    #[test]
+    fn test_subcommand_config_wins_over_global_config() {
+        let cli = Cli::try_parse_from([
+            "beltic",
+            "--config",
+            "ci.yaml",
+            "fingerprint",
+            "--config",
+            "a.yaml",
+        ])
+        .unwrap();
+        assert_eq!(cli.config, Some(PathBuf::from("ci.yaml")));
+        let Command::Fingerprint(mut args) = cli.command else {
+            panic!("expected fingerprint");
+        };
+        args.global_config = cli.config;
+        assert_eq!(
+            commands::fingerprint::config_path(&args),
+            Some(PathBuf::from("a.yaml"))
+        );
+    }
+
+    #[test]
+    fn test_quiet_is_global() {
+        let cli = Cli::try_parse_from(["beltic", "fingerprint", "-q"]).unwrap();
+        assert!(cli.quiet);
+        assert!(Cli::try_parse_from(["beltic", "-q", "-v", "fingerprint"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_definition_is_consistent() {
+        Cli::command().debug_assert();
+    }
+}
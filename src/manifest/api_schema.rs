@@ -0,0 +1,469 @@
+//! Data categories from API schemas
+//!
+//! OpenAPI/Swagger documents and GraphQL SDL name the fields an API-first
+//! agent actually exchanges, which is a better signal than searching source
+//! files for words like "email". Field names are split into words and the
+//! trailing words are matched against known sensitive names, so
+//! `customerEmail` counts but `emailTemplateId` does not. Boolean fields such
+//! as `emailVerified` or `hasSsn` are never counted.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{debug, trace};
+use regex::Regex;
+use serde_json::Value;
+
+use crate::manifest::schema::DataCategory;
+
+/// A field declared in an API schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaField {
+    pub name: String,
+    /// JSON Schema `type` or GraphQL type name
+    pub type_name: Option<String>,
+    /// JSON Schema `format`
+    pub format: Option<String>,
+}
+
+/// A data category found in an API schema, with the field and file it came
+/// from
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaFinding {
+    pub category: DataCategory,
+    pub field: String,
+    pub source: PathBuf,
+}
+
+/// Field name suffixes (as snake_case words) for each category
+const FIELD_NAMES: &[(DataCategory, &[&str])] = &[
+    (
+        DataCategory::GovernmentId,
+        &[
+            "ssn",
+            "social_security_number",
+            "national_id",
+            "national_insurance_number",
+            "passport_number",
+            "drivers_license",
+            "driver_license",
+            "drivers_license_number",
+            "driver_license_number",
+            "tax_id",
+            "tin",
+        ],
+    ),
+    (
+        DataCategory::Financial,
+        &[
+            "iban",
+            "bic",
+            "swift_code",
+            "account_number",
+            "bank_account",
+            "bank_account_number",
+            "routing_number",
+            "sort_code",
+            "card_number",
+            "credit_card",
+            "credit_card_number",
+            "cvv",
+            "cvc",
+        ],
+    ),
+    (
+        DataCategory::Phi,
+        &[
+            "diagnosis",
+            "diagnoses",
+            "medical_record_number",
+            "mrn",
+            "prescription",
+            "prescriptions",
+            "icd_10",
+            "icd_code",
+            "blood_type",
+            "allergies",
+        ],
+    ),
+    (
+        DataCategory::Authentication,
+        &[
+            "password",
+            "password_hash",
+            "api_key",
+            "access_token",
+            "refresh_token",
+            "client_secret",
+            "mfa_secret",
+            "totp_secret",
+        ],
+    ),
+    (
+        DataCategory::Biometric,
+        &["face_embedding", "voiceprint", "iris_scan", "retina_scan"],
+    ),
+    (
+        DataCategory::Pii,
+        &[
+            "email",
+            "email_address",
+            "phone",
+            "phone_number",
+            "mobile_number",
+            "dob",
+            "date_of_birth",
+            "birth_date",
+            "birthdate",
+            "first_name",
+            "last_name",
+            "full_name",
+            "street_address",
+            "home_address",
+            "postal_code",
+            "zip_code",
+            "ip_address",
+        ],
+    ),
+];
+
+/// Categories implied by a JSON Schema `format` or a GraphQL scalar name
+const TYPE_NAMES: &[(&str, DataCategory)] = &[
+    ("email", DataCategory::Pii),
+    ("emailaddress", DataCategory::Pii),
+    ("phonenumber", DataCategory::Pii),
+    ("password", DataCategory::Authentication),
+    ("iban", DataCategory::Financial),
+];
+
+/// The category a field holds, if its name or type says so
+pub fn classify_field(field: &SchemaField) -> Option<DataCategory> {
+    let type_name = field.type_name.as_deref().map(str::to_ascii_lowercase);
+    if matches!(type_name.as_deref(), Some("boolean") | Some("bool")) {
+        return None;
+    }
+
+    let words = split_words(&field.name);
+    for (category, names) in FIELD_NAMES {
+        let matched = names.iter().any(|name| {
+            let name: Vec<&str> = name.split('_').collect();
+            words.len() >= name.len() && words[words.len() - name.len()..] == name[..]
+        });
+        if matched {
+            return Some(category.clone());
+        }
+    }
+
+    let format = field.format.as_deref().map(str::to_ascii_lowercase);
+    TYPE_NAMES
+        .iter()
+        .find(|(name, _)| format.as_deref() == Some(*name) || type_name.as_deref() == Some(*name))
+        .map(|(_, category)| category.clone())
+}
+
+/// Lowercase words of a camelCase, snake_case or kebab-case name
+fn split_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev: Option<char> = None;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev = None;
+            continue;
+        }
+        let boundary = prev.is_some_and(|p| {
+            (c.is_ascii_uppercase() && p.is_ascii_lowercase())
+                || c.is_ascii_digit() != p.is_ascii_digit()
+        });
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c.to_ascii_lowercase());
+        prev = Some(c);
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// Fields of an OpenAPI 3 or Swagger 2 document: every named property of
+/// every schema, and every named parameter
+pub fn openapi_fields(document: &Value) -> Vec<SchemaField> {
+    let mut fields = Vec::new();
+    collect_openapi_fields(document, &mut fields);
+    fields
+}
+
+fn collect_openapi_fields(value: &Value, fields: &mut Vec<SchemaField>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::Object(properties)) = object.get("properties") {
+                for (name, schema) in properties {
+                    fields.push(SchemaField {
+                        name: name.clone(),
+                        type_name: string_field(schema, "type"),
+                        format: string_field(schema, "format"),
+                    });
+                }
+            }
+            // Parameter objects: {"name": ..., "in": ..., "schema": {...}}
+            if let (Some(Value::String(name)), Some(_)) = (object.get("name"), object.get("in")) {
+                let schema = object.get("schema").unwrap_or(value);
+                fields.push(SchemaField {
+                    name: name.clone(),
+                    type_name: string_field(schema, "type"),
+                    format: string_field(schema, "format"),
+                });
+            }
+            for child in object.values() {
+                collect_openapi_fields(child, fields);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_openapi_fields(item, fields);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn string_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// Fields of `type`, `input` and `interface` definitions in GraphQL SDL
+pub fn graphql_fields(sdl: &str) -> Vec<SchemaField> {
+    let block =
+        Regex::new(r"(?s)\b(?:type|input|interface)\s+\w+[^{]*\{([^}]*)\}").expect("valid regex");
+    let field = Regex::new(r"([_A-Za-z]\w*)\s*:\s*\[*\s*([_A-Za-z]\w*)").expect("valid regex");
+    // Drop comments, descriptions and argument lists first so their words
+    // aren't taken for fields
+    let noise = Regex::new(r#"(?m)#.*$|"""(?s:.*?)"""|"[^"\n]*"|\([^)]*\)"#).expect("valid regex");
+    let sdl = noise.replace_all(sdl, "");
+
+    block
+        .captures_iter(&sdl)
+        .flat_map(|captures| {
+            field
+                .captures_iter(&captures[1])
+                .map(|field| SchemaField {
+                    name: field[1].to_string(),
+                    type_name: Some(field[2].to_string()),
+                    format: None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Directories never searched for API schemas
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", ".git", "dist", "build"];
+
+/// OpenAPI/Swagger and GraphQL schema files under `base_dir`
+pub fn find_schema_files(base_dir: &Path) -> Vec<PathBuf> {
+    let mut patterns = vec!["**/*.graphql".to_string(), "**/*.gql".to_string()];
+    for ext in ["json", "yaml", "yml"] {
+        for name in ["openapi", "swagger", "*.openapi"] {
+            patterns.push(format!("**/{}.{}", name, ext));
+        }
+    }
+    let mut files: Vec<PathBuf> = patterns
+        .iter()
+        .filter_map(|pattern| glob::glob(&base_dir.join(pattern).to_string_lossy()).ok())
+        .flat_map(|paths| paths.flatten())
+        .filter(|path| {
+            !path
+                .strip_prefix(base_dir)
+                .unwrap_or(path)
+                .components()
+                .any(|c| SKIPPED_DIRS.contains(&c.as_os_str().to_string_lossy().as_ref()))
+        })
+        .collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Data categories of the fields in the API schemas under `base_dir`
+pub fn detect_schema_categories(base_dir: &Path) -> Vec<SchemaFinding> {
+    let mut findings = Vec::new();
+    for path in find_schema_files(base_dir) {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let is_graphql = path
+            .extension()
+            .is_some_and(|ext| ext == "graphql" || ext == "gql");
+        let fields = if is_graphql {
+            graphql_fields(&content)
+        } else {
+            match serde_yaml::from_str::<Value>(&content) {
+                Ok(document)
+                    if document.get("openapi").is_some() || document.get("swagger").is_some() =>
+                {
+                    openapi_fields(&document)
+                }
+                _ => {
+                    debug!("{} is not an OpenAPI document; skipping", path.display());
+                    continue;
+                }
+            }
+        };
+        trace!("{} fields in {}", fields.len(), path.display());
+
+        let source = path.strip_prefix(base_dir).unwrap_or(&path).to_path_buf();
+        for field in fields {
+            if let Some(category) = classify_field(&field) {
+                let duplicate = findings.iter().any(|f: &SchemaFinding| {
+                    f.category == category && f.field == field.name && f.source == source
+                });
+                if !duplicate {
+                    findings.push(SchemaFinding {
+                        category,
+                        field: field.name,
+                        source: source.clone(),
+                    });
+                }
+            }
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field(name: &str, type_name: Option<&str>) -> SchemaField {
+        SchemaField {
+            name: name.to_string(),
+            type_name: type_name.map(str::to_string),
+            format: None,
+        }
+    }
+
+    #[test]
+    fn test_classify_field_names() {
+        let category = |name: &str| classify_field(&field(name, Some("string")));
+        assert_eq!(category("ssn"), Some(DataCategory::GovernmentId));
+        assert_eq!(category("dateOfBirth"), Some(DataCategory::Pii));
+        assert_eq!(category("customer_email"), Some(DataCategory::Pii));
+        assert_eq!(category("IBAN"), Some(DataCategory::Financial));
+        assert_eq!(category("payoutIban"), Some(DataCategory::Financial));
+        assert_eq!(
+            category("refresh-token"),
+            Some(DataCategory::Authentication)
+        );
+        // Not a suffix, or not a sensitive name at all
+        assert_eq!(category("emailTemplateId"), None);
+        assert_eq!(category("lessonPlan"), None);
+        assert_eq!(category("description"), None);
+        // Booleans are flags about the data, not the data
+        assert_eq!(
+            classify_field(&field("emailVerified", Some("boolean"))),
+            None
+        );
+        assert_eq!(classify_field(&field("hasSsn", Some("Boolean"))), None);
+    }
+
+    #[test]
+    fn test_classify_by_format_and_scalar() {
+        let contact = SchemaField {
+            name: "contact".to_string(),
+            type_name: Some("string".to_string()),
+            format: Some("email".to_string()),
+        };
+        assert_eq!(classify_field(&contact), Some(DataCategory::Pii));
+        assert_eq!(
+            classify_field(&field("owner", Some("EmailAddress"))),
+            Some(DataCategory::Pii)
+        );
+    }
+
+    #[test]
+    fn test_openapi_properties_and_parameters() {
+        let document = json!({
+            "openapi": "3.0.0",
+            "paths": {"/customers": {"get": {"parameters": [
+                {"name": "phoneNumber", "in": "query", "schema": {"type": "string"}}
+            ]}}},
+            "components": {"schemas": {"Customer": {"properties": {
+                "ssn": {"type": "string"},
+                "marketingOptIn": {"type": "boolean"},
+                "address": {"type": "object", "properties": {"postalCode": {"type": "string"}}}
+            }}}}
+        });
+        let names: Vec<String> = openapi_fields(&document)
+            .into_iter()
+            .map(|field| field.name)
+            .collect();
+        for name in [
+            "phoneNumber",
+            "ssn",
+            "marketingOptIn",
+            "address",
+            "postalCode",
+        ] {
+            assert!(names.iter().any(|n| n == name), "missing {}", name);
+        }
+    }
+
+    #[test]
+    fn test_graphql_fields() {
+        let sdl = r#"
+# The email of the account owner is not a field
+type Account implements Node {
+  id: ID!
+  "Primary holder's date of birth"
+  dob: Date
+  iban: String!
+  transactions(first: Int): [Transaction!]!
+}
+
+input NewAccount { holderEmail: EmailAddress, isBusiness: Boolean }
+"#;
+        let fields = graphql_fields(sdl);
+        let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "id",
+                "dob",
+                "iban",
+                "transactions",
+                "holderEmail",
+                "isBusiness"
+            ]
+        );
+        assert_eq!(fields[3].type_name.as_deref(), Some("Transaction"));
+    }
+
+    #[test]
+    fn test_detect_schema_categories_records_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("openapi.yaml"),
+            "openapi: 3.0.0\ncomponents:\n  schemas:\n    Patient:\n      properties:\n        diagnosis:\n          type: string\n        email:\n          type: string\n",
+        )
+        .unwrap();
+        fs::create_dir_all(dir.path().join("node_modules/sdk")).unwrap();
+        fs::write(
+            dir.path().join("node_modules/sdk/schema.graphql"),
+            "type Card { cardNumber: String }",
+        )
+        .unwrap();
+
+        let findings = detect_schema_categories(dir.path());
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].category, DataCategory::Phi);
+        assert_eq!(findings[0].field, "diagnosis");
+        assert_eq!(findings[0].source, PathBuf::from("openapi.yaml"));
+        assert_eq!(findings[1].category, DataCategory::Pii);
+    }
+}
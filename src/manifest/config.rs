@@ -1,13 +1,73 @@
 use anyhow::Result;
+use globset::Glob;
+use jsonschema::{error::ValidationErrorKind, Draft, JSONSchema};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use crate::error::BelticError;
+use crate::manifest::schema::{ArchitectureType, DeploymentType, Modality};
+use crate::manifest::validator::{IssueLevel, ValidationResult, RULE_SCHEMA_VIOLATION};
+
+/// JSON Schema for `.beltic.yaml`
+pub const CONFIG_SCHEMA: &str = include_str!("../../schemas/config/beltic-config.schema.json");
+
+/// Rule id for keys the configuration format doesn't know
+pub const RULE_UNKNOWN_KEY: &str = "beltic/config-unknown-key";
+/// Rule id for include/exclude patterns that aren't valid globs
+pub const RULE_INVALID_GLOB: &str = "beltic/config-invalid-glob";
+/// Rule id for settings that contradict each other
+pub const RULE_CONFLICT: &str = "beltic/config-conflict";
 
 /// Beltic configuration file structure (.beltic.yaml)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BelticConfig {
     pub version: String,
     pub agent: AgentConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<SchemaConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overrides: Option<DetectionOverrides>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub templates: Option<TemplateConfig>,
+}
+
+/// Schema settings, e.g. a version pin that keeps validation stable
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchemaConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// Values that replace auto-detection results. Explicit CLI flags still win.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DetectionOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub architecture_type: Option<ArchitectureType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deployment_type: Option<DeploymentType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_provider: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_family: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modalities: Option<Vec<Modality>>,
+}
+
+/// Organization text for boilerplate manifest fields. `init --template-dir`
+/// takes precedence over both the inline text and `dir`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TemplateConfig {
+    /// Directory of template files, relative to the current directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecation_policy: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fail_safe_behavior: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitoring_coverage: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,33 +108,207 @@ pub struct DeploymentConfig {
 
 impl BelticConfig {
     /// Load config from a file path
-    pub fn from_file(path: &Path) -> Result<Self> {
-        let content = fs::read_to_string(path)?;
-        let config = serde_yaml::from_str(&content)?;
-        Ok(config)
+    pub fn from_file(path: &Path) -> Result<Self, BelticError> {
+        let content = fs::read_to_string(path).map_err(|err| {
+            BelticError::Io(format!("failed to read {}: {}", path.display(), err))
+        })?;
+        serde_yaml::from_str(&content).map_err(|err| {
+            BelticError::Parse(format!("invalid config {}: {}", path.display(), err))
+        })
     }
 
     /// Try to find and load .beltic.yaml in current or parent directories
-    pub fn find_and_load(start_dir: &Path) -> Result<Option<Self>> {
+    pub fn find_and_load(start_dir: &Path) -> Result<Option<Self>, BelticError> {
+        Self::load(None, start_dir)
+    }
+
+    /// Load `explicit` (a `--config` path) if given, otherwise the nearest
+    /// .beltic.yaml from `start_dir`
+    pub fn load(explicit: Option<&Path>, start_dir: &Path) -> Result<Option<Self>, BelticError> {
+        match Self::locate(explicit, start_dir) {
+            Some(config_path) => Ok(Some(Self::from_file(&config_path)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// `explicit` if given, otherwise [`BelticConfig::find_path`]
+    pub fn locate(explicit: Option<&Path>, start_dir: &Path) -> Option<PathBuf> {
+        match explicit {
+            Some(path) => Some(path.to_path_buf()),
+            None => Self::find_path(start_dir),
+        }
+    }
+
+    /// Find the path of .beltic.yaml in current or parent directories
+    pub fn find_path(start_dir: &Path) -> Option<PathBuf> {
         let mut current = start_dir.to_path_buf();
 
         loop {
             let config_path = current.join(".beltic.yaml");
             if config_path.exists() {
-                return Ok(Some(Self::from_file(&config_path)?));
+                return Some(config_path);
             }
 
             let config_path = current.join(".beltic.yml");
             if config_path.exists() {
-                return Ok(Some(Self::from_file(&config_path)?));
+                return Some(config_path);
             }
 
             if !current.pop() {
-                break;
+                return None;
+            }
+        }
+    }
+
+    /// Lint config text: YAML syntax, unknown keys and types against
+    /// [`CONFIG_SCHEMA`], then the semantic checks of [`BelticConfig::validate`]
+    pub fn lint(content: &str) -> ValidationResult {
+        let mut result = ValidationResult::new();
+
+        let value: Value = match serde_yaml::from_str(content) {
+            Ok(value) => value,
+            Err(err) => {
+                result.add_error_at(RULE_SCHEMA_VIOLATION, "", format!("invalid YAML: {}", err));
+                return result;
+            }
+        };
+
+        let schema: Value =
+            serde_json::from_str(CONFIG_SCHEMA).expect("embedded config schema should parse");
+        let compiled = JSONSchema::options()
+            .with_draft(Draft::Draft202012)
+            .compile(&schema)
+            .expect("embedded config schema should compile");
+        if let Err(errors) = compiled.validate(&value) {
+            for err in errors {
+                let pointer = err.instance_path.to_string();
+                match &err.kind {
+                    ValidationErrorKind::AdditionalProperties { unexpected } => {
+                        let known = known_keys(&schema, &pointer);
+                        for key in unexpected {
+                            let mut message = format!("unknown key `{}`", key);
+                            if let Some(suggestion) = closest_key(key, &known) {
+                                message.push_str(&format!(" (did you mean `{}`?)", suggestion));
+                            }
+                            result.add_error_at(
+                                RULE_UNKNOWN_KEY,
+                                &format!("{}/{}", pointer, key),
+                                message,
+                            );
+                        }
+                    }
+                    _ => result.add_error_at(RULE_SCHEMA_VIOLATION, &pointer, err.to_string()),
+                }
+            }
+        }
+
+        if let Ok(config) = serde_json::from_value::<BelticConfig>(value) {
+            let semantic = config.validate();
+            for issue in semantic.issues {
+                let pointer = issue.pointer.unwrap_or_default();
+                match issue.level {
+                    IssueLevel::Error => {
+                        result.add_error_at(issue.rule_id, &pointer, issue.message)
+                    }
+                    IssueLevel::Warning => {
+                        result.add_warning_at(issue.rule_id, &pointer, issue.message)
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Check glob patterns and contradictory settings
+    pub fn validate(&self) -> ValidationResult {
+        let mut result = ValidationResult::new();
+        let paths = &self.agent.paths;
+
+        for (field, patterns) in [("include", &paths.include), ("exclude", &paths.exclude)] {
+            for (i, pattern) in patterns.iter().enumerate() {
+                let pointer = format!("/agent/paths/{}/{}", field, i);
+                if let Err(err) = Glob::new(pattern) {
+                    result.add_error_at(
+                        RULE_INVALID_GLOB,
+                        &pointer,
+                        format!("invalid glob `{}`: {}", pattern, err.kind()),
+                    );
+                } else if Path::new(pattern).is_absolute()
+                    || pattern.split('/').any(|part| part == "..")
+                {
+                    result.add_warning_at(
+                        RULE_CONFLICT,
+                        &pointer,
+                        format!("`{}` points outside the project directory", pattern),
+                    );
+                }
+            }
+        }
+
+        for (i, pattern) in paths.exclude.iter().enumerate() {
+            let pointer = format!("/agent/paths/exclude/{}", i);
+            if paths.include.contains(pattern) {
+                result.add_error_at(
+                    RULE_CONFLICT,
+                    &pointer,
+                    format!("`{}` is both included and excluded", pattern),
+                );
+            }
+            if matches!(pattern.as_str(), "*" | "**" | "**/*" | "**/**") {
+                result.add_error_at(
+                    RULE_CONFLICT,
+                    &pointer,
+                    format!("`{}` excludes every file", pattern),
+                );
             }
         }
 
-        Ok(None)
+        if let Some(deployment) = &self.agent.deployment {
+            if deployment.deployment_type == "monorepo" && deployment.location.is_none() {
+                result.add_warning_at(
+                    RULE_CONFLICT,
+                    "/agent/deployment",
+                    "monorepo deployment without a `location`".to_string(),
+                );
+            }
+
+            let overridden = self
+                .overrides
+                .as_ref()
+                .and_then(|o| o.deployment_type.as_ref())
+                .and_then(|t| serde_json::to_value(t).ok());
+            if let Some(Value::String(overridden)) = overridden {
+                if overridden != deployment.deployment_type {
+                    result.add_warning_at(
+                        RULE_CONFLICT,
+                        "/overrides/deployment_type",
+                        format!(
+                            "override `{}` contradicts agent.deployment.type `{}`",
+                            overridden, deployment.deployment_type
+                        ),
+                    );
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Pinned schema version, if any
+    pub fn schema_version(&self) -> Option<&str> {
+        self.schema.as_ref().and_then(|s| s.version.as_deref())
+    }
+
+    /// Default configuration for a deployment profile (standalone, monorepo,
+    /// plugin or serverless); unknown profiles fall back to standalone
+    pub fn default_for_profile(profile: &str, agent_path: &str) -> Self {
+        match profile {
+            "monorepo" => Self::default_monorepo(agent_path),
+            "plugin" => Self::default_plugin(),
+            "serverless" => Self::default_serverless(),
+            _ => Self::default_standalone(),
+        }
     }
 
     /// Create a default configuration for standalone agents
@@ -108,6 +342,9 @@ impl BelticConfig {
                     location: None,
                 }),
             },
+            schema: None,
+            overrides: None,
+            templates: None,
         }
     }
 
@@ -143,6 +380,9 @@ impl BelticConfig {
                     location: Some(agent_path.to_string()),
                 }),
             },
+            schema: None,
+            overrides: None,
+            templates: None,
         }
     }
 
@@ -171,6 +411,9 @@ impl BelticConfig {
                     location: None,
                 }),
             },
+            schema: None,
+            overrides: None,
+            templates: None,
         }
     }
 
@@ -202,6 +445,9 @@ impl BelticConfig {
                     location: None,
                 }),
             },
+            schema: None,
+            overrides: None,
+            templates: None,
         }
     }
 
@@ -248,11 +494,75 @@ agent:
     host_application: "main-platform"
     runtime: "node:18-alpine"
     location: "agents/customer-service"
+
+# Pin the credential schema version used for validation (optional)
+# schema:
+#   version: "v1"
+
+# Correct auto-detected values (optional); CLI flags still take precedence
+# overrides:
+#   architecture_type: "rag"  # single_agent|rag|tool_using|multi_agent|agentic_workflow|fine_tuned|hybrid
+#   deployment_type: "standalone"
+#   model_provider: "Anthropic"
+#   model_family: "Claude-3.5 Sonnet"
+#   modalities: ["text", "code"]
+
+# Organization text for boilerplate fields (optional); placeholders
+# {agent_name} and {oversight_mode} are substituted. Files in `dir`
+# (deprecation-policy.txt, fail-safe-behavior.txt, monitoring-coverage.txt)
+# are used for fields without inline text; `init --template-dir` wins over both.
+# templates:
+#   dir: "compliance/templates"
+#   deprecation_policy: "{agent_name} follows the Acme deprecation standard."
 "#
         .to_string()
     }
 }
 
+/// Property names the schema allows at an instance pointer like `/agent/paths`
+fn known_keys(schema: &Value, pointer: &str) -> Vec<String> {
+    let mut node = schema;
+    for segment in pointer.split('/').filter(|s| !s.is_empty()) {
+        match node.get("properties").and_then(|p| p.get(segment)) {
+            Some(next) => node = next,
+            None => return Vec::new(),
+        }
+    }
+    node.get("properties")
+        .and_then(Value::as_object)
+        .map(|properties| properties.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// The known key within two edits of `key`, if any
+fn closest_key<'a>(key: &str, known: &'a [String]) -> Option<&'a str> {
+    known
+        .iter()
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(current)
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
 /// Resolve paths based on config
 pub fn resolve_paths(config: &PathConfig, base_dir: &Path) -> (Vec<String>, Vec<String>) {
     let includes = config
@@ -265,3 +575,72 @@ pub fn resolve_paths(config: &PathConfig, base_dir: &Path) -> (Vec<String>, Vec<
 
     (includes, excludes)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_file_error_kinds() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".beltic.yaml");
+        let err = BelticConfig::from_file(&path).unwrap_err();
+        assert!(matches!(err, BelticError::Io(_)), "{:?}", err);
+
+        fs::write(&path, "version: [").unwrap();
+        let err = BelticConfig::from_file(&path).unwrap_err();
+        assert!(matches!(err, BelticError::Parse(_)), "{:?}", err);
+        assert!(err.to_string().contains(".beltic.yaml"), "{}", err);
+    }
+
+    #[test]
+    fn test_default_profiles_are_valid() {
+        for config in [
+            BelticConfig::default_standalone(),
+            BelticConfig::default_monorepo("agents/support"),
+            BelticConfig::default_plugin(),
+            BelticConfig::default_serverless(),
+        ] {
+            let yaml = serde_yaml::to_string(&config).unwrap();
+            let result = BelticConfig::lint(&yaml);
+            assert!(result.issues.is_empty(), "{:?}", result.issues);
+        }
+        assert!(BelticConfig::lint(&BelticConfig::generate_example()).is_valid);
+    }
+
+    #[test]
+    fn test_unknown_key_suggests_correction() {
+        let result = BelticConfig::lint(
+            "version: \"1.0\"\nagent:\n  paths:\n    include: [\"src/**\"]\n    exlude: [\"target/**\"]\n",
+        );
+        assert!(!result.is_valid);
+        let issue = &result.issues[0];
+        assert_eq!(issue.rule_id, RULE_UNKNOWN_KEY);
+        assert_eq!(issue.pointer.as_deref(), Some("/agent/paths/exlude"));
+        assert!(issue.message.contains("did you mean `exclude`"));
+    }
+
+    #[test]
+    fn test_invalid_glob_and_conflicts() {
+        let mut config = BelticConfig::default_standalone();
+        config.agent.paths.include.push("src/[".to_string());
+        config.agent.paths.exclude.push("src/**".to_string());
+        config.overrides = Some(DetectionOverrides {
+            deployment_type: Some(DeploymentType::Serverless),
+            ..Default::default()
+        });
+
+        let result = config.validate();
+        let rules: Vec<_> = result.issues.iter().map(|i| i.rule_id).collect();
+        assert!(rules.contains(&RULE_INVALID_GLOB));
+        assert_eq!(
+            result
+                .issues
+                .iter()
+                .filter(|i| i.rule_id == RULE_CONFLICT)
+                .count(),
+            2
+        );
+        assert!(!result.is_valid);
+    }
+}
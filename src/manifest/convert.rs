@@ -0,0 +1,511 @@
+//! Conversion between the internal [`AgentManifest`] and the schema-compliant
+//! [`AgentCredential`] (`beltic convert --to`).
+//!
+//! Fields both documents share are carried over; credential-only fields
+//! (safety metrics, credential metadata, proof scaffolding) get the same
+//! self-attested defaults as `init --credential`, and manifest-only fields
+//! (fingerprint metadata, deployment context) are dropped.
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::credential::{
+    AgentCredential, CloudProvider, DeploymentEnvType, DeploymentEnvironment, EncryptionStandard,
+    ModelFamily, ModelProvider,
+};
+use super::schema::AgentManifest;
+use super::{
+    convert_age_restriction, convert_agent_status, convert_architecture_type,
+    convert_compliance_cert, convert_cred_age_restriction, convert_cred_agent_status,
+    convert_cred_architecture_type, convert_cred_compliance_cert, convert_cred_data_category,
+    convert_cred_kyb_tier, convert_cred_modality, convert_data_category, convert_kyb_tier,
+    convert_modality, required_compliance_certs,
+};
+
+/// Which of the two agent documents a file holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    Manifest,
+    Credential,
+}
+
+impl DocumentKind {
+    /// Tell a manifest from a credential by their identifying fields
+    pub fn detect(document: &Value) -> Option<Self> {
+        if document.get("manifestSchemaVersion").is_some() {
+            Some(DocumentKind::Manifest)
+        } else if document.get("credentialId").is_some() && document.get("agentId").is_some() {
+            Some(DocumentKind::Credential)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DocumentKind::Manifest => "manifest",
+            DocumentKind::Credential => "credential",
+        }
+    }
+}
+
+impl FromStr for DocumentKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "manifest" => Ok(DocumentKind::Manifest),
+            "credential" => Ok(DocumentKind::Credential),
+            other => Err(format!(
+                "unknown document kind '{}': expected manifest or credential",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for DocumentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Build a credential issued by `issuer_did` from a manifest
+pub fn manifest_to_credential(
+    manifest: &AgentManifest,
+    issuer_did: &str,
+) -> Result<AgentCredential> {
+    let mut credential = AgentCredential::new_with_defaults(
+        manifest.agent_name.clone(),
+        manifest.agent_version.clone(),
+        manifest.system_config_fingerprint.clone(),
+        issuer_did.to_string(),
+    );
+
+    credential.agent_id = manifest.agent_id;
+    credential.agent_description = manifest.agent_description.clone();
+    credential.first_release_date = manifest.first_release_date.clone();
+    credential.current_status = convert_agent_status(&manifest.current_status);
+    credential.developer_credential_id = manifest.developer_credential_id;
+    credential.developer_credential_verified = manifest.developer_credential_verified;
+
+    credential.primary_model_provider =
+        ModelProvider::from_display_name(&manifest.primary_model_provider);
+    credential.primary_model_family =
+        ModelFamily::from_display_name(&manifest.primary_model_family);
+    credential.model_context_window = manifest.model_context_window;
+    credential.modality_support = manifest
+        .modality_support
+        .iter()
+        .map(convert_modality)
+        .collect();
+    credential.language_capabilities = manifest.language_capabilities.clone();
+    credential.architecture_type = convert_architecture_type(&manifest.architecture_type);
+    credential.system_config_last_updated = manifest.system_config_last_updated.clone();
+    credential.deployment_environment = deployment_environment(&manifest.deployment_environment);
+    credential.data_location_profile =
+        recode(&manifest.data_location_profile, "dataLocationProfile")?;
+
+    if let Some(tools) = &manifest.tools_list {
+        let today = manifest
+            .tools_last_audited
+            .clone()
+            .unwrap_or_else(|| credential.system_config_last_updated.clone());
+        credential.set_tools(recode(tools, "toolsList")?, &today);
+    }
+
+    credential.data_categories_processed = manifest
+        .data_categories_processed
+        .iter()
+        .map(convert_data_category)
+        .collect();
+    // Declared certifications, plus any the schema requires for the data
+    // categories processed
+    let mut certs: Vec<_> = manifest
+        .compliance_certifications
+        .iter()
+        .flatten()
+        .map(convert_compliance_cert)
+        .collect();
+    for cert in required_compliance_certs(&credential.data_categories_processed) {
+        if !certs.contains(&cert) {
+            certs.push(cert);
+        }
+    }
+    credential.compliance_certifications = Some(certs);
+    credential.data_retention_max_period = manifest.data_retention_max_period.clone();
+    credential.data_retention_by_category = manifest.data_retention_by_category.clone();
+    credential.training_data_usage = recode(&manifest.training_data_usage, "trainingDataUsage")?;
+    credential.pii_detection_enabled = manifest.pii_detection_enabled;
+    credential.pii_redaction_capability =
+        recode(&manifest.pii_redaction_capability, "piiRedactionCapability")?;
+    credential.pii_redaction_pipeline = manifest.pii_redaction_pipeline.clone();
+    credential.data_encryption_standards =
+        encryption_standards(&manifest.data_encryption_standards);
+
+    credential.incident_response_contact = manifest.incident_response_contact.clone();
+    credential.incident_response_slo = manifest.incident_response_slo.clone();
+    credential.deprecation_policy = manifest.deprecation_policy.clone();
+    credential.update_cadence = recode(&manifest.update_cadence, "updateCadence")?;
+    credential.human_oversight_mode = recode(&manifest.human_oversight_mode, "humanOversightMode")?;
+    credential.fail_safe_behavior = manifest.fail_safe_behavior.clone();
+    credential.monitoring_coverage = manifest.monitoring_coverage.clone();
+
+    credential.approved_use_cases = manifest.approved_use_cases.clone();
+    credential.prohibited_use_cases = manifest.prohibited_use_cases.clone();
+    credential.age_restrictions = convert_age_restriction(&manifest.age_restrictions);
+    credential.regulatory_approvals = manifest.regulatory_approvals.clone();
+    credential.kyb_tier_required = convert_kyb_tier(&manifest.kyb_tier_required);
+
+    Ok(credential)
+}
+
+/// Build a manifest from a credential
+pub fn credential_to_manifest(credential: &AgentCredential) -> Result<AgentManifest> {
+    let mut manifest = AgentManifest::new_with_defaults();
+
+    manifest.system_config_fingerprint = format!("sha256:{}", credential.system_config_fingerprint);
+    manifest.agent_id = credential.agent_id;
+    manifest.agent_name = credential.agent_name.clone();
+    manifest.agent_version = credential.agent_version.clone();
+    manifest.agent_description = credential.agent_description.clone();
+    manifest.first_release_date = credential.first_release_date.clone();
+    manifest.current_status = convert_cred_agent_status(&credential.current_status);
+    manifest.developer_credential_id = credential.developer_credential_id;
+    manifest.developer_credential_verified = credential.developer_credential_verified;
+
+    manifest.primary_model_provider = serde_name(&credential.primary_model_provider)?;
+    manifest.primary_model_family = serde_name(&credential.primary_model_family)?;
+    manifest.model_context_window = credential.model_context_window;
+    manifest.modality_support = credential
+        .modality_support
+        .iter()
+        .map(convert_cred_modality)
+        .collect();
+    manifest.language_capabilities = credential.language_capabilities.clone();
+    manifest.architecture_type = convert_cred_architecture_type(&credential.architecture_type);
+    manifest.system_config_last_updated = credential.system_config_last_updated.clone();
+    manifest.deployment_environment = describe_environment(&credential.deployment_environment)?;
+    manifest.data_location_profile =
+        recode(&credential.data_location_profile, "dataLocationProfile")?;
+
+    manifest.tools_list = credential
+        .tools_list
+        .as_ref()
+        .map(|tools| recode(tools, "toolsList"))
+        .transpose()?;
+    manifest.tools_last_audited = credential.tools_last_audited.clone();
+
+    manifest.data_categories_processed = credential
+        .data_categories_processed
+        .iter()
+        .map(convert_cred_data_category)
+        .collect();
+    manifest.compliance_certifications = credential
+        .compliance_certifications
+        .as_ref()
+        .map(|certs| certs.iter().map(convert_cred_compliance_cert).collect());
+    manifest.data_retention_max_period = credential.data_retention_max_period.clone();
+    manifest.data_retention_by_category = credential.data_retention_by_category.clone();
+    manifest.training_data_usage = recode(&credential.training_data_usage, "trainingDataUsage")?;
+    manifest.pii_detection_enabled = credential.pii_detection_enabled;
+    manifest.pii_redaction_capability = recode(
+        &credential.pii_redaction_capability,
+        "piiRedactionCapability",
+    )?;
+    manifest.pii_redaction_pipeline = credential.pii_redaction_pipeline.clone();
+    manifest.data_encryption_standards = credential
+        .data_encryption_standards
+        .iter()
+        .map(serde_name)
+        .collect::<Result<_>>()?;
+
+    manifest.incident_response_contact = credential.incident_response_contact.clone();
+    manifest.incident_response_slo = credential.incident_response_slo.clone();
+    manifest.deprecation_policy = credential.deprecation_policy.clone();
+    manifest.update_cadence = recode(&credential.update_cadence, "updateCadence")?;
+    manifest.human_oversight_mode = recode(&credential.human_oversight_mode, "humanOversightMode")?;
+    manifest.fail_safe_behavior = credential.fail_safe_behavior.clone();
+    manifest.monitoring_coverage = credential.monitoring_coverage.clone();
+
+    manifest.approved_use_cases = credential.approved_use_cases.clone();
+    manifest.prohibited_use_cases = credential.prohibited_use_cases.clone();
+    manifest.age_restrictions = convert_cred_age_restriction(&credential.age_restrictions);
+    manifest.regulatory_approvals = credential.regulatory_approvals.clone();
+    manifest.kyb_tier_required = convert_cred_kyb_tier(&credential.kyb_tier_required);
+
+    Ok(manifest)
+}
+
+/// Convert between a manifest and credential type that serialize the same way
+fn recode<T: Serialize, U: DeserializeOwned>(value: &T, field: &str) -> Result<U> {
+    serde_json::from_value(serde_json::to_value(value)?)
+        .with_context(|| format!("failed to convert {}", field))
+}
+
+/// The schema value of a serialized enum, e.g. `gpt-4o` for `ModelFamily::Gpt4o`
+fn serde_name<T: Serialize>(value: &T) -> Result<String> {
+    match serde_json::to_value(value)? {
+        Value::String(name) => Ok(name),
+        other => Ok(other.to_string()),
+    }
+}
+
+/// Credential deployment environment from the manifest's free-form
+/// description, e.g. "AWS us-east-1" or "on-premises"
+fn deployment_environment(description: &str) -> DeploymentEnvironment {
+    let lower = description.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|word| lower.contains(word));
+
+    let environment_type = if has(&["on_premises", "on-prem", "on prem", "local"]) {
+        DeploymentEnvType::OnPremises
+    } else if has(&["self_managed", "self-managed", "self managed"]) {
+        DeploymentEnvType::CloudSelfManaged
+    } else if has(&["hybrid"]) {
+        DeploymentEnvType::Hybrid
+    } else if has(&["edge"]) {
+        DeploymentEnvType::Edge
+    } else {
+        DeploymentEnvType::CloudManaged
+    };
+    let cloud_provider = if has(&["aws", "amazon"]) {
+        CloudProvider::Aws
+    } else if has(&["gcp", "google"]) {
+        CloudProvider::Gcp
+    } else if has(&["azure"]) {
+        CloudProvider::Azure
+    } else if has(&["oracle"]) {
+        CloudProvider::Oracle
+    } else if has(&["ibm"]) {
+        CloudProvider::Ibm
+    } else if has(&["alibaba"]) {
+        CloudProvider::Alibaba
+    } else {
+        CloudProvider::None
+    };
+
+    DeploymentEnvironment {
+        environment_type,
+        cloud_provider,
+        primary_region: Some("US".to_string()),
+        compliance_notes: None,
+    }
+}
+
+/// Manifest description of a credential deployment environment, in the form
+/// [`deployment_environment`] reads back
+fn describe_environment(environment: &DeploymentEnvironment) -> Result<String> {
+    let mut parts = vec![serde_name(&environment.environment_type)?];
+    if environment.cloud_provider != CloudProvider::None {
+        parts.push(serde_name(&environment.cloud_provider)?);
+    }
+    if let Some(region) = &environment.primary_region {
+        parts.push(region.clone());
+    }
+    Ok(parts.join(", "))
+}
+
+/// Credential encryption standards from the manifest's free-form names,
+/// e.g. "TLS 1.3 in transit" or "AES-256 at rest"
+fn encryption_standards(names: &[String]) -> Vec<EncryptionStandard> {
+    let mut standards: Vec<EncryptionStandard> = Vec::new();
+    for name in names {
+        let key: String = name
+            .to_lowercase()
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect();
+        let standard = if key.contains("tls13") {
+            EncryptionStandard::Tls13InTransit
+        } else if key.contains("tls12") {
+            EncryptionStandard::Tls12InTransit
+        } else if key.contains("aes256gcm") {
+            EncryptionStandard::Aes256Gcm
+        } else if key.contains("aes128gcm") {
+            EncryptionStandard::Aes128Gcm
+        } else if key.contains("aes256") {
+            EncryptionStandard::Aes256AtRest
+        } else if key.contains("aes128") {
+            EncryptionStandard::Aes128AtRest
+        } else if key.contains("chacha20") {
+            EncryptionStandard::ChaCha20Poly1305
+        } else if key.contains("rsa4096") {
+            EncryptionStandard::Rsa4096
+        } else if key.contains("rsa2048") {
+            EncryptionStandard::Rsa2048
+        } else if key.contains("ecdhe") {
+            EncryptionStandard::Ecdhe
+        } else {
+            EncryptionStandard::Other
+        };
+        if !standards.contains(&standard) {
+            standards.push(standard);
+        }
+    }
+    if standards.is_empty() {
+        standards.push(EncryptionStandard::Tls13InTransit);
+    }
+    standards
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credential::{validate_credential, CredentialKind};
+    use crate::manifest::credential::ComplianceCert as CredComplianceCert;
+    use crate::manifest::schema::{
+        AgentStatus, ComplianceCert, DataCategory, Modality, TrainingDataUsage,
+    };
+
+    fn manifest() -> AgentManifest {
+        let mut manifest = AgentManifest::new_with_defaults();
+        manifest.system_config_fingerprint = format!("sha256:{}", "ab".repeat(32));
+        manifest.agent_name = "Support Agent".to_string();
+        manifest.agent_description =
+            "Answers customer support questions and looks up order status for customers."
+                .to_string();
+        manifest.primary_model_provider = "OpenAI".to_string();
+        manifest.primary_model_family = "GPT-4o".to_string();
+        manifest.model_context_window = 128000;
+        manifest.modality_support = vec![Modality::Text, Modality::Image];
+        manifest.deployment_environment = "AWS us-east-1".to_string();
+        manifest.data_categories_processed = vec![DataCategory::Pii, DataCategory::Financial];
+        manifest.training_data_usage = TrainingDataUsage::OptOutAvailable;
+        manifest.data_encryption_standards = vec![
+            "TLS 1.3 in transit".to_string(),
+            "AES-256 at rest".to_string(),
+        ];
+        manifest.incident_response_contact = "security@acme.io".to_string();
+        manifest.deprecation_policy =
+            "Minimum 30-day notice via email with a migration guide.".to_string();
+        manifest.fail_safe_behavior =
+            "On error, returns a helpful message and logs the incident.".to_string();
+        manifest.monitoring_coverage =
+            "Real-time monitoring with logging, error tracking, and alerting.".to_string();
+        manifest
+    }
+
+    #[test]
+    fn test_manifest_round_trips_through_credential() {
+        let manifest = manifest();
+        let credential = manifest_to_credential(&manifest, "did:web:example.com").unwrap();
+        let errors = validate_credential(
+            CredentialKind::Agent,
+            &serde_json::to_value(&credential).unwrap(),
+        )
+        .unwrap();
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        assert_eq!(credential.agent_id, manifest.agent_id);
+        assert_eq!(credential.system_config_fingerprint, "ab".repeat(32));
+        assert_eq!(credential.primary_model_provider, ModelProvider::Openai);
+        assert_eq!(credential.primary_model_family, ModelFamily::Gpt4o);
+        assert_eq!(
+            credential.deployment_environment.cloud_provider,
+            CloudProvider::Aws
+        );
+        assert_eq!(
+            credential.data_encryption_standards,
+            vec![
+                EncryptionStandard::Tls13InTransit,
+                EncryptionStandard::Aes256AtRest
+            ]
+        );
+        assert_eq!(credential.issuer_did, "did:web:example.com");
+
+        let back = credential_to_manifest(&credential).unwrap();
+        assert_eq!(
+            back.system_config_fingerprint,
+            manifest.system_config_fingerprint
+        );
+        assert_eq!(back.agent_name, manifest.agent_name);
+        assert_eq!(back.modality_support, manifest.modality_support);
+        assert_eq!(
+            back.data_categories_processed,
+            manifest.data_categories_processed
+        );
+        assert_eq!(back.primary_model_family, "gpt-4o");
+        assert_eq!(
+            deployment_environment(&back.deployment_environment).cloud_provider,
+            CloudProvider::Aws
+        );
+        let report = crate::manifest::validator::validate_manifest(&back);
+        assert!(report.is_valid, "{:?}", report.errors);
+    }
+
+    #[test]
+    fn test_detects_document_kind() {
+        let manifest = serde_json::to_value(manifest()).unwrap();
+        assert_eq!(
+            DocumentKind::detect(&manifest),
+            Some(DocumentKind::Manifest)
+        );
+        let credential = serde_json::to_value(
+            manifest_to_credential(&AgentManifest::new_with_defaults(), "did:web:example.com")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            DocumentKind::detect(&credential),
+            Some(DocumentKind::Credential)
+        );
+        assert_eq!(DocumentKind::detect(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn test_code_modality_is_not_downgraded_to_text() {
+        let mut manifest = manifest();
+        manifest.modality_support = vec![Modality::Code, Modality::StructuredData];
+        let credential = manifest_to_credential(&manifest, "did:web:example.com").unwrap();
+        let value = serde_json::to_value(&credential).unwrap();
+        assert_eq!(
+            value["modalitySupport"],
+            serde_json::json!(["code", "structured_data"])
+        );
+
+        let back = credential_to_manifest(&credential).unwrap();
+        assert_eq!(back.modality_support, manifest.modality_support);
+    }
+
+    #[test]
+    fn test_internal_status_is_preserved() {
+        let mut manifest = manifest();
+        manifest.current_status = AgentStatus::Internal;
+        let credential = manifest_to_credential(&manifest, "did:web:example.com").unwrap();
+        assert_eq!(
+            serde_json::to_value(&credential).unwrap()["currentStatus"],
+            "internal"
+        );
+        let back = credential_to_manifest(&credential).unwrap();
+        assert_eq!(back.current_status, AgentStatus::Internal);
+    }
+
+    #[test]
+    fn test_compliance_certifications_carry_over() {
+        let mut manifest = manifest();
+        manifest.compliance_certifications =
+            Some(vec![ComplianceCert::Iso27017, ComplianceCert::Ccpa]);
+        let credential = manifest_to_credential(&manifest, "did:web:example.com").unwrap();
+        let certs = credential.compliance_certifications.clone().unwrap();
+        // Declared certifications first, then those required for PII and
+        // financial data
+        assert_eq!(
+            certs[..2],
+            [
+                CredComplianceCert::Iso27017,
+                CredComplianceCert::CcpaCompliant
+            ]
+        );
+        assert!(certs.contains(&CredComplianceCert::PciDss));
+
+        let back = credential_to_manifest(&credential).unwrap();
+        let back = back.compliance_certifications.unwrap();
+        assert_eq!(back[..2], [ComplianceCert::Iso27017, ComplianceCert::Ccpa]);
+        assert!(back.contains(&ComplianceCert::Gdpr));
+    }
+}
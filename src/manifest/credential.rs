@@ -3,10 +3,33 @@
 //! This is the schema-compliant credential that gets signed as a JWS.
 //! It differs from AgentManifest which is the internal configuration format.
 
-use chrono::Utc;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::duration::IsoDuration;
+
+/// UUIDv5 namespace for deterministic agent IDs (`init --deterministic-id`).
+/// Changing it changes every derived ID.
+pub const AGENT_ID_NAMESPACE: Uuid = Uuid::from_u128(0x5f3c_9a1e_7b24_4c8d_a0e6_2d91_b8c4_f173);
+
+/// Deterministic `agentId`: a UUIDv5 of the issuer DID and agent name, so the
+/// same agent from the same issuer always gets the same ID
+pub fn deterministic_agent_id(issuer_did: &str, agent_name: &str) -> Uuid {
+    // DIDs never contain '|', so the name can't be confused with the issuer
+    Uuid::new_v5(
+        &AGENT_ID_NAMESPACE,
+        format!("{}|{}", issuer_did, agent_name).as_bytes(),
+    )
+}
+
+/// Deterministic `credentialId`: a UUIDv5 of the agent version in the
+/// agent ID's namespace, so each version of an agent has one credential ID
+pub fn deterministic_credential_id(agent_id: &Uuid, agent_version: &str) -> Uuid {
+    Uuid::new_v5(agent_id, agent_version.as_bytes())
+}
+
 /// AgentCredential v1 - matches schema at beltic-spec/schemas/agent/v1/agent-credential-v1.schema.json
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -285,7 +308,8 @@ impl ModelFamily {
             ModelFamily::Gpt4 => 32000,
             ModelFamily::Gpt4Turbo | ModelFamily::Gpt4o | ModelFamily::Gpt4oMini => 128000,
             ModelFamily::GeminiPro | ModelFamily::GeminiUltra | ModelFamily::Gemini15 => 1000000,
-            ModelFamily::Llama3 | ModelFamily::Llama31 => 128000,
+            ModelFamily::Llama3 => 8192,
+            ModelFamily::Llama31 => 128000,
             ModelFamily::MistralLarge | ModelFamily::MistralMedium => 32000,
             ModelFamily::CommandR | ModelFamily::CommandRPlus => 128000,
             ModelFamily::Other => 8192,
@@ -613,6 +637,42 @@ pub enum ProofPurpose {
 }
 
 impl AgentCredential {
+    /// Set `credentialExpirationDate` to `valid_for` after the issuance date
+    pub fn set_valid_for(&mut self, valid_for: &IsoDuration) -> Result<()> {
+        let issued = DateTime::parse_from_rfc3339(&self.credential_issuance_date)
+            .map_err(|e| anyhow!("invalid credentialIssuanceDate: {}", e))?
+            .with_timezone(&Utc);
+        let expires = valid_for
+            .after(issued)
+            .ok_or_else(|| anyhow!("--valid-for {} is out of range", valid_for))?;
+        self.credential_expiration_date = expires.to_rfc3339();
+        Ok(())
+    }
+
+    /// Replace the random `agentId` and `credentialId` with IDs derived from
+    /// the issuer DID, agent name and version (see [`deterministic_agent_id`])
+    pub fn use_deterministic_ids(&mut self) {
+        self.agent_id = deterministic_agent_id(&self.issuer_did, &self.agent_name);
+        self.credential_id = deterministic_credential_id(&self.agent_id, &self.agent_version);
+    }
+
+    /// Declare `tools` as audited on `today`. The schema requires tool abuse
+    /// metrics once tools are declared, so missing ones get the same
+    /// self-attested placeholders as the other safety metrics.
+    pub fn set_tools(&mut self, tools: Vec<Tool>, today: &str) {
+        self.tools_list = Some(tools);
+        self.tools_last_audited = Some(today.to_string());
+        self.tool_abuse_robustness_score.get_or_insert(0.0);
+        self.tool_abuse_benchmark_name
+            .get_or_insert_with(|| "self-evaluation".to_string());
+        self.tool_abuse_benchmark_version
+            .get_or_insert_with(|| "0.0.0".to_string());
+        self.tool_abuse_evaluation_date
+            .get_or_insert_with(|| today.to_string());
+        self.tool_abuse_assurance_source
+            .get_or_insert(AssuranceSource::SelfAttested);
+    }
+
     /// Create a new credential with self-signing defaults
     pub fn new_with_defaults(
         agent_name: String,
@@ -746,3 +806,36 @@ impl AgentCredential {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic_ids_are_stable() {
+        let credential = |name: &str, version: &str| {
+            let mut credential = AgentCredential::new_with_defaults(
+                name.to_string(),
+                version.to_string(),
+                "sha256:abc".to_string(),
+                "did:web:example.com".to_string(),
+            );
+            credential.use_deterministic_ids();
+            credential
+        };
+
+        let first = credential("support-agent", "1.0.0");
+        let again = credential("support-agent", "1.0.0");
+        assert_eq!(first.agent_id, again.agent_id);
+        assert_eq!(first.credential_id, again.credential_id);
+        assert_eq!(first.agent_id.get_version_num(), 5);
+
+        let next_version = credential("support-agent", "1.1.0");
+        assert_eq!(next_version.agent_id, first.agent_id);
+        assert_ne!(next_version.credential_id, first.credential_id);
+        assert_ne!(
+            credential("billing-agent", "1.0.0").agent_id,
+            first.agent_id
+        );
+    }
+}
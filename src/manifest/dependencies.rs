@@ -0,0 +1,330 @@
+//! External dependencies declared in a project's package manifests
+//!
+//! Reads the runtime dependencies (name and version requirement, as written)
+//! from Cargo.toml, package.json, pyproject.toml, requirements.txt, go.mod
+//! and composer.json. Lock files are not read, so versions are the declared
+//! requirements rather than resolved versions. Dependencies without a
+//! version (path or git dependencies) are left out.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use log::debug;
+use serde_json::Value;
+
+/// A dependency declared in a package manifest
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DeclaredDependency {
+    /// Package ecosystem, as a package URL type (`cargo`, `npm`, `pypi`, ...)
+    pub ecosystem: String,
+    pub name: String,
+    /// Version requirement as declared, e.g. `^4.0.0` or `>=0.1`
+    pub version: String,
+}
+
+impl DeclaredDependency {
+    fn new(ecosystem: &str, name: &str, version: &str) -> Self {
+        Self {
+            ecosystem: ecosystem.to_string(),
+            name: name.trim().to_string(),
+            version: version.trim().to_string(),
+        }
+    }
+
+    /// Package URL for this dependency (see [`purl`])
+    pub fn purl(&self) -> String {
+        purl(&self.ecosystem, &self.name, &self.version)
+    }
+}
+
+/// Package URL (purl); the version is only included when the requirement
+/// pins an exact one
+pub fn purl(ecosystem: &str, name: &str, version: &str) -> String {
+    let name = match name.strip_prefix('@') {
+        Some(scoped) => format!("%40{}", scoped),
+        None => name.to_string(),
+    };
+    // A bare Cargo version is a caret requirement and a bare Python one is
+    // not valid, so only `=` and `==` pin there
+    let pinned = match ecosystem {
+        "cargo" => version.strip_prefix('='),
+        "pypi" => version.strip_prefix("=="),
+        _ => Some(version.strip_prefix('=').unwrap_or(version)),
+    }
+    .map(str::trim)
+    .filter(|version| is_exact_version(version));
+    match pinned {
+        Some(version) => format!("pkg:{}/{}@{}", ecosystem, name, version),
+        None => format!("pkg:{}/{}", ecosystem, name),
+    }
+}
+
+fn is_exact_version(version: &str) -> bool {
+    version.starts_with(|c: char| c.is_ascii_digit() || c == 'v')
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))
+}
+
+type Parser = fn(&str) -> Result<Vec<DeclaredDependency>>;
+
+const PARSERS: &[(&str, Parser)] = &[
+    ("Cargo.toml", parse_cargo_toml),
+    ("package.json", parse_package_json),
+    ("pyproject.toml", parse_pyproject_toml),
+    ("requirements.txt", parse_requirements_txt),
+    ("go.mod", parse_go_mod),
+    ("composer.json", parse_composer_json),
+];
+
+/// Dependencies declared in the package manifests in `base_dir`, sorted and
+/// without duplicates
+pub fn declared_dependencies(base_dir: &Path) -> Result<Vec<DeclaredDependency>> {
+    let mut dependencies = Vec::new();
+    for (file, parse) in PARSERS {
+        let path = base_dir.join(file);
+        if !path.is_file() {
+            continue;
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let found =
+            parse(&content).with_context(|| format!("failed to parse {}", path.display()))?;
+        debug!("{} declares {} dependencies", file, found.len());
+        dependencies.extend(found);
+    }
+    dependencies.sort();
+    dependencies.dedup_by(|a, b| a.ecosystem == b.ecosystem && a.name == b.name);
+    Ok(dependencies)
+}
+
+fn parse_cargo_toml(content: &str) -> Result<Vec<DeclaredDependency>> {
+    let cargo: toml::Value = content.parse()?;
+    let Some(deps) = cargo.get("dependencies").and_then(|d| d.as_table()) else {
+        return Ok(Vec::new());
+    };
+    Ok(deps
+        .iter()
+        .filter_map(|(name, spec)| {
+            let version = match spec {
+                toml::Value::String(version) => Some(version.as_str()),
+                toml::Value::Table(table) => table.get("version").and_then(|v| v.as_str()),
+                _ => None,
+            }?;
+            // `package = "..."` renames the dependency; the crate is the package
+            let name = spec.get("package").and_then(|p| p.as_str()).unwrap_or(name);
+            Some(DeclaredDependency::new("cargo", name, version))
+        })
+        .collect())
+}
+
+fn parse_package_json(content: &str) -> Result<Vec<DeclaredDependency>> {
+    let package: Value = serde_json::from_str(content)?;
+    Ok(json_requirements(&package, "dependencies", "npm", |_| true))
+}
+
+fn parse_composer_json(content: &str) -> Result<Vec<DeclaredDependency>> {
+    let composer: Value = serde_json::from_str(content)?;
+    // `php` and `ext-*` are platform requirements, not packages
+    Ok(json_requirements(
+        &composer,
+        "require",
+        "composer",
+        |name| name.contains('/'),
+    ))
+}
+
+/// `{ "name": "version" }` objects from package.json and composer.json
+fn json_requirements(
+    document: &Value,
+    key: &str,
+    ecosystem: &str,
+    keep: fn(&str) -> bool,
+) -> Vec<DeclaredDependency> {
+    document
+        .get(key)
+        .and_then(Value::as_object)
+        .map(|deps| {
+            deps.iter()
+                .filter(|(name, _)| keep(name))
+                .filter_map(|(name, version)| {
+                    Some(DeclaredDependency::new(ecosystem, name, version.as_str()?))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_pyproject_toml(content: &str) -> Result<Vec<DeclaredDependency>> {
+    let pyproject: toml::Value = content.parse()?;
+    let mut dependencies: Vec<DeclaredDependency> = pyproject
+        .get("project")
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_array())
+        .map(|deps| {
+            deps.iter()
+                .filter_map(|d| d.as_str())
+                .filter_map(parse_pep508)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if let Some(deps) = pyproject
+        .get("tool")
+        .and_then(|t| t.get("poetry"))
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_table())
+    {
+        for (name, spec) in deps {
+            let version = match spec {
+                toml::Value::String(version) => Some(version.as_str()),
+                toml::Value::Table(table) => table.get("version").and_then(|v| v.as_str()),
+                _ => None,
+            };
+            match version {
+                Some(version) if name != "python" => {
+                    dependencies.push(DeclaredDependency::new("pypi", name, version))
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(dependencies)
+}
+
+fn parse_requirements_txt(content: &str) -> Result<Vec<DeclaredDependency>> {
+    Ok(content
+        .lines()
+        .map(|line| line.split(" #").next().unwrap_or(line).trim())
+        // Options (-r, -e, --index-url) and comments declare no package
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', '-']))
+        .filter_map(parse_pep508)
+        .collect())
+}
+
+/// A PEP 508 requirement such as `langchain[openai]>=0.1; python_version>"3.8"`.
+/// A requirement without a version specifier gets `*`.
+fn parse_pep508(requirement: &str) -> Option<DeclaredDependency> {
+    let requirement = requirement.split(';').next().unwrap_or(requirement).trim();
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+    let name = &requirement[..end];
+    if name.is_empty() {
+        return None;
+    }
+    let mut rest = requirement[end..].trim_start();
+    if rest.starts_with('[') {
+        rest = rest
+            .split_once(']')
+            .map(|(_, r)| r)
+            .unwrap_or("")
+            .trim_start();
+    }
+    // Direct references (`name @ https://...`) have no version
+    if rest.starts_with('@') {
+        return None;
+    }
+    let version = if rest.is_empty() { "*" } else { rest };
+    Some(DeclaredDependency::new(
+        "pypi",
+        &name.to_lowercase(),
+        &version.replace(' ', ""),
+    ))
+}
+
+fn parse_go_mod(content: &str) -> Result<Vec<DeclaredDependency>> {
+    let mut dependencies = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or(line).trim();
+        let requirement = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_block = true;
+            continue;
+        } else if let Some(single) = line.strip_prefix("require ") {
+            single
+        } else {
+            continue;
+        };
+        let mut parts = requirement.split_whitespace();
+        if let (Some(module), Some(version)) = (parts.next(), parts.next()) {
+            dependencies.push(DeclaredDependency::new("golang", module, version));
+        }
+    }
+    Ok(dependencies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_reads_each_manifest_format() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            r#"
+[package]
+name = "agent"
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+anyhow = "1"
+local = { path = "../local" }
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("package.json"),
+            r#"{"dependencies": {"openai": "^4.0.0", "@langchain/core": "0.2.1"}}"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("requirements.txt"),
+            "# tools\nlangchain[openai] >= 0.1 ; python_version > \"3.8\"\n-r base.txt\nrequests\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("go.mod"),
+            "module example.com/agent\n\nrequire (\n\tgithub.com/sashabaranov/go-openai v1.20.0 // indirect\n)\nrequire golang.org/x/net v0.20.0\n",
+        )
+        .unwrap();
+
+        let deps = declared_dependencies(dir.path()).unwrap();
+        let found: Vec<_> = deps
+            .iter()
+            .map(|d| format!("{} {} {}", d.ecosystem, d.name, d.version))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                "cargo anyhow 1",
+                "cargo serde 1.0",
+                "golang github.com/sashabaranov/go-openai v1.20.0",
+                "golang golang.org/x/net v0.20.0",
+                "npm @langchain/core 0.2.1",
+                "npm openai ^4.0.0",
+                "pypi langchain >=0.1",
+                "pypi requests *",
+            ]
+        );
+        assert_eq!(deps[4].purl(), "pkg:npm/%40langchain/core@0.2.1");
+        assert_eq!(deps[5].purl(), "pkg:npm/openai");
+        assert_eq!(deps[1].purl(), "pkg:cargo/serde");
+        assert_eq!(
+            purl("pypi", "requests", "==2.31.0"),
+            "pkg:pypi/requests@2.31.0"
+        );
+        assert_eq!(
+            deps[2].purl(),
+            "pkg:golang/github.com/sashabaranov/go-openai@v1.20.0"
+        );
+    }
+}
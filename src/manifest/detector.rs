@@ -1,9 +1,14 @@
 use anyhow::Result;
+use log::{debug, trace};
+use regex::Regex;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
 
+use crate::manifest::api_schema::detect_schema_categories;
+use crate::manifest::config::DetectionOverrides;
+use crate::manifest::fingerprint::glob_files;
 use crate::manifest::schema::{
     AgentStatus, ArchitectureType, DataCategory, DeploymentContext, DeploymentType, Modality,
     RepositoryStructure,
@@ -24,30 +29,99 @@ pub struct DetectionResults {
     pub language_capabilities: Vec<String>,
     pub data_categories: Vec<DataCategory>,
     pub deployment_context: Option<DeploymentContext>,
+    /// Model provider; not auto-detected, only set from overrides
+    pub model_provider: Option<String>,
+    /// Model family; not auto-detected, only set from overrides
+    pub model_family: Option<String>,
     pub detection_sources: HashMap<String, String>,
 }
 
+/// Detection source recorded for values taken from `.beltic.yaml` overrides
+pub const OVERRIDE_SOURCE: &str = "override (.beltic.yaml)";
+
+/// Detection source recorded for values that fell back to a default
+pub const DEFAULT_SOURCE: &str = "default";
+
+impl DetectionResults {
+    /// Replace detected values with the `overrides:` block from `.beltic.yaml`
+    pub fn apply_overrides(&mut self, overrides: &DetectionOverrides) {
+        if let Some(architecture) = &overrides.architecture_type {
+            self.architecture_type = Some(architecture.clone());
+            self.record_override("architecture_type");
+        }
+        if let Some(deployment) = &overrides.deployment_type {
+            self.deployment_type = Some(deployment.clone());
+            self.record_override("deployment_type");
+        }
+        if let Some(provider) = &overrides.model_provider {
+            self.model_provider = Some(provider.clone());
+            self.record_override("model_provider");
+        }
+        if let Some(family) = &overrides.model_family {
+            self.model_family = Some(family.clone());
+            self.record_override("model_family");
+        }
+        if let Some(modalities) = &overrides.modalities {
+            self.modality_support = modalities.clone();
+            self.record_override("modality_support");
+        }
+    }
+
+    /// Whether `field` was detected from the project or set by an override,
+    /// rather than left unset or defaulted
+    pub fn is_confident(&self, field: &str) -> bool {
+        self.detection_sources
+            .get(field)
+            .is_some_and(|source| source != DEFAULT_SOURCE)
+    }
+
+    fn record_override(&mut self, field: &str) {
+        debug!("{} overridden by .beltic.yaml", field);
+        self.detection_sources
+            .insert(field.to_string(), OVERRIDE_SOURCE.to_string());
+    }
+}
+
+type Detector = fn(&Path, &mut DetectionResults);
+
+/// Detection strategies in order. Rails and Laravel apps also ship a
+/// package.json for frontend assets, so the Ruby and PHP manifests take
+/// precedence over it.
+const DETECTORS: &[(&str, Detector)] = &[
+    ("Cargo.toml", detect_from_cargo_toml),
+    ("gemspec", detect_from_gemspec),
+    ("Gemfile", detect_from_gemfile),
+    ("composer.json", detect_from_composer_json),
+    ("package.json", detect_from_package_json),
+    ("pyproject.toml", detect_from_pyproject_toml),
+    ("setup.py", detect_from_setup_py),
+    ("requirements.txt", detect_from_requirements_txt),
+    ("go.mod", detect_from_go_mod),
+    ("git", detect_from_git),
+    ("README", detect_from_readme),
+    ("architecture patterns", detect_architecture_patterns),
+    ("API schemas", detect_from_api_schemas),
+    ("AI frameworks", detect_ai_frameworks),
+    ("deployment type", detect_deployment_type),
+    ("language support", detect_language_support),
+    ("modalities", detect_modalities),
+];
+
 /// Detect project information from various sources
 pub fn detect_project_info(base_dir: &Path) -> Result<DetectionResults> {
     let mut results = DetectionResults::default();
 
     // Try different detection strategies
-    detect_from_cargo_toml(base_dir, &mut results);
-    detect_from_package_json(base_dir, &mut results);
-    detect_from_pyproject_toml(base_dir, &mut results);
-    detect_from_setup_py(base_dir, &mut results);
-    detect_from_requirements_txt(base_dir, &mut results);
-    detect_from_go_mod(base_dir, &mut results);
-    detect_from_git(base_dir, &mut results);
-    detect_from_readme(base_dir, &mut results);
-    detect_architecture_patterns(base_dir, &mut results);
-    detect_ai_frameworks(base_dir, &mut results);
-    detect_deployment_type(base_dir, &mut results);
-    detect_language_support(base_dir, &mut results);
-    detect_modalities(base_dir, &mut results);
+    for (name, detect) in DETECTORS {
+        trace!("running {} detector", name);
+        let before = results.detection_sources.clone();
+        detect(base_dir, &mut results);
+        log_new_sources(name, &before, &results.detection_sources);
+    }
 
     // Fallback for project name
     if results.project_name.is_none() {
+        debug!("no project name detected; falling back to the directory name");
         results.project_name = base_dir
             .file_name()
             .and_then(|n| n.to_str())
@@ -59,15 +133,62 @@ pub fn detect_project_info(base_dir: &Path) -> Result<DetectionResults> {
 
     // Default version if not found
     if results.project_version.is_none() {
+        debug!("no project version detected; defaulting to 0.1.0");
         results.project_version = Some("0.1.0".to_string());
         results
             .detection_sources
-            .insert("project_version".to_string(), "default".to_string());
+            .insert("project_version".to_string(), DEFAULT_SOURCE.to_string());
+    }
+
+    Ok(results)
+}
+
+/// Detect project information for an agent in a subdirectory of a repository
+///
+/// Project files are read from `repo_root/agent_path`, git details from
+/// `repo_root`, and `repositoryStructure.agentPath` records `agent_path`.
+pub fn detect_agent_in_repo(repo_root: &Path, agent_path: &str) -> Result<DetectionResults> {
+    let mut results = detect_project_info(&repo_root.join(agent_path))?;
+    if results.git_remote.is_none() {
+        detect_from_git(repo_root, &mut results);
+    }
+
+    if let (Some(context), Some(remote)) = (
+        results.deployment_context.as_mut(),
+        results.git_remote.clone(),
+    ) {
+        let structure = context
+            .repository_structure
+            .get_or_insert_with(|| RepositoryStructure {
+                root: remote,
+                agent_path: String::new(),
+            });
+        structure.agent_path = agent_path.to_string();
     }
 
     Ok(results)
 }
 
+/// Log the fields a detector set or changed, with the source it recorded
+fn log_new_sources(
+    detector: &str,
+    before: &HashMap<String, String>,
+    after: &HashMap<String, String>,
+) {
+    let mut fired: Vec<_> = after
+        .iter()
+        .filter(|(field, source)| before.get(*field) != Some(*source))
+        .collect();
+    if fired.is_empty() {
+        trace!("{} detector found nothing", detector);
+        return;
+    }
+    fired.sort();
+    for (field, source) in fired {
+        debug!("{} detector set {} from {}", detector, field, source);
+    }
+}
+
 /// Detect from Cargo.toml
 fn detect_from_cargo_toml(base_dir: &Path, results: &mut DetectionResults) {
     let cargo_path = base_dir.join("Cargo.toml");
@@ -116,6 +237,10 @@ fn detect_from_cargo_toml(base_dir: &Path, results: &mut DetectionResults) {
             // Check for workspace (monorepo indicator)
             if cargo_toml.get("workspace").is_some() {
                 results.deployment_type = Some(DeploymentType::Monorepo);
+                results.detection_sources.insert(
+                    "deployment_type".to_string(),
+                    "Cargo.toml (workspace)".to_string(),
+                );
             }
 
             results.primary_language = Some("Rust".to_string());
@@ -166,6 +291,10 @@ fn detect_from_package_json(base_dir: &Path, results: &mut DetectionResults) {
             // Check for workspaces (monorepo)
             if package_json.get("workspaces").is_some() {
                 results.deployment_type = Some(DeploymentType::Monorepo);
+                results.detection_sources.insert(
+                    "deployment_type".to_string(),
+                    "package.json (workspaces)".to_string(),
+                );
             }
 
             // Check dependencies for AI/ML libraries
@@ -381,7 +510,7 @@ fn detect_from_python_deps(deps: &[toml::Value], results: &mut DetectionResults)
         .filter_map(|d| d.as_str())
         .map(|s| {
             // Extract package name from dependency string (e.g., "langchain>=0.1.0" -> "langchain")
-            s.split(|c| c == '>' || c == '<' || c == '=' || c == '[' || c == ';')
+            s.split(['>', '<', '=', '[', ';'])
                 .next()
                 .unwrap_or(s)
                 .trim()
@@ -437,6 +566,172 @@ fn detect_from_go_mod(base_dir: &Path, results: &mut DetectionResults) {
     }
 }
 
+/// Detect from a *.gemspec (Ruby gem)
+fn detect_from_gemspec(base_dir: &Path, results: &mut DetectionResults) {
+    let Some(gemspec_path) = fs::read_dir(base_dir).ok().and_then(|entries| {
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|path| path.extension().is_some_and(|ext| ext == "gemspec"))
+    }) else {
+        return;
+    };
+    let Ok(content) = fs::read_to_string(&gemspec_path) else {
+        return;
+    };
+    let source = gemspec_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "gemspec".to_string());
+
+    // Only literal strings; values like `Foo::VERSION` are left undetected
+    let field_re =
+        Regex::new(r#"(?m)^\s*\w+\.(name|version|summary|description)\s*=\s*["']([^"']+)["']"#)
+            .unwrap();
+    let mut fields = HashMap::new();
+    for cap in field_re.captures_iter(&content) {
+        fields.insert(cap[1].to_string(), cap[2].to_string());
+    }
+
+    if results.project_name.is_none() {
+        if let Some(name) = fields.get("name") {
+            results.project_name = Some(name.clone());
+            results
+                .detection_sources
+                .insert("project_name".to_string(), source.clone());
+        }
+    }
+    if results.project_version.is_none() {
+        if let Some(version) = fields.get("version") {
+            results.project_version = Some(version.clone());
+            results
+                .detection_sources
+                .insert("project_version".to_string(), source.clone());
+        }
+    }
+    if results.project_description.is_none() {
+        if let Some(desc) = fields.get("description").or_else(|| fields.get("summary")) {
+            results.project_description = Some(desc.clone());
+            results
+                .detection_sources
+                .insert("project_description".to_string(), source.clone());
+        }
+    }
+
+    let dep_re = Regex::new(
+        r#"(?m)^\s*\w+\.add_(?:runtime_|development_)?dependency\s*\(?\s*["']([^"']+)["']"#,
+    )
+    .unwrap();
+    let gems: Vec<String> = dep_re
+        .captures_iter(&content)
+        .map(|cap| cap[1].to_string())
+        .collect();
+    detect_ai_packages(&gems, &source, results);
+
+    if results.primary_language.is_none() {
+        results.primary_language = Some("Ruby".to_string());
+    }
+}
+
+/// Detect from Gemfile (Ruby/Rails)
+fn detect_from_gemfile(base_dir: &Path, results: &mut DetectionResults) {
+    let gemfile_path = base_dir.join("Gemfile");
+    let Ok(content) = fs::read_to_string(&gemfile_path) else {
+        return;
+    };
+
+    let gem_re = Regex::new(r#"(?m)^\s*gem\s+["']([^"']+)["']"#).unwrap();
+    let gems: Vec<String> = gem_re
+        .captures_iter(&content)
+        .map(|cap| cap[1].to_string())
+        .collect();
+    detect_ai_packages(&gems, "Gemfile", results);
+
+    if results.primary_language.is_none() {
+        results.primary_language = Some("Ruby".to_string());
+    }
+}
+
+/// Detect from composer.json (PHP/Laravel)
+fn detect_from_composer_json(base_dir: &Path, results: &mut DetectionResults) {
+    let composer_path = base_dir.join("composer.json");
+    let Ok(content) = fs::read_to_string(&composer_path) else {
+        return;
+    };
+    let Ok(composer) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return;
+    };
+
+    if results.project_name.is_none() {
+        // "vendor/package" -> "package"
+        if let Some(name) = composer.get("name").and_then(|n| n.as_str()) {
+            let name = name.rsplit('/').next().unwrap_or(name);
+            results.project_name = Some(name.to_string());
+            results
+                .detection_sources
+                .insert("project_name".to_string(), "composer.json".to_string());
+        }
+    }
+    if results.project_version.is_none() {
+        if let Some(version) = composer.get("version").and_then(|v| v.as_str()) {
+            results.project_version = Some(version.to_string());
+            results
+                .detection_sources
+                .insert("project_version".to_string(), "composer.json".to_string());
+        }
+    }
+    if results.project_description.is_none() {
+        if let Some(desc) = composer.get("description").and_then(|d| d.as_str()) {
+            results.project_description = Some(desc.to_string());
+            results.detection_sources.insert(
+                "project_description".to_string(),
+                "composer.json".to_string(),
+            );
+        }
+    }
+
+    let packages: Vec<String> = ["require", "require-dev"]
+        .iter()
+        .filter_map(|key| composer.get(*key).and_then(|deps| deps.as_object()))
+        .flat_map(|deps| deps.keys().cloned())
+        .collect();
+    detect_ai_packages(&packages, "composer.json", results);
+
+    if results.primary_language.is_none() {
+        results.primary_language = Some("PHP".to_string());
+    }
+}
+
+/// Infer architecture from Ruby gem or Composer package names, unless a
+/// more authoritative source already set it
+fn detect_ai_packages(packages: &[String], source: &str, results: &mut DetectionResults) {
+    if results.architecture_type.is_some() {
+        return;
+    }
+
+    let is_rag = |p: &String| p == "langchainrb" || p == "theodo-group/llphant";
+    let is_sdk = |p: &String| {
+        matches!(
+            p.as_str(),
+            "ruby-openai" | "anthropic" | "openai-php/client" | "openai-php/laravel"
+        ) || p.contains("anthropic")
+    };
+
+    let (architecture, kind) = if packages.iter().any(is_rag) {
+        (ArchitectureType::Rag, "RAG framework")
+    } else if packages.iter().any(is_sdk) {
+        (ArchitectureType::ToolUsing, "AI SDK")
+    } else {
+        return;
+    };
+
+    results.architecture_type = Some(architecture);
+    results.detection_sources.insert(
+        "architecture_type".to_string(),
+        format!("{} ({})", source, kind),
+    );
+}
+
 /// Detect AI framework patterns from dependency names
 fn detect_ai_deps_from_names(dep_names: &[String], results: &mut DetectionResults) {
     let has_langchain = dep_names.iter().any(|d| d.contains("langchain"));
@@ -487,7 +782,7 @@ fn detect_ai_deps_from_content(content: &str, results: &mut DetectionResults) {
         .lines()
         .filter(|l| !l.trim().starts_with('#') && !l.trim().is_empty())
         .map(|l| {
-            l.split(|c| c == '>' || c == '<' || c == '=' || c == '[' || c == ';')
+            l.split(['>', '<', '=', '[', ';'])
                 .next()
                 .unwrap_or(l)
                 .trim()
@@ -564,8 +859,8 @@ fn detect_ai_frameworks(base_dir: &Path, results: &mut DetectionResults) {
     }
 
     // Check TypeScript/JavaScript files for framework imports
-    if let Ok(paths) = glob::glob(&base_dir.join("**/*.{ts,js}").to_string_lossy()) {
-        for path in paths.flatten().take(50) {
+    if let Ok(paths) = glob_files(base_dir, "**/*.{ts,js}") {
+        for path in paths.into_iter().take(50) {
             if let Ok(content) = fs::read_to_string(&path) {
                 if content.contains("@langchain") || content.contains("langchain") {
                     results.architecture_type = Some(ArchitectureType::Rag);
@@ -575,23 +870,21 @@ fn detect_ai_frameworks(base_dir: &Path, results: &mut DetectionResults) {
                     );
                     return;
                 }
-                if content.contains("@anthropic-ai/sdk") || content.contains("anthropic") {
-                    if results.architecture_type.is_none() {
-                        results.architecture_type = Some(ArchitectureType::ToolUsing);
-                        results.detection_sources.insert(
-                            "architecture_type".to_string(),
-                            "code (anthropic import)".to_string(),
-                        );
-                    }
+                if (content.contains("@anthropic-ai/sdk") || content.contains("anthropic"))
+                    && results.architecture_type.is_none()
+                {
+                    results.architecture_type = Some(ArchitectureType::ToolUsing);
+                    results.detection_sources.insert(
+                        "architecture_type".to_string(),
+                        "code (anthropic import)".to_string(),
+                    );
                 }
-                if content.contains("openai") {
-                    if results.architecture_type.is_none() {
-                        results.architecture_type = Some(ArchitectureType::ToolUsing);
-                        results.detection_sources.insert(
-                            "architecture_type".to_string(),
-                            "code (openai import)".to_string(),
-                        );
-                    }
+                if content.contains("openai") && results.architecture_type.is_none() {
+                    results.architecture_type = Some(ArchitectureType::ToolUsing);
+                    results.detection_sources.insert(
+                        "architecture_type".to_string(),
+                        "code (openai import)".to_string(),
+                    );
                 }
             }
         }
@@ -615,6 +908,10 @@ fn detect_from_npm_deps(deps: &serde_json::Value, results: &mut DetectionResults
             } else {
                 results.architecture_type = Some(ArchitectureType::ToolUsing);
             }
+            results.detection_sources.insert(
+                "architecture_type".to_string(),
+                "package.json (dependencies)".to_string(),
+            );
         }
 
         // Check for image processing libraries
@@ -645,7 +942,7 @@ fn detect_from_git(base_dir: &Path, results: &mut DetectionResults) {
     // Get remote URL
     if let Ok(output) = Command::new("git")
         .current_dir(base_dir)
-        .args(&["remote", "get-url", "origin"])
+        .args(["remote", "get-url", "origin"])
         .output()
     {
         if output.status.success() {
@@ -683,7 +980,7 @@ fn detect_from_git(base_dir: &Path, results: &mut DetectionResults) {
     if results.first_release_date.is_none() {
         if let Ok(output) = Command::new("git")
             .current_dir(base_dir)
-            .args(&["log", "--reverse", "--format=%ad", "--date=short", "-1"])
+            .args(["log", "--reverse", "--format=%ad", "--date=short", "-1"])
             .output()
         {
             if output.status.success() {
@@ -756,15 +1053,27 @@ fn detect_from_readme(base_dir: &Path, results: &mut DetectionResults) {
                 let content_lower = content.to_lowercase();
                 if content_lower.contains("rag") || content_lower.contains("retrieval") {
                     results.architecture_type = Some(ArchitectureType::Rag);
+                    results
+                        .detection_sources
+                        .insert("architecture_type".to_string(), name.to_string());
                 }
                 if content_lower.contains("multi-agent") || content_lower.contains("multi agent") {
                     results.architecture_type = Some(ArchitectureType::MultiAgent);
+                    results
+                        .detection_sources
+                        .insert("architecture_type".to_string(), name.to_string());
                 }
                 if content_lower.contains("plugin") || content_lower.contains("extension") {
                     results.deployment_type = Some(DeploymentType::Plugin);
+                    results
+                        .detection_sources
+                        .insert("deployment_type".to_string(), name.to_string());
                 }
                 if content_lower.contains("serverless") || content_lower.contains("lambda") {
                     results.deployment_type = Some(DeploymentType::Serverless);
+                    results
+                        .detection_sources
+                        .insert("deployment_type".to_string(), name.to_string());
                 }
             }
             break;
@@ -794,6 +1103,10 @@ fn detect_architecture_patterns(base_dir: &Path, results: &mut DetectionResults)
                         || content.contains("tools:")
                     {
                         results.architecture_type = Some(ArchitectureType::ToolUsing);
+                        results.detection_sources.insert(
+                            "architecture_type".to_string(),
+                            "code (tool call patterns)".to_string(),
+                        );
                     }
 
                     // Check for RAG patterns
@@ -802,18 +1115,22 @@ fn detect_architecture_patterns(base_dir: &Path, results: &mut DetectionResults)
                         || content.contains("similarity_search")
                     {
                         results.architecture_type = Some(ArchitectureType::Rag);
+                        results.detection_sources.insert(
+                            "architecture_type".to_string(),
+                            "code (vector store patterns)".to_string(),
+                        );
                     }
 
                     // Check for data categories
-                    if content.contains("email") || content.contains("phone") {
-                        if !results.data_categories.contains(&DataCategory::Pii) {
-                            results.data_categories.push(DataCategory::Pii);
-                        }
+                    if (content.contains("email") || content.contains("phone"))
+                        && !results.data_categories.contains(&DataCategory::Pii)
+                    {
+                        results.data_categories.push(DataCategory::Pii);
                     }
-                    if content.contains("credit_card") || content.contains("payment") {
-                        if !results.data_categories.contains(&DataCategory::Financial) {
-                            results.data_categories.push(DataCategory::Financial);
-                        }
+                    if (content.contains("credit_card") || content.contains("payment"))
+                        && !results.data_categories.contains(&DataCategory::Financial)
+                    {
+                        results.data_categories.push(DataCategory::Financial);
                     }
                 }
             }
@@ -821,51 +1138,75 @@ fn detect_architecture_patterns(base_dir: &Path, results: &mut DetectionResults)
     }
 }
 
-/// Detect deployment type
-fn detect_deployment_type(base_dir: &Path, results: &mut DetectionResults) {
-    // Already detected? Skip
-    if results.deployment_type.is_some() {
-        return;
-    }
-
-    // Check for serverless configs
-    if base_dir.join("serverless.yml").exists()
-        || base_dir.join("serverless.yaml").exists()
-        || base_dir.join("serverless.json").exists()
-    {
-        results.deployment_type = Some(DeploymentType::Serverless);
+/// Detect data categories from the field names of OpenAPI and GraphQL
+/// schemas, adding to the ones the source heuristic found
+fn detect_from_api_schemas(base_dir: &Path, results: &mut DetectionResults) {
+    let findings = detect_schema_categories(base_dir);
+    if findings.is_empty() {
         return;
     }
 
-    // Check for plugin/extension manifests
-    if base_dir.join("plugin.json").exists()
-        || base_dir.join("extension.json").exists()
-        || base_dir.join("manifest.json").exists()
-    {
-        results.deployment_type = Some(DeploymentType::Plugin);
-        return;
+    let mut sources: Vec<(String, Vec<&str>)> = Vec::new();
+    for finding in &findings {
+        if !results.data_categories.contains(&finding.category) {
+            results.data_categories.push(finding.category.clone());
+        }
+        let file = finding.source.display().to_string();
+        match sources.iter_mut().find(|(source, _)| *source == file) {
+            Some((_, fields)) => fields.push(&finding.field),
+            None => sources.push((file, vec![&finding.field])),
+        }
     }
+    let sources: Vec<String> = sources
+        .iter()
+        .map(|(file, fields)| format!("{}: {}", file, fields.join(", ")))
+        .collect();
+    results
+        .detection_sources
+        .insert("data_categories".to_string(), sources.join("; "));
+}
 
-    // Check for monorepo indicators
-    if base_dir.join("lerna.json").exists()
-        || base_dir.join("nx.json").exists()
-        || base_dir.join("pnpm-workspace.yaml").exists()
-    {
-        results.deployment_type = Some(DeploymentType::Monorepo);
+/// Detect deployment type
+fn detect_deployment_type(base_dir: &Path, results: &mut DetectionResults) {
+    // Already detected? Skip
+    if results.deployment_type.is_some() {
         return;
     }
 
-    // Check if we're in a subdirectory of a monorepo
-    if let Some(parent) = base_dir.parent() {
-        if parent.join("package.json").exists() || parent.join("Cargo.toml").exists() {
+    let found = |names: &[&str]| {
+        names
+            .iter()
+            .find(|name| base_dir.join(name).exists())
+            .map(|name| name.to_string())
+    };
+
+    let (deployment_type, source) =
+        if let Some(config) = found(&["serverless.yml", "serverless.yaml", "serverless.json"]) {
+            // Serverless configs
+            (DeploymentType::Serverless, config)
+        } else if let Some(manifest) = found(&["plugin.json", "extension.json", "manifest.json"]) {
+            // Plugin/extension manifests
+            (DeploymentType::Plugin, manifest)
+        } else if let Some(workspace) = found(&["lerna.json", "nx.json", "pnpm-workspace.yaml"]) {
+            // Monorepo indicators
+            (DeploymentType::Monorepo, workspace)
+        } else if base_dir.parent().is_some_and(|parent| {
+            parent.join("package.json").exists() || parent.join("Cargo.toml").exists()
+        }) {
             // We might be in a monorepo subdirectory
-            results.deployment_type = Some(DeploymentType::Monorepo);
-            return;
-        }
-    }
-
-    // Default to standalone
-    results.deployment_type = Some(DeploymentType::Standalone);
+            (
+                DeploymentType::Monorepo,
+                "project manifest in parent directory".to_string(),
+            )
+        } else {
+            // Default to standalone
+            (DeploymentType::Standalone, DEFAULT_SOURCE.to_string())
+        };
+
+    results.deployment_type = Some(deployment_type);
+    results
+        .detection_sources
+        .insert("deployment_type".to_string(), source);
 }
 
 /// Detect language support
@@ -908,16 +1249,15 @@ fn detect_modalities(base_dir: &Path, results: &mut DetectionResults) {
     // Look for code files → code modality
     if glob::glob(
         &base_dir
-            .join("**/*.{ts,js,py,rs,go,java}")
+            .join("**/*.{ts,js,py,rs,go,java,rb,php}")
             .to_string_lossy(),
     )
     .ok()
     .and_then(|paths| paths.flatten().next())
     .is_some()
+        && !results.modality_support.contains(&Modality::Code)
     {
-        if !results.modality_support.contains(&Modality::Code) {
-            results.modality_support.push(Modality::Code);
-        }
+        results.modality_support.push(Modality::Code);
     }
 
     // Look for structured data files
@@ -929,10 +1269,9 @@ fn detect_modalities(base_dir: &Path, results: &mut DetectionResults) {
     .ok()
     .and_then(|paths| paths.flatten().next())
     .is_some()
+        && !results.modality_support.contains(&Modality::StructuredData)
     {
-        if !results.modality_support.contains(&Modality::StructuredData) {
-            results.modality_support.push(Modality::StructuredData);
-        }
+        results.modality_support.push(Modality::StructuredData);
     }
 }
 
@@ -942,11 +1281,198 @@ pub fn infer_status_from_version(version: &str) -> AgentStatus {
 
     if version_lower.contains("alpha") || version_lower.starts_with("0.0") {
         AgentStatus::Alpha
-    } else if version_lower.contains("beta") || version_lower.contains("rc") {
-        AgentStatus::Beta
-    } else if version_lower.starts_with("0.") {
+    } else if version_lower.contains("beta")
+        || version_lower.contains("rc")
+        || version_lower.starts_with("0.")
+    {
         AgentStatus::Beta
     } else {
         AgentStatus::Production
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::config::BelticConfig;
+
+    #[test]
+    fn test_overrides_replace_detection_and_record_source() {
+        let config: BelticConfig = serde_yaml::from_str(
+            r#"
+version: "1.0"
+agent:
+  paths:
+    include: ["src/**"]
+overrides:
+  architecture_type: rag
+  model_family: "GPT-4o"
+  modalities: [text, code]
+"#,
+        )
+        .unwrap();
+
+        let mut results = DetectionResults {
+            architecture_type: Some(ArchitectureType::ToolUsing),
+            deployment_type: Some(DeploymentType::Standalone),
+            ..Default::default()
+        };
+        results.detection_sources.insert(
+            "architecture_type".to_string(),
+            "code (langchain import)".to_string(),
+        );
+
+        results.apply_overrides(config.overrides.as_ref().unwrap());
+
+        assert!(matches!(
+            results.architecture_type,
+            Some(ArchitectureType::Rag)
+        ));
+        assert!(matches!(
+            results.deployment_type,
+            Some(DeploymentType::Standalone)
+        ));
+        assert_eq!(results.model_family.as_deref(), Some("GPT-4o"));
+        assert_eq!(results.model_provider, None);
+        assert_eq!(
+            results.modality_support,
+            vec![Modality::Text, Modality::Code]
+        );
+
+        assert_eq!(
+            results.detection_sources["architecture_type"],
+            OVERRIDE_SOURCE
+        );
+        assert_eq!(results.detection_sources["model_family"], OVERRIDE_SOURCE);
+        assert!(!results.detection_sources.contains_key("deployment_type"));
+    }
+
+    #[test]
+    fn test_detects_ruby_gem() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("support_agent.gemspec"),
+            r#"Gem::Specification.new do |spec|
+  spec.name        = "support_agent"
+  spec.version     = "0.3.1"
+  spec.summary     = "Answers support tickets"
+  spec.add_dependency "ruby-openai", "~> 7.0"
+end
+"#,
+        )
+        .unwrap();
+        fs::write(dir.path().join("package.json"), r#"{"name": "assets"}"#).unwrap();
+
+        let results = detect_project_info(dir.path()).unwrap();
+        assert_eq!(results.project_name.as_deref(), Some("support_agent"));
+        assert_eq!(results.project_version.as_deref(), Some("0.3.1"));
+        assert_eq!(results.primary_language.as_deref(), Some("Ruby"));
+        assert!(matches!(
+            results.architecture_type,
+            Some(ArchitectureType::ToolUsing)
+        ));
+        assert_eq!(
+            results.detection_sources["project_name"],
+            "support_agent.gemspec"
+        );
+        assert_eq!(
+            results.detection_sources["architecture_type"],
+            "support_agent.gemspec (AI SDK)"
+        );
+    }
+
+    #[test]
+    fn test_detects_laravel_without_clobbering_cargo() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("composer.json"),
+            r#"{
+  "name": "acme/helpdesk",
+  "description": "Laravel helpdesk agent",
+  "require": {"php": "^8.2", "openai-php/laravel": "^0.10"}
+}"#,
+        )
+        .unwrap();
+
+        let results = detect_project_info(dir.path()).unwrap();
+        assert_eq!(results.project_name.as_deref(), Some("helpdesk"));
+        assert_eq!(results.primary_language.as_deref(), Some("PHP"));
+        assert!(matches!(
+            results.architecture_type,
+            Some(ArchitectureType::ToolUsing)
+        ));
+
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"core\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+        let results = detect_project_info(dir.path()).unwrap();
+        assert_eq!(results.project_name.as_deref(), Some("core"));
+        assert_eq!(results.primary_language.as_deref(), Some("Rust"));
+        assert_eq!(
+            results.detection_sources["project_description"],
+            "composer.json"
+        );
+    }
+
+    #[test]
+    fn test_agent_in_repo_uses_root_git_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .current_dir(dir.path())
+                .args(args)
+                .output()
+                .unwrap()
+        };
+        git(&["init", "-q"]);
+        git(&[
+            "remote",
+            "add",
+            "origin",
+            "https://github.com/acme/agents.git",
+        ]);
+        let agent_dir = dir.path().join("packages/support");
+        fs::create_dir_all(&agent_dir).unwrap();
+        fs::write(
+            agent_dir.join("package.json"),
+            r#"{"name": "support-agent", "version": "1.2.0"}"#,
+        )
+        .unwrap();
+
+        let results = detect_agent_in_repo(dir.path(), "packages/support").unwrap();
+        assert_eq!(results.project_name.as_deref(), Some("support-agent"));
+        assert_eq!(
+            results.git_remote.as_deref(),
+            Some("https://github.com/acme/agents.git")
+        );
+        let structure = results
+            .deployment_context
+            .and_then(|context| context.repository_structure)
+            .unwrap();
+        assert_eq!(structure.root, "https://github.com/acme/agents.git");
+        assert_eq!(structure.agent_path, "packages/support");
+    }
+
+    #[test]
+    fn test_api_schemas_add_to_source_data_categories() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("notify.py"), "send(user.email)\n").unwrap();
+        fs::write(
+            dir.path().join("schema.graphql"),
+            "type Payout {\n  iban: String!\n  holderEmail: String\n}\n",
+        )
+        .unwrap();
+
+        let results = detect_project_info(dir.path()).unwrap();
+        assert_eq!(
+            results.data_categories,
+            vec![DataCategory::Pii, DataCategory::Financial]
+        );
+        assert_eq!(
+            results.detection_sources["data_categories"],
+            "schema.graphql: iban, holderEmail"
+        );
+    }
+}
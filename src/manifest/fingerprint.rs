@@ -1,18 +1,27 @@
 use anyhow::{Context, Result};
 use chrono::Utc;
-use glob::glob;
-use globset::{Glob, GlobSetBuilder};
+use globset::{Glob, GlobBuilder, GlobSetBuilder};
 use ignore::WalkBuilder;
+use indicatif::{ProgressBar, ProgressStyle};
+use log::{debug, trace};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
 use std::fs;
 use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
 
+use crate::error::BelticError;
 use crate::manifest::config::PathConfig;
+use crate::manifest::dependencies::declared_dependencies;
 use crate::manifest::schema::{
     ExternalDep, FingerprintMetadata, FingerprintScope, InternalDep, PathConfiguration,
 };
+use crate::output::is_quiet;
 
 /// Result of fingerprinting operation
 #[derive(Debug)]
@@ -24,14 +33,80 @@ pub struct FingerprintResult {
     pub files_hashed: Vec<PathBuf>,
 }
 
+/// Per-file hash cache, relative to the fingerprint root. Never fingerprinted itself.
+pub const HASH_CACHE_PATH: &str = ".beltic/fingerprint-cache.json";
+
+/// Default read buffer for hashing a file
+pub const DEFAULT_HASH_BUFFER_SIZE: usize = 8192;
+
+/// Smallest file memory-mapped when mapping is turned on; below this the
+/// mapping costs more than it saves
+pub const DEFAULT_MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+/// Descriptor of the combination scheme, recorded in the metadata so that a
+/// release which changes it is detectable. Change it whenever
+/// [`fingerprint_files`] changes how the combined hash is computed.
+pub const NORMALIZATION: &str = concat!(
+    "v2: forward-slash relative paths (raw bytes if not UTF-8), sorted bytewise; ",
+    "sha256 over path+':'+sha256hex+'\\n' per file"
+);
+
+/// The exact recipe behind [`NORMALIZATION`], as printed by `fingerprint --explain`
+pub const COMBINATION_RECIPE: &str = "\
+1. Collect the in-scope files (include/exclude globs, .gitignore, optional binary skip).
+2. Take each file's path relative to the fingerprint root and replace '\\' with '/'.
+   On Unix, a name that is not valid UTF-8 is used as its raw bytes.
+3. Hash each file's contents with SHA-256, as lowercase hex.
+4. Sort the entries by path, comparing bytes.
+5. Feed '<path>:<hex hash>\\n' for every entry, in order, into one SHA-256.
+6. The fingerprint is 'sha256:' followed by that digest in lowercase hex.";
+
+/// Tool and version recorded as `generatedBy` in the fingerprint metadata
+pub fn generated_by() -> String {
+    format!("beltic {}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Progress callback, called with (files hashed, total files)
+pub type ProgressCallback = Arc<dyn Fn(usize, usize) + Send + Sync>;
+
 /// Options for fingerprinting
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct FingerprintOptions {
     pub include_patterns: Vec<String>,
     pub exclude_patterns: Vec<String>,
     pub root_path: PathBuf,
     pub include_dependencies: bool,
     pub respect_gitignore: bool,
+    /// Called after each file is hashed; `None` for no reporting
+    pub progress: Option<ProgressCallback>,
+    /// Reuse per-file hashes from `.beltic/` for files whose size and mtime
+    /// are unchanged. Off by default; the combined hash is the same either way.
+    pub use_cache: bool,
+    /// Skip files that look binary (NUL byte in the first few KB)
+    pub exclude_binary: bool,
+    /// Read buffer for hashing each file, in bytes
+    pub buffer_size: usize,
+    /// Memory-map files of at least this many bytes instead of reading them
+    /// (with the `mmap` feature); `None` to always read. Hashes are the same
+    /// either way.
+    pub mmap_threshold: Option<u64>,
+}
+
+impl std::fmt::Debug for FingerprintOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FingerprintOptions")
+            .field("include_patterns", &self.include_patterns)
+            .field("exclude_patterns", &self.exclude_patterns)
+            .field("root_path", &self.root_path)
+            .field("include_dependencies", &self.include_dependencies)
+            .field("respect_gitignore", &self.respect_gitignore)
+            .field("progress", &self.progress.is_some())
+            .field("use_cache", &self.use_cache)
+            .field("exclude_binary", &self.exclude_binary)
+            .field("buffer_size", &self.buffer_size)
+            .field("mmap_threshold", &self.mmap_threshold)
+            .finish()
+    }
 }
 
 impl Default for FingerprintOptions {
@@ -50,6 +125,11 @@ impl Default for FingerprintOptions {
             root_path: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
             include_dependencies: false,
             respect_gitignore: true,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
         }
     }
 }
@@ -63,45 +143,173 @@ impl FingerprintOptions {
             root_path: root,
             include_dependencies: false,
             respect_gitignore: true,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
         }
     }
+
+    /// Report progress to a terminal progress bar when stderr is a TTY
+    pub fn with_terminal_progress(mut self) -> Self {
+        self.progress = terminal_progress();
+        self
+    }
+}
+
+/// A progress callback that drives an `indicatif` bar on stderr.
+///
+/// Returns `None` when stderr is not a terminal so piped output stays clean,
+/// and with `--quiet`.
+pub fn terminal_progress() -> Option<ProgressCallback> {
+    if is_quiet() || !console::Term::stderr().is_term() {
+        return None;
+    }
+
+    let bar = ProgressBar::new(0);
+    bar.set_style(
+        ProgressStyle::with_template("  {bar:30.cyan/blue} {pos}/{len} files hashed")
+            .unwrap_or_else(|_| ProgressStyle::default_bar()),
+    );
+
+    Some(Arc::new(move |done, total| {
+        bar.set_length(total as u64);
+        bar.set_position(done as u64);
+        if done >= total {
+            bar.finish_and_clear();
+        }
+    }))
 }
 
 /// Generate a SHA256 fingerprint of the codebase
-pub fn generate_fingerprint(options: &FingerprintOptions) -> Result<FingerprintResult> {
+///
+/// ```
+/// use beltic::fingerprint::{generate_fingerprint, FingerprintOptions};
+///
+/// let dir = tempfile::tempdir()?;
+/// std::fs::write(dir.path().join("main.py"), "print('hello')\n")?;
+///
+/// let options = FingerprintOptions {
+///     include_patterns: vec!["**/*.py".to_string()],
+///     root_path: dir.path().to_path_buf(),
+///     ..Default::default()
+/// };
+/// let result = generate_fingerprint(&options)?;
+/// assert_eq!(result.file_count, 1);
+/// assert!(result.hash.starts_with("sha256:"));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn generate_fingerprint(
+    options: &FingerprintOptions,
+) -> Result<FingerprintResult, BelticError> {
+    collect_files(options)
+        .and_then(|files| fingerprint_files(options, files))
+        .map_err(|err| BelticError::classify(err, BelticError::Validation))
+}
+
+/// Fingerprint an explicit list of files (e.g. a subset of [`matching_files`])
+/// with the same hashing, cache and metadata as [`generate_fingerprint`]
+pub fn fingerprint_files(
+    options: &FingerprintOptions,
+    files: Vec<PathBuf>,
+) -> Result<FingerprintResult> {
     let mut hasher = Sha256::new();
-    let mut file_hashes = BTreeMap::new(); // Use BTreeMap for deterministic ordering
+    // Normalized path -> (hash, file); BTreeMap for deterministic ordering
+    let mut file_hashes: BTreeMap<Vec<u8>, (String, PathBuf)> = BTreeMap::new();
     let mut total_size = 0u64;
     let mut files_hashed = Vec::new();
 
-    // Collect all files to hash
-    let files = collect_files(options)?;
+    let cache = if options.use_cache {
+        Some(HashCache::load(&options.root_path))
+    } else {
+        None
+    };
+    let mut used_entries = BTreeMap::new();
+    let mut skipped_binaries = Vec::new();
+
+    let total = files.len();
+    if let Some(progress) = &options.progress {
+        progress(0, total);
+    }
 
     // Hash each file
-    for file_path in files {
+    for (index, file_path) in files.into_iter().enumerate() {
         if file_path.is_file() {
             let relative_path = file_path
                 .strip_prefix(&options.root_path)
-                .unwrap_or(&file_path)
-                .to_string_lossy()
-                .to_string();
+                .unwrap_or(&file_path);
+            let normalized = NormalizedPath::new(relative_path);
+            let normalized_path = normalized.display();
+
+            // Two files with one key would leave the hash depending on
+            // which was seen last (e.g. `a\b` and `a/b` on Unix)
+            if let Some((_, first)) = file_hashes.get(&normalized.bytes) {
+                return Err(BelticError::Validation(format!(
+                    "{} and {} both normalize to `{}`; rename one or exclude it from the fingerprint",
+                    first.display(),
+                    file_path.display(),
+                    normalized_path
+                ))
+                .into());
+            }
 
-            // Normalize path separators for cross-platform consistency
-            // Always use forward slashes, regardless of OS
-            let normalized_path = relative_path.replace('\\', "/");
+            if options.exclude_binary && is_binary_file(&file_path)? {
+                debug!("skipping {}: binary file", normalized_path);
+                skipped_binaries.push(normalized_path);
+                if let Some(progress) = &options.progress {
+                    progress(index + 1, total);
+                }
+                continue;
+            }
 
-            let file_hash = hash_file(&file_path)?;
-            let file_size = fs::metadata(&file_path)?.len();
+            let file_metadata = fs::metadata(&file_path)?;
+            let file_size = file_metadata.len();
+            let file_hash = match &cache {
+                // Lossy names could share a cache key, so only UTF-8 paths are cached
+                Some(cache) if normalized.is_utf8() => {
+                    let entry = cache.lookup_or_hash(
+                        &normalized_path,
+                        &file_path,
+                        &file_metadata,
+                        options,
+                    )?;
+                    let hash = entry.hash.clone();
+                    used_entries.insert(normalized_path.clone(), entry);
+                    hash
+                }
+                _ => hash_file(&file_path, options)?,
+            };
+            trace!("hashed {} ({} bytes)", normalized_path, file_size);
 
-            file_hashes.insert(normalized_path, file_hash);
+            file_hashes.insert(normalized.bytes, (file_hash, file_path.clone()));
             total_size += file_size;
             files_hashed.push(file_path);
         }
+
+        if let Some(progress) = &options.progress {
+            progress(index + 1, total);
+        }
+    }
+
+    debug!(
+        "hashed {} file(s), {} bytes; skipped {} binary file(s)",
+        file_hashes.len(),
+        total_size,
+        skipped_binaries.len()
+    );
+
+    // Keep only entries for files seen in this run
+    if cache.is_some() {
+        HashCache {
+            entries: used_entries,
+        }
+        .save(&options.root_path)?;
     }
 
     // Create deterministic combined hash
-    for (path, hash) in &file_hashes {
-        hasher.update(path.as_bytes());
+    for (path, (hash, _)) in &file_hashes {
+        hasher.update(path);
         hasher.update(b":");
         hasher.update(hash.as_bytes());
         hasher.update(b"\n");
@@ -126,7 +334,10 @@ pub fn generate_fingerprint(options: &FingerprintOptions) -> Result<FingerprintR
             },
             files_processed: file_hashes.len(),
             total_size,
+            skipped_binaries,
         },
+        generated_by: Some(generated_by()),
+        normalization: Some(NORMALIZATION.to_string()),
         dependencies: None, // Will be populated if include_dependencies is true
     };
 
@@ -139,6 +350,184 @@ pub fn generate_fingerprint(options: &FingerprintOptions) -> Result<FingerprintR
     })
 }
 
+/// A root-relative path as it enters the combined hash.
+///
+/// UTF-8 paths use `/` as separator on every OS. A name that is not valid
+/// UTF-8 keeps its raw bytes on Unix (and its unpaired surrogates, escaped,
+/// on Windows) rather than a lossy string, so two such names never collapse
+/// into the same entry.
+struct NormalizedPath {
+    bytes: Vec<u8>,
+    utf8: bool,
+}
+
+impl NormalizedPath {
+    fn new(relative: &Path) -> Self {
+        if let Some(path) = relative.to_str() {
+            return Self {
+                bytes: path.replace('\\', "/").into_bytes(),
+                utf8: true,
+            };
+        }
+
+        #[cfg(unix)]
+        let bytes = {
+            use std::os::unix::ffi::OsStrExt;
+            relative.as_os_str().as_bytes().to_vec()
+        };
+        #[cfg(windows)]
+        let bytes = {
+            use std::os::windows::ffi::OsStrExt;
+            // `\` is a separator here, so an escape can't be confused with a real name
+            char::decode_utf16(relative.as_os_str().encode_wide())
+                .map(|unit| match unit {
+                    Ok('\\') => "/".to_string(),
+                    Ok(c) => c.to_string(),
+                    Err(e) => format!("\\u{{{:x}}}", e.unpaired_surrogate()),
+                })
+                .collect::<String>()
+                .into_bytes()
+        };
+        #[cfg(not(any(unix, windows)))]
+        let bytes = relative.to_string_lossy().replace('\\', "/").into_bytes();
+
+        Self { bytes, utf8: false }
+    }
+
+    fn is_utf8(&self) -> bool {
+        self.utf8
+    }
+
+    /// The path for logs and metadata; lossy for non-UTF-8 names
+    fn display(&self) -> String {
+        String::from_utf8_lossy(&self.bytes).into_owned()
+    }
+}
+
+/// Files the fingerprint would hash, without hashing them. Binary files are
+/// dropped when `exclude_binary` is set, which reads their first few KB.
+pub fn matching_files(options: &FingerprintOptions) -> Result<Vec<PathBuf>> {
+    let mut files = collect_files(options)?;
+    if options.exclude_binary {
+        let mut text_files = Vec::with_capacity(files.len());
+        for file in files {
+            if !is_binary_file(&file)? {
+                text_files.push(file);
+            }
+        }
+        files = text_files;
+    }
+    Ok(files)
+}
+
+/// Size and mtime of every in-scope file, to notice changes by polling
+fn scope_snapshot(options: &FingerprintOptions) -> Result<BTreeMap<PathBuf, (u64, SystemTime)>> {
+    let mut snapshot = BTreeMap::new();
+    for file in matching_files(options)? {
+        // A file deleted between listing and stat shows up in the next poll
+        if let Ok(metadata) = fs::metadata(&file) {
+            let mtime = metadata.modified().unwrap_or(UNIX_EPOCH);
+            snapshot.insert(file, (metadata.len(), mtime));
+        }
+    }
+    Ok(snapshot)
+}
+
+/// Fingerprint now and again whenever in-scope files change, until `stop`
+/// is set. The scope is polled every `interval`; a change is fingerprinted
+/// once the scope has been quiet for `debounce`, so a burst of saves gives
+/// one report.
+pub fn watch_fingerprint(
+    options: &FingerprintOptions,
+    interval: Duration,
+    debounce: Duration,
+    stop: &AtomicBool,
+    mut report: impl FnMut(&FingerprintResult) -> Result<()>,
+) -> Result<()> {
+    let mut snapshot = scope_snapshot(options)?;
+    report(&fingerprint_files(
+        options,
+        snapshot.keys().cloned().collect(),
+    )?)?;
+
+    let mut changed_at: Option<Instant> = None;
+    while !stop.load(Ordering::SeqCst) {
+        std::thread::sleep(interval);
+        let current = scope_snapshot(options)?;
+        if current != snapshot {
+            trace!("scope changed; waiting for it to settle");
+            snapshot = current;
+            changed_at = Some(Instant::now());
+            continue;
+        }
+        if changed_at.is_some_and(|at| at.elapsed() >= debounce) {
+            changed_at = None;
+            report(&fingerprint_files(
+                options,
+                snapshot.keys().cloned().collect(),
+            )?)?;
+        }
+    }
+    Ok(())
+}
+
+/// Paths that differ from `git_ref` (committed, staged, unstaged and
+/// untracked), relative to `root`. Deleted files are included.
+pub fn changed_files_since(root: &Path, git_ref: &str) -> Result<Vec<String>> {
+    if !root.ancestors().any(|dir| dir.join(".git").exists()) {
+        anyhow::bail!("{} is not inside a git repository", root.display());
+    }
+
+    let git = |args: &[&str]| -> Result<Vec<String>> {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .output()
+            .context("Failed to run git")?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(str::to_string)
+            .collect())
+    };
+
+    let mut changed = git(&["diff", "--name-only", "-z", "--relative", git_ref, "--"])?;
+    changed.extend(git(&["ls-files", "--others", "--exclude-standard", "-z"])?);
+    changed.sort();
+    changed.dedup();
+    Ok(changed)
+}
+
+/// Whether a root-relative path falls under the include/exclude patterns,
+/// without touching the filesystem (so it also works for deleted files)
+pub fn path_in_scope(options: &FingerprintOptions, relative: &str) -> Result<bool> {
+    let exclude_set = build_globset(&options.exclude_patterns)?;
+    if relative == HASH_CACHE_PATH || exclude_set.is_match(relative) {
+        return Ok(false);
+    }
+
+    for pattern in &options.include_patterns {
+        if pattern.contains('*') || pattern.contains('?') || pattern.contains('[') {
+            if Glob::new(pattern)?.compile_matcher().is_match(relative) {
+                return Ok(true);
+            }
+        } else {
+            let pattern = pattern.trim_end_matches('/');
+            if relative == pattern || relative.starts_with(&format!("{}/", pattern)) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
 /// Collect files based on include/exclude patterns
 fn collect_files(options: &FingerprintOptions) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
@@ -150,18 +539,18 @@ fn collect_files(options: &FingerprintOptions) -> Result<Vec<PathBuf>> {
 
     // Process each include pattern
     for pattern in &options.include_patterns {
-        let full_pattern = options.root_path.join(pattern);
-        let pattern_str = full_pattern.to_string_lossy();
-
-        // Use glob for pattern matching
-        if pattern.contains('*') || pattern.contains('?') || pattern.contains('[') {
-            for entry in glob(&pattern_str).context(format!("Invalid glob pattern: {}", pattern))? {
-                if let Ok(path) = entry {
-                    if should_include_file(&path, &options.root_path, &exclude_set)? {
-                        if seen.insert(path.clone()) {
-                            files.push(path);
-                        }
-                    }
+        let matched_before = files.len();
+
+        if is_glob_pattern(pattern) {
+            for path in glob_files(&options.root_path, pattern)? {
+                if should_include_file(
+                    &path,
+                    &options.root_path,
+                    &exclude_set,
+                    &options.exclude_patterns,
+                )? && seen.insert(path.clone())
+                {
+                    files.push(path);
                 }
             }
         } else {
@@ -169,10 +558,14 @@ fn collect_files(options: &FingerprintOptions) -> Result<Vec<PathBuf>> {
             let path = options.root_path.join(pattern);
             if path.exists() {
                 if path.is_file() {
-                    if should_include_file(&path, &options.root_path, &exclude_set)? {
-                        if seen.insert(path.clone()) {
-                            files.push(path);
-                        }
+                    if should_include_file(
+                        &path,
+                        &options.root_path,
+                        &exclude_set,
+                        &options.exclude_patterns,
+                    )? && seen.insert(path.clone())
+                    {
+                        files.push(path);
                     }
                 } else if path.is_dir() {
                     // Walk directory
@@ -192,25 +585,29 @@ fn collect_files(options: &FingerprintOptions) -> Result<Vec<PathBuf>> {
                             .build()
                     };
 
-                    for entry in walker {
-                        if let Ok(entry) = entry {
-                            let entry_path = entry.path().to_path_buf();
-                            if entry_path.is_file() {
-                                if should_include_file(
-                                    &entry_path,
-                                    &options.root_path,
-                                    &exclude_set,
-                                )? {
-                                    if seen.insert(entry_path.clone()) {
-                                        files.push(entry_path);
-                                    }
-                                }
-                            }
+                    for entry in walker.flatten() {
+                        let entry_path = entry.path().to_path_buf();
+                        if entry_path.is_file()
+                            && should_include_file(
+                                &entry_path,
+                                &options.root_path,
+                                &exclude_set,
+                                &options.exclude_patterns,
+                            )?
+                            && seen.insert(entry_path.clone())
+                        {
+                            files.push(entry_path);
                         }
                     }
                 }
             }
         }
+
+        debug!(
+            "include pattern `{}` matched {} new file(s)",
+            pattern,
+            files.len() - matched_before
+        );
     }
 
     // Sort files for deterministic output
@@ -219,6 +616,62 @@ fn collect_files(options: &FingerprintOptions) -> Result<Vec<PathBuf>> {
     Ok(files)
 }
 
+/// Characters that make a path pattern a glob
+const GLOB_META: &[char] = &['*', '?', '[', '{'];
+
+fn is_glob_pattern(pattern: &str) -> bool {
+    pattern.contains(GLOB_META)
+}
+
+/// Files under `root` matching a glob `pattern` relative to it, using the
+/// same `globset` syntax as exclude patterns: `*`, `?`, `[abc]`, `**` and
+/// `{a,b}` alternatives. Unlike excludes, `*` and `?` don't match `/`, so
+/// `src/*.rs` only matches files directly in `src`.
+///
+/// Only the part of the tree below the pattern's literal prefix (`src` in
+/// `src/**/*.rs`) is walked. Symlinks are not followed.
+pub(crate) fn glob_files(root: &Path, pattern: &str) -> Result<Vec<PathBuf>> {
+    let matcher = GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .context(format!("Invalid glob pattern: {}", pattern))?
+        .compile_matcher();
+
+    let prefix: Vec<&str> = pattern
+        .split('/')
+        .take_while(|component| !is_glob_pattern(component))
+        .collect();
+    let base = prefix
+        .iter()
+        .fold(root.to_path_buf(), |path, c| path.join(c));
+    if !base.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in WalkDir::new(&base)
+        .follow_links(false)
+        .into_iter()
+        .flatten()
+    {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(&base).unwrap_or(entry.path());
+        let mut candidate = prefix.join("/");
+        for component in relative.components() {
+            if !candidate.is_empty() {
+                candidate.push('/');
+            }
+            candidate.push_str(&component.as_os_str().to_string_lossy());
+        }
+        if matcher.is_match(&candidate) {
+            files.push(entry.into_path());
+        }
+    }
+    Ok(files)
+}
+
 /// Build a GlobSet from patterns for efficient matching
 fn build_globset(patterns: &[String]) -> Result<globset::GlobSet> {
     let mut builder = GlobSetBuilder::new();
@@ -233,7 +686,12 @@ fn build_globset(patterns: &[String]) -> Result<globset::GlobSet> {
 }
 
 /// Check if a file should be included based on exclude patterns
-fn should_include_file(path: &Path, root: &Path, exclude_set: &globset::GlobSet) -> Result<bool> {
+fn should_include_file(
+    path: &Path,
+    root: &Path,
+    exclude_set: &globset::GlobSet,
+    exclude_patterns: &[String],
+) -> Result<bool> {
     // Get relative path from root
     let relative_path = path.strip_prefix(root).unwrap_or(path).to_string_lossy();
 
@@ -241,20 +699,56 @@ fn should_include_file(path: &Path, root: &Path, exclude_set: &globset::GlobSet)
     let normalized_path = relative_path.replace('\\', "/");
 
     // Check if path matches any exclude pattern
-    if exclude_set.is_match(&normalized_path) {
+    if normalized_path == HASH_CACHE_PATH {
+        trace!("skipping {}: hash cache", normalized_path);
+        return Ok(false);
+    }
+    if let Some(&index) = exclude_set.matches(&normalized_path).first() {
+        trace!(
+            "skipping {}: matches exclude pattern `{}`",
+            normalized_path,
+            exclude_patterns[index]
+        );
         return Ok(false);
     }
 
     Ok(true)
 }
 
-/// Hash a single file
-fn hash_file(path: &Path) -> Result<String> {
+/// Bytes sniffed for a NUL byte when deciding if a file is binary
+const BINARY_SNIFF_LEN: usize = 8192;
+
+/// Whether a file looks binary: a NUL byte in its first few KB
+fn is_binary_file(path: &Path) -> Result<bool> {
+    let file = fs::File::open(path).context(format!("Failed to open file: {}", path.display()))?;
+
+    let mut buffer = Vec::with_capacity(BINARY_SNIFF_LEN);
+    file.take(BINARY_SNIFF_LEN as u64)
+        .read_to_end(&mut buffer)?;
+
+    Ok(buffer.contains(&0))
+}
+
+/// Hash a single file, memory-mapping it if it is at least
+/// `options.mmap_threshold` bytes and reading it in `options.buffer_size`
+/// chunks otherwise (or if the mapping fails)
+fn hash_file(path: &Path, options: &FingerprintOptions) -> Result<String> {
     let mut file =
         fs::File::open(path).context(format!("Failed to open file: {}", path.display()))?;
 
+    if let Some(threshold) = options.mmap_threshold {
+        let len = file.metadata()?.len();
+        // Empty files can't be mapped
+        if len > 0 && len >= threshold {
+            match hash_mapped(&file) {
+                Some(hash) => return Ok(hash),
+                None => debug!("mmap failed for {}, reading it instead", path.display()),
+            }
+        }
+    }
+
     let mut hasher = Sha256::new();
-    let mut buffer = [0; 8192];
+    let mut buffer = vec![0; options.buffer_size.max(1)];
 
     loop {
         let bytes_read = file.read(&mut buffer)?;
@@ -267,6 +761,91 @@ fn hash_file(path: &Path) -> Result<String> {
     Ok(format!("{:x}", hasher.finalize()))
 }
 
+/// Hash a file through a read-only mapping; `None` if it can't be mapped
+#[cfg(feature = "mmap")]
+fn hash_mapped(file: &fs::File) -> Option<String> {
+    // SAFETY: the mapping is only read while it is hashed. As with any mmap
+    // reader, a file truncated meanwhile can fault, which is why mapping is
+    // opt-in.
+    let mapped = unsafe { memmap2::Mmap::map(file) }.ok()?;
+    #[cfg(unix)]
+    let _ = mapped.advise(memmap2::Advice::Sequential);
+    Some(format!("{:x}", Sha256::digest(&mapped[..])))
+}
+
+#[cfg(not(feature = "mmap"))]
+fn hash_mapped(_file: &fs::File) -> Option<String> {
+    None
+}
+
+/// Cached hash of a file, valid while its size and mtime are unchanged
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CacheEntry {
+    size: u64,
+    mtime_secs: u64,
+    mtime_nanos: u32,
+    hash: String,
+}
+
+/// On-disk per-file hash cache, keyed by normalized relative path
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HashCache {
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+impl HashCache {
+    /// Load the cache, starting empty if it is missing or unreadable
+    fn load(root: &Path) -> Self {
+        fs::read_to_string(root.join(HASH_CACHE_PATH))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, root: &Path) -> Result<()> {
+        let path = root.join(HASH_CACHE_PATH);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::write(&path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write hash cache: {}", path.display()))
+    }
+
+    /// Reuse the cached hash if size and mtime match, otherwise re-hash
+    fn lookup_or_hash(
+        &self,
+        key: &str,
+        path: &Path,
+        metadata: &fs::Metadata,
+        options: &FingerprintOptions,
+    ) -> Result<CacheEntry> {
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default();
+
+        if let Some(entry) = self.entries.get(key) {
+            if entry.size == metadata.len()
+                && entry.mtime_secs == mtime.as_secs()
+                && entry.mtime_nanos == mtime.subsec_nanos()
+            {
+                trace!("cache hit for {}", key);
+                return Ok(entry.clone());
+            }
+            trace!("cache stale for {}: size or mtime changed", key);
+        }
+
+        Ok(CacheEntry {
+            size: metadata.len(),
+            mtime_secs: mtime.as_secs(),
+            mtime_nanos: mtime.subsec_nanos(),
+            hash: hash_file(path, options)?,
+        })
+    }
+}
+
 /// Generate fingerprint for internal dependencies
 pub fn fingerprint_internal_dependencies(
     deps: &[String],
@@ -293,15 +872,49 @@ pub fn fingerprint_internal_dependencies(
     Ok(results)
 }
 
-/// Parse and fingerprint external dependencies (placeholder for now)
+/// Fingerprint external dependencies: those declared in the package
+/// manifests in `base_dir` (see [`declared_dependencies`]) plus `deps`, the
+/// `name@version` entries from `.beltic.yaml` that are not declared there.
+/// Each hash covers the dependency specification, not the package contents.
 pub fn fingerprint_external_dependencies(
-    _deps: &[String],
-    _base_dir: &Path,
+    deps: &[String],
+    base_dir: &Path,
 ) -> Result<Vec<ExternalDep>> {
-    // This would parse package.json, Cargo.toml, requirements.txt, etc.
-    // and generate hashes of the dependency specifications
-    // For now, return empty vec
-    Ok(vec![])
+    let spec_hash = |name: &str, version: &str| {
+        format!(
+            "sha256:{:x}",
+            Sha256::digest(format!("{}@{}", name, version))
+        )
+    };
+
+    let mut results: Vec<ExternalDep> = declared_dependencies(base_dir)?
+        .into_iter()
+        .map(|dep| ExternalDep {
+            hash: spec_hash(&dep.name, &dep.version),
+            name: dep.name,
+            version: dep.version,
+            ecosystem: Some(dep.ecosystem),
+        })
+        .collect();
+
+    for entry in deps {
+        // The version follows the last '@', which may not be the scope's
+        let (name, version) = match entry.rsplit_once('@') {
+            Some((name, version)) if !name.is_empty() => (name, version),
+            _ => (entry.as_str(), "*"),
+        };
+        if results.iter().any(|dep| dep.name == name) {
+            continue;
+        }
+        results.push(ExternalDep {
+            name: name.to_string(),
+            version: version.to_string(),
+            hash: spec_hash(name, version),
+            ecosystem: None,
+        });
+    }
+
+    Ok(results)
 }
 
 /// Update an existing manifest's fingerprint
@@ -312,8 +925,7 @@ pub fn update_manifest_fingerprint(
     let fingerprint = generate_fingerprint(options)?;
 
     // Read existing manifest
-    let manifest_content = fs::read_to_string(manifest_path)?;
-    let mut manifest: serde_json::Value = serde_json::from_str(&manifest_content)?;
+    let mut manifest = crate::document::read_document(manifest_path, None)?;
 
     // Update fingerprint fields
     if let Some(obj) = manifest.as_object_mut() {
@@ -328,8 +940,7 @@ pub fn update_manifest_fingerprint(
     }
 
     // Write updated manifest
-    let updated = serde_json::to_string_pretty(&manifest)?;
-    fs::write(manifest_path, updated)?;
+    crate::document::write_document(manifest_path, &manifest, None)?;
 
     Ok(fingerprint.hash)
 }
@@ -345,7 +956,7 @@ mod tests {
         let file_path = dir.path().join("test.txt");
         fs::write(&file_path, "hello world").unwrap();
 
-        let hash = hash_file(&file_path).unwrap();
+        let hash = hash_file(&file_path, &FingerprintOptions::default()).unwrap();
         // SHA256 of "hello world"
         assert_eq!(
             hash,
@@ -353,6 +964,169 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hash_file_buffer_and_mmap_agree() {
+        let dir = tempdir().unwrap();
+        let big = dir.path().join("big.bin");
+        let content: Vec<u8> = (0..3 * 1024 * 1024 + 17).map(|i| (i % 251) as u8).collect();
+        fs::write(&big, &content).unwrap();
+        let small = dir.path().join("small.txt");
+        fs::write(&small, "hello world").unwrap();
+        let empty = dir.path().join("empty");
+        fs::write(&empty, "").unwrap();
+
+        let buffered = FingerprintOptions::default();
+        let modes = [
+            FingerprintOptions {
+                buffer_size: 1024 * 1024,
+                ..Default::default()
+            },
+            FingerprintOptions {
+                buffer_size: 7,
+                ..Default::default()
+            },
+            // Everything, even tiny files, goes through the mapping
+            FingerprintOptions {
+                mmap_threshold: Some(0),
+                ..Default::default()
+            },
+            FingerprintOptions {
+                mmap_threshold: Some(DEFAULT_MMAP_THRESHOLD),
+                ..Default::default()
+            },
+        ];
+        for path in [&big, &small, &empty] {
+            let expected = hash_file(path, &buffered).unwrap();
+            for options in &modes {
+                assert_eq!(hash_file(path, options).unwrap(), expected, "{:?}", options);
+            }
+        }
+        assert_eq!(
+            hash_file(&big, &buffered).unwrap(),
+            format!("{:x}", Sha256::digest(&content))
+        );
+        #[cfg(feature = "mmap")]
+        assert_eq!(
+            hash_mapped(&fs::File::open(&big).unwrap()),
+            Some(format!("{:x}", Sha256::digest(&content)))
+        );
+    }
+
+    #[test]
+    fn test_cached_fingerprint_matches_cold_run() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "pub fn lib() {}").unwrap();
+
+        let cold = FingerprintOptions {
+            root_path: dir.path().to_path_buf(),
+            include_patterns: vec!["**/*".to_string()],
+            exclude_patterns: vec![],
+            include_dependencies: false,
+            respect_gitignore: false,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
+        };
+        let cached = FingerprintOptions {
+            use_cache: true,
+            ..cold.clone()
+        };
+
+        let cold_result = generate_fingerprint(&cold).unwrap();
+
+        // First cached run populates the cache, second one hits it
+        let populate = generate_fingerprint(&cached).unwrap();
+        assert!(dir.path().join(HASH_CACHE_PATH).exists());
+        let hit = generate_fingerprint(&cached).unwrap();
+
+        assert_eq!(cold_result.hash, populate.hash);
+        assert_eq!(cold_result.hash, hit.hash);
+        assert_eq!(hit.file_count, 2, "cache file must not be fingerprinted");
+
+        // A changed file is re-hashed
+        fs::write(dir.path().join("src/lib.rs"), "pub fn lib() { todo!() }").unwrap();
+        let changed_cold = generate_fingerprint(&cold).unwrap();
+        let changed_cached = generate_fingerprint(&cached).unwrap();
+        assert_ne!(changed_cold.hash, cold_result.hash);
+        assert_eq!(changed_cold.hash, changed_cached.hash);
+    }
+
+    #[test]
+    fn test_exclude_binary_skips_png() {
+        let dir = tempdir().unwrap();
+        // PNG signature followed by the start of an IHDR chunk (contains NULs)
+        fs::write(
+            dir.path().join("logo.png"),
+            b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR\x00\x00\x00\x10",
+        )
+        .unwrap();
+        fs::write(dir.path().join("notes.txt"), "plain text").unwrap();
+
+        let mut options = FingerprintOptions {
+            root_path: dir.path().to_path_buf(),
+            include_patterns: vec!["*".to_string()],
+            exclude_patterns: vec![],
+            include_dependencies: false,
+            respect_gitignore: false,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
+        };
+
+        let all = generate_fingerprint(&options).unwrap();
+        assert_eq!(all.file_count, 2);
+        assert!(all.metadata.scope.skipped_binaries.is_empty());
+
+        options.exclude_binary = true;
+        let text_only = generate_fingerprint(&options).unwrap();
+        assert_eq!(text_only.file_count, 1);
+        assert!(text_only.files_hashed[0].ends_with("notes.txt"));
+        assert_eq!(text_only.metadata.scope.skipped_binaries, vec!["logo.png"]);
+    }
+
+    #[test]
+    fn test_progress_callback_reports_each_file() {
+        use std::sync::Mutex;
+
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "content a").unwrap();
+        fs::write(dir.path().join("b.txt"), "content b").unwrap();
+        fs::write(dir.path().join("c.txt"), "content c").unwrap();
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let options = FingerprintOptions {
+            root_path: dir.path().to_path_buf(),
+            include_patterns: vec!["*.txt".to_string()],
+            exclude_patterns: vec![],
+            include_dependencies: false,
+            respect_gitignore: false,
+            progress: Some(Arc::new(move |done, total| {
+                recorded.lock().unwrap().push((done, total));
+            })),
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
+        };
+
+        let with_progress = generate_fingerprint(&options).unwrap();
+        assert_eq!(*calls.lock().unwrap(), vec![(0, 3), (1, 3), (2, 3), (3, 3)]);
+
+        let without_progress = generate_fingerprint(&FingerprintOptions {
+            progress: None,
+            ..options
+        })
+        .unwrap();
+        assert_eq!(with_progress.hash, without_progress.hash);
+    }
+
     #[test]
     fn test_deterministic_fingerprint() {
         let dir = tempdir().unwrap();
@@ -365,6 +1139,11 @@ mod tests {
             exclude_patterns: vec![],
             include_dependencies: false,
             respect_gitignore: false,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
         };
 
         let result1 = generate_fingerprint(&options).unwrap();
@@ -374,6 +1153,154 @@ mod tests {
         assert_eq!(result1.file_count, 2);
     }
 
+    #[test]
+    fn test_combination_scheme_is_pinned() {
+        // If this hash changes, the combination scheme changed: bump
+        // NORMALIZATION and update COMBINATION_RECIPE to match.
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("a")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.path().join("README.md"), "hello\n").unwrap();
+        fs::write(dir.path().join("a/b.txt"), "").unwrap();
+
+        let options = FingerprintOptions {
+            root_path: dir.path().to_path_buf(),
+            exclude_patterns: vec![],
+            respect_gitignore: false,
+            ..Default::default()
+        };
+        let result = generate_fingerprint(&options).unwrap();
+
+        assert_eq!(
+            result.hash,
+            "sha256:f44696e74ad5f79306ea995247ded68257db10e18d3583a78016496afeca287a"
+        );
+        assert_eq!(
+            result.metadata.normalization.as_deref(),
+            Some(NORMALIZATION)
+        );
+        assert_eq!(
+            result.metadata.generated_by,
+            Some(format!("beltic {}", env!("CARGO_PKG_VERSION")))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_names_do_not_collide() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        // Both names are "caf\u{FFFD}.txt" when converted lossily
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        let latin1 = dir
+            .path()
+            .join("src")
+            .join(OsStr::from_bytes(b"caf\xe9.txt"));
+        let other = dir
+            .path()
+            .join("src")
+            .join(OsStr::from_bytes(b"caf\xe8.txt"));
+        fs::write(&latin1, "first").unwrap();
+        fs::write(&other, "second").unwrap();
+
+        let options = FingerprintOptions {
+            root_path: dir.path().to_path_buf(),
+            include_patterns: vec!["src".to_string()],
+            exclude_patterns: vec![],
+            respect_gitignore: false,
+            use_cache: true,
+            ..Default::default()
+        };
+        let result = generate_fingerprint(&options).unwrap();
+        assert_eq!(result.file_count, 2);
+        assert_eq!(generate_fingerprint(&options).unwrap().hash, result.hash);
+
+        let mut expected = Sha256::new();
+        for (name, content) in [
+            (&b"src/caf\xe8.txt"[..], "second"),
+            (b"src/caf\xe9.txt", "first"),
+        ] {
+            expected.update(name);
+            expected.update(b":");
+            expected.update(format!("{:x}", Sha256::digest(content)).as_bytes());
+            expected.update(b"\n");
+        }
+        assert_eq!(result.hash, format!("sha256:{:x}", expected.finalize()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_colliding_normalized_paths_are_rejected() {
+        // On Unix `\` is an ordinary file name character, so `a\b.txt`
+        // normalizes to the same key as `a/b.txt`
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("a")).unwrap();
+        fs::write(dir.path().join("a/b.txt"), "nested").unwrap();
+        fs::write(dir.path().join("a\\b.txt"), "flat").unwrap();
+
+        let options = FingerprintOptions {
+            root_path: dir.path().to_path_buf(),
+            include_patterns: vec!["**/*.txt".to_string()],
+            exclude_patterns: vec![],
+            respect_gitignore: false,
+            ..Default::default()
+        };
+        let error = generate_fingerprint(&options).unwrap_err();
+        assert!(matches!(error, BelticError::Validation(_)), "{:?}", error);
+        let error = error.to_string();
+        assert!(error.contains("both normalize to `a/b.txt`"), "{}", error);
+        assert!(error.contains("a\\b.txt"), "{}", error);
+
+        // Same outcome whichever file comes first
+        let files = vec![dir.path().join("a/b.txt"), dir.path().join("a\\b.txt")];
+        let reversed = files.iter().rev().cloned().collect();
+        assert!(fingerprint_files(&options, files).is_err());
+        assert!(fingerprint_files(&options, reversed).is_err());
+    }
+
+    #[test]
+    fn test_watch_reports_after_changes_settle() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "one").unwrap();
+        let options = FingerprintOptions {
+            root_path: dir.path().to_path_buf(),
+            include_patterns: vec!["*.txt".to_string()],
+            exclude_patterns: vec![],
+            respect_gitignore: false,
+            ..Default::default()
+        };
+
+        let stop = AtomicBool::new(false);
+        let mut hashes = Vec::new();
+        watch_fingerprint(
+            &options,
+            Duration::from_millis(10),
+            Duration::from_millis(30),
+            &stop,
+            |result| {
+                hashes.push((result.hash.clone(), result.file_count));
+                match hashes.len() {
+                    // Two edits in a row are reported once, with both applied
+                    1 => {
+                        fs::write(dir.path().join("a.txt"), "two").unwrap();
+                        fs::write(dir.path().join("b.txt"), "new").unwrap();
+                    }
+                    _ => stop.store(true, Ordering::SeqCst),
+                }
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(hashes.len(), 2);
+        assert_ne!(hashes[0].0, hashes[1].0);
+        assert_eq!(hashes[1].1, 2);
+        assert_eq!(hashes[1].0, generate_fingerprint(&options).unwrap().hash);
+    }
+
     #[test]
     fn test_cross_platform_paths() {
         let dir = tempdir().unwrap();
@@ -386,6 +1313,11 @@ mod tests {
             exclude_patterns: vec![],
             include_dependencies: false,
             respect_gitignore: false,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
         };
 
         let result = generate_fingerprint(&options).unwrap();
@@ -413,6 +1345,11 @@ mod tests {
             exclude_patterns: vec!["**/target/**".to_string()],
             include_dependencies: false,
             respect_gitignore: false,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
         };
 
         let result = generate_fingerprint(&options).unwrap();
@@ -443,6 +1380,11 @@ mod tests {
             exclude_patterns: vec!["*.log".to_string()],
             include_dependencies: false,
             respect_gitignore: false,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
         };
 
         let result = generate_fingerprint(&options).unwrap();
@@ -471,6 +1413,11 @@ mod tests {
             exclude_patterns: vec![],
             include_dependencies: false,
             respect_gitignore: false,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
         };
 
         let result = generate_fingerprint(&options).unwrap();
@@ -490,6 +1437,11 @@ mod tests {
             exclude_patterns: vec![],
             include_dependencies: false,
             respect_gitignore: false,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
         };
 
         let result = generate_fingerprint(&options).unwrap();
@@ -517,6 +1469,11 @@ mod tests {
             exclude_patterns: vec![],
             include_dependencies: false,
             respect_gitignore: false,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
         };
 
         let result = generate_fingerprint(&options).unwrap();
@@ -540,6 +1497,11 @@ mod tests {
             exclude_patterns: vec![],
             include_dependencies: false,
             respect_gitignore: false,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
         };
 
         let result = generate_fingerprint(&options).unwrap();
@@ -562,6 +1524,11 @@ mod tests {
             exclude_patterns: vec![],
             include_dependencies: false,
             respect_gitignore: false,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
         };
 
         let result = generate_fingerprint(&options).unwrap();
@@ -583,6 +1550,11 @@ mod tests {
             exclude_patterns: vec![],
             include_dependencies: false,
             respect_gitignore: false,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
         };
 
         let result = generate_fingerprint(&options).unwrap();
@@ -598,6 +1570,64 @@ mod tests {
         assert!(included_files.contains(&"test.rs".to_string()));
     }
 
+    #[test]
+    fn test_include_pattern_brace_expansion() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.path().join("src/nested/lib.rs"), "// lib").unwrap();
+        fs::write(dir.path().join("src/nested/config.toml"), "a = 1").unwrap();
+        fs::write(dir.path().join("src/notes.md"), "notes").unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "[package]").unwrap();
+
+        let options = FingerprintOptions {
+            root_path: dir.path().to_path_buf(),
+            include_patterns: vec!["src/**/*.{rs,toml}".to_string()],
+            ..Default::default()
+        };
+        let files: Vec<String> = collect_files(&options)
+            .unwrap()
+            .iter()
+            .map(|p| {
+                p.strip_prefix(dir.path())
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+
+        assert_eq!(
+            files,
+            vec!["src/main.rs", "src/nested/config.toml", "src/nested/lib.rs"]
+        );
+    }
+
+    #[test]
+    fn test_glob_files_star_does_not_cross_directories() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        fs::write(dir.path().join("src/nested/lib.rs"), "").unwrap();
+        fs::write(dir.path().join("top.rs"), "").unwrap();
+
+        let names = |pattern: &str| -> Vec<String> {
+            let mut files: Vec<String> = glob_files(dir.path(), pattern)
+                .unwrap()
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+                .collect();
+            files.sort();
+            files
+        };
+
+        assert_eq!(names("src/*.rs"), vec!["main.rs"]);
+        assert_eq!(names("src/**/*.rs"), vec!["lib.rs", "main.rs"]);
+        assert_eq!(names("**/*.rs"), vec!["lib.rs", "main.rs", "top.rs"]);
+        assert_eq!(names("{src,lib}/*.rs"), vec!["main.rs"]);
+        assert!(names("missing/**/*.rs").is_empty());
+        assert!(glob_files(dir.path(), "src/{rs").is_err());
+    }
+
     #[test]
     fn test_build_globset() {
         let patterns = vec![
@@ -618,4 +1648,42 @@ mod tests {
         assert!(!globset.is_match("src/main.rs"));
         assert!(!globset.is_match("data.json"));
     }
+
+    #[test]
+    fn test_changed_files_since_git_ref() {
+        let dir = tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .current_dir(dir.path())
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?}", args);
+        };
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "").unwrap();
+        git(&["init", "-q"]);
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "init"]);
+
+        fs::write(dir.path().join("src/main.rs"), "fn main() { run() }").unwrap();
+        fs::write(dir.path().join("src/new.rs"), "").unwrap();
+        fs::remove_file(dir.path().join("src/lib.rs")).unwrap();
+
+        let changed = changed_files_since(dir.path(), "HEAD").unwrap();
+        assert_eq!(changed, vec!["src/lib.rs", "src/main.rs", "src/new.rs"]);
+        assert!(changed_files_since(dir.path(), "no-such-ref").is_err());
+
+        let options = FingerprintOptions {
+            root_path: dir.path().to_path_buf(),
+            include_patterns: vec!["src/**/*.rs".to_string()],
+            exclude_patterns: vec!["src/new.rs".to_string()],
+            ..Default::default()
+        };
+        assert!(path_in_scope(&options, "src/lib.rs").unwrap());
+        assert!(!path_in_scope(&options, "src/new.rs").unwrap());
+    }
 }
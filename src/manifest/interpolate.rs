@@ -0,0 +1,102 @@
+//! `${VAR}` expansion for values supplied to non-interactive init.
+//!
+//! Lets CI template org-specific values (issuer DID, contact address,
+//! revocation list URL) through environment variables. A reference to an
+//! unset variable is an error, so a literal `${VAR}` is never written out.
+
+use anyhow::{bail, Result};
+
+/// Expand `${VAR}` references from the process environment.
+///
+/// `field` names the value in error messages.
+pub fn expand_env(field: &str, value: &str) -> Result<String> {
+    expand_with(field, value, |name| std::env::var(name).ok())
+}
+
+/// Expand `${VAR}` references using `lookup`
+pub fn expand_with<F>(field: &str, value: &str, lookup: F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+
+        let Some(end) = after.find('}') else {
+            bail!("Unterminated ${{...}} in {}: {}", field, value);
+        };
+
+        let name = &after[..end];
+        if !is_valid_name(name) {
+            bail!("Invalid variable name '${{{}}}' in {}", name, field);
+        }
+
+        match lookup(name) {
+            Some(resolved) => output.push_str(&resolved),
+            None => bail!(
+                "Environment variable {} is not set (referenced in {})",
+                name,
+                field
+            ),
+        }
+
+        rest = &after[end + 1..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "ORG_DOMAIN" => Some("acme.dev".to_string()),
+            "TEAM" => Some("security".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expands_variables() {
+        assert_eq!(
+            expand_with("issuer DID", "did:web:${ORG_DOMAIN}", lookup).unwrap(),
+            "did:web:acme.dev"
+        );
+        assert_eq!(
+            expand_with("contact", "${TEAM}@${ORG_DOMAIN}", lookup).unwrap(),
+            "security@acme.dev"
+        );
+        assert_eq!(
+            expand_with("contact", "oncall@acme.dev", lookup).unwrap(),
+            "oncall@acme.dev"
+        );
+        assert_eq!(expand_with("price", "$5", lookup).unwrap(), "$5");
+    }
+
+    #[test]
+    fn test_missing_variable_is_named_in_error() {
+        let err = expand_with("issuer DID", "did:web:${MISSING_DOMAIN}", lookup).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("MISSING_DOMAIN"), "{}", message);
+        assert!(message.contains("issuer DID"), "{}", message);
+    }
+
+    #[test]
+    fn test_malformed_references() {
+        assert!(expand_with("contact", "${TEAM", lookup).is_err());
+        assert!(expand_with("contact", "${1TEAM}", lookup).is_err());
+        assert!(expand_with("contact", "${}", lookup).is_err());
+    }
+}
@@ -1,28 +1,59 @@
+pub mod api_schema;
 pub mod config;
+pub mod convert;
 pub mod credential;
+pub mod dependencies;
 pub mod detector;
 pub mod fingerprint;
+pub mod interpolate;
+pub mod monorepo;
+pub mod preset;
 pub mod prompts;
+pub mod risk;
+pub mod sarif;
+pub mod sbom;
 pub mod schema;
 pub mod templates;
+pub mod tools;
+pub mod update;
 pub mod validator;
 
-use anyhow::Result;
+pub use schema::AgentManifest;
+pub use validator::{validate_manifest, ValidationResult};
+
+use anyhow::{Context, Result};
 use chrono::Utc;
-use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+use crate::document::{read_document, write_document, DocumentFormat};
+use crate::duration::IsoDuration;
+use crate::error::BelticError;
 use crate::manifest::config::BelticConfig;
 use crate::manifest::credential::{
-    AgentCredential, AgentStatus as CredAgentStatus, ArchitectureType as CredArchType,
-    ComplianceCert, DataCategory as CredDataCategory, Modality as CredModality,
+    AgeRestriction as CredAgeRestriction, AgentCredential, AgentStatus as CredAgentStatus,
+    ArchitectureType as CredArchType, ComplianceCert as CredComplianceCert,
+    DataCategory as CredDataCategory, KybTier as CredKybTier, Modality as CredModality,
+    ModelFamily, ModelProvider,
+};
+use crate::manifest::detector::{detect_agent_in_repo, detect_project_info, DetectionResults};
+use crate::manifest::fingerprint::{
+    generate_fingerprint, FingerprintOptions, FingerprintResult, DEFAULT_HASH_BUFFER_SIZE,
+    DEFAULT_MMAP_THRESHOLD, NORMALIZATION,
 };
-use crate::manifest::detector::detect_project_info;
-use crate::manifest::fingerprint::{generate_fingerprint, FingerprintOptions};
+use crate::manifest::interpolate::expand_env;
+use crate::manifest::monorepo::{discover_agent_dirs, run_parallel, PROJECT_FILES};
+use crate::manifest::preset::PresetStore;
+use crate::manifest::risk::{assess, assess_manifest, label, tier_below, RiskAssessment};
 use crate::manifest::schema::{
-    AgentManifest, AgentStatus, ArchitectureType, DataCategory, GenerationMetadata, Modality,
+    AgeRestriction, AgentStatus, ArchitectureType, ComplianceCert, DataCategory,
+    GenerationMetadata, KybTier, Modality, Tool,
 };
+use crate::manifest::templates::{ManifestTemplates, TextOverrides};
+use crate::manifest::tools::load_tools_file;
+use crate::manifest::update::{detected_values, merge_detected, recorded_values};
+use crate::manifest::validator::{IssueLevel, RULE_SAFETY_METRICS};
+use crate::status;
 
 /// Options for manifest initialization
 #[derive(Debug, Clone)]
@@ -33,13 +64,62 @@ pub struct InitOptions {
     pub exclude_patterns: Option<Vec<String>>,
     pub deployment_type: Option<String>,
     pub developer_id: Option<Uuid>,
+    /// Whether `developer_id` was read from a developer credential whose
+    /// signature verified
+    pub developer_credential_verified: bool,
     pub force: bool,
     pub interactive: bool,
     pub validate: bool,
     /// Output schema-compliant AgentCredential instead of AgentManifest
     pub credential: bool,
-    /// Issuer DID for self-signed credentials
+    /// Issuer DID for self-signed credentials (may contain `${VAR}`)
     pub issuer_did: Option<String>,
+    /// Incident response contact email (may contain `${VAR}`)
+    pub incident_response_contact: Option<String>,
+    /// Revocation list URL for credentials (may contain `${VAR}`)
+    pub revocation_list_url: Option<String>,
+    /// Output format (default: from the output extension, else JSON)
+    pub format: Option<DocumentFormat>,
+    /// Skip binary files when fingerprinting
+    pub exclude_binary: bool,
+    /// Only ask for identity; template defaults fill data handling and operations.
+    /// Credentials are always generated this way, so it has no effect there.
+    pub minimal: bool,
+    /// Agent subdirectory relative to the current directory (see
+    /// [`resolve_agent_path`]); it becomes the fingerprint root and the
+    /// manifest's `repositoryStructure.agentPath`
+    pub agent_path: Option<String>,
+    /// Credential validity (default: 90 days)
+    pub valid_for: Option<IsoDuration>,
+    /// Credential preset applied before detection and flags
+    pub preset: Option<String>,
+    /// File of tool specifications (see [`tools::load_tools_file`]); replaces
+    /// the interactive tool prompts
+    pub tools_file: Option<PathBuf>,
+    /// Derive `agentId` and `credentialId` from the issuer DID, agent name
+    /// and version instead of generating random ones
+    pub deterministic_id: bool,
+    /// Fail instead of falling back to defaults when key fields were neither
+    /// detected nor supplied (see [`check_strict_detection`])
+    pub strict_detection: bool,
+    /// Lifecycle status for non-interactive and credential init (default: alpha)
+    pub status: Option<AgentStatus>,
+    /// Update the existing manifest in place (see [`update_manifest`])
+    pub update: bool,
+    /// Refuse to write a manifest with validation warnings (see
+    /// [`check_fail_on_warning`])
+    pub fail_on_warning: bool,
+    /// Directory of boilerplate text templates (see [`load_text_overrides`])
+    pub template_dir: Option<PathBuf>,
+    /// Write a manifest for every agent directory found under the current
+    /// directory (see [`init_monorepo_scan`])
+    pub monorepo_scan: bool,
+    /// Agents detected and fingerprinted at once by `monorepo_scan`
+    /// (default: available parallelism)
+    pub jobs: Option<usize>,
+    /// Directory to run from instead of the current directory (see
+    /// [`resolve_base_dir`])
+    pub base_dir: Option<PathBuf>,
 }
 
 impl Default for InitOptions {
@@ -51,11 +131,30 @@ impl Default for InitOptions {
             exclude_patterns: None,
             deployment_type: None,
             developer_id: None,
+            developer_credential_verified: false,
             force: false,
             interactive: true, // Default to interactive mode
             validate: true,    // Default to validating
             credential: false, // Default to manifest output
             issuer_did: None,
+            incident_response_contact: None,
+            revocation_list_url: None,
+            format: None,
+            minimal: false,
+            exclude_binary: false,
+            agent_path: None,
+            valid_for: None,
+            preset: None,
+            tools_file: None,
+            deterministic_id: false,
+            strict_detection: false,
+            status: None,
+            update: false,
+            fail_on_warning: false,
+            template_dir: None,
+            monorepo_scan: false,
+            jobs: None,
+            base_dir: None,
         }
     }
 }
@@ -67,6 +166,18 @@ pub fn init_manifest(options: &InitOptions) -> Result<()> {
         return init_credential(options);
     }
 
+    if options.update {
+        return update_manifest(options);
+    }
+
+    if options.monorepo_scan {
+        return init_monorepo_scan(options);
+    }
+
+    if options.minimal {
+        return init_manifest_minimal(options);
+    }
+
     // Use enhanced version if interactive mode is enabled (default)
     if options.interactive {
         init_manifest_interactive(options)
@@ -82,12 +193,17 @@ fn init_manifest_interactive(options: &InitOptions) -> Result<()> {
     use crate::manifest::validator::validate_manifest;
     use console::style;
 
-    let base_dir = std::env::current_dir()?;
+    let base_dir = resolve_base_dir(options.base_dir.as_deref())?;
     let output_path = options
         .output_path
         .as_ref()
         .map(|p| Path::new(p).to_path_buf())
-        .unwrap_or_else(|| base_dir.join("agent-manifest.json"));
+        .unwrap_or_else(|| {
+            base_dir.join(format!(
+                "agent-manifest.{}",
+                options.format.unwrap_or_default().extension()
+            ))
+        });
 
     // Check if manifest already exists
     if output_path.exists() && !options.force {
@@ -97,13 +213,16 @@ fn init_manifest_interactive(options: &InitOptions) -> Result<()> {
         );
     }
 
-    println!(
+    let file_tools = load_init_tools(options)?;
+
+    status!(
         "{}",
         style("🚀 Beltic Agent Manifest Generator").bold().cyan()
     );
 
     // Auto-detect project information first
-    let detection_results = detect_project_info(&base_dir)?;
+    let detection_results = detect_agent(&base_dir, options.agent_path.as_deref())?;
+    let detected_values = detected_values(&detection_results);
 
     // Initialize interactive prompts
     let prompts = InteractivePrompts::new();
@@ -128,8 +247,11 @@ fn init_manifest_interactive(options: &InitOptions) -> Result<()> {
     // 2. Technical Profile
     let technical_profile = prompts.prompt_technical_profile()?;
 
-    // 3. Tools
-    let tools = prompts.prompt_tools()?;
+    // 3. Tools (from --tools when given)
+    let tools = match file_tools {
+        Some(tools) => Some(tools),
+        None => prompts.prompt_tools()?,
+    };
 
     // 4. Data Handling
     let data_handling = prompts.prompt_data_handling()?;
@@ -145,22 +267,13 @@ fn init_manifest_interactive(options: &InitOptions) -> Result<()> {
     };
 
     // Generate fingerprint
-    println!("\n✓ Generating codebase fingerprint...");
+    status!("\n✓ Generating codebase fingerprint...");
     let config = load_or_create_config(&base_dir, options)?;
-    let fingerprint_options = if let Some(ref includes) = options.include_patterns {
-        FingerprintOptions {
-            include_patterns: includes.clone(),
-            exclude_patterns: options.exclude_patterns.clone().unwrap_or_default(),
-            root_path: base_dir.clone(),
-            include_dependencies: true,
-            respect_gitignore: true,
-        }
-    } else {
-        FingerprintOptions::from_path_config(&config.agent.paths, base_dir.clone())
-    };
+    let text_overrides = load_text_overrides(options, &config, &base_dir)?;
+    let fingerprint_options = init_fingerprint_options(options, &config, &base_dir);
 
     let fingerprint_result = generate_fingerprint(&fingerprint_options)?;
-    println!(
+    status!(
         "✓ Generated fingerprint: {} ({} files)",
         style(&fingerprint_result.hash).green(),
         fingerprint_result.file_count
@@ -209,6 +322,7 @@ fn init_manifest_interactive(options: &InitOptions) -> Result<()> {
     manifest.human_oversight_mode = operations.human_oversight_mode;
     manifest.fail_safe_behavior = operations.fail_safe_behavior;
     manifest.monitoring_coverage = operations.monitoring_coverage;
+    text_overrides.apply_to_manifest(&mut manifest);
 
     // Apply defaults for remaining fields
     manifest.system_config_fingerprint = fingerprint_result.hash;
@@ -221,7 +335,8 @@ fn init_manifest_interactive(options: &InitOptions) -> Result<()> {
 
     // Developer credential
     manifest.developer_credential_id = developer_id.unwrap_or_else(Uuid::nil);
-    manifest.developer_credential_verified = false;
+    manifest.developer_credential_verified =
+        options.developer_id.is_some() && options.developer_credential_verified;
 
     // Use cases
     manifest.approved_use_cases = Some(ManifestTemplates::default_approved_use_cases(
@@ -238,9 +353,18 @@ fn init_manifest_interactive(options: &InitOptions) -> Result<()> {
     manifest.metadata = Some(GenerationMetadata {
         generated_by: format!("beltic v{}", env!("CARGO_PKG_VERSION")),
         generated_at: Utc::now(),
+        detected_values,
         auto_detected: detection_results.detection_sources,
     });
 
+    // Risk hint (advisory; only applied on confirmation)
+    let assessment = assess_manifest(&manifest);
+    if print_risk_hint(&assessment, &convert_kyb_tier(&manifest.kyb_tier_required))
+        && prompts.confirm_risk_suggestion(&label(&assessment.kyb_tier))?
+    {
+        manifest.kyb_tier_required = convert_cred_kyb_tier(&assessment.kyb_tier);
+    }
+
     // Validate before writing
     if options.validate {
         let validation_result = validate_manifest(&manifest);
@@ -253,19 +377,19 @@ fn init_manifest_interactive(options: &InitOptions) -> Result<()> {
         } else {
             prompts.display_validation(0, warnings)?;
         }
+        check_fail_on_warning(options, &validation_result)?;
     }
 
     // Write manifest
-    let json = serde_json::to_string_pretty(&manifest)?;
-    fs::write(&output_path, json)?;
+    write_document(&output_path, &manifest, options.format)?;
 
-    println!("\n✓ Created {}", style(output_path.display()).green());
+    status!("\n✓ Created {}", style(output_path.display()).green());
 
     // Write .beltic.yaml if it doesn't exist
     let beltic_yaml_path = base_dir.join(".beltic.yaml");
     if !beltic_yaml_path.exists() {
         config.save_to_file(&beltic_yaml_path)?;
-        println!("✓ Created {}", style(beltic_yaml_path.display()).green());
+        status!("✓ Created {}", style(beltic_yaml_path.display()).green());
     }
 
     Ok(())
@@ -273,16 +397,19 @@ fn init_manifest_interactive(options: &InitOptions) -> Result<()> {
 
 /// Initialize manifest without prompts (non-interactive mode)
 fn init_manifest_noninteractive(options: &InitOptions) -> Result<()> {
-    use crate::manifest::schema::DeploymentType;
-    use crate::manifest::templates::generate_complete_defaults;
     use crate::manifest::validator::validate_manifest;
 
-    let base_dir = std::env::current_dir()?;
+    let base_dir = resolve_base_dir(options.base_dir.as_deref())?;
     let output_path = options
         .output_path
         .as_ref()
         .map(|p| Path::new(p).to_path_buf())
-        .unwrap_or_else(|| base_dir.join("agent-manifest.json"));
+        .unwrap_or_else(|| {
+            base_dir.join(format!(
+                "agent-manifest.{}",
+                options.format.unwrap_or_default().extension()
+            ))
+        });
 
     // Check if manifest already exists
     if output_path.exists() && !options.force {
@@ -292,25 +419,32 @@ fn init_manifest_noninteractive(options: &InitOptions) -> Result<()> {
         );
     }
 
-    println!("✓ Initializing agent manifest (non-interactive)...");
+    let contact = expand_option(
+        "incident response contact",
+        &options.incident_response_contact,
+    )?;
+    let file_tools = load_init_tools(options)?;
+
+    status!("✓ Initializing agent manifest (non-interactive)...");
 
     // Load or create config
     let config = if let Some(config_path) = &options.config_path {
         let path = Path::new(config_path);
         if path.exists() {
-            println!("✓ Found config file: {}", config_path);
+            status!("✓ Found config file: {}", config_path);
             BelticConfig::from_file(path)?
         } else {
             anyhow::bail!("Config file not found: {}", config_path);
         }
     } else if let Some(config) = BelticConfig::find_and_load(&base_dir)? {
-        println!("✓ Found .beltic.yaml configuration");
+        status!("✓ Found .beltic.yaml configuration");
         config
     } else {
         // Create default config based on deployment type
         let deployment_type = options.deployment_type.as_deref().unwrap_or("standalone");
         match deployment_type {
-            "monorepo" => {
+            // With --agent-path, patterns are relative to the agent directory
+            "monorepo" if options.agent_path.is_none() => {
                 let agent_path = base_dir
                     .file_name()
                     .and_then(|n| n.to_str())
@@ -323,9 +457,13 @@ fn init_manifest_noninteractive(options: &InitOptions) -> Result<()> {
         }
     };
 
+    let text_overrides = load_text_overrides(options, &config, &base_dir)?;
+
     // Auto-detect project information
-    println!("✓ Detecting project information...");
-    let detection_results = detect_project_info(&base_dir)?;
+    status!("✓ Detecting project information...");
+    let detection_results =
+        detect_with_overrides(&base_dir, options.agent_path.as_deref(), &config)?;
+    check_strict_detection(options, &detection_results, STRICT_MANIFEST_FIELDS)?;
 
     // Get name and version with defaults (no TODOs)
     let name = detection_results.project_name.clone().unwrap_or_else(|| {
@@ -341,8 +479,99 @@ fn init_manifest_noninteractive(options: &InitOptions) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "0.1.0".to_string());
 
-    println!("✓ Using agent name: {}", name);
-    println!("✓ Using version: {}", version);
+    status!("✓ Using agent name: {}", name);
+    status!("✓ Using version: {}", version);
+
+    // Generate fingerprint
+    status!("✓ Generating codebase fingerprint...");
+    let fingerprint_options = init_fingerprint_options(options, &config, &base_dir);
+
+    let fingerprint_result = generate_fingerprint(&fingerprint_options)?;
+    status!(
+        "✓ Generated fingerprint ({} files, {})",
+        fingerprint_result.file_count,
+        fingerprint_result.hash
+    );
+
+    let inputs = ManifestInputs {
+        contact,
+        tools: file_tools,
+        text_overrides,
+    };
+    let manifest = build_manifest(
+        options,
+        &inputs,
+        name,
+        version,
+        detection_results,
+        fingerprint_result,
+    );
+
+    print_risk_hint(
+        &assess_manifest(&manifest),
+        &convert_kyb_tier(&manifest.kyb_tier_required),
+    );
+
+    // Validate if requested
+    if options.validate {
+        let validation_result = validate_manifest(&manifest);
+        if !validation_result.is_valid {
+            println!("\n⚠ Validation warnings:");
+            for warning in &validation_result.warnings {
+                println!("  • {}", warning);
+            }
+            for error in &validation_result.errors {
+                println!("  ✗ {}", error);
+            }
+        }
+        check_fail_on_warning(options, &validation_result)?;
+    }
+
+    // Write manifest
+    write_document(&output_path, &manifest, options.format)?;
+
+    status!("✓ Created {}", output_path.display());
+
+    // Write .beltic.yaml if it doesn't exist
+    let beltic_yaml_path = base_dir.join(".beltic.yaml");
+    if !beltic_yaml_path.exists() {
+        config.save_to_file(&beltic_yaml_path)?;
+        status!("✓ Created {}", beltic_yaml_path.display());
+    }
+
+    status!("\nNext steps:");
+    status!("1. Review {} and adjust as needed", output_path.display());
+    if manifest.developer_credential_id == Uuid::nil() {
+        status!("2. Obtain developer credential ID from Beltic platform");
+    }
+    status!("3. Run 'beltic fingerprint' after code changes");
+    status!("4. Sign manifest with 'beltic sign'");
+
+    Ok(())
+}
+
+/// What non-interactive init resolves once and puts into every manifest it
+/// builds: one, or one per agent with `--monorepo-scan`
+struct ManifestInputs {
+    contact: Option<String>,
+    tools: Option<Vec<Tool>>,
+    text_overrides: TextOverrides,
+}
+
+/// Build a non-interactive manifest: template defaults, then detection
+/// results, the fingerprint and `inputs`
+fn build_manifest(
+    options: &InitOptions,
+    inputs: &ManifestInputs,
+    name: String,
+    version: String,
+    detection_results: DetectionResults,
+    fingerprint_result: FingerprintResult,
+) -> AgentManifest {
+    use crate::manifest::schema::DeploymentType;
+    use crate::manifest::templates::generate_complete_defaults;
+
+    let detected_values = detected_values(&detection_results);
 
     // Determine deployment type
     let deployment_type = match options.deployment_type.as_deref() {
@@ -352,36 +581,28 @@ fn init_manifest_noninteractive(options: &InitOptions) -> Result<()> {
         Some("embedded") => DeploymentType::Embedded,
         _ => detection_results
             .deployment_type
+            .clone()
             .unwrap_or(DeploymentType::Standalone),
     };
 
     // Determine architecture type
     let architecture = detection_results
         .architecture_type
+        .clone()
         .unwrap_or(crate::manifest::schema::ArchitectureType::SingleAgent);
 
-    // Generate fingerprint
-    println!("✓ Generating codebase fingerprint...");
-    let fingerprint_options = if let Some(ref includes) = options.include_patterns {
-        FingerprintOptions {
-            include_patterns: includes.clone(),
-            exclude_patterns: options.exclude_patterns.clone().unwrap_or_default(),
-            root_path: base_dir.clone(),
-            include_dependencies: true,
-            respect_gitignore: true,
-        }
-    } else {
-        FingerprintOptions::from_path_config(&config.agent.paths, base_dir.clone())
-    };
-
-    let fingerprint_result = generate_fingerprint(&fingerprint_options)?;
-    println!(
-        "✓ Generated fingerprint ({} files, {})",
-        fingerprint_result.file_count, fingerprint_result.hash
-    );
-
     // Create manifest with complete defaults (no TODOs)
     let mut manifest = generate_complete_defaults(name, version, architecture, deployment_type);
+    apply_model_overrides(&mut manifest, &detection_results);
+    if let Some(status) = &options.status {
+        manifest.current_status = status.clone();
+        manifest.incident_response_slo =
+            ManifestTemplates::incident_response_slo_for_status(status);
+    }
+    if let Some(contact) = &inputs.contact {
+        manifest.incident_response_contact = contact.clone();
+    }
+    set_manifest_tools(&mut manifest, inputs.tools.clone());
 
     // Apply fingerprint
     manifest.system_config_fingerprint = fingerprint_result.hash.clone();
@@ -423,60 +644,827 @@ fn init_manifest_noninteractive(options: &InitOptions) -> Result<()> {
     // Apply developer ID if provided
     if let Some(dev_id) = options.developer_id {
         manifest.developer_credential_id = dev_id;
-        manifest.developer_credential_verified = false;
+        manifest.developer_credential_verified = options.developer_credential_verified;
     }
+    inputs.text_overrides.apply_to_manifest(&mut manifest);
 
     // Generate metadata
     manifest.metadata = Some(GenerationMetadata {
         generated_by: format!("beltic v{}", env!("CARGO_PKG_VERSION")),
         generated_at: Utc::now(),
+        detected_values,
         auto_detected: detection_results.detection_sources,
     });
 
-    // Validate if requested
+    manifest
+}
+
+/// Outcome of one agent directory in `init --monorepo-scan`
+enum ScanOutcome {
+    Written {
+        name: String,
+        version: String,
+        file_count: usize,
+        fingerprint: String,
+    },
+    /// A manifest already exists and `--force` was not given
+    Exists,
+    Failed(String),
+}
+
+/// Write a non-interactive manifest into every agent directory under the
+/// current directory (see [`discover_agent_dirs`]), detecting and
+/// fingerprinting up to `options.jobs` agents at once, then print a summary
+/// table. Fails if any agent failed, after the others were written.
+fn init_monorepo_scan(options: &InitOptions) -> Result<()> {
+    let base_dir = resolve_base_dir(options.base_dir.as_deref())?;
+    let contact = expand_option(
+        "incident response contact",
+        &options.incident_response_contact,
+    )?;
+    let tools = load_init_tools(options)?;
+    let config = load_or_create_config(&base_dir, options)?;
+    let inputs = ManifestInputs {
+        contact,
+        tools,
+        text_overrides: load_text_overrides(options, &config, &base_dir)?,
+    };
+
+    let dirs = discover_agent_dirs(&base_dir)?;
+    if dirs.is_empty() {
+        anyhow::bail!(
+            "No agent directories found under {} (looked for {})",
+            base_dir.display(),
+            PROJECT_FILES.join(", ")
+        );
+    }
+    let jobs = options
+        .jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+        .max(1);
+    status!(
+        "✓ Found {} agent director{}; scanning {} at a time...",
+        dirs.len(),
+        if dirs.len() == 1 { "y" } else { "ies" },
+        jobs
+    );
+
+    let outcomes = run_parallel(&dirs, jobs, |dir| {
+        scan_agent(options, &inputs, &config, &base_dir, dir)
+            .unwrap_or_else(|e| ScanOutcome::Failed(format!("{:#}", e)))
+    });
+    print_scan_summary(&dirs, &outcomes);
+
+    let beltic_yaml_path = base_dir.join(".beltic.yaml");
+    if !beltic_yaml_path.exists() {
+        config.save_to_file(&beltic_yaml_path)?;
+        status!("✓ Created {}", beltic_yaml_path.display());
+    }
+
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, ScanOutcome::Failed(_)))
+        .count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} agents failed", failed, dirs.len());
+    }
+    Ok(())
+}
+
+/// Detect, fingerprint and write the manifest for one agent directory
+fn scan_agent(
+    options: &InitOptions,
+    inputs: &ManifestInputs,
+    config: &BelticConfig,
+    base_dir: &Path,
+    dir: &str,
+) -> Result<ScanOutcome> {
+    use crate::manifest::validator::validate_manifest;
+
+    let output_path = base_dir.join(dir).join(format!(
+        "agent-manifest.{}",
+        options.format.unwrap_or_default().extension()
+    ));
+    if output_path.exists() && !options.force {
+        return Ok(ScanOutcome::Exists);
+    }
+
+    let options = InitOptions {
+        agent_path: Some(dir.to_string()),
+        ..options.clone()
+    };
+    let detection_results = detect_with_overrides(base_dir, Some(dir), config)?;
+    check_strict_detection(&options, &detection_results, STRICT_MANIFEST_FIELDS)?;
+
+    // Detection falls back to the directory name, so this is only a backstop
+    let name = detection_results
+        .project_name
+        .clone()
+        .unwrap_or_else(|| dir.rsplit('/').next().unwrap_or(dir).to_string());
+    let version = detection_results
+        .project_version
+        .clone()
+        .unwrap_or_else(|| "0.1.0".to_string());
+
+    let fingerprint_result =
+        generate_fingerprint(&agent_fingerprint_options(&options, config, base_dir))?;
+    let file_count = fingerprint_result.file_count;
+    let manifest = build_manifest(
+        &options,
+        inputs,
+        name,
+        version,
+        detection_results,
+        fingerprint_result,
+    );
+
+    if options.validate {
+        check_fail_on_warning(&options, &validate_manifest(&manifest))?;
+    }
+    write_document(&output_path, &manifest, options.format)?;
+
+    Ok(ScanOutcome::Written {
+        name: manifest.agent_name,
+        version: manifest.agent_version,
+        file_count,
+        fingerprint: manifest.system_config_fingerprint,
+    })
+}
+
+/// Print one row per agent directory: what was detected and written
+fn print_scan_summary(dirs: &[String], outcomes: &[ScanOutcome]) {
+    use console::{style, Color};
+
+    let header = [
+        "DIRECTORY",
+        "NAME",
+        "VERSION",
+        "FILES",
+        "FINGERPRINT",
+        "RESULT",
+    ];
+    let rows: Vec<[String; 6]> = dirs
+        .iter()
+        .zip(outcomes)
+        .map(|(dir, outcome)| match outcome {
+            ScanOutcome::Written {
+                name,
+                version,
+                file_count,
+                fingerprint,
+            } => [
+                dir.clone(),
+                name.clone(),
+                version.clone(),
+                file_count.to_string(),
+                fingerprint
+                    .trim_start_matches("sha256:")
+                    .chars()
+                    .take(12)
+                    .collect(),
+                "written".to_string(),
+            ],
+            ScanOutcome::Exists => [
+                dir.clone(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "exists (use --force)".to_string(),
+            ],
+            ScanOutcome::Failed(error) => [
+                dir.clone(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                format!("failed: {}", error),
+            ],
+        })
+        .collect();
+
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let line = |cells: Vec<String>| println!("{}", cells.join("  ").trim_end());
+    println!();
+    line(
+        header
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell))
+            .collect(),
+    );
+    for (row, outcome) in rows.iter().zip(outcomes) {
+        let color = match outcome {
+            ScanOutcome::Written { .. } => Color::Green,
+            ScanOutcome::Exists => Color::Yellow,
+            ScanOutcome::Failed(_) => Color::Red,
+        };
+        line(
+            row.iter()
+                .zip(widths)
+                .enumerate()
+                .map(|(column, (cell, width))| {
+                    let padded = format!("{:<width$}", cell);
+                    if column == header.len() - 1 {
+                        style(padded).fg(color).to_string()
+                    } else {
+                        padded
+                    }
+                })
+                .collect(),
+        );
+    }
+}
+
+/// Initialize manifest with only agent identity (`init --minimal`).
+///
+/// The technical profile comes from project detection and everything else
+/// from templates, so the result passes `validate_manifest` without the
+/// full questionnaire. Only name, version, description, status and the
+/// incident response contact are prompted for (and only when interactive).
+fn init_manifest_minimal(options: &InitOptions) -> Result<()> {
+    use crate::manifest::prompts::InteractivePrompts;
+    use crate::manifest::schema::DeploymentType;
+    use crate::manifest::templates::{
+        generate_complete_defaults, ManifestTemplates, MINIMAL_DEFAULTED_FIELDS,
+    };
+    use crate::manifest::validator::validate_manifest;
+    use console::style;
+
+    let base_dir = resolve_base_dir(options.base_dir.as_deref())?;
+    let output_path = options
+        .output_path
+        .as_ref()
+        .map(|p| Path::new(p).to_path_buf())
+        .unwrap_or_else(|| {
+            base_dir.join(format!(
+                "agent-manifest.{}",
+                options.format.unwrap_or_default().extension()
+            ))
+        });
+
+    // Check if manifest already exists
+    if output_path.exists() && !options.force {
+        anyhow::bail!(
+            "Manifest already exists at {}. Use --force to overwrite.",
+            output_path.display()
+        );
+    }
+
+    let contact = expand_option(
+        "incident response contact",
+        &options.incident_response_contact,
+    )?;
+    let file_tools = load_init_tools(options)?;
+
+    status!("✓ Initializing minimal agent manifest...");
+
+    let config = load_or_create_config(&base_dir, options)?;
+    let text_overrides = load_text_overrides(options, &config, &base_dir)?;
+    let detection_results =
+        detect_with_overrides(&base_dir, options.agent_path.as_deref(), &config)?;
+    check_strict_detection(options, &detection_results, STRICT_MANIFEST_FIELDS)?;
+    let detected_values = detected_values(&detection_results);
+
+    let architecture = detection_results
+        .architecture_type
+        .clone()
+        .unwrap_or(ArchitectureType::SingleAgent);
+    let deployment_type = match options.deployment_type.as_deref() {
+        Some("monorepo") => DeploymentType::Monorepo,
+        Some("plugin") => DeploymentType::Plugin,
+        Some("serverless") => DeploymentType::Serverless,
+        Some("embedded") => DeploymentType::Embedded,
+        _ => detection_results
+            .deployment_type
+            .clone()
+            .unwrap_or(DeploymentType::Standalone),
+    };
+
+    let detected_name = detection_results.project_name.clone().unwrap_or_else(|| {
+        base_dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("agent")
+            .to_string()
+    });
+    let detected_version = detection_results
+        .project_version
+        .clone()
+        .unwrap_or_else(|| "0.1.0".to_string());
+    let detected_description = detection_results
+        .project_description
+        .clone()
+        .filter(|desc| desc.len() >= 50 && desc.len() <= 1000)
+        .unwrap_or_else(|| ManifestTemplates::generate_description(&detected_name, &architecture));
+    let default_contact = contact.or_else(|| git_user_email(&base_dir));
+
+    // Identity is the only thing asked for
+    let (name, version, description, status, contact) = if options.interactive {
+        let prompts = InteractivePrompts::new();
+        let (name, version, description, status) = prompts.prompt_identity(Some((
+            &detected_name,
+            &detected_version,
+            &detected_description,
+        )))?;
+        let contact = prompts.prompt_contact(default_contact.as_deref())?;
+        (name, version, description, status, contact)
+    } else {
+        let contact = default_contact.ok_or_else(|| {
+            anyhow::anyhow!(
+                "No incident response contact available. Pass --contact, set `git config user.email`, or run without --non-interactive"
+            )
+        })?;
+        (
+            detected_name,
+            detected_version,
+            detected_description,
+            options.status.clone().unwrap_or(AgentStatus::Alpha),
+            contact,
+        )
+    };
+
+    // Generate fingerprint
+    status!("✓ Generating codebase fingerprint...");
+    let fingerprint_options = init_fingerprint_options(options, &config, &base_dir);
+    let fingerprint_result = generate_fingerprint(&fingerprint_options)?;
+
+    // Template defaults for everything that isn't identity or detected
+    let mut manifest = generate_complete_defaults(name, version, architecture, deployment_type);
+    apply_model_overrides(&mut manifest, &detection_results);
+    manifest.agent_description = description;
+    manifest.current_status = status;
+    manifest.incident_response_slo =
+        ManifestTemplates::incident_response_slo_for_status(&manifest.current_status);
+    manifest.incident_response_contact = contact;
+    manifest.first_release_date = detection_results
+        .first_release_date
+        .unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string());
+
+    // Detected technical profile
+    if !detection_results.modality_support.is_empty() {
+        manifest.modality_support = detection_results.modality_support;
+    }
+    if !detection_results.language_capabilities.is_empty() {
+        manifest.language_capabilities = detection_results.language_capabilities;
+    }
+    if !detection_results.data_categories.is_empty() {
+        manifest.data_categories_processed = detection_results.data_categories;
+    }
+    if detection_results.deployment_context.is_some() {
+        manifest.deployment_context = detection_results.deployment_context;
+    }
+
+    manifest.system_config_fingerprint = fingerprint_result.hash.clone();
+    manifest.fingerprint_metadata = Some(fingerprint_result.metadata);
+    manifest.system_config_last_updated = Utc::now().format("%Y-%m-%d").to_string();
+    set_manifest_tools(&mut manifest, file_tools);
+
+    if let Some(dev_id) = options.developer_id {
+        manifest.developer_credential_id = dev_id;
+        manifest.developer_credential_verified = options.developer_credential_verified;
+    }
+    text_overrides.apply_to_manifest(&mut manifest);
+
+    manifest.metadata = Some(GenerationMetadata {
+        generated_by: format!("beltic v{}", env!("CARGO_PKG_VERSION")),
+        generated_at: Utc::now(),
+        detected_values,
+        auto_detected: detection_results.detection_sources,
+    });
+
+    print_risk_hint(
+        &assess_manifest(&manifest),
+        &convert_kyb_tier(&manifest.kyb_tier_required),
+    );
+
     if options.validate {
         let validation_result = validate_manifest(&manifest);
         if !validation_result.is_valid {
-            println!("\n⚠ Validation warnings:");
-            for warning in &validation_result.warnings {
-                println!("  • {}", warning);
-            }
             for error in &validation_result.errors {
                 println!("  ✗ {}", error);
             }
+            for field in &validation_result.missing_fields {
+                println!("  ✗ Missing required field: {}", field);
+            }
+            anyhow::bail!("Manifest validation failed. Please fix errors and try again.");
         }
+        check_fail_on_warning(options, &validation_result)?;
     }
 
-    // Write manifest
-    let json = serde_json::to_string_pretty(&manifest)?;
-    fs::write(&output_path, json)?;
+    write_document(&output_path, &manifest, options.format)?;
 
-    println!("✓ Created {}", output_path.display());
+    status!(
+        "✓ Generated fingerprint ({} files, {})",
+        fingerprint_result.file_count,
+        fingerprint_result.hash
+    );
+    status!("✓ Created {}", style(output_path.display()).green());
 
-    // Write .beltic.yaml if it doesn't exist
     let beltic_yaml_path = base_dir.join(".beltic.yaml");
     if !beltic_yaml_path.exists() {
         config.save_to_file(&beltic_yaml_path)?;
-        println!("✓ Created {}", beltic_yaml_path.display());
+        status!("✓ Created {}", beltic_yaml_path.display());
     }
 
-    println!("\nNext steps:");
-    println!("1. Review {} and adjust as needed", output_path.display());
-    if manifest.developer_credential_id == Uuid::nil() {
-        println!("2. Obtain developer credential ID from Beltic platform");
+    println!(
+        "\n{}",
+        style("Defaulted from templates (review before production):").yellow()
+    );
+    for field in MINIMAL_DEFAULTED_FIELDS {
+        println!("  • {}", field);
     }
-    println!("3. Run 'beltic fingerprint' after code changes");
-    println!("4. Sign manifest with 'beltic sign'");
+    println!("Safety metrics are evaluated and set by the Beltic platform.");
 
     Ok(())
 }
 
+/// Update an existing manifest (`init --update`): re-run detection and
+/// fingerprinting, and replace detected fields only where the manifest still
+/// holds the value detection last wrote, so manual edits survive
+fn update_manifest(options: &InitOptions) -> Result<()> {
+    use console::style;
+
+    let base_dir = resolve_base_dir(options.base_dir.as_deref())?;
+    let manifest_path = options
+        .output_path
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| default_manifest_path(&base_dir));
+
+    if !manifest_path.exists() {
+        anyhow::bail!(
+            "Manifest not found at {}. Run `beltic init` first.",
+            manifest_path.display()
+        );
+    }
+    let mut manifest = read_document(&manifest_path, options.format)?;
+    if !manifest.is_object() {
+        anyhow::bail!("{} is not a manifest object", manifest_path.display());
+    }
+
+    status!("✓ Updating {}...", manifest_path.display());
+
+    let config = load_or_create_config(&base_dir, options)?;
+    let detection_results =
+        detect_with_overrides(&base_dir, options.agent_path.as_deref(), &config)?;
+    let previous = recorded_values(&manifest);
+    let fresh = detected_values(&detection_results);
+    let report = merge_detected(&mut manifest, &previous, &fresh);
+
+    status!("✓ Generating codebase fingerprint...");
+    let fingerprint_options = init_fingerprint_options(options, &config, &base_dir);
+    let fingerprint_result = generate_fingerprint(&fingerprint_options)?;
+    let fingerprint_changed = manifest
+        .get("systemConfigFingerprint")
+        .and_then(|f| f.as_str())
+        != Some(fingerprint_result.hash.as_str());
+
+    let metadata = GenerationMetadata {
+        generated_by: format!("beltic v{}", env!("CARGO_PKG_VERSION")),
+        generated_at: Utc::now(),
+        auto_detected: detection_results.detection_sources,
+        detected_values: fresh,
+    };
+    if let Some(obj) = manifest.as_object_mut() {
+        obj.insert(
+            "systemConfigFingerprint".to_string(),
+            serde_json::json!(fingerprint_result.hash),
+        );
+        obj.insert(
+            "fingerprintMetadata".to_string(),
+            serde_json::to_value(fingerprint_result.metadata)?,
+        );
+        obj.insert(
+            "systemConfigLastUpdated".to_string(),
+            serde_json::json!(Utc::now().format("%Y-%m-%d").to_string()),
+        );
+        obj.insert("_metadata".to_string(), serde_json::to_value(metadata)?);
+    }
+
+    write_document(&manifest_path, &manifest, options.format)?;
+
+    if report.changed.is_empty() {
+        status!("✓ No detected fields changed");
+    } else {
+        status!("✓ Updated {} field(s):", report.changed.len());
+        for change in &report.changed {
+            let from = change
+                .from
+                .as_ref()
+                .map_or_else(|| "(unset)".to_string(), |v| v.to_string());
+            status!(
+                "  • {}: {} → {}",
+                change.field,
+                from,
+                style(&change.to).green()
+            );
+        }
+    }
+    if !report.kept.is_empty() {
+        status!(
+            "✓ Kept {} manually edited field(s): {}",
+            report.kept.len(),
+            report.kept.join(", ")
+        );
+        if previous.is_empty() {
+            status!("  (no detected values were recorded; later updates can tell edits apart)");
+        }
+    }
+    if fingerprint_changed {
+        status!("✓ New fingerprint: {}", fingerprint_result.hash);
+    }
+    status!("✓ Updated {}", manifest_path.display());
+
+    Ok(())
+}
+
+/// The git `user.email` for the repository, used as a default contact
+fn git_user_email(base_dir: &Path) -> Option<String> {
+    let output = std::process::Command::new("git")
+        .current_dir(base_dir)
+        .args(["config", "user.email"])
+        .output()
+        .ok()?;
+
+    let email = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && email.contains('@')).then_some(email)
+}
+
+/// Fingerprint options for init, with a progress bar on terminals
+fn init_fingerprint_options(
+    options: &InitOptions,
+    config: &BelticConfig,
+    base_dir: &Path,
+) -> FingerprintOptions {
+    agent_fingerprint_options(options, config, base_dir).with_terminal_progress()
+}
+
+/// Fingerprint options for the agent: explicit include/exclude patterns win
+/// over the config's paths, which are relative to the agent directory
+fn agent_fingerprint_options(
+    options: &InitOptions,
+    config: &BelticConfig,
+    base_dir: &Path,
+) -> FingerprintOptions {
+    let agent_dir = options.agent_path.as_ref().map(|p| base_dir.join(p));
+    let base_dir = agent_dir.as_deref().unwrap_or(base_dir);
+    let fingerprint_options = if let Some(ref includes) = options.include_patterns {
+        FingerprintOptions {
+            include_patterns: includes.clone(),
+            exclude_patterns: options.exclude_patterns.clone().unwrap_or_default(),
+            root_path: base_dir.to_path_buf(),
+            include_dependencies: true,
+            respect_gitignore: true,
+            progress: None,
+            use_cache: false,
+            exclude_binary: false,
+            buffer_size: DEFAULT_HASH_BUFFER_SIZE,
+            mmap_threshold: None,
+        }
+    } else {
+        FingerprintOptions::from_path_config(&config.agent.paths, base_dir.to_path_buf())
+    };
+
+    FingerprintOptions {
+        exclude_binary: options.exclude_binary,
+        ..fingerprint_options
+    }
+}
+
+/// Boilerplate text overrides for init: files in `--template-dir`, then the
+/// inline text in `.beltic.yaml`'s `templates:`, then files in its `dir`;
+/// fields none of them set keep the built-in templates
+fn load_text_overrides(
+    options: &InitOptions,
+    config: &BelticConfig,
+    base_dir: &Path,
+) -> Result<TextOverrides> {
+    let mut overrides = match &options.template_dir {
+        Some(dir) => TextOverrides::from_dir(dir)?,
+        None => TextOverrides::default(),
+    };
+    if let Some(templates) = &config.templates {
+        overrides = overrides.or(TextOverrides {
+            deprecation_policy: templates.deprecation_policy.clone(),
+            fail_safe_behavior: templates.fail_safe_behavior.clone(),
+            monitoring_coverage: templates.monitoring_coverage.clone(),
+        });
+        if let Some(dir) = &templates.dir {
+            overrides = overrides.or(TextOverrides::from_dir(&base_dir.join(dir))?);
+        }
+    }
+    Ok(overrides)
+}
+
+/// Tools from `--tools`, validated before anything else runs; an empty list
+/// declares no tools
+fn load_init_tools(options: &InitOptions) -> Result<Option<Vec<Tool>>> {
+    let Some(path) = &options.tools_file else {
+        return Ok(None);
+    };
+    let tools = load_tools_file(path)?;
+    Ok(Some(tools).filter(|tools| !tools.is_empty()))
+}
+
+/// Set a manifest's tools from `--tools`, stamping `toolsLastAudited`
+fn set_manifest_tools(manifest: &mut AgentManifest, tools: Option<Vec<Tool>>) {
+    if let Some(tools) = tools {
+        manifest.tools_list = Some(tools);
+        manifest.tools_last_audited = Some(Utc::now().format("%Y-%m-%d").to_string());
+    }
+}
+
+/// Expand `${VAR}` references in an optional user-supplied value
+fn expand_option(field: &str, value: &Option<String>) -> Result<Option<String>> {
+    value
+        .as_deref()
+        .map(|value| expand_env(field, value))
+        .transpose()
+}
+
+/// Directory a command runs from: `base_dir` (relative to the current
+/// directory) when given, else the current directory. Detection,
+/// fingerprinting, config discovery and default output paths start here;
+/// explicitly given files such as `--output` or `--config` stay relative to
+/// the current directory.
+pub fn resolve_base_dir(base_dir: Option<&Path>) -> Result<PathBuf> {
+    let cwd = std::env::current_dir().context("failed to get current directory")?;
+    let Some(base_dir) = base_dir else {
+        return Ok(cwd);
+    };
+    let dir = cwd.join(base_dir);
+    if !dir.is_dir() {
+        anyhow::bail!("Base directory {} is not a directory", base_dir.display());
+    }
+    Ok(dir)
+}
+
+/// Resolve `--agent-path` against `base_dir`: the directory must exist inside
+/// `base_dir`. Returns the path relative to `base_dir` with `/` separators.
+pub fn resolve_agent_path(base_dir: &Path, agent_path: &Path) -> Result<String> {
+    let dir = base_dir.join(agent_path);
+    if !dir.is_dir() {
+        anyhow::bail!("Agent path {} is not a directory", dir.display());
+    }
+
+    let root = base_dir.canonicalize()?;
+    let dir = dir.canonicalize()?;
+    let relative = dir.strip_prefix(&root).map_err(|_| {
+        anyhow::anyhow!(
+            "Agent path {} is outside {}",
+            agent_path.display(),
+            base_dir.display()
+        )
+    })?;
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    Ok(if relative.is_empty() {
+        ".".to_string()
+    } else {
+        relative
+    })
+}
+
+/// Auto-detect project information for the agent: `base_dir` itself, or the
+/// `agent_path` subdirectory with git details from `base_dir`
+fn detect_agent(base_dir: &Path, agent_path: Option<&str>) -> Result<DetectionResults> {
+    match agent_path {
+        Some(agent_path) => detect_agent_in_repo(base_dir, agent_path),
+        None => detect_project_info(base_dir),
+    }
+}
+
+/// Auto-detect project information, then apply `.beltic.yaml` overrides
+fn detect_with_overrides(
+    base_dir: &Path,
+    agent_path: Option<&str>,
+    config: &BelticConfig,
+) -> Result<DetectionResults> {
+    let mut detection_results = detect_agent(base_dir, agent_path)?;
+    if let Some(overrides) = &config.overrides {
+        detection_results.apply_overrides(overrides);
+    }
+    Ok(detection_results)
+}
+
+/// Fields `--strict-detection` requires for a manifest
+const STRICT_MANIFEST_FIELDS: &[&str] = &[
+    "architecture_type",
+    "model_provider",
+    "model_family",
+    "deployment_type",
+];
+
+/// Fields `--strict-detection` requires for a credential, which has no
+/// deployment type
+const STRICT_CREDENTIAL_FIELDS: &[&str] = &["architecture_type", "model_provider", "model_family"];
+
+/// With `--strict-detection`, fail listing each of `fields` that was neither
+/// detected nor supplied, rather than letting it fall back to a default
+fn check_strict_detection(
+    options: &InitOptions,
+    detection_results: &DetectionResults,
+    fields: &[&str],
+) -> Result<()> {
+    if !options.strict_detection {
+        return Ok(());
+    }
+    let missing: Vec<String> = fields
+        .iter()
+        .filter(|field| !detection_results.is_confident(field))
+        .filter(|field| !(**field == "deployment_type" && options.deployment_type.is_some()))
+        .map(|field| match *field {
+            "deployment_type" => {
+                "  - deployment_type: pass --type or set overrides.deployment_type in .beltic.yaml"
+                    .to_string()
+            }
+            field => format!("  - {}: set overrides.{} in .beltic.yaml", field, field),
+        })
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    anyhow::bail!(
+        "--strict-detection: could not detect {} key field(s):\n{}",
+        missing.len(),
+        missing.join("\n")
+    )
+}
+
+/// With `--fail-on-warning`, fail listing every validation warning so the
+/// manifest is not written. The safety-metrics placeholder is exempt, since
+/// every manifest has it until the platform evaluates the agent.
+fn check_fail_on_warning(
+    options: &InitOptions,
+    validation_result: &ValidationResult,
+) -> Result<()> {
+    if !options.fail_on_warning {
+        return Ok(());
+    }
+    let warnings: Vec<String> = validation_result
+        .issues
+        .iter()
+        .filter(|issue| issue.level == IssueLevel::Warning)
+        .filter(|issue| issue.rule_id != RULE_SAFETY_METRICS)
+        .map(|issue| format!("  - {}", issue.message))
+        .collect();
+    if warnings.is_empty() {
+        return Ok(());
+    }
+    Err(BelticError::Validation(format!(
+        "--fail-on-warning: manifest not written, {} validation warning(s):\n{}",
+        warnings.len(),
+        warnings.join("\n")
+    ))
+    .into())
+}
+
+/// Apply model provider/family overrides on top of template defaults
+fn apply_model_overrides(manifest: &mut AgentManifest, detection_results: &DetectionResults) {
+    if let Some(provider) = &detection_results.model_provider {
+        manifest.primary_model_provider = provider.clone();
+    }
+    if let Some(family) = &detection_results.model_family {
+        manifest.primary_model_family = family.clone();
+        manifest.model_context_window = ManifestTemplates::default_context_window(family);
+    }
+}
+
+/// Print the advisory risk hint when it suggests a higher KYB tier than declared.
+/// Returns whether anything was printed.
+fn print_risk_hint(assessment: &RiskAssessment, declared_tier: &CredKybTier) -> bool {
+    if !tier_below(declared_tier, &assessment.kyb_tier) {
+        return false;
+    }
+
+    println!("\n{}", console::style("Risk hint (advisory):").yellow());
+    println!(
+        "  kybTierRequired: {} declared, {} suggested (safety rating: {})",
+        label(declared_tier),
+        label(&assessment.kyb_tier),
+        label(&assessment.safety_rating)
+    );
+    for finding in &assessment.findings {
+        println!(
+            "  • {}: {} [{}]",
+            finding.subject, finding.reason, finding.rule
+        );
+    }
+    true
+}
+
 /// Helper function to load or create config
 fn load_or_create_config(base_dir: &Path, options: &InitOptions) -> Result<BelticConfig> {
     if let Some(config_path) = &options.config_path {
         let path = Path::new(config_path);
         if path.exists() {
-            BelticConfig::from_file(path)
+            Ok(BelticConfig::from_file(path)?)
         } else {
             anyhow::bail!("Config file not found: {}", config_path);
         }
@@ -486,7 +1474,8 @@ fn load_or_create_config(base_dir: &Path, options: &InitOptions) -> Result<Belti
         // Create default config based on deployment type
         let deployment_type = options.deployment_type.as_deref().unwrap_or("standalone");
         Ok(match deployment_type {
-            "monorepo" => {
+            // With --agent-path, patterns are relative to the agent directory
+            "monorepo" if options.agent_path.is_none() => {
                 let agent_path = base_dir
                     .file_name()
                     .and_then(|n| n.to_str())
@@ -500,10 +1489,66 @@ fn load_or_create_config(base_dir: &Path, options: &InitOptions) -> Result<Belti
     }
 }
 
+/// Default manifest path: the first of agent-manifest.{json,yaml,yml} that
+/// exists in `base_dir`, else agent-manifest.json
+pub fn default_manifest_path(base_dir: &Path) -> std::path::PathBuf {
+    ["json", "yaml", "yml"]
+        .iter()
+        .map(|ext| base_dir.join(format!("agent-manifest.{}", ext)))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| base_dir.join("agent-manifest.json"))
+}
+
+/// Flags for the `fingerprint` command's update and verify modes
+#[derive(Debug, Clone, Default)]
+pub struct FingerprintFlags {
+    /// Reuse cached per-file hashes from `.beltic/`
+    pub use_cache: bool,
+    /// Skip binary files
+    pub exclude_binary: bool,
+    /// Config file; the nearest `.beltic.yaml` when unset
+    pub config_path: Option<PathBuf>,
+    /// Agent subdirectory to fingerprint instead of the current directory
+    pub agent_path: Option<PathBuf>,
+    /// Read buffer for hashing, in bytes; the default when unset
+    pub buffer_size: Option<usize>,
+    /// Memory-map files of at least [`DEFAULT_MMAP_THRESHOLD`] bytes
+    pub mmap: bool,
+    /// Directory to run from instead of the current directory (see
+    /// [`resolve_base_dir`])
+    pub base_dir: Option<PathBuf>,
+}
+
+impl FingerprintFlags {
+    /// Fingerprint options for a run from `base_dir`: the config's paths,
+    /// rooted at the agent directory
+    pub fn options(&self, base_dir: &Path) -> Result<FingerprintOptions> {
+        let config = match &self.config_path {
+            Some(path) => BelticConfig::from_file(path)
+                .with_context(|| format!("failed to load config {}", path.display()))?,
+            None => BelticConfig::find_and_load(base_dir)?
+                .unwrap_or_else(BelticConfig::default_standalone),
+        };
+        let root = match &self.agent_path {
+            Some(agent_path) => base_dir.join(resolve_agent_path(base_dir, agent_path)?),
+            None => base_dir.to_path_buf(),
+        };
+
+        let mut options = FingerprintOptions::from_path_config(&config.agent.paths, root);
+        options.use_cache = self.use_cache;
+        options.exclude_binary = self.exclude_binary;
+        if let Some(buffer_size) = self.buffer_size {
+            options.buffer_size = buffer_size;
+        }
+        options.mmap_threshold = self.mmap.then_some(DEFAULT_MMAP_THRESHOLD);
+        Ok(options)
+    }
+}
+
 /// Update fingerprint in existing manifest
-pub fn update_fingerprint(manifest_path: Option<&str>) -> Result<()> {
-    let base_dir = std::env::current_dir()?;
-    let default_path = base_dir.join("agent-manifest.json");
+pub fn update_fingerprint(manifest_path: Option<&str>, flags: FingerprintFlags) -> Result<()> {
+    let base_dir = resolve_base_dir(flags.base_dir.as_deref())?;
+    let default_path = default_manifest_path(&base_dir);
     let manifest_path = manifest_path.map(Path::new).unwrap_or(&default_path);
 
     if !manifest_path.exists() {
@@ -511,8 +1556,7 @@ pub fn update_fingerprint(manifest_path: Option<&str>) -> Result<()> {
     }
 
     // Read existing manifest
-    let content = fs::read_to_string(&manifest_path)?;
-    let mut manifest: serde_json::Value = serde_json::from_str(&content)?;
+    let mut manifest = read_document(manifest_path, None)?;
 
     // Get current fingerprint
     let current_fingerprint = manifest
@@ -520,17 +1564,12 @@ pub fn update_fingerprint(manifest_path: Option<&str>) -> Result<()> {
         .and_then(|f| f.as_str())
         .map(|s| s.to_string());
 
-    println!("✓ Current fingerprint: {:?}", current_fingerprint);
+    status!("✓ Current fingerprint: {:?}", current_fingerprint);
 
     // Generate new fingerprint
-    println!("✓ Generating new fingerprint...");
+    status!("✓ Generating new fingerprint...");
 
-    // Try to load config
-    let config =
-        BelticConfig::find_and_load(&base_dir)?.unwrap_or_else(BelticConfig::default_standalone);
-
-    let fingerprint_options =
-        FingerprintOptions::from_path_config(&config.agent.paths, base_dir.clone());
+    let fingerprint_options = flags.options(&base_dir)?.with_terminal_progress();
 
     let fingerprint_result = generate_fingerprint(&fingerprint_options)?;
 
@@ -551,25 +1590,24 @@ pub fn update_fingerprint(manifest_path: Option<&str>) -> Result<()> {
     }
 
     // Write updated manifest
-    let updated = serde_json::to_string_pretty(&manifest)?;
-    fs::write(&manifest_path, updated)?;
+    write_document(manifest_path, &manifest, None)?;
 
-    println!("✓ New fingerprint: {}", fingerprint_result.hash);
-    println!("✓ Updated {}", manifest_path.display());
+    status!("✓ New fingerprint: {}", fingerprint_result.hash);
+    status!("✓ Updated {}", manifest_path.display());
 
     if current_fingerprint.as_deref() != Some(&fingerprint_result.hash) {
-        println!("\nNote: Remember to increment agentVersion if behavior changed");
+        status!("\nNote: Remember to increment agentVersion if behavior changed");
     }
 
     Ok(())
 }
 
 /// Verify fingerprint without updating the manifest
-pub fn verify_fingerprint(manifest_path: Option<&str>) -> Result<()> {
+pub fn verify_fingerprint(manifest_path: Option<&str>, flags: FingerprintFlags) -> Result<()> {
     use console::style;
 
-    let base_dir = std::env::current_dir()?;
-    let default_path = base_dir.join("agent-manifest.json");
+    let base_dir = resolve_base_dir(flags.base_dir.as_deref())?;
+    let default_path = default_manifest_path(&base_dir);
     let manifest_path = manifest_path.map(Path::new).unwrap_or(&default_path);
 
     if !manifest_path.exists() {
@@ -577,8 +1615,7 @@ pub fn verify_fingerprint(manifest_path: Option<&str>) -> Result<()> {
     }
 
     // Read existing manifest
-    let content = fs::read_to_string(&manifest_path)?;
-    let manifest: serde_json::Value = serde_json::from_str(&content)?;
+    let manifest = read_document(manifest_path, None)?;
 
     // Get stored fingerprint
     let stored_fingerprint = manifest
@@ -586,28 +1623,23 @@ pub fn verify_fingerprint(manifest_path: Option<&str>) -> Result<()> {
         .and_then(|f| f.as_str())
         .ok_or_else(|| anyhow::anyhow!("No fingerprint found in manifest"))?;
 
-    println!(
+    status!(
         "📋 Stored fingerprint: {}",
         style(stored_fingerprint).cyan()
     );
 
     // Generate new fingerprint
-    println!("🔍 Generating current fingerprint...");
+    status!("🔍 Generating current fingerprint...");
 
-    // Try to load config
-    let config =
-        BelticConfig::find_and_load(&base_dir)?.unwrap_or_else(BelticConfig::default_standalone);
-
-    let fingerprint_options =
-        FingerprintOptions::from_path_config(&config.agent.paths, base_dir.clone());
+    let fingerprint_options = flags.options(&base_dir)?.with_terminal_progress();
 
     let fingerprint_result = generate_fingerprint(&fingerprint_options)?;
 
-    println!(
+    status!(
         "📋 Current fingerprint:  {}",
         style(&fingerprint_result.hash).cyan()
     );
-    println!("📊 Files processed: {}", fingerprint_result.file_count);
+    status!("📊 Files processed: {}", fingerprint_result.file_count);
 
     // Compare fingerprints
     if stored_fingerprint == fingerprint_result.hash {
@@ -615,17 +1647,20 @@ pub fn verify_fingerprint(manifest_path: Option<&str>) -> Result<()> {
             "\n{}",
             style("✓ VERIFIED: Fingerprints match!").green().bold()
         );
-        println!("  The codebase has not changed since the manifest was created.");
+        status!("  The codebase has not changed since the manifest was created.");
     } else {
         println!(
             "\n{}",
             style("✗ MISMATCH: Fingerprints differ!").red().bold()
         );
-        println!("  The codebase has changed since the manifest was created.");
-        println!("\n{}", style("Recommendations:").yellow());
-        println!("  1. Review what has changed");
-        println!("  2. Run 'beltic fingerprint' to update the manifest");
-        println!("  3. Consider incrementing the agent version if behavior changed");
+        status!("  The codebase has changed since the manifest was created.");
+        if let Some(note) = normalization_note(&manifest) {
+            println!("  {}", style(note).yellow());
+        }
+        status!("\n{}", style("Recommendations:").yellow());
+        status!("  1. Review what has changed");
+        status!("  2. Run 'beltic fingerprint' to update the manifest");
+        status!("  3. Consider incrementing the agent version if behavior changed");
 
         // Exit with error code
         anyhow::bail!("Fingerprint verification failed");
@@ -634,16 +1669,45 @@ pub fn verify_fingerprint(manifest_path: Option<&str>) -> Result<()> {
     Ok(())
 }
 
+/// Explain a mismatch caused by a different combination scheme rather than
+/// by changed files: `None` when the manifest records the current scheme
+fn normalization_note(manifest: &serde_json::Value) -> Option<String> {
+    let metadata = manifest.get("fingerprintMetadata")?;
+    let generated_by = metadata
+        .get("generatedBy")
+        .and_then(|v| v.as_str())
+        .unwrap_or("an older beltic release");
+    match metadata.get("normalization").and_then(|v| v.as_str()) {
+        Some(recorded) if recorded == NORMALIZATION => None,
+        Some(recorded) => Some(format!(
+            "The stored fingerprint was made by {} with a different combination scheme ({}); this release uses {}. Run 'beltic fingerprint --explain' for details.",
+            generated_by,
+            recorded,
+            NORMALIZATION
+        )),
+        None => Some(format!(
+            "The stored fingerprint does not record its combination scheme (made by {}); this release uses {}.",
+            generated_by,
+            NORMALIZATION
+        )),
+    }
+}
+
 // === Credential Generation Functions ===
 
 /// Initialize a schema-compliant agent credential (non-interactive)
 pub fn init_credential(options: &InitOptions) -> Result<()> {
-    let base_dir = std::env::current_dir()?;
+    let base_dir = resolve_base_dir(options.base_dir.as_deref())?;
     let output_path = options
         .output_path
         .as_ref()
         .map(|p| Path::new(p).to_path_buf())
-        .unwrap_or_else(|| base_dir.join("agent-credential.json"));
+        .unwrap_or_else(|| {
+            base_dir.join(format!(
+                "agent-credential.{}",
+                options.format.unwrap_or_default().extension()
+            ))
+        });
 
     // Check if credential already exists
     if output_path.exists() && !options.force {
@@ -653,27 +1717,39 @@ pub fn init_credential(options: &InitOptions) -> Result<()> {
         );
     }
 
-    println!("Initializing agent credential...");
+    let issuer_did = expand_option("issuer DID", &options.issuer_did)?;
+    let contact = expand_option(
+        "incident response contact",
+        &options.incident_response_contact,
+    )?;
+    let revocation_list_url = expand_option("revocation list URL", &options.revocation_list_url)?;
+    let file_tools = load_init_tools(options)?;
+
+    status!("Initializing agent credential...");
 
     // Load or create config
     let config = if let Some(config_path) = &options.config_path {
         let path = Path::new(config_path);
         if path.exists() {
-            println!("  Found config file: {}", config_path);
+            status!("  Found config file: {}", config_path);
             BelticConfig::from_file(path)?
         } else {
             anyhow::bail!("Config file not found: {}", config_path);
         }
     } else if let Some(config) = BelticConfig::find_and_load(&base_dir)? {
-        println!("  Found .beltic.yaml configuration");
+        status!("  Found .beltic.yaml configuration");
         config
     } else {
         BelticConfig::default_standalone()
     };
 
+    let text_overrides = load_text_overrides(options, &config, &base_dir)?;
+
     // Auto-detect project information
-    println!("  Detecting project information...");
-    let detection_results = detect_project_info(&base_dir)?;
+    status!("  Detecting project information...");
+    let detection_results =
+        detect_with_overrides(&base_dir, options.agent_path.as_deref(), &config)?;
+    check_strict_detection(options, &detection_results, STRICT_CREDENTIAL_FIELDS)?;
 
     let name = detection_results.project_name.clone().unwrap_or_else(|| {
         base_dir
@@ -688,31 +1764,22 @@ pub fn init_credential(options: &InitOptions) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "0.1.0".to_string());
 
-    println!("  Agent name: {}", name);
-    println!("  Version: {}", version);
+    status!("  Agent name: {}", name);
+    status!("  Version: {}", version);
 
     // Generate fingerprint
-    println!("  Generating codebase fingerprint...");
-    let fingerprint_options = if let Some(ref includes) = options.include_patterns {
-        FingerprintOptions {
-            include_patterns: includes.clone(),
-            exclude_patterns: options.exclude_patterns.clone().unwrap_or_default(),
-            root_path: base_dir.clone(),
-            include_dependencies: true,
-            respect_gitignore: true,
-        }
-    } else {
-        FingerprintOptions::from_path_config(&config.agent.paths, base_dir.clone())
-    };
+    status!("  Generating codebase fingerprint...");
+    let fingerprint_options = init_fingerprint_options(options, &config, &base_dir);
 
     let fingerprint_result = generate_fingerprint(&fingerprint_options)?;
-    println!(
+    status!(
         "  Fingerprint: {} ({} files)",
-        fingerprint_result.hash, fingerprint_result.file_count
+        fingerprint_result.hash,
+        fingerprint_result.file_count
     );
 
     // Determine issuer DID
-    let issuer_did = options.issuer_did.clone().unwrap_or_else(|| {
+    let issuer_did = issuer_did.unwrap_or_else(|| {
         format!(
             "did:web:self.{}.local",
             name.to_lowercase().replace(' ', "-")
@@ -726,6 +1793,35 @@ pub fn init_credential(options: &InitOptions) -> Result<()> {
         fingerprint_result.hash,
         issuer_did,
     );
+    // Org-wide text first, so a preset can still replace it
+    text_overrides.apply_to_credential(&mut credential);
+
+    if let Some(name) = &options.preset {
+        let preset = PresetStore::new(&base_dir).load(name)?;
+        let applied = preset::apply(&mut credential, &preset)
+            .with_context(|| format!("failed to apply preset '{}'", name))?;
+        status!("  Applied preset '{}' ({} fields)", name, applied.len());
+    }
+
+    if let Some(status) = &options.status {
+        credential.current_status = convert_agent_status(status);
+    }
+    if let Some(contact) = contact {
+        credential.incident_response_contact = contact;
+    }
+    if let Some(url) = revocation_list_url {
+        credential.revocation_list_url = url;
+    }
+    if let Some(valid_for) = &options.valid_for {
+        credential.set_valid_for(valid_for)?;
+    }
+    if let Some(tools) = file_tools {
+        let count = tools.len();
+        let tools = serde_json::from_value(serde_json::to_value(tools)?)
+            .context("failed to convert tools for the credential")?;
+        credential.set_tools(tools, &Utc::now().format("%Y-%m-%d").to_string());
+        status!("  Tools: {} declared", count);
+    }
 
     // Apply detected values
     if let Some(desc) = detection_results.project_description {
@@ -738,6 +1834,15 @@ pub fn init_credential(options: &InitOptions) -> Result<()> {
         credential.first_release_date = date;
     }
 
+    // Model overrides from .beltic.yaml
+    if let Some(provider) = &detection_results.model_provider {
+        credential.primary_model_provider = ModelProvider::from_display_name(provider);
+    }
+    if let Some(family) = &detection_results.model_family {
+        credential.primary_model_family = ModelFamily::from_display_name(family);
+        credential.model_context_window = ManifestTemplates::default_context_window(family);
+    }
+
     // Convert architecture type
     if let Some(arch) = detection_results.architecture_type {
         credential.architecture_type = convert_architecture_type(&arch);
@@ -761,29 +1866,8 @@ pub fn init_credential(options: &InitOptions) -> Result<()> {
             .collect();
 
         // Update compliance certifications based on data categories
-        // Schema requires specific certs for certain data types
-        let mut certs = vec![ComplianceCert::GdprCompliant];
-
-        // PHI data requires HIPAA
-        if converted_categories.contains(&CredDataCategory::Phi) {
-            certs.push(ComplianceCert::Hipaa);
-        }
-
-        // Financial data requires PCI-DSS or SOC2 Type 2
-        if converted_categories.contains(&CredDataCategory::Financial) {
-            certs.push(ComplianceCert::PciDss);
-        }
-
-        // PII data with SOC2 Type 1 for general attestation
-        if converted_categories.contains(&CredDataCategory::Pii) {
-            certs.push(ComplianceCert::Soc2Type1);
-        }
-
-        // Deduplicate
-        certs.sort_by(|a, b| format!("{:?}", a).cmp(&format!("{:?}", b)));
-        certs.dedup();
-
-        credential.compliance_certifications = Some(certs);
+        credential.compliance_certifications =
+            Some(required_compliance_certs(&converted_categories));
         credential.data_categories_processed = converted_categories;
     }
 
@@ -795,19 +1879,36 @@ pub fn init_credential(options: &InitOptions) -> Result<()> {
     // Apply developer ID if provided
     if let Some(dev_id) = options.developer_id {
         credential.developer_credential_id = dev_id;
+        credential.developer_credential_verified = options.developer_credential_verified;
+    }
+
+    if options.deterministic_id {
+        credential.use_deterministic_ids();
+        status!(
+            "  Deterministic IDs: agent {}, credential {}",
+            credential.agent_id,
+            credential.credential_id
+        );
     }
 
+    print_risk_hint(
+        &assess(
+            credential.tools_list.as_deref().unwrap_or_default(),
+            &credential.data_categories_processed,
+        ),
+        &credential.kyb_tier_required,
+    );
+
     // Write credential
-    let json = serde_json::to_string_pretty(&credential)?;
-    fs::write(&output_path, json)?;
+    write_document(&output_path, &credential, options.format)?;
 
-    println!("\nCreated {}", output_path.display());
-    println!("\nNext steps:");
+    status!("\nCreated {}", output_path.display());
+    status!("\nNext steps:");
     if credential.developer_credential_id == Uuid::nil() {
-        println!("1. Obtain developer credential from Beltic or create self-signed");
-        println!("2. Run: beltic init --developer-id <credential-id>");
+        status!("1. Obtain developer credential from Beltic or create self-signed");
+        status!("2. Run: beltic init --developer-id <credential-id>");
     }
-    println!(
+    status!(
         "3. Sign credential: beltic sign --payload {}",
         output_path.display()
     );
@@ -816,7 +1917,7 @@ pub fn init_credential(options: &InitOptions) -> Result<()> {
     let beltic_yaml_path = base_dir.join(".beltic.yaml");
     if !beltic_yaml_path.exists() {
         config.save_to_file(&beltic_yaml_path)?;
-        println!("Created {}", beltic_yaml_path.display());
+        status!("Created {}", beltic_yaml_path.display());
     }
 
     Ok(())
@@ -824,6 +1925,32 @@ pub fn init_credential(options: &InitOptions) -> Result<()> {
 
 // === Type conversion helpers ===
 
+/// Compliance certifications the credential schema requires for the data
+/// categories processed
+fn required_compliance_certs(categories: &[CredDataCategory]) -> Vec<CredComplianceCert> {
+    let mut certs = vec![CredComplianceCert::GdprCompliant];
+
+    // PHI data requires HIPAA
+    if categories.contains(&CredDataCategory::Phi) {
+        certs.push(CredComplianceCert::Hipaa);
+    }
+
+    // Financial data requires PCI-DSS or SOC2 Type 2
+    if categories.contains(&CredDataCategory::Financial) {
+        certs.push(CredComplianceCert::PciDss);
+    }
+
+    // PII data with SOC2 Type 1 for general attestation
+    if categories.contains(&CredDataCategory::Pii) {
+        certs.push(CredComplianceCert::Soc2Type1);
+    }
+
+    // Deduplicate
+    certs.sort_by(|a, b| format!("{:?}", a).cmp(&format!("{:?}", b)));
+    certs.dedup();
+    certs
+}
+
 fn convert_architecture_type(arch: &ArchitectureType) -> CredArchType {
     match arch {
         ArchitectureType::SingleAgent => CredArchType::SingleAgent,
@@ -836,6 +1963,18 @@ fn convert_architecture_type(arch: &ArchitectureType) -> CredArchType {
     }
 }
 
+fn convert_cred_architecture_type(arch: &CredArchType) -> ArchitectureType {
+    match arch {
+        CredArchType::SingleAgent => ArchitectureType::SingleAgent,
+        CredArchType::MultiAgent => ArchitectureType::MultiAgent,
+        CredArchType::Rag => ArchitectureType::Rag,
+        CredArchType::ToolUsing => ArchitectureType::ToolUsing,
+        CredArchType::AgenticWorkflow => ArchitectureType::AgenticWorkflow,
+        CredArchType::FineTuned => ArchitectureType::FineTuned,
+        CredArchType::Hybrid => ArchitectureType::Hybrid,
+    }
+}
+
 fn convert_modality(modality: &Modality) -> CredModality {
     match modality {
         Modality::Text => CredModality::Text,
@@ -847,6 +1986,47 @@ fn convert_modality(modality: &Modality) -> CredModality {
     }
 }
 
+fn convert_cred_modality(modality: &CredModality) -> Modality {
+    match modality {
+        CredModality::Text => Modality::Text,
+        CredModality::Image => Modality::Image,
+        CredModality::Audio => Modality::Audio,
+        CredModality::Video => Modality::Video,
+        CredModality::Code => Modality::Code,
+        CredModality::StructuredData => Modality::StructuredData,
+    }
+}
+
+fn convert_compliance_cert(cert: &ComplianceCert) -> CredComplianceCert {
+    match cert {
+        ComplianceCert::Soc2Type1 => CredComplianceCert::Soc2Type1,
+        ComplianceCert::Soc2Type2 => CredComplianceCert::Soc2Type2,
+        ComplianceCert::Iso27001 => CredComplianceCert::Iso27001,
+        ComplianceCert::Iso27017 => CredComplianceCert::Iso27017,
+        ComplianceCert::Iso27018 => CredComplianceCert::Iso27018,
+        ComplianceCert::Hipaa => CredComplianceCert::Hipaa,
+        ComplianceCert::PciDss => CredComplianceCert::PciDss,
+        ComplianceCert::Fedramp => CredComplianceCert::Fedramp,
+        ComplianceCert::Gdpr => CredComplianceCert::GdprCompliant,
+        ComplianceCert::Ccpa => CredComplianceCert::CcpaCompliant,
+    }
+}
+
+fn convert_cred_compliance_cert(cert: &CredComplianceCert) -> ComplianceCert {
+    match cert {
+        CredComplianceCert::Soc2Type1 => ComplianceCert::Soc2Type1,
+        CredComplianceCert::Soc2Type2 => ComplianceCert::Soc2Type2,
+        CredComplianceCert::Iso27001 => ComplianceCert::Iso27001,
+        CredComplianceCert::Iso27017 => ComplianceCert::Iso27017,
+        CredComplianceCert::Iso27018 => ComplianceCert::Iso27018,
+        CredComplianceCert::Hipaa => ComplianceCert::Hipaa,
+        CredComplianceCert::PciDss => ComplianceCert::PciDss,
+        CredComplianceCert::Fedramp => ComplianceCert::Fedramp,
+        CredComplianceCert::GdprCompliant => ComplianceCert::Gdpr,
+        CredComplianceCert::CcpaCompliant => ComplianceCert::Ccpa,
+    }
+}
+
 fn convert_data_category(cat: &DataCategory) -> CredDataCategory {
     match cat {
         DataCategory::Pii => CredDataCategory::Pii,
@@ -862,7 +2042,41 @@ fn convert_data_category(cat: &DataCategory) -> CredDataCategory {
     }
 }
 
-#[allow(dead_code)]
+fn convert_cred_data_category(cat: &CredDataCategory) -> DataCategory {
+    match cat {
+        CredDataCategory::Pii => DataCategory::Pii,
+        CredDataCategory::Phi => DataCategory::Phi,
+        CredDataCategory::Financial => DataCategory::Financial,
+        CredDataCategory::Biometric => DataCategory::Biometric,
+        CredDataCategory::Behavioral => DataCategory::Behavioral,
+        CredDataCategory::Authentication => DataCategory::Authentication,
+        CredDataCategory::Proprietary => DataCategory::Proprietary,
+        CredDataCategory::GovernmentId => DataCategory::GovernmentId,
+        CredDataCategory::ChildrenData => DataCategory::ChildrenData,
+        CredDataCategory::None => DataCategory::None,
+    }
+}
+
+fn convert_kyb_tier(tier: &KybTier) -> CredKybTier {
+    match tier {
+        KybTier::Tier0 => CredKybTier::Tier0,
+        KybTier::Tier1 => CredKybTier::Tier1,
+        KybTier::Tier2 => CredKybTier::Tier2,
+        KybTier::Tier3 => CredKybTier::Tier3,
+        KybTier::Tier4 => CredKybTier::Tier4,
+    }
+}
+
+fn convert_cred_kyb_tier(tier: &CredKybTier) -> KybTier {
+    match tier {
+        CredKybTier::Tier0 => KybTier::Tier0,
+        CredKybTier::Tier1 => KybTier::Tier1,
+        CredKybTier::Tier2 => KybTier::Tier2,
+        CredKybTier::Tier3 => KybTier::Tier3,
+        CredKybTier::Tier4 => KybTier::Tier4,
+    }
+}
+
 fn convert_agent_status(status: &AgentStatus) -> CredAgentStatus {
     match status {
         AgentStatus::Alpha => CredAgentStatus::Alpha,
@@ -870,7 +2084,38 @@ fn convert_agent_status(status: &AgentStatus) -> CredAgentStatus {
         AgentStatus::Production => CredAgentStatus::Production,
         AgentStatus::Deprecated => CredAgentStatus::Deprecated,
         AgentStatus::Retired => CredAgentStatus::Retired,
-        AgentStatus::Internal => CredAgentStatus::Alpha, // Map Internal to Alpha
+        AgentStatus::Internal => CredAgentStatus::Internal,
+    }
+}
+
+fn convert_cred_agent_status(status: &CredAgentStatus) -> AgentStatus {
+    match status {
+        CredAgentStatus::Alpha => AgentStatus::Alpha,
+        CredAgentStatus::Beta => AgentStatus::Beta,
+        CredAgentStatus::Production => AgentStatus::Production,
+        CredAgentStatus::Internal => AgentStatus::Internal,
+        CredAgentStatus::Deprecated => AgentStatus::Deprecated,
+        CredAgentStatus::Retired => AgentStatus::Retired,
+    }
+}
+
+fn convert_age_restriction(age: &AgeRestriction) -> CredAgeRestriction {
+    match age {
+        AgeRestriction::None => CredAgeRestriction::None,
+        AgeRestriction::ThirteenPlus => CredAgeRestriction::ThirteenPlus,
+        AgeRestriction::SixteenPlus => CredAgeRestriction::SixteenPlus,
+        AgeRestriction::EighteenPlus => CredAgeRestriction::EighteenPlus,
+        AgeRestriction::TwentyOnePlus => CredAgeRestriction::TwentyOnePlus,
+    }
+}
+
+fn convert_cred_age_restriction(age: &CredAgeRestriction) -> AgeRestriction {
+    match age {
+        CredAgeRestriction::None => AgeRestriction::None,
+        CredAgeRestriction::ThirteenPlus => AgeRestriction::ThirteenPlus,
+        CredAgeRestriction::SixteenPlus => AgeRestriction::SixteenPlus,
+        CredAgeRestriction::EighteenPlus => AgeRestriction::EighteenPlus,
+        CredAgeRestriction::TwentyOnePlus => AgeRestriction::TwentyOnePlus,
     }
 }
 
@@ -878,6 +2123,72 @@ fn convert_agent_status(status: &AgentStatus) -> CredAgentStatus {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_strict_detection_lists_missing_fields() {
+        let mut options = InitOptions {
+            strict_detection: true,
+            ..Default::default()
+        };
+        let mut results = DetectionResults {
+            architecture_type: Some(ArchitectureType::Rag),
+            deployment_type: Some(schema::DeploymentType::Standalone),
+            detection_sources: std::collections::HashMap::from([
+                ("architecture_type".to_string(), "README.md".to_string()),
+                (
+                    "deployment_type".to_string(),
+                    detector::DEFAULT_SOURCE.to_string(),
+                ),
+            ]),
+            ..Default::default()
+        };
+
+        let err = check_strict_detection(&options, &results, STRICT_MANIFEST_FIELDS)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("3 key field(s)"));
+        assert!(err.contains("model_provider"));
+        assert!(err.contains("model_family"));
+        assert!(err.contains("deployment_type: pass --type"));
+        assert!(!err.contains("architecture_type"));
+
+        options.deployment_type = Some("standalone".to_string());
+        results.apply_overrides(&config::DetectionOverrides {
+            model_provider: Some("openai".to_string()),
+            model_family: Some("gpt-4o".to_string()),
+            ..Default::default()
+        });
+        check_strict_detection(&options, &results, STRICT_MANIFEST_FIELDS).unwrap();
+
+        options.strict_detection = false;
+        check_strict_detection(
+            &options,
+            &DetectionResults::default(),
+            STRICT_MANIFEST_FIELDS,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_normalization_note_flags_other_schemes() {
+        let manifest =
+            |metadata: serde_json::Value| serde_json::json!({ "fingerprintMetadata": metadata });
+        let current = manifest(serde_json::json!({ "normalization": NORMALIZATION }));
+        assert!(normalization_note(&current).is_none());
+        assert!(normalization_note(&serde_json::json!({})).is_none());
+
+        let other = manifest(serde_json::json!({
+            "generatedBy": "beltic 0.0.1",
+            "normalization": "v0: something else"
+        }));
+        let note = normalization_note(&other).unwrap();
+        assert!(note.contains("beltic 0.0.1") && note.contains("v0: something else"));
+
+        let legacy = manifest(serde_json::json!({ "algorithm": "sha256" }));
+        assert!(normalization_note(&legacy)
+            .unwrap()
+            .contains("does not record"));
+    }
+
     #[test]
     fn test_convert_modality_preserves_all_variants() {
         // Verify all modality types are preserved during conversion
@@ -923,4 +2234,142 @@ mod tests {
             "StructuredData modality should be preserved as StructuredData"
         );
     }
+
+    #[test]
+    fn test_convert_compliance_cert_round_trips_every_variant() {
+        let certs = [
+            ComplianceCert::Soc2Type1,
+            ComplianceCert::Soc2Type2,
+            ComplianceCert::Iso27001,
+            ComplianceCert::Iso27017,
+            ComplianceCert::Iso27018,
+            ComplianceCert::Hipaa,
+            ComplianceCert::PciDss,
+            ComplianceCert::Fedramp,
+            ComplianceCert::Gdpr,
+            ComplianceCert::Ccpa,
+        ];
+        for cert in certs {
+            let converted = convert_compliance_cert(&cert);
+            // Both enums serialize to the same schema value
+            assert_eq!(
+                serde_json::to_value(&cert).unwrap(),
+                serde_json::to_value(&converted).unwrap()
+            );
+            assert_eq!(convert_cred_compliance_cert(&converted), cert);
+        }
+        assert_eq!(
+            convert_compliance_cert(&ComplianceCert::Gdpr),
+            CredComplianceCert::GdprCompliant
+        );
+    }
+
+    #[test]
+    fn test_compliance_cert_reads_legacy_names() {
+        let certs: Vec<ComplianceCert> =
+            serde_json::from_str(r#"["gdpr", "ccpa", "gdpr_compliant", "iso27018"]"#).unwrap();
+        assert_eq!(
+            certs,
+            [
+                ComplianceCert::Gdpr,
+                ComplianceCert::Ccpa,
+                ComplianceCert::Gdpr,
+                ComplianceCert::Iso27018
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_agent_status_preserves_internal() {
+        assert_eq!(
+            convert_agent_status(&AgentStatus::Internal),
+            CredAgentStatus::Internal
+        );
+        for status in [
+            "production",
+            "beta",
+            "alpha",
+            "internal",
+            "deprecated",
+            "retired",
+        ] {
+            let status: AgentStatus = status.parse().unwrap();
+            let converted = convert_agent_status(&status);
+            assert_eq!(
+                serde_json::to_value(&status).unwrap(),
+                serde_json::to_value(&converted).unwrap()
+            );
+            assert_eq!(convert_cred_agent_status(&converted), status);
+        }
+        assert!("launched".parse::<AgentStatus>().is_err());
+    }
+
+    #[test]
+    fn test_fail_on_warning_exempts_safety_metrics_placeholder() {
+        let mut options = InitOptions {
+            fail_on_warning: true,
+            ..Default::default()
+        };
+        let manifest = templates::generate_complete_defaults(
+            "agent".to_string(),
+            "1.0.0".to_string(),
+            ArchitectureType::SingleAgent,
+            schema::DeploymentType::Standalone,
+        );
+        let mut result = validator::validate_manifest(&manifest);
+        assert!(result
+            .issues
+            .iter()
+            .any(|issue| issue.rule_id == RULE_SAFETY_METRICS));
+
+        // Only the placeholder note left: nothing blocks
+        result
+            .issues
+            .retain(|issue| issue.rule_id == RULE_SAFETY_METRICS);
+        check_fail_on_warning(&options, &result).unwrap();
+
+        result.add_warning("PII detection is disabled".to_string());
+        let err = check_fail_on_warning(&options, &result)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("1 validation warning"), "{}", err);
+        assert!(err.contains("PII detection is disabled"), "{}", err);
+
+        options.fail_on_warning = false;
+        check_fail_on_warning(&options, &result).unwrap();
+    }
+
+    #[test]
+    fn test_base_dir_overrides_current_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("package.json"),
+            r#"{"name": "elsewhere-agent", "version": "1.2.3"}"#,
+        )
+        .unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/index.js"), "console.log('hi');\n").unwrap();
+
+        let options = InitOptions {
+            interactive: false,
+            validate: false,
+            base_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        init_manifest(&options).unwrap();
+        let manifest = read_document(&dir.path().join("agent-manifest.json"), None).unwrap();
+        assert_eq!(manifest["agentName"], "elsewhere-agent");
+
+        let flags = FingerprintFlags {
+            base_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        verify_fingerprint(None, flags.clone()).unwrap();
+        std::fs::write(dir.path().join("src/index.js"), "console.log('bye');\n").unwrap();
+        assert!(verify_fingerprint(None, flags.clone()).is_err());
+        update_fingerprint(None, flags.clone()).unwrap();
+        verify_fingerprint(None, flags).unwrap();
+
+        assert!(resolve_base_dir(Some(&dir.path().join("missing"))).is_err());
+    }
 }
@@ -0,0 +1,162 @@
+//! Agent discovery and parallel runs for `init --monorepo-scan`
+//!
+//! An agent directory is any subdirectory with its own project file. Each
+//! is detected and fingerprinted on its own, like `init --agent-path`.
+
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use anyhow::{Context, Result};
+
+/// Files that mark a directory as a project of its own
+pub const PROJECT_FILES: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "setup.py",
+    "go.mod",
+    "composer.json",
+    "Gemfile",
+];
+
+/// Dependency and build output directories, never searched for agents
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "vendor",
+    "dist",
+    "build",
+    "venv",
+    "__pycache__",
+];
+
+/// How deep below the root agent directories are searched for
+const MAX_DEPTH: usize = 6;
+
+/// Subdirectories of `root` with their own project file, relative to `root`
+/// with `/` separators and sorted. The root itself is not a candidate, and
+/// neither are directories inside a discovered agent, hidden directories or
+/// [`SKIPPED_DIRS`].
+pub fn discover_agent_dirs(root: &Path) -> Result<Vec<String>> {
+    let mut found = Vec::new();
+    search(root, "", 0, &mut found)?;
+    found.sort();
+    Ok(found)
+}
+
+fn search(dir: &Path, relative: &str, depth: usize, found: &mut Vec<String>) -> Result<()> {
+    if depth > 0 && PROJECT_FILES.iter().any(|file| dir.join(file).is_file()) {
+        found.push(relative.to_string());
+        return Ok(());
+    }
+    if depth == MAX_DEPTH {
+        return Ok(());
+    }
+
+    let entries = fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))?;
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        if name.starts_with('.') || SKIPPED_DIRS.contains(&name) {
+            continue;
+        }
+        let child = if relative.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", relative, name)
+        };
+        search(&entry.path(), &child, depth + 1, found)?;
+    }
+    Ok(())
+}
+
+/// Run `job` on every item with at most `jobs` running at once; results are
+/// in item order
+pub fn run_parallel<T, R, F>(items: &[T], jobs: usize, job: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let result = job(item);
+                results.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .map(|result| result.expect("every item is processed"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_discover_agent_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for (path, file) in [
+            ("", "package.json"),
+            ("agents/support", "package.json"),
+            ("agents/support/plugins/extra", "Cargo.toml"),
+            ("agents/triage", "pyproject.toml"),
+            ("services/billing", "go.mod"),
+            ("node_modules/left-pad", "package.json"),
+            (".cache/tool", "Cargo.toml"),
+            ("docs", "README.md"),
+        ] {
+            fs::create_dir_all(root.join(path)).unwrap();
+            fs::write(root.join(path).join(file), "").unwrap();
+        }
+
+        assert_eq!(
+            discover_agent_dirs(root).unwrap(),
+            vec!["agents/support", "agents/triage", "services/billing"]
+        );
+    }
+
+    #[test]
+    fn test_run_parallel_keeps_order_and_limit() {
+        let items: Vec<u64> = (0..8).collect();
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        let results = run_parallel(&items, 4, |item| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(50));
+            running.fetch_sub(1, Ordering::SeqCst);
+            item * 10
+        });
+
+        assert_eq!(results, (0..8).map(|i| i * 10).collect::<Vec<_>>());
+        let peak = peak.load(Ordering::SeqCst);
+        assert!(peak > 1 && peak <= 4, "peak concurrency {}", peak);
+        assert!(run_parallel(&[] as &[u64], 4, |item| *item).is_empty());
+    }
+}
@@ -0,0 +1,329 @@
+//! Credential presets: shared data-handling and compliance defaults
+//!
+//! A preset is a JSON object of AgentCredential fields (camelCase, as in the
+//! credential itself) stored as `<name>.json` in `.beltic/presets/` of the
+//! current directory or in the user's `beltic/presets/` config directory
+//! (`~/.config/beltic/presets/` on Linux). The repo-local copy wins.
+//!
+//! Identity fields (agent name and IDs, dates, fingerprint, issuer, proof)
+//! are never stored in or applied from a preset.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use directories::BaseDirs;
+use serde_json::{Map, Value};
+
+use crate::document::read_document;
+use crate::manifest::credential::AgentCredential;
+
+/// Credential fields that describe one agent or one issuance, which a
+/// preset neither saves nor applies
+pub const IDENTITY_FIELDS: &[&str] = &[
+    "$schema",
+    "schemaVersion",
+    "agentId",
+    "agentName",
+    "agentVersion",
+    "agentDescription",
+    "firstReleaseDate",
+    "currentStatus",
+    "developerCredentialId",
+    "developerCredentialVerified",
+    "systemConfigFingerprint",
+    "systemConfigLastUpdated",
+    "credentialIssuanceDate",
+    "credentialExpirationDate",
+    "credentialId",
+    "issuerDid",
+    "subjectDid",
+    "verificationMethod",
+    "proof",
+];
+
+/// Where a preset was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetScope {
+    /// `.beltic/presets/` in the current directory
+    Local,
+    /// The user's config directory
+    User,
+}
+
+impl PresetScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PresetScope::Local => "local",
+            PresetScope::User => "user",
+        }
+    }
+}
+
+/// A named preset on disk
+#[derive(Debug, Clone)]
+pub struct PresetEntry {
+    pub name: String,
+    pub scope: PresetScope,
+    pub path: PathBuf,
+}
+
+/// Preset directories, searched local first
+#[derive(Debug, Clone)]
+pub struct PresetStore {
+    pub local_dir: PathBuf,
+    pub user_dir: Option<PathBuf>,
+}
+
+impl PresetStore {
+    /// `.beltic/presets/` under `base_dir` and the user's config directory
+    pub fn new(base_dir: &Path) -> Self {
+        Self {
+            local_dir: base_dir.join(".beltic").join("presets"),
+            user_dir: BaseDirs::new().map(|dirs| dirs.config_dir().join("beltic").join("presets")),
+        }
+    }
+
+    fn dir(&self, scope: PresetScope) -> Result<&Path> {
+        match scope {
+            PresetScope::Local => Ok(&self.local_dir),
+            PresetScope::User => self
+                .user_dir
+                .as_deref()
+                .context("failed to determine the user config directory"),
+        }
+    }
+
+    fn dirs(&self) -> impl Iterator<Item = (PresetScope, &Path)> {
+        std::iter::once((PresetScope::Local, self.local_dir.as_path()))
+            .chain(self.user_dir.as_deref().map(|dir| (PresetScope::User, dir)))
+    }
+
+    /// All presets, sorted by name; a local preset hides a user preset of
+    /// the same name
+    pub fn list(&self) -> Result<Vec<PresetEntry>> {
+        let mut entries: Vec<PresetEntry> = Vec::new();
+        for (scope, dir) in self.dirs() {
+            let Ok(read_dir) = fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in read_dir.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                    continue;
+                }
+                let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                if entries.iter().any(|e| e.name == name) {
+                    continue;
+                }
+                entries.push(PresetEntry {
+                    name: name.to_string(),
+                    scope,
+                    path: path.clone(),
+                });
+            }
+        }
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// Find a preset by name, local first
+    pub fn find(&self, name: &str) -> Result<PresetEntry> {
+        check_name(name)?;
+        self.dirs()
+            .map(|(scope, dir)| (scope, dir.join(format!("{}.json", name))))
+            .find(|(_, path)| path.is_file())
+            .map(|(scope, path)| PresetEntry {
+                name: name.to_string(),
+                scope,
+                path,
+            })
+            .with_context(|| {
+                format!(
+                    "preset '{}' not found (run `beltic preset list` to see available presets)",
+                    name
+                )
+            })
+    }
+
+    /// Load a preset's fields by name
+    pub fn load(&self, name: &str) -> Result<Map<String, Value>> {
+        let entry = self.find(name)?;
+        let value = read_document(&entry.path, None)
+            .with_context(|| format!("failed to read preset {}", entry.path.display()))?;
+        match value {
+            Value::Object(fields) => Ok(fields),
+            _ => bail!("preset {} must be a JSON object", entry.path.display()),
+        }
+    }
+
+    /// Write `fields` as preset `name` in `scope`, returning its path
+    pub fn save(
+        &self,
+        name: &str,
+        scope: PresetScope,
+        fields: &Map<String, Value>,
+        force: bool,
+    ) -> Result<PathBuf> {
+        check_name(name)?;
+        let dir = self.dir(scope)?;
+        let path = dir.join(format!("{}.json", name));
+        if path.exists() && !force {
+            bail!(
+                "preset {} already exists. Use --force to overwrite.",
+                path.display()
+            );
+        }
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        fs::write(&path, serde_json::to_string_pretty(fields)? + "\n")
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+/// Preset names become file names, so keep them to letters, digits, `-` and `_`
+fn check_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        bail!(
+            "invalid preset name '{}': use letters, digits, '-' and '_'",
+            name
+        );
+    }
+    Ok(())
+}
+
+/// The non-identity fields of a credential, as saved by `preset save`
+pub fn snapshot(credential: &Value) -> Result<Map<String, Value>> {
+    let Value::Object(fields) = credential else {
+        bail!("credential must be a JSON object");
+    };
+    Ok(fields
+        .iter()
+        .filter(|(key, _)| !IDENTITY_FIELDS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect())
+}
+
+/// Overlay a preset's fields onto `credential`, skipping identity fields.
+/// Returns the names of the fields applied.
+pub fn apply(credential: &mut AgentCredential, preset: &Map<String, Value>) -> Result<Vec<String>> {
+    let mut value = serde_json::to_value(&*credential)?;
+    let fields = value
+        .as_object_mut()
+        .context("credential did not serialize to an object")?;
+
+    let mut applied = Vec::new();
+    for (key, field) in preset {
+        if IDENTITY_FIELDS.contains(&key.as_str()) {
+            continue;
+        }
+        fields.insert(key.clone(), field.clone());
+        applied.push(key.clone());
+    }
+
+    *credential =
+        serde_json::from_value(value).context("preset has fields that are not valid here")?;
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn credential() -> AgentCredential {
+        AgentCredential::new_with_defaults(
+            "support-agent".to_string(),
+            "1.0.0".to_string(),
+            "sha256:abc".to_string(),
+            "did:web:example.com".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_snapshot_round_trips_shared_fields() {
+        let dir = tempdir().unwrap();
+        let store = PresetStore {
+            local_dir: dir.path().join("local"),
+            user_dir: Some(dir.path().join("user")),
+        };
+
+        let mut source = serde_json::to_value(credential()).unwrap();
+        source["dataRetentionMaxPeriod"] = json!("P7D");
+        source["prohibitedUseCases"] = json!(["Medical diagnosis"]);
+        let fields = snapshot(&source).unwrap();
+        assert!(!fields.contains_key("agentName"));
+        assert!(!fields.contains_key("credentialId"));
+        store
+            .save("finance", PresetScope::User, &fields, false)
+            .unwrap();
+        assert!(store
+            .save("finance", PresetScope::User, &fields, false)
+            .is_err());
+
+        let mut target = AgentCredential::new_with_defaults(
+            "billing-agent".to_string(),
+            "0.1.0".to_string(),
+            "sha256:def".to_string(),
+            "did:web:example.com".to_string(),
+        );
+        let applied = apply(&mut target, &store.load("finance").unwrap()).unwrap();
+        assert!(applied.contains(&"dataRetentionMaxPeriod".to_string()));
+        assert_eq!(target.agent_name, "billing-agent");
+        assert_eq!(target.data_retention_max_period, "P7D");
+        assert_eq!(
+            target.prohibited_use_cases,
+            Some(vec!["Medical diagnosis".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_local_presets_shadow_user_presets() {
+        let dir = tempdir().unwrap();
+        let store = PresetStore {
+            local_dir: dir.path().join("local"),
+            user_dir: Some(dir.path().join("user")),
+        };
+        let fields = |period: &str| {
+            let mut map = Map::new();
+            map.insert("dataRetentionMaxPeriod".to_string(), json!(period));
+            map
+        };
+        store
+            .save("shared", PresetScope::User, &fields("P30D"), false)
+            .unwrap();
+        store
+            .save("shared", PresetScope::Local, &fields("P1D"), false)
+            .unwrap();
+        store
+            .save("other", PresetScope::User, &fields("P2D"), false)
+            .unwrap();
+
+        let names: Vec<_> = store
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.name, e.scope))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("other".to_string(), PresetScope::User),
+                ("shared".to_string(), PresetScope::Local)
+            ]
+        );
+        assert_eq!(
+            store.load("shared").unwrap()["dataRetentionMaxPeriod"],
+            "P1D"
+        );
+        assert!(store.find("../etc").is_err());
+        assert!(store.find("missing").is_err());
+    }
+}
@@ -4,6 +4,7 @@ use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
 use regex::Regex;
 use uuid::Uuid;
 
+use crate::manifest::risk::label;
 use crate::manifest::schema::*;
 use crate::manifest::templates::ManifestTemplates;
 
@@ -12,6 +13,12 @@ pub struct InteractivePrompts {
     term: Term,
 }
 
+impl Default for InteractivePrompts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl InteractivePrompts {
     pub fn new() -> Self {
         Self {
@@ -83,7 +90,7 @@ impl InteractivePrompts {
             })
             .interact_text()?;
 
-        let status_options = vec![
+        let status_options = [
             ("Production", AgentStatus::Production),
             ("Beta", AgentStatus::Beta),
             ("Alpha", AgentStatus::Alpha),
@@ -104,6 +111,25 @@ impl InteractivePrompts {
         ))
     }
 
+    /// Prompt for the incident response contact on its own (used by `init --minimal`)
+    pub fn prompt_contact(&self, default: Option<&str>) -> Result<String> {
+        let mut input = Input::<String>::with_theme(&self.theme);
+        input = input.with_prompt("Incident response contact email");
+        if let Some(default) = default {
+            input = input.default(default.to_string());
+        }
+
+        Ok(input
+            .validate_with(|input: &String| -> Result<(), &str> {
+                if input.contains('@') && input.contains('.') {
+                    Ok(())
+                } else {
+                    Err("Please enter a valid email address")
+                }
+            })
+            .interact_text()?)
+    }
+
     /// Prompt for technical profile
     pub fn prompt_technical_profile(&self) -> Result<TechnicalProfile> {
         self.section_header("🤖", "Technical Profile")?;
@@ -138,7 +164,7 @@ impl InteractivePrompts {
             .interact_text()?;
 
         // Architecture type
-        let arch_options = vec![
+        let arch_options = [
             ("Single Agent", ArchitectureType::SingleAgent),
             ("RAG (Retrieval-Augmented)", ArchitectureType::Rag),
             ("Tool-Using Agent", ArchitectureType::ToolUsing),
@@ -157,7 +183,7 @@ impl InteractivePrompts {
         let architecture = arch_options[arch_idx].1.clone();
 
         // Modalities
-        let modality_options = vec![
+        let modality_options = [
             ("Text", Modality::Text, true),
             ("Image", Modality::Image, false),
             ("Audio", Modality::Audio, false),
@@ -227,118 +253,201 @@ impl InteractivePrompts {
         let mut tools = Vec::new();
 
         for i in 1..=tool_count {
-            self.term
-                .write_line(&format!("\n{}:", style(format!("Tool {}", i)).yellow()))?;
+            tools.push(self.prompt_tool(&format!("Tool {}", i), &format!("tool_{}", i), None)?);
+        }
 
-            let tool_id = Input::<String>::with_theme(&self.theme)
-                .with_prompt("  Tool ID")
-                .default(format!("tool_{}", i))
-                .interact_text()?;
+        Ok(Some(tools))
+    }
 
-            let tool_name = Input::<String>::with_theme(&self.theme)
-                .with_prompt("  Name")
-                .interact_text()?;
+    /// Prompt for a single tool, offering the fields of `existing` as defaults
+    pub fn prompt_tool(
+        &self,
+        title: &str,
+        default_id: &str,
+        existing: Option<&Tool>,
+    ) -> Result<Tool> {
+        self.term
+            .write_line(&format!("\n{}:", style(title).yellow()))?;
 
-            let tool_description = Input::<String>::with_theme(&self.theme)
-                .with_prompt("  Description (10-1000 chars)")
-                .validate_with(|input: &String| -> Result<(), &str> {
-                    if input.len() < 10 || input.len() > 1000 {
-                        Err("Description must be 10-1000 characters")
-                    } else {
-                        Ok(())
-                    }
-                })
-                .interact_text()?;
+        let tool_id = Input::<String>::with_theme(&self.theme)
+            .with_prompt("  Tool ID")
+            .default(
+                existing
+                    .map_or(default_id, |t| t.tool_id.as_str())
+                    .to_string(),
+            )
+            .interact_text()?;
 
-            let risk_categories = vec![
-                ("Data", RiskCategory::Data),
-                ("Compute", RiskCategory::Compute),
-                ("Financial", RiskCategory::Financial),
-                ("External", RiskCategory::External),
-            ];
+        let mut name_input = Input::<String>::with_theme(&self.theme).with_prompt("  Name");
+        if let Some(tool) = existing {
+            name_input = name_input.default(tool.tool_name.clone());
+        }
+        let tool_name = name_input.interact_text()?;
 
-            let risk_idx = Select::with_theme(&self.theme)
-                .with_prompt("  Risk category")
-                .items(&risk_categories.iter().map(|r| r.0).collect::<Vec<_>>())
-                .interact()?;
+        let mut description_input = Input::<String>::with_theme(&self.theme)
+            .with_prompt("  Description (10-1000 chars)")
+            .validate_with(|input: &String| -> Result<(), &str> {
+                if input.len() < 10 || input.len() > 1000 {
+                    Err("Description must be 10-1000 characters")
+                } else {
+                    Ok(())
+                }
+            });
+        if let Some(tool) = existing {
+            description_input = description_input.default(tool.tool_description.clone());
+        }
+        let tool_description = description_input.interact_text()?;
+
+        let risk_categories = [
+            ("Data", RiskCategory::Data),
+            ("Compute", RiskCategory::Compute),
+            ("Financial", RiskCategory::Financial),
+            ("External", RiskCategory::External),
+        ];
+
+        let risk_idx = Select::with_theme(&self.theme)
+            .with_prompt("  Risk category")
+            .items(&risk_categories.iter().map(|r| r.0).collect::<Vec<_>>())
+            .default(
+                existing
+                    .and_then(|t| risk_categories.iter().position(|r| r.1 == t.risk_category))
+                    .unwrap_or(0),
+            )
+            .interact()?;
 
-            let risk_category = risk_categories[risk_idx].1.clone();
+        let risk_category = risk_categories[risk_idx].1.clone();
 
-            let risk_subcategory = self.prompt_risk_subcategory(&risk_category)?;
+        let risk_subcategory = self.prompt_risk_subcategory(
+            &risk_category,
+            existing.map(|t| t.risk_subcategory.as_str()),
+        )?;
 
-            let requires_auth = Confirm::with_theme(&self.theme)
-                .with_prompt("  Requires authentication?")
-                .default(true)
-                .interact()?;
+        let requires_auth = Confirm::with_theme(&self.theme)
+            .with_prompt("  Requires authentication?")
+            .default(existing.is_none_or(|t| t.requires_auth))
+            .interact()?;
 
-            let requires_human_approval = Confirm::with_theme(&self.theme)
-                .with_prompt("  Requires human approval?")
-                .default(false)
+        let requires_human_approval = Confirm::with_theme(&self.theme)
+            .with_prompt("  Requires human approval?")
+            .default(existing.is_some_and(|t| t.requires_human_approval))
+            .interact()?;
+
+        let mitigations = if requires_human_approval || risk_category == RiskCategory::Financial {
+            let mut mitigations_input = Input::<String>::with_theme(&self.theme)
+                .with_prompt("  Mitigations (optional)")
+                .allow_empty(true);
+            if let Some(mitigations) = existing.and_then(|t| t.mitigations.clone()) {
+                mitigations_input = mitigations_input.default(mitigations);
+            }
+            Some(mitigations_input.interact_text()?).filter(|s| !s.is_empty())
+        } else {
+            None
+        };
+
+        Ok(Tool {
+            tool_id,
+            tool_name,
+            tool_description,
+            risk_category,
+            risk_subcategory,
+            requires_auth,
+            requires_human_approval,
+            mitigations,
+        })
+    }
+
+    /// Add, modify and remove tools until the user saves or quits.
+    /// Returns `None` when the user quits without saving.
+    pub fn edit_tools(&self, mut tools: Vec<Tool>) -> Result<Option<Vec<Tool>>> {
+        self.section_header("🔧", "Edit Tools")?;
+
+        loop {
+            self.term.write_line("")?;
+            if tools.is_empty() {
+                self.term
+                    .write_line(&style("  No tools declared").dim().to_string())?;
+            }
+            for (i, tool) in tools.iter().enumerate() {
+                self.term.write_line(&format!(
+                    "  {}. {} ({}, {}/{})",
+                    i + 1,
+                    style(&tool.tool_id).cyan(),
+                    tool.tool_name,
+                    label(&tool.risk_category),
+                    tool.risk_subcategory
+                ))?;
+            }
+
+            let mut actions = vec!["Add a tool"];
+            if !tools.is_empty() {
+                actions.push("Modify a tool");
+                actions.push("Remove a tool");
+            }
+            actions.push("Save and exit");
+            actions.push("Quit without saving");
+
+            let action = Select::with_theme(&self.theme)
+                .with_prompt("What would you like to do?")
+                .items(&actions)
+                .default(0)
                 .interact()?;
 
-            let mitigations = if requires_human_approval || risk_category == RiskCategory::Financial
-            {
-                Some(
-                    Input::<String>::with_theme(&self.theme)
-                        .with_prompt("  Mitigations (optional)")
-                        .allow_empty(true)
-                        .interact_text()?,
-                )
-                .filter(|s| !s.is_empty())
-            } else {
-                None
-            };
-
-            tools.push(Tool {
-                tool_id,
-                tool_name,
-                tool_description,
-                risk_category,
-                risk_subcategory,
-                requires_auth,
-                requires_human_approval,
-                mitigations,
-            });
+            match actions[action] {
+                "Add a tool" => {
+                    let n = tools.len() + 1;
+                    let tool =
+                        self.prompt_tool(&format!("Tool {}", n), &format!("tool_{}", n), None)?;
+                    tools.push(tool);
+                }
+                "Modify a tool" => {
+                    let idx = self.select_tool(&tools, "Tool to modify")?;
+                    let title = format!("Tool {}", idx + 1);
+                    tools[idx] =
+                        self.prompt_tool(&title, &tools[idx].tool_id, Some(&tools[idx]))?;
+                }
+                "Remove a tool" => {
+                    let idx = self.select_tool(&tools, "Tool to remove")?;
+                    let confirmed = Confirm::with_theme(&self.theme)
+                        .with_prompt(format!("Remove {}?", tools[idx].tool_id))
+                        .default(false)
+                        .interact()?;
+                    if confirmed {
+                        tools.remove(idx);
+                    }
+                }
+                "Save and exit" => return Ok(Some(tools)),
+                _ => return Ok(None),
+            }
         }
+    }
 
-        Ok(Some(tools))
+    fn select_tool(&self, tools: &[Tool], prompt: &str) -> Result<usize> {
+        let items: Vec<String> = tools
+            .iter()
+            .map(|t| format!("{} ({})", t.tool_id, t.tool_name))
+            .collect();
+        Ok(Select::with_theme(&self.theme)
+            .with_prompt(prompt)
+            .items(&items)
+            .default(0)
+            .interact()?)
     }
 
-    fn prompt_risk_subcategory(&self, category: &RiskCategory) -> Result<String> {
-        let subcategories = match category {
-            RiskCategory::Data => vec![
-                "data_read_internal",
-                "data_read_external",
-                "data_write_internal",
-                "data_write_external",
-                "data_delete",
-                "data_export",
-            ],
-            RiskCategory::Compute => vec![
-                "compute_code_execution",
-                "compute_query_generation",
-                "compute_api_call",
-                "compute_transformation",
-                "compute_analysis",
-            ],
-            RiskCategory::Financial => vec![
-                "financial_read",
-                "financial_transaction",
-                "financial_account_access",
-                "financial_payment_initiation",
-            ],
-            RiskCategory::External => vec![
-                "external_internet_access",
-                "external_email",
-                "external_notification",
-                "external_authentication",
-                "external_file_access",
-            ],
-        };
+    fn prompt_risk_subcategory(
+        &self,
+        category: &RiskCategory,
+        current: Option<&str>,
+    ) -> Result<String> {
+        let subcategories = category.subcategories();
 
         let idx = Select::with_theme(&self.theme)
             .with_prompt("  Risk subcategory")
-            .items(&subcategories)
+            .items(subcategories)
+            .default(
+                current
+                    .and_then(|c| subcategories.iter().position(|s| *s == c))
+                    .unwrap_or(0),
+            )
             .interact()?;
 
         Ok(subcategories[idx].to_string())
@@ -381,7 +490,7 @@ impl InteractivePrompts {
             selected_categories.push(DataCategory::None);
         }
 
-        let retention_options = vec![
+        let retention_options = [
             ("7 days", "P7D"),
             ("30 days", "P30D"),
             ("90 days", "P90D"),
@@ -404,7 +513,7 @@ impl InteractivePrompts {
             retention_options[retention_idx].1.to_string()
         };
 
-        let training_usage_options = vec![
+        let training_usage_options = [
             ("Never", TrainingDataUsage::Never),
             ("Anonymized Only", TrainingDataUsage::AnonymizedOnly),
             ("Aggregated Only", TrainingDataUsage::AggregatedOnly),
@@ -441,7 +550,7 @@ impl InteractivePrompts {
         };
 
         let pii_redaction = if has_pii {
-            let redaction_options = vec![
+            let redaction_options = [
                 ("None", PiiRedactionCapability::None),
                 ("Basic", PiiRedactionCapability::Basic),
                 ("Advanced", PiiRedactionCapability::Advanced),
@@ -484,7 +593,7 @@ impl InteractivePrompts {
             })
             .interact_text()?;
 
-        let slo_options = vec![
+        let slo_options = [
             ("2 hours", "PT2H"),
             ("4 hours", "PT4H"),
             ("8 hours", "PT8H"),
@@ -498,7 +607,7 @@ impl InteractivePrompts {
             .default(1) // Default to 4 hours
             .interact()?;
 
-        let update_cadence_options = vec![
+        let update_cadence_options = [
             ("Continuous", UpdateCadence::Continuous),
             ("Weekly", UpdateCadence::Weekly),
             ("Biweekly", UpdateCadence::Biweekly),
@@ -519,7 +628,7 @@ impl InteractivePrompts {
             .default(5) // Default to As Needed
             .interact()?;
 
-        let oversight_options = vec![
+        let oversight_options = [
             (
                 "Autonomous (Low Risk)",
                 HumanOversightMode::AutonomousLowRisk,
@@ -596,6 +705,14 @@ impl InteractivePrompts {
         }
     }
 
+    /// Ask whether to raise the KYB tier to the risk suggestion
+    pub fn confirm_risk_suggestion(&self, suggested_tier: &str) -> Result<bool> {
+        Ok(Confirm::with_theme(&self.theme)
+            .with_prompt(format!("Raise kybTierRequired to {}?", suggested_tier))
+            .default(false)
+            .interact()?)
+    }
+
     /// Display validation results
     pub fn display_validation(&self, missing_count: usize, warnings: Vec<String>) -> Result<()> {
         self.section_header("📊", "Validation Results")?;
@@ -0,0 +1,341 @@
+//! Aggregate risk hints from tools and processed data categories.
+//!
+//! Each rule looks at one tool or data category and asks for a minimum KYB
+//! tier and safety rating; the suggestion is the highest of those. This is
+//! advisory: callers show it next to the declared values and never apply it
+//! without confirmation.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::manifest::credential::{
+    AgentCredential, DataCategory, KybTier, RiskCategory, RiskSubcategory, SafetyRating, Tool,
+};
+use crate::manifest::schema::AgentManifest;
+
+/// One rule that fired, with the minimum it requires
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskFinding {
+    pub rule: &'static str,
+    /// Tool id or data category the rule matched
+    pub subject: String,
+    pub reason: String,
+    pub kyb_tier: KybTier,
+    pub safety_rating: SafetyRating,
+}
+
+/// Suggested rating and the findings behind it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskAssessment {
+    pub kyb_tier: KybTier,
+    pub safety_rating: SafetyRating,
+    pub findings: Vec<RiskFinding>,
+}
+
+/// The parts of a tool the rules look at
+struct ToolView {
+    id: String,
+    category: Option<RiskCategory>,
+    subcategory: Option<RiskSubcategory>,
+    requires_human_approval: bool,
+}
+
+/// Assess credential tools and data categories
+pub fn assess(tools: &[Tool], data_categories: &[DataCategory]) -> RiskAssessment {
+    let views: Vec<ToolView> = tools
+        .iter()
+        .map(|tool| ToolView {
+            id: tool.tool_id.clone(),
+            category: Some(tool.risk_category.clone()),
+            subcategory: Some(tool.risk_subcategory.clone()),
+            requires_human_approval: tool.requires_human_approval,
+        })
+        .collect();
+    assess_views(&views, data_categories)
+}
+
+/// Assess an agent credential
+pub fn assess_credential(credential: &AgentCredential) -> RiskAssessment {
+    assess(
+        credential.tools_list.as_deref().unwrap_or_default(),
+        &credential.data_categories_processed,
+    )
+}
+
+/// Assess a manifest. Free-form subcategories that don't match a known value
+/// only count through their risk category.
+pub fn assess_manifest(manifest: &AgentManifest) -> RiskAssessment {
+    let views: Vec<ToolView> = manifest
+        .tools_list
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .map(|tool| ToolView {
+            id: tool.tool_id.clone(),
+            category: convert(&tool.risk_category),
+            subcategory: convert(&tool.risk_subcategory),
+            requires_human_approval: tool.requires_human_approval,
+        })
+        .collect();
+    let data_categories: Vec<DataCategory> = manifest
+        .data_categories_processed
+        .iter()
+        .filter_map(convert)
+        .collect();
+    assess_views(&views, &data_categories)
+}
+
+/// Serialized form of an enum value, e.g. `tier_2` or `high_risk`
+pub fn label<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(Value::String(s)) => s,
+        _ => String::new(),
+    }
+}
+
+/// Whether `tier` is below `minimum`
+pub fn tier_below(tier: &KybTier, minimum: &KybTier) -> bool {
+    tier_rank(tier) < tier_rank(minimum)
+}
+
+/// Whether `rating` is below `minimum`. A pending evaluation never is.
+pub fn rating_below(rating: &SafetyRating, minimum: &SafetyRating) -> bool {
+    match (rating_rank(rating), rating_rank(minimum)) {
+        (Some(rating), Some(minimum)) => rating < minimum,
+        _ => false,
+    }
+}
+
+fn assess_views(tools: &[ToolView], data_categories: &[DataCategory]) -> RiskAssessment {
+    let mut findings = Vec::new();
+    for tool in tools {
+        tool_findings(tool, &mut findings);
+    }
+    for category in data_categories {
+        data_findings(category, &mut findings);
+    }
+
+    let mut kyb_tier = KybTier::Tier0;
+    let mut safety_rating = SafetyRating::MinimalRisk;
+    for finding in &findings {
+        if tier_below(&kyb_tier, &finding.kyb_tier) {
+            kyb_tier = finding.kyb_tier.clone();
+        }
+        if rating_below(&safety_rating, &finding.safety_rating) {
+            safety_rating = finding.safety_rating.clone();
+        }
+    }
+
+    RiskAssessment {
+        kyb_tier,
+        safety_rating,
+        findings,
+    }
+}
+
+fn tool_findings(tool: &ToolView, findings: &mut Vec<RiskFinding>) {
+    let mut push = |rule, reason: String, kyb_tier, safety_rating| {
+        findings.push(RiskFinding {
+            rule,
+            subject: tool.id.clone(),
+            reason,
+            kyb_tier,
+            safety_rating,
+        })
+    };
+
+    match &tool.subcategory {
+        Some(
+            RiskSubcategory::FinancialTransaction | RiskSubcategory::FinancialPaymentInitiation,
+        ) => {
+            if tool.requires_human_approval {
+                push(
+                    "financial-transaction",
+                    "moves money (human approval required)".to_string(),
+                    KybTier::Tier2,
+                    SafetyRating::ModerateRisk,
+                );
+            } else {
+                push(
+                    "financial-transaction",
+                    "moves money without human approval".to_string(),
+                    KybTier::Tier2,
+                    SafetyRating::HighRisk,
+                );
+            }
+        }
+        Some(RiskSubcategory::FinancialAccountAccess | RiskSubcategory::FinancialRead) => push(
+            "financial-access",
+            "reads financial accounts".to_string(),
+            KybTier::Tier1,
+            SafetyRating::LowRisk,
+        ),
+        Some(RiskSubcategory::ComputeCodeExecution) => push(
+            "code-execution",
+            "executes code".to_string(),
+            KybTier::Tier1,
+            SafetyRating::ModerateRisk,
+        ),
+        Some(
+            subcategory @ (RiskSubcategory::DataDelete
+            | RiskSubcategory::DataWriteExternal
+            | RiskSubcategory::DataExport
+            | RiskSubcategory::ExternalEmail),
+        ) if !tool.requires_human_approval => push(
+            "unreviewed-side-effect",
+            format!("{} without human approval", label(subcategory)),
+            KybTier::Tier0,
+            SafetyRating::ModerateRisk,
+        ),
+        Some(_) => {}
+        None if tool.category == Some(RiskCategory::Financial) => push(
+            "financial-access",
+            "financial tool".to_string(),
+            KybTier::Tier1,
+            SafetyRating::LowRisk,
+        ),
+        None => {}
+    }
+}
+
+fn data_findings(category: &DataCategory, findings: &mut Vec<RiskFinding>) {
+    let (rule, reason, kyb_tier, safety_rating) = match category {
+        DataCategory::ChildrenData => (
+            "children-data",
+            "processes children's data",
+            KybTier::Tier3,
+            SafetyRating::HighRisk,
+        ),
+        DataCategory::Phi | DataCategory::Biometric | DataCategory::GovernmentId => (
+            "sensitive-data",
+            "processes special-category personal data",
+            KybTier::Tier2,
+            SafetyRating::ModerateRisk,
+        ),
+        DataCategory::Pii
+        | DataCategory::Financial
+        | DataCategory::Authentication
+        | DataCategory::Behavioral => (
+            "personal-data",
+            "processes personal or account data",
+            KybTier::Tier1,
+            SafetyRating::LowRisk,
+        ),
+        DataCategory::None | DataCategory::Proprietary => return,
+    };
+
+    findings.push(RiskFinding {
+        rule,
+        subject: label(category),
+        reason: reason.to_string(),
+        kyb_tier,
+        safety_rating,
+    });
+}
+
+fn tier_rank(tier: &KybTier) -> u8 {
+    match tier {
+        KybTier::Tier0 => 0,
+        KybTier::Tier1 => 1,
+        KybTier::Tier2 => 2,
+        KybTier::Tier3 => 3,
+        KybTier::Tier4 => 4,
+    }
+}
+
+fn rating_rank(rating: &SafetyRating) -> Option<u8> {
+    match rating {
+        SafetyRating::MinimalRisk => Some(0),
+        SafetyRating::LowRisk => Some(1),
+        SafetyRating::ModerateRisk => Some(2),
+        SafetyRating::HighRisk => Some(3),
+        SafetyRating::EvaluationPending => None,
+    }
+}
+
+/// Map a manifest enum onto the credential enum with the same serialized name
+/// (or the other way round)
+pub(crate) fn convert<S: Serialize, T: serde::de::DeserializeOwned>(value: &S) -> Option<T> {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| serde_json::from_value(v).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(id: &str, subcategory: RiskSubcategory, human_approval: bool) -> Tool {
+        Tool {
+            tool_id: id.to_string(),
+            tool_name: id.to_string(),
+            tool_description: format!("{} tool", id),
+            risk_category: RiskCategory::Financial,
+            risk_subcategory: subcategory,
+            requires_auth: true,
+            requires_human_approval: human_approval,
+            mitigations: None,
+        }
+    }
+
+    #[test]
+    fn test_no_tools_or_data_is_minimal() {
+        let assessment = assess(&[], &[DataCategory::None]);
+        assert_eq!(assessment.kyb_tier, KybTier::Tier0);
+        assert_eq!(assessment.safety_rating, SafetyRating::MinimalRisk);
+        assert!(assessment.findings.is_empty());
+    }
+
+    #[test]
+    fn test_financial_transaction_requires_tier2() {
+        let approved = assess(
+            &[tool("refund", RiskSubcategory::FinancialTransaction, true)],
+            &[],
+        );
+        assert_eq!(approved.kyb_tier, KybTier::Tier2);
+        assert_eq!(approved.safety_rating, SafetyRating::ModerateRisk);
+
+        let unapproved = assess(
+            &[tool(
+                "pay",
+                RiskSubcategory::FinancialPaymentInitiation,
+                false,
+            )],
+            &[],
+        );
+        assert_eq!(unapproved.safety_rating, SafetyRating::HighRisk);
+        assert_eq!(unapproved.findings[0].subject, "pay");
+    }
+
+    #[test]
+    fn test_children_data_is_high_risk() {
+        let assessment = assess(
+            &[tool("balance", RiskSubcategory::FinancialRead, false)],
+            &[DataCategory::Pii, DataCategory::ChildrenData],
+        );
+        assert_eq!(assessment.kyb_tier, KybTier::Tier3);
+        assert_eq!(assessment.safety_rating, SafetyRating::HighRisk);
+        assert_eq!(assessment.findings.len(), 3);
+    }
+
+    #[test]
+    fn test_manifest_free_form_subcategory_uses_category() {
+        let mut manifest = AgentManifest::new_with_defaults();
+        manifest.tools_list = Some(vec![crate::manifest::schema::Tool {
+            tool_id: "ledger".to_string(),
+            tool_name: "Ledger".to_string(),
+            tool_description: "Ledger lookups".to_string(),
+            risk_category: crate::manifest::schema::RiskCategory::Financial,
+            risk_subcategory: "custom_ledger".to_string(),
+            requires_auth: true,
+            requires_human_approval: false,
+            mitigations: None,
+        }]);
+
+        let assessment = assess_manifest(&manifest);
+        assert_eq!(assessment.kyb_tier, KybTier::Tier1);
+        assert_eq!(assessment.findings[0].rule, "financial-access");
+    }
+}
@@ -0,0 +1,203 @@
+//! SARIF 2.1.0 output for manifest validation results
+//!
+//! Lets CI upload validation findings to GitHub code scanning.
+
+use serde_json::{json, Value};
+
+use crate::manifest::validator::{IssueLevel, ValidationResult};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/belticlabs/beltic-cli";
+
+/// Short description for a rule id, shown in the code scanning UI
+fn rule_description(rule_id: &str) -> &'static str {
+    match rule_id {
+        "beltic/missing-field" => "A required manifest field is missing or empty",
+        "beltic/placeholder" => "A field still contains a placeholder value",
+        "beltic/invalid-version" => "Agent version is not a semantic version",
+        "beltic/invalid-email" => "Contact is not a valid email address",
+        "beltic/invalid-date" => "Date is not an ISO 8601 date (YYYY-MM-DD)",
+        "beltic/invalid-duration" => "Duration is not an ISO 8601 duration",
+        "beltic/invalid-fingerprint" => "Fingerprint is not a SHA-256 hex digest",
+        "beltic/invalid-language-code" => "Language code is not ISO 639-1",
+        "beltic/invalid-region-code" => "Region code is not ISO 3166-1 alpha-2",
+        "beltic/field-length" => "Field length is outside the allowed range",
+        "beltic/missing-developer-credential" => "Developer credential ID is not set",
+        "beltic/schema-violation" => "Document does not match the JSON Schema",
+        "beltic/recommendation" => "Manifest does not follow a recommended practice",
+        "beltic/safety-metrics" => "Safety metrics are evaluated by the Beltic platform",
+        _ => "Manifest value is invalid",
+    }
+}
+
+/// Convert validation results into a SARIF 2.1.0 log.
+///
+/// `artifact_uri` is the manifest path as it should appear in the report.
+/// When `source` (the manifest text) is given, JSON pointers are resolved
+/// to line/column regions.
+pub fn to_sarif(result: &ValidationResult, artifact_uri: &str, source: Option<&str>) -> Value {
+    let mut rule_ids: Vec<&str> = result.issues.iter().map(|i| i.rule_id).collect();
+    rule_ids.sort_unstable();
+    rule_ids.dedup();
+
+    let rules: Vec<Value> = rule_ids
+        .iter()
+        .map(|id| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": rule_description(id) },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = result
+        .issues
+        .iter()
+        .map(|issue| {
+            let level = match issue.level {
+                IssueLevel::Error => "error",
+                IssueLevel::Warning => "warning",
+            };
+
+            let mut physical = json!({
+                "artifactLocation": { "uri": artifact_uri },
+            });
+            if let Some((line, column)) = issue
+                .pointer
+                .as_deref()
+                .zip(source)
+                .and_then(|(pointer, source)| locate_pointer(source, pointer))
+            {
+                physical["region"] = json!({ "startLine": line, "startColumn": column });
+            }
+
+            let mut location = json!({ "physicalLocation": physical });
+            if let Some(pointer) = issue.pointer.as_deref().filter(|p| !p.is_empty()) {
+                location["logicalLocations"] = json!([{
+                    "fullyQualifiedName": pointer,
+                    "kind": "member",
+                }]);
+            }
+
+            json!({
+                "ruleId": issue.rule_id,
+                "level": level,
+                "message": { "text": issue.message },
+                "locations": [location],
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "beltic",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": INFORMATION_URI,
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+/// Find the 1-based line and column of the key a JSON pointer refers to.
+///
+/// This is a textual search for each object key in order, which is enough
+/// for pretty-printed manifests. Array indexes are skipped, so pointers into
+/// arrays resolve to the enclosing key.
+fn locate_pointer(source: &str, pointer: &str) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    let mut found = None;
+
+    for segment in pointer.split('/').skip(1) {
+        if segment.is_empty() || segment.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+
+        let key = format!("\"{}\"", segment.replace("~1", "/").replace("~0", "~"));
+        let mut search_from = offset;
+        let position = loop {
+            let relative = source[search_from..].find(&key)?;
+            let start = search_from + relative;
+            let after = source[start + key.len()..].trim_start();
+            if after.starts_with(':') {
+                break start;
+            }
+            search_from = start + key.len();
+        };
+
+        offset = position + key.len();
+        found = Some(position);
+    }
+
+    let position = found?;
+    let before = &source[..position];
+    let line = before.matches('\n').count() + 1;
+    let column = position - before.rfind('\n').map(|i| i + 1).unwrap_or(0) + 1;
+    Some((line, column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locate_pointer() {
+        let source = "{\n  \"agentName\": \"x\",\n  \"dataLocationProfile\": {\n    \"storageRegions\": [\"usa\"]\n  }\n}";
+
+        assert_eq!(locate_pointer(source, "/agentName"), Some((2, 3)));
+        assert_eq!(
+            locate_pointer(source, "/dataLocationProfile/storageRegions/0"),
+            Some((4, 5))
+        );
+        assert_eq!(locate_pointer(source, "/missing"), None);
+        assert_eq!(locate_pointer(source, ""), None);
+    }
+
+    #[test]
+    fn test_to_sarif_maps_issues() {
+        let mut result = ValidationResult::new();
+        result.add_missing_field("agentName".to_string());
+        result.add_error_at(
+            "beltic/invalid-duration",
+            "/incidentResponseSlo",
+            "Invalid ISO duration for SLO: 4h".to_string(),
+        );
+        result.add_warning("Something to consider".to_string());
+
+        let source = "{\n  \"agentName\": \"\",\n  \"incidentResponseSlo\": \"4h\"\n}";
+        let sarif = to_sarif(&result, "agent-manifest.json", Some(source));
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+
+        assert_eq!(results[0]["ruleId"], "beltic/missing-field");
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            2
+        );
+
+        assert_eq!(results[1]["ruleId"], "beltic/invalid-duration");
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            3
+        );
+
+        assert_eq!(results[2]["level"], "warning");
+        assert!(results[2]["locations"][0]["physicalLocation"]
+            .get("region")
+            .is_none());
+
+        let rules = sarif["runs"][0]["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap();
+        assert_eq!(rules.len(), 3);
+    }
+}
@@ -0,0 +1,327 @@
+//! Software bill of materials for an agent (`beltic sbom`)
+//!
+//! A minimal CycloneDX 1.5 or SPDX 2.3 JSON document listing the agent's
+//! external dependencies (see [`fingerprint_external_dependencies`]). The
+//! agent itself is the described component, carrying its `agentId` and
+//! `systemConfigFingerprint` so the SBOM can be cross-linked with the
+//! credential or manifest it was generated from.
+//!
+//! [`fingerprint_external_dependencies`]: crate::manifest::fingerprint::fingerprint_external_dependencies
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::manifest::dependencies::purl;
+use crate::manifest::fingerprint::generated_by;
+use crate::manifest::schema::ExternalDep;
+
+/// SBOM document format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SbomFormat {
+    #[default]
+    CycloneDx,
+    Spdx,
+}
+
+impl FromStr for SbomFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cyclonedx" | "cdx" => Ok(SbomFormat::CycloneDx),
+            "spdx" => Ok(SbomFormat::Spdx),
+            _ => Err(format!(
+                "invalid SBOM format '{}': use 'cyclonedx' or 'spdx'",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SbomFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SbomFormat::CycloneDx => f.write_str("cyclonedx"),
+            SbomFormat::Spdx => f.write_str("spdx"),
+        }
+    }
+}
+
+/// The agent an SBOM describes, taken from its credential or manifest
+#[derive(Debug, Clone)]
+pub struct SbomSubject {
+    pub agent_id: String,
+    pub agent_name: String,
+    pub agent_version: String,
+    /// `systemConfigFingerprint`, e.g. `sha256:<hex>`
+    pub fingerprint: String,
+}
+
+impl SbomSubject {
+    /// Read the agent fields from a credential or manifest
+    pub fn from_document(document: &Value) -> Result<Self> {
+        let field = |name: &str| document.get(name).and_then(Value::as_str);
+        let required = |name: &str| {
+            field(name)
+                .map(str::to_string)
+                .with_context(|| format!("document has no {}", name))
+        };
+        Ok(Self {
+            agent_id: required("agentId")?,
+            agent_name: required("agentName")?,
+            agent_version: field("agentVersion").unwrap_or("0.0.0").to_string(),
+            fingerprint: required("systemConfigFingerprint")?,
+        })
+    }
+
+    /// Hex digest of a `sha256:` fingerprint
+    fn sha256(&self) -> Option<&str> {
+        self.fingerprint.strip_prefix("sha256:")
+    }
+}
+
+/// Build an SBOM document in `format`
+pub fn generate(format: SbomFormat, subject: &SbomSubject, deps: &[ExternalDep]) -> Value {
+    let (timestamp, serial) = (Utc::now(), Uuid::new_v4());
+    match format {
+        SbomFormat::CycloneDx => cyclonedx(subject, deps, timestamp, serial),
+        SbomFormat::Spdx => spdx(subject, deps, timestamp, serial),
+    }
+}
+
+fn dependency_purl(dep: &ExternalDep) -> Option<String> {
+    dep.ecosystem
+        .as_deref()
+        .map(|ecosystem| purl(ecosystem, &dep.name, &dep.version))
+}
+
+fn cyclonedx(
+    subject: &SbomSubject,
+    deps: &[ExternalDep],
+    timestamp: DateTime<Utc>,
+    serial: Uuid,
+) -> Value {
+    let agent_ref = format!("agent:{}", subject.agent_id);
+    let mut agent = json!({
+        "type": "application",
+        "bom-ref": agent_ref,
+        "name": subject.agent_name,
+        "version": subject.agent_version,
+        "properties": [
+            { "name": "beltic:agentId", "value": subject.agent_id },
+            { "name": "beltic:systemConfigFingerprint", "value": subject.fingerprint },
+        ],
+    });
+    if let Some(hex) = subject.sha256() {
+        agent["hashes"] = json!([{ "alg": "SHA-256", "content": hex }]);
+    }
+
+    let mut refs = Vec::with_capacity(deps.len());
+    let components: Vec<Value> = deps
+        .iter()
+        .map(|dep| {
+            let purl = dependency_purl(dep);
+            let bom_ref = match &dep.ecosystem {
+                Some(ecosystem) => format!("{}:{}", ecosystem, dep.name),
+                None => dep.name.clone(),
+            };
+            refs.push(bom_ref.clone());
+            let mut component = json!({
+                "type": "library",
+                "bom-ref": bom_ref,
+                "name": dep.name,
+                "version": dep.version,
+            });
+            if let Some(purl) = purl {
+                component["purl"] = json!(purl);
+            }
+            component
+        })
+        .collect();
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", serial),
+        "version": 1,
+        "metadata": {
+            "timestamp": timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "beltic",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }],
+            },
+            "component": agent,
+        },
+        "components": components,
+        "dependencies": [{ "ref": agent_ref, "dependsOn": refs }],
+    })
+}
+
+fn spdx(
+    subject: &SbomSubject,
+    deps: &[ExternalDep],
+    timestamp: DateTime<Utc>,
+    namespace: Uuid,
+) -> Value {
+    let mut packages = vec![json!({
+        "SPDXID": "SPDXRef-Agent",
+        "name": subject.agent_name,
+        "versionInfo": subject.agent_version,
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "externalRefs": [
+            {
+                "referenceCategory": "OTHER",
+                "referenceType": "beltic-agent-id",
+                "referenceLocator": subject.agent_id,
+            },
+            {
+                "referenceCategory": "OTHER",
+                "referenceType": "beltic-system-config-fingerprint",
+                "referenceLocator": subject.fingerprint,
+            },
+        ],
+    })];
+    let mut relationships = vec![json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": "SPDXRef-Agent",
+    })];
+
+    for (index, dep) in deps.iter().enumerate() {
+        let id = format!("SPDXRef-Package-{}", index + 1);
+        let mut package = json!({
+            "SPDXID": id,
+            "name": dep.name,
+            "versionInfo": dep.version,
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+        });
+        if let Some(purl) = dependency_purl(dep) {
+            package["externalRefs"] = json!([{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": purl,
+            }]);
+        }
+        packages.push(package);
+        relationships.push(json!({
+            "spdxElementId": "SPDXRef-Agent",
+            "relationshipType": "DEPENDS_ON",
+            "relatedSpdxElement": id,
+        }));
+    }
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": format!("{}-{}", subject.agent_name, subject.agent_version),
+        "documentNamespace": format!(
+            "https://spdx.org/spdxdocs/{}-{}",
+            subject.agent_name, namespace
+        ),
+        "creationInfo": {
+            "created": timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            "creators": [format!("Tool: {}", generated_by().replace(' ', "-"))],
+        },
+        "documentDescribes": ["SPDXRef-Agent"],
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subject() -> SbomSubject {
+        SbomSubject::from_document(&json!({
+            "agentId": "3f1c9a1e-7b24-4c8d-a0e6-2d91b8c4f173",
+            "agentName": "support-agent",
+            "agentVersion": "1.2.0",
+            "systemConfigFingerprint": "sha256:abc123",
+        }))
+        .unwrap()
+    }
+
+    fn deps() -> Vec<ExternalDep> {
+        vec![
+            ExternalDep {
+                name: "openai".to_string(),
+                version: "4.20.1".to_string(),
+                hash: "sha256:00".to_string(),
+                ecosystem: Some("npm".to_string()),
+            },
+            ExternalDep {
+                name: "internal-sdk".to_string(),
+                version: "*".to_string(),
+                hash: "sha256:11".to_string(),
+                ecosystem: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_cyclonedx_structure() {
+        let bom = generate(SbomFormat::CycloneDx, &subject(), &deps());
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        assert_eq!(bom["specVersion"], "1.5");
+        assert!(bom["serialNumber"]
+            .as_str()
+            .unwrap()
+            .starts_with("urn:uuid:"));
+
+        let agent = &bom["metadata"]["component"];
+        assert_eq!(agent["name"], "support-agent");
+        assert_eq!(agent["hashes"][0]["content"], "abc123");
+        assert_eq!(
+            agent["properties"][0]["value"],
+            "3f1c9a1e-7b24-4c8d-a0e6-2d91b8c4f173"
+        );
+        assert_eq!(agent["properties"][1]["value"], "sha256:abc123");
+
+        let components = bom["components"].as_array().unwrap();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0]["purl"], "pkg:npm/openai@4.20.1");
+        assert!(components[1].get("purl").is_none());
+        assert_eq!(bom["dependencies"][0]["ref"], agent["bom-ref"]);
+        assert_eq!(
+            bom["dependencies"][0]["dependsOn"],
+            json!(["npm:openai", "internal-sdk"])
+        );
+    }
+
+    #[test]
+    fn test_spdx_structure() {
+        let doc = generate(SbomFormat::Spdx, &subject(), &deps());
+        assert_eq!(doc["spdxVersion"], "SPDX-2.3");
+        assert_eq!(doc["documentDescribes"], json!(["SPDXRef-Agent"]));
+
+        let packages = doc["packages"].as_array().unwrap();
+        assert_eq!(packages.len(), 3);
+        assert_eq!(
+            packages[0]["externalRefs"][1]["referenceLocator"],
+            "sha256:abc123"
+        );
+        assert_eq!(
+            packages[1]["externalRefs"][0]["referenceLocator"],
+            "pkg:npm/openai@4.20.1"
+        );
+        let relationships = doc["relationships"].as_array().unwrap();
+        assert_eq!(relationships.len(), 3);
+        assert_eq!(relationships[2]["relatedSpdxElement"], "SPDXRef-Package-2");
+
+        assert!(SbomSubject::from_document(&json!({ "agentName": "x" })).is_err());
+        assert_eq!("SPDX".parse::<SbomFormat>(), Ok(SbomFormat::Spdx));
+        assert!("xml".parse::<SbomFormat>().is_err());
+    }
+}
@@ -93,6 +93,12 @@ pub struct FingerprintMetadata {
     pub algorithm: String,
     pub timestamp: DateTime<Utc>,
     pub scope: FingerprintScope,
+    /// Tool and version that produced the fingerprint, e.g. `beltic 0.2.0`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated_by: Option<String>,
+    /// How paths and file hashes were combined (see `beltic fingerprint --explain`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalization: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dependencies: Option<Dependencies>,
 }
@@ -105,6 +111,9 @@ pub struct FingerprintScope {
     pub paths: PathConfiguration,
     pub files_processed: usize,
     pub total_size: u64,
+    /// Binary files left out because of `exclude_binary`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_binaries: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -138,6 +147,10 @@ pub struct ExternalDep {
     pub name: String,
     pub version: String,
     pub hash: String,
+    /// Package URL type (`npm`, `cargo`, ...); unset for dependencies only
+    /// listed in `.beltic.yaml`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ecosystem: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -207,6 +220,10 @@ pub struct GenerationMetadata {
     pub generated_by: String,
     pub generated_at: DateTime<Utc>,
     pub auto_detected: HashMap<String, String>,
+    /// Values init wrote from detection, by manifest field; `init --update`
+    /// only replaces fields that still hold them
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub detected_values: HashMap<String, serde_json::Value>,
 }
 
 // Enums for various fields
@@ -222,6 +239,25 @@ pub enum AgentStatus {
     Retired,
 }
 
+impl std::str::FromStr for AgentStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "production" => Ok(AgentStatus::Production),
+            "beta" => Ok(AgentStatus::Beta),
+            "alpha" => Ok(AgentStatus::Alpha),
+            "internal" => Ok(AgentStatus::Internal),
+            "deprecated" => Ok(AgentStatus::Deprecated),
+            "retired" => Ok(AgentStatus::Retired),
+            other => Err(format!(
+                "unknown status '{}': expected production, beta, alpha, internal, deprecated or retired",
+                other
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum Modality {
@@ -245,16 +281,22 @@ pub enum ArchitectureType {
     Hybrid,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Serialized names match the credential's `complianceCertifications`;
+/// `gdpr` and `ccpa` are still read from older manifests
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum ComplianceCert {
     Soc2Type1,
     Soc2Type2,
     Iso27001,
+    Iso27017,
+    Iso27018,
     Hipaa,
     PciDss,
     Fedramp,
+    #[serde(rename = "gdpr_compliant", alias = "gdpr")]
     Gdpr,
+    #[serde(rename = "ccpa_compliant", alias = "ccpa")]
     Ccpa,
 }
 
@@ -348,6 +390,42 @@ pub enum RiskCategory {
     External,
 }
 
+impl RiskCategory {
+    /// The `riskSubcategory` values that belong to this category
+    pub fn subcategories(&self) -> &'static [&'static str] {
+        match self {
+            RiskCategory::Data => &[
+                "data_read_internal",
+                "data_read_external",
+                "data_write_internal",
+                "data_write_external",
+                "data_delete",
+                "data_export",
+            ],
+            RiskCategory::Compute => &[
+                "compute_code_execution",
+                "compute_query_generation",
+                "compute_api_call",
+                "compute_transformation",
+                "compute_analysis",
+            ],
+            RiskCategory::Financial => &[
+                "financial_read",
+                "financial_transaction",
+                "financial_account_access",
+                "financial_payment_initiation",
+            ],
+            RiskCategory::External => &[
+                "external_internet_access",
+                "external_email",
+                "external_notification",
+                "external_authentication",
+                "external_file_access",
+            ],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum DeploymentType {
@@ -1,3 +1,9 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::manifest::credential::{AgentCredential, ModelFamily};
 use crate::manifest::schema::*;
 
 /// Provides default templates and values for agent manifest fields
@@ -62,6 +68,18 @@ impl ManifestTemplates {
         }
     }
 
+    /// Get default incident response SLO for an agent's lifecycle status
+    pub fn incident_response_slo_for_status(status: &AgentStatus) -> String {
+        let risk_level = match status {
+            AgentStatus::Production => "production",
+            AgentStatus::Beta => "beta",
+            AgentStatus::Alpha => "alpha",
+            AgentStatus::Internal => "alpha", // Treat internal same as alpha
+            AgentStatus::Deprecated | AgentStatus::Retired => "low_risk",
+        };
+        Self::default_incident_response_slo(risk_level)
+    }
+
     /// Generate deprecation policy template
     pub fn deprecation_policy_template() -> String {
         "Minimum 30-day notice via email and API deprecation headers. \
@@ -180,9 +198,13 @@ impl ManifestTemplates {
         }
     }
 
-    /// Get default context window for model family
+    /// Get default context window for model family: the known family's
+    /// window (see [`ModelFamily::default_context_window`]), else a guess
     pub fn default_context_window(model_family: &str) -> u32 {
-        if model_family.contains("Claude-3") {
+        let family = ModelFamily::from_display_name(model_family);
+        if family != ModelFamily::Other {
+            family.default_context_window()
+        } else if model_family.contains("Claude-3") {
             200000
         } else if model_family.contains("GPT-4") && model_family.contains("Turbo") {
             128000
@@ -239,6 +261,8 @@ impl ManifestTemplates {
             ("SOC 2 Type I", ComplianceCert::Soc2Type1),
             ("SOC 2 Type II", ComplianceCert::Soc2Type2),
             ("ISO 27001", ComplianceCert::Iso27001),
+            ("ISO 27017", ComplianceCert::Iso27017),
+            ("ISO 27018", ComplianceCert::Iso27018),
             ("HIPAA", ComplianceCert::Hipaa),
             ("PCI DSS", ComplianceCert::PciDss),
             ("FedRAMP", ComplianceCert::Fedramp),
@@ -261,6 +285,136 @@ impl Default for SafetyBenchmarks {
     }
 }
 
+/// Fields that `init --minimal` fills from templates instead of prompting
+pub const MINIMAL_DEFAULTED_FIELDS: &[&str] = &[
+    "primaryModelProvider",
+    "primaryModelFamily",
+    "modelContextWindow",
+    "deploymentEnvironment",
+    "dataRetentionMaxPeriod",
+    "trainingDataUsage",
+    "piiDetectionEnabled",
+    "piiRedactionCapability",
+    "dataEncryptionStandards",
+    "incidentResponseSlo",
+    "deprecationPolicy",
+    "updateCadence",
+    "humanOversightMode",
+    "failSafeBehavior",
+    "monitoringCoverage",
+    "approvedUseCases",
+    "prohibitedUseCases",
+    "ageRestrictions",
+];
+
+/// Template file for the deprecation policy in a `--template-dir`
+pub const DEPRECATION_POLICY_FILE: &str = "deprecation-policy.txt";
+/// Template file for the fail-safe behavior in a `--template-dir`
+pub const FAIL_SAFE_BEHAVIOR_FILE: &str = "fail-safe-behavior.txt";
+/// Template file for the monitoring coverage in a `--template-dir`
+pub const MONITORING_COVERAGE_FILE: &str = "monitoring-coverage.txt";
+
+/// Organization text replacing the built-in deprecation policy, fail-safe
+/// behavior and monitoring coverage. Unset fields keep the built-ins.
+///
+/// Templates may contain `{agent_name}` and `{oversight_mode}` (e.g.
+/// `human_review_post_action`), substituted when applied.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextOverrides {
+    pub deprecation_policy: Option<String>,
+    pub fail_safe_behavior: Option<String>,
+    pub monitoring_coverage: Option<String>,
+}
+
+impl TextOverrides {
+    /// Read the template files present in `dir`; absent files are left unset
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        if !dir.is_dir() {
+            bail!("Template directory {} does not exist", dir.display());
+        }
+        let read = |file: &str| -> Result<Option<String>> {
+            let path = dir.join(file);
+            if !path.exists() {
+                return Ok(None);
+            }
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read template {}", path.display()))?;
+            let text = text.trim();
+            if text.is_empty() {
+                bail!("Template {} is empty", path.display());
+            }
+            Ok(Some(text.to_string()))
+        };
+        Ok(Self {
+            deprecation_policy: read(DEPRECATION_POLICY_FILE)?,
+            fail_safe_behavior: read(FAIL_SAFE_BEHAVIOR_FILE)?,
+            monitoring_coverage: read(MONITORING_COVERAGE_FILE)?,
+        })
+    }
+
+    /// These overrides, with unset fields taken from `fallback`
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            deprecation_policy: self.deprecation_policy.or(fallback.deprecation_policy),
+            fail_safe_behavior: self.fail_safe_behavior.or(fallback.fail_safe_behavior),
+            monitoring_coverage: self.monitoring_coverage.or(fallback.monitoring_coverage),
+        }
+    }
+
+    /// Replace a manifest's boilerplate fields with the set templates
+    pub fn apply_to_manifest(&self, manifest: &mut AgentManifest) {
+        let oversight_mode = serde_label(&manifest.human_oversight_mode);
+        self.apply(
+            &manifest.agent_name,
+            &oversight_mode,
+            [
+                &mut manifest.deprecation_policy,
+                &mut manifest.fail_safe_behavior,
+                &mut manifest.monitoring_coverage,
+            ],
+        );
+    }
+
+    /// Replace a credential's boilerplate fields with the set templates
+    pub fn apply_to_credential(&self, credential: &mut AgentCredential) {
+        let oversight_mode = serde_label(&credential.human_oversight_mode);
+        self.apply(
+            &credential.agent_name,
+            &oversight_mode,
+            [
+                &mut credential.deprecation_policy,
+                &mut credential.fail_safe_behavior,
+                &mut credential.monitoring_coverage,
+            ],
+        );
+    }
+
+    /// `fields` are the deprecation policy, fail-safe behavior and
+    /// monitoring coverage, in that order
+    fn apply(&self, agent_name: &str, oversight_mode: &str, fields: [&mut String; 3]) {
+        let templates = [
+            &self.deprecation_policy,
+            &self.fail_safe_behavior,
+            &self.monitoring_coverage,
+        ];
+        for (template, field) in templates.into_iter().zip(fields) {
+            if let Some(template) = template {
+                *field = template
+                    .replace("{agent_name}", agent_name)
+                    .replace("{oversight_mode}", oversight_mode);
+            }
+        }
+    }
+}
+
+/// The serialized name of a unit enum variant, e.g. `human_review_post_action`
+fn serde_label<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
 /// Generate a complete manifest with sensible defaults (no TODOs)
 pub fn generate_complete_defaults(
     name: String,
@@ -294,15 +448,9 @@ pub fn generate_complete_defaults(
     // Set operational fields
     manifest.incident_response_contact = "security@example.com".to_string(); // Will be replaced in interactive mode
 
-    // Convert current_status to string for SLO lookup (not version!)
-    let status_str = match manifest.current_status {
-        AgentStatus::Production => "production",
-        AgentStatus::Beta => "beta",
-        AgentStatus::Alpha => "alpha",
-        AgentStatus::Internal => "alpha", // Treat internal same as alpha
-        AgentStatus::Deprecated | AgentStatus::Retired => "low_risk",
-    };
-    manifest.incident_response_slo = ManifestTemplates::default_incident_response_slo(status_str);
+    // SLO is based on current_status (not version!)
+    manifest.incident_response_slo =
+        ManifestTemplates::incident_response_slo_for_status(&manifest.current_status);
     manifest.deprecation_policy = ManifestTemplates::deprecation_policy_template();
     manifest.human_oversight_mode = oversight_mode.clone();
     manifest.fail_safe_behavior = ManifestTemplates::failsafe_behavior_template(&oversight_mode);
@@ -394,4 +542,79 @@ mod tests {
             "Incident response SLO should be based on status (alpha=PT8H), not version string"
         );
     }
+
+    #[test]
+    fn test_minimal_defaults_pass_validation() {
+        use crate::manifest::validator::validate_manifest;
+
+        let mut manifest = generate_complete_defaults(
+            "Support Bot".to_string(),
+            "0.1.0".to_string(),
+            ArchitectureType::ToolUsing,
+            DeploymentType::Standalone,
+        );
+        // Identity and fingerprint are the only fields `init --minimal` supplies
+        manifest.incident_response_contact = "oncall@acme.dev".to_string();
+        manifest.system_config_fingerprint = "a".repeat(64);
+
+        let result = validate_manifest(&manifest);
+        assert!(result.is_valid, "errors: {:?}", result.errors);
+
+        let json = serde_json::to_value(&manifest).unwrap();
+        for field in MINIMAL_DEFAULTED_FIELDS {
+            assert!(
+                json.get(field).is_some(),
+                "{} is not a manifest field",
+                field
+            );
+        }
+    }
+
+    #[test]
+    fn test_text_overrides_from_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(DEPRECATION_POLICY_FILE),
+            "{agent_name} follows the Acme deprecation standard.\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join(FAIL_SAFE_BEHAVIOR_FILE),
+            "Under {oversight_mode}, {agent_name} halts and pages on-call.",
+        )
+        .unwrap();
+
+        let overrides = TextOverrides::from_dir(dir.path()).unwrap();
+        assert!(overrides.monitoring_coverage.is_none());
+
+        let mut manifest = generate_complete_defaults(
+            "Support Bot".to_string(),
+            "0.1.0".to_string(),
+            ArchitectureType::ToolUsing,
+            DeploymentType::Standalone,
+        );
+        let builtin_monitoring = manifest.monitoring_coverage.clone();
+        overrides
+            .clone()
+            .or(TextOverrides {
+                deprecation_policy: Some("ignored".to_string()),
+                ..Default::default()
+            })
+            .apply_to_manifest(&mut manifest);
+
+        assert_eq!(
+            manifest.deprecation_policy,
+            "Support Bot follows the Acme deprecation standard."
+        );
+        assert_eq!(
+            manifest.fail_safe_behavior,
+            "Under human_review_post_action, Support Bot halts and pages on-call."
+        );
+        assert_eq!(manifest.monitoring_coverage, builtin_monitoring);
+
+        fs::write(dir.path().join(MONITORING_COVERAGE_FILE), "  \n").unwrap();
+        let err = TextOverrides::from_dir(dir.path()).unwrap_err().to_string();
+        assert!(err.contains("is empty"), "{}", err);
+        assert!(TextOverrides::from_dir(&dir.path().join("missing")).is_err());
+    }
 }
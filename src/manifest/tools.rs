@@ -0,0 +1,134 @@
+//! Tool specifications from a file (`init --tools`)
+//!
+//! The file is a JSON (or YAML) array of tool objects in the credential's
+//! `toolsList` shape, so tool definitions can be kept in version control and
+//! declared without the interactive prompts.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+use crate::document::read_document;
+use crate::manifest::risk::label;
+use crate::manifest::schema::Tool;
+
+/// Read and validate a tools file. Errors name the offending tool by its
+/// `toolId` (or its position when it has none).
+pub fn load_tools_file(path: &Path) -> Result<Vec<Tool>> {
+    let document = read_document(path, None)?;
+    let Value::Array(items) = document else {
+        bail!("{} must contain an array of tools", path.display());
+    };
+
+    let mut tools = Vec::with_capacity(items.len());
+    let mut ids = HashSet::new();
+    for (index, item) in items.into_iter().enumerate() {
+        let name = match item.get("toolId").and_then(Value::as_str) {
+            Some(id) => format!("tool '{}'", id),
+            None => format!("tool #{}", index + 1),
+        };
+        let tool: Tool = serde_json::from_value(item)
+            .with_context(|| format!("{} in {} is invalid", name, path.display()))?;
+        if let Err(problem) = check_tool(&tool) {
+            bail!("{} in {}: {}", name, path.display(), problem);
+        }
+        if !ids.insert(tool.tool_id.clone()) {
+            bail!("{} in {} is declared more than once", name, path.display());
+        }
+        tools.push(tool);
+    }
+    Ok(tools)
+}
+
+/// Check the constraints the schema places on a single tool
+pub fn check_tool(tool: &Tool) -> std::result::Result<(), String> {
+    if tool.tool_id.trim().is_empty() {
+        return Err("toolId must not be empty".to_string());
+    }
+    if tool.tool_name.trim().is_empty() {
+        return Err("toolName must not be empty".to_string());
+    }
+    let description_len = tool.tool_description.len();
+    if !(10..=1000).contains(&description_len) {
+        return Err(format!(
+            "toolDescription must be 10-1000 characters (current: {})",
+            description_len
+        ));
+    }
+    let subcategories = tool.risk_category.subcategories();
+    if !subcategories.contains(&tool.risk_subcategory.as_str()) {
+        return Err(format!(
+            "riskSubcategory '{}' does not belong to riskCategory '{}' (expected one of: {})",
+            tool.risk_subcategory,
+            label(&tool.risk_category),
+            subcategories.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn tool(id: &str, category: &str, subcategory: &str) -> Value {
+        json!({
+            "toolId": id,
+            "toolName": "Refund",
+            "toolDescription": "Issue a refund to a customer",
+            "riskCategory": category,
+            "riskSubcategory": subcategory,
+            "requiresAuth": true,
+            "requiresHumanApproval": true
+        })
+    }
+
+    fn load(tools: Value) -> Result<Vec<Tool>> {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("tools.json");
+        std::fs::write(&path, tools.to_string()).unwrap();
+        load_tools_file(&path)
+    }
+
+    #[test]
+    fn test_loads_valid_tools() {
+        let tools = load(json!([
+            tool("refund", "financial", "financial_transaction"),
+            tool("lookup", "data", "data_read_internal"),
+        ]))
+        .unwrap();
+        assert_eq!(tools.len(), 2);
+        assert_eq!(tools[1].risk_subcategory, "data_read_internal");
+    }
+
+    #[test]
+    fn test_errors_name_the_tool() {
+        let err = load(json!([tool("refund", "financial", "data_delete")])).unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("tool 'refund'"), "{}", message);
+        assert!(message.contains("does not belong to riskCategory 'financial'"));
+
+        let mut short = tool("lookup", "data", "data_read_internal");
+        short["toolDescription"] = json!("short");
+        let message = format!("{:#}", load(json!([short])).unwrap_err());
+        assert!(message.contains("tool 'lookup'") && message.contains("10-1000"));
+
+        let mut missing = tool("notify", "external", "external_email");
+        missing.as_object_mut().unwrap().remove("toolName");
+        let message = format!("{:#}", load(json!([missing])).unwrap_err());
+        assert!(message.contains("tool 'notify'") && message.contains("toolName"));
+
+        let duplicate = tool("refund", "financial", "financial_read");
+        let message = format!(
+            "{:#}",
+            load(json!([duplicate.clone(), duplicate])).unwrap_err()
+        );
+        assert!(message.contains("more than once"));
+
+        assert!(load(json!({"toolId": "refund"})).is_err());
+    }
+}
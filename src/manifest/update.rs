@@ -0,0 +1,248 @@
+//! Incremental manifest update (`init --update`)
+//!
+//! Init records each field it took from detection in `_metadata`: the
+//! detection source in `autoDetected` and the value it wrote in
+//! `detectedValues`. On update, a field is replaced by the freshly detected
+//! value only while the manifest still holds the value detection last wrote;
+//! anything else was edited by hand and is left alone.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::Value;
+
+use crate::manifest::detector::DetectionResults;
+use crate::manifest::templates::ManifestTemplates;
+
+/// A manifest field replaced by a newly detected value
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: String,
+    /// The previous value; `None` when the field was missing
+    pub from: Option<Value>,
+    pub to: Value,
+}
+
+/// Outcome of [`merge_detected`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    /// Fields that still held their detected value and were updated
+    pub changed: Vec<FieldChange>,
+    /// Fields whose new detected value differs from a manual edit, which was kept
+    pub kept: Vec<String>,
+}
+
+/// Manifest fields (by their JSON name) as init sets them from detection.
+/// Fields detection left unset are absent, since init fills those from
+/// templates rather than detection.
+pub fn detected_values(results: &DetectionResults) -> HashMap<String, Value> {
+    let mut values = HashMap::new();
+    let mut insert = |field: &str, value: Value| {
+        values.insert(field.to_string(), value);
+    };
+
+    if let Some(name) = &results.project_name {
+        insert("agentName", Value::from(name.as_str()));
+    }
+    if let Some(version) = &results.project_version {
+        insert("agentVersion", Value::from(version.as_str()));
+    }
+    if let Some(description) = &results.project_description {
+        if (50..=1000).contains(&description.len()) {
+            insert("agentDescription", Value::from(description.as_str()));
+        }
+    }
+    if let Some(date) = &results.first_release_date {
+        insert("firstReleaseDate", Value::from(date.as_str()));
+    }
+    if let Some(architecture) = &results.architecture_type {
+        insert("architectureType", to_value(architecture));
+    }
+    if let Some(provider) = &results.model_provider {
+        insert("primaryModelProvider", Value::from(provider.as_str()));
+    }
+    if let Some(family) = &results.model_family {
+        insert("primaryModelFamily", Value::from(family.as_str()));
+        insert(
+            "modelContextWindow",
+            Value::from(ManifestTemplates::default_context_window(family)),
+        );
+    }
+    if !results.modality_support.is_empty() {
+        insert("modalitySupport", to_value(&results.modality_support));
+    }
+    if !results.language_capabilities.is_empty() {
+        insert(
+            "languageCapabilities",
+            to_value(&results.language_capabilities),
+        );
+    }
+    if !results.data_categories.is_empty() {
+        insert(
+            "dataCategoriesProcessed",
+            to_value(&results.data_categories),
+        );
+    }
+    if let Some(context) = &results.deployment_context {
+        insert("deploymentContext", to_value(context));
+    }
+    values
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).expect("detected values serialize to JSON")
+}
+
+/// The `detectedValues` recorded in a manifest's `_metadata`; empty for
+/// manifests written before they were recorded
+pub fn recorded_values(manifest: &Value) -> HashMap<String, Value> {
+    manifest
+        .get("_metadata")
+        .and_then(|metadata| metadata.get("detectedValues"))
+        .and_then(Value::as_object)
+        .map(|values| values.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .unwrap_or_default()
+}
+
+/// Apply `fresh` detected values to `manifest`, replacing a field only when
+/// its current value equals the `previous` detected one (both missing counts
+/// as equal). A field with no recorded previous value is only filled in when
+/// the manifest lacks it.
+pub fn merge_detected(
+    manifest: &mut Value,
+    previous: &HashMap<String, Value>,
+    fresh: &HashMap<String, Value>,
+) -> MergeReport {
+    let mut report = MergeReport::default();
+    let Some(object) = manifest.as_object_mut() else {
+        return report;
+    };
+
+    // Sorted so the summary is stable
+    let fresh: BTreeMap<_, _> = fresh.iter().collect();
+    for (field, value) in fresh {
+        let current = object.get(field.as_str());
+        if current == Some(value) {
+            continue;
+        }
+        if current == previous.get(field.as_str()) {
+            report.changed.push(FieldChange {
+                field: field.clone(),
+                from: current.cloned(),
+                to: value.clone(),
+            });
+            object.insert(field.clone(), value.clone());
+        } else {
+            report.kept.push(field.clone());
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::schema::ArchitectureType;
+    use serde_json::json;
+
+    fn values(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_merge_replaces_only_untouched_fields() {
+        let mut manifest = json!({
+            "agentName": "my-agent",
+            "agentVersion": "1.0.0",
+            "languageCapabilities": ["en", "fr"],
+            "agentDescription": "hand written",
+        });
+        let previous = values(json!({
+            "agentName": "my-agent",
+            "agentVersion": "0.9.0",
+            "languageCapabilities": ["en"],
+        }));
+        let fresh = values(json!({
+            "agentName": "my-agent",
+            "agentVersion": "1.1.0",
+            "languageCapabilities": ["en", "de"],
+            "architectureType": "tool_using",
+        }));
+
+        let report = merge_detected(&mut manifest, &previous, &fresh);
+
+        // agentVersion and languageCapabilities were edited since detection
+        assert_eq!(report.kept, vec!["agentVersion", "languageCapabilities"]);
+        // architectureType was never there, so it is added
+        assert_eq!(
+            report.changed,
+            vec![FieldChange {
+                field: "architectureType".to_string(),
+                from: None,
+                to: json!("tool_using"),
+            }]
+        );
+        assert_eq!(manifest["agentVersion"], "1.0.0");
+        assert_eq!(manifest["languageCapabilities"], json!(["en", "fr"]));
+        assert_eq!(manifest["agentDescription"], "hand written");
+        assert_eq!(manifest["architectureType"], "tool_using");
+    }
+
+    #[test]
+    fn test_merge_updates_fields_still_at_detected_value() {
+        let mut manifest = json!({
+            "agentVersion": "0.9.0",
+            "languageCapabilities": ["en"],
+        });
+        let previous = values(json!({
+            "agentVersion": "0.9.0",
+            "languageCapabilities": ["en"],
+        }));
+        let fresh = values(json!({
+            "agentVersion": "0.9.0",
+            "languageCapabilities": ["en", "de"],
+        }));
+
+        let report = merge_detected(&mut manifest, &previous, &fresh);
+
+        assert!(report.kept.is_empty());
+        assert_eq!(report.changed.len(), 1);
+        assert_eq!(report.changed[0].field, "languageCapabilities");
+        assert_eq!(report.changed[0].from, Some(json!(["en"])));
+        assert_eq!(manifest["languageCapabilities"], json!(["en", "de"]));
+    }
+
+    #[test]
+    fn test_merge_without_recorded_values_keeps_existing_fields() {
+        let mut manifest = json!({ "agentVersion": "0.9.0" });
+        let fresh = values(json!({ "agentVersion": "1.0.0", "firstReleaseDate": "2024-01-01" }));
+
+        let report = merge_detected(&mut manifest, &HashMap::new(), &fresh);
+
+        assert_eq!(report.kept, vec!["agentVersion"]);
+        assert_eq!(report.changed.len(), 1);
+        assert_eq!(manifest["agentVersion"], "0.9.0");
+        assert_eq!(manifest["firstReleaseDate"], "2024-01-01");
+    }
+
+    #[test]
+    fn test_detected_values_and_recorded_values() {
+        let results = DetectionResults {
+            project_name: Some("agent".to_string()),
+            project_description: Some("too short".to_string()),
+            architecture_type: Some(ArchitectureType::ToolUsing),
+            model_family: Some("gpt-4o".to_string()),
+            ..Default::default()
+        };
+        let detected = detected_values(&results);
+        assert_eq!(detected["agentName"], "agent");
+        assert_eq!(detected["architectureType"], "tool_using");
+        assert!(detected.contains_key("modelContextWindow"));
+        // Init ignores descriptions outside 50-1000 characters
+        assert!(!detected.contains_key("agentDescription"));
+        assert!(!detected.contains_key("modalitySupport"));
+
+        let manifest = json!({ "_metadata": { "detectedValues": detected } });
+        assert_eq!(recorded_values(&manifest), detected);
+        assert!(recorded_values(&json!({})).is_empty());
+    }
+}
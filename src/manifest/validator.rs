@@ -3,8 +3,41 @@ use regex::Regex;
 use serde_json::Value;
 use uuid::Uuid;
 
+use crate::error::BelticError;
+use crate::manifest::credential::ModelFamily;
 use crate::manifest::schema::AgentManifest;
 
+/// Rule id for errors without a more specific rule
+pub const RULE_INVALID_VALUE: &str = "beltic/invalid-value";
+/// Rule id for warnings without a more specific rule
+pub const RULE_RECOMMENDATION: &str = "beltic/recommendation";
+/// Rule id for missing required fields
+pub const RULE_MISSING_FIELD: &str = "beltic/missing-field";
+/// Rule id for JSON Schema violations
+pub const RULE_SCHEMA_VIOLATION: &str = "beltic/schema-violation";
+/// Rule id for a context window far from the model family's usual one
+pub const RULE_CONTEXT_WINDOW: &str = "beltic/context-window";
+/// Rule id for the note that the platform sets safety metrics. Every manifest
+/// gets it, so `init --fail-on-warning` does not count it.
+pub const RULE_SAFETY_METRICS: &str = "beltic/safety-metrics";
+
+/// Severity of a validation issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueLevel {
+    Error,
+    Warning,
+}
+
+/// A single validation finding with a stable rule id
+#[derive(Debug, Clone)]
+pub struct ValidationIssue {
+    pub rule_id: &'static str,
+    pub level: IssueLevel,
+    pub message: String,
+    /// JSON pointer to the offending field, when known
+    pub pointer: Option<String>,
+}
+
 /// Validation result with errors and warnings
 #[derive(Debug, Default)]
 pub struct ValidationResult {
@@ -12,6 +45,8 @@ pub struct ValidationResult {
     pub errors: Vec<String>,
     pub warnings: Vec<String>,
     pub missing_fields: Vec<String>,
+    /// Every error, warning, and missing field in the order found
+    pub issues: Vec<ValidationIssue>,
 }
 
 impl ValidationResult {
@@ -21,25 +56,103 @@ impl ValidationResult {
             errors: Vec::new(),
             warnings: Vec::new(),
             missing_fields: Vec::new(),
+            issues: Vec::new(),
         }
     }
 
     pub fn add_error(&mut self, error: String) {
-        self.errors.push(error);
-        self.is_valid = false;
+        self.push_error(RULE_INVALID_VALUE, None, error);
     }
 
     pub fn add_warning(&mut self, warning: String) {
-        self.warnings.push(warning);
+        self.push_warning(RULE_RECOMMENDATION, None, warning);
     }
 
     pub fn add_missing_field(&mut self, field: String) {
+        self.issues.push(ValidationIssue {
+            rule_id: RULE_MISSING_FIELD,
+            level: IssueLevel::Error,
+            message: format!("Missing required field: {}", field),
+            pointer: Some(format!("/{}", field.replace('.', "/"))),
+        });
         self.missing_fields.push(field);
         self.is_valid = false;
     }
+
+    /// Add an error under a specific rule, located at a JSON pointer
+    pub fn add_error_at(&mut self, rule_id: &'static str, pointer: &str, error: String) {
+        self.push_error(rule_id, Some(pointer.to_string()), error);
+    }
+
+    /// Add a warning under a specific rule, located at a JSON pointer
+    pub fn add_warning_at(&mut self, rule_id: &'static str, pointer: &str, warning: String) {
+        self.push_warning(rule_id, Some(pointer.to_string()), warning);
+    }
+
+    /// `Ok(self)` when no errors were found, else the errors joined as a
+    /// [`BelticError::Validation`] (warnings do not fail)
+    pub fn into_result(self) -> Result<Self, BelticError> {
+        if self.is_valid {
+            Ok(self)
+        } else {
+            let errors: Vec<&str> = self
+                .issues
+                .iter()
+                .filter(|issue| issue.level == IssueLevel::Error)
+                .map(|issue| issue.message.as_str())
+                .collect();
+            Err(BelticError::Validation(format!(
+                "manifest validation failed: {}",
+                errors.join("; ")
+            )))
+        }
+    }
+
+    fn push_error(&mut self, rule_id: &'static str, pointer: Option<String>, error: String) {
+        self.issues.push(ValidationIssue {
+            rule_id,
+            level: IssueLevel::Error,
+            message: error.clone(),
+            pointer,
+        });
+        self.errors.push(error);
+        self.is_valid = false;
+    }
+
+    fn push_warning(&mut self, rule_id: &'static str, pointer: Option<String>, warning: String) {
+        self.issues.push(ValidationIssue {
+            rule_id,
+            level: IssueLevel::Warning,
+            message: warning.clone(),
+            pointer,
+        });
+        self.warnings.push(warning);
+    }
 }
 
 /// Validate an agent manifest against Beltic v1 schema
+///
+/// ```
+/// use beltic::manifest::schema::{ArchitectureType, DeploymentType};
+/// use beltic::manifest::templates::generate_complete_defaults;
+/// use beltic::manifest::validate_manifest;
+///
+/// let manifest = generate_complete_defaults(
+///     "Support Bot".to_string(),
+///     "1.0.0".to_string(),
+///     ArchitectureType::ToolUsing,
+///     DeploymentType::Standalone,
+/// );
+/// let result = validate_manifest(&manifest);
+/// for issue in &result.issues {
+///     println!("{:?} [{}] {}", issue.level, issue.rule_id, issue.message);
+/// }
+///
+/// // Or fail with every error in one `BelticError::Validation`
+/// if let Err(err) = result.into_result() {
+///     eprintln!("{}", err);
+/// }
+/// ```
 pub fn validate_manifest(manifest: &AgentManifest) -> ValidationResult {
     let mut result = ValidationResult::new();
 
@@ -66,16 +179,26 @@ fn check_no_todos(manifest: &AgentManifest, result: &mut ValidationResult) {
     let json_str = serde_json::to_string(manifest).unwrap_or_default();
 
     if json_str.contains("TODO") {
-        result.add_error("Manifest contains TODO placeholders that must be replaced".to_string());
+        result.add_error_at(
+            "beltic/placeholder",
+            "",
+            "Manifest contains TODO placeholders that must be replaced".to_string(),
+        );
     }
 
     // Check specific fields that commonly have TODOs
     if manifest.agent_name.starts_with("TODO") || manifest.agent_name == "my-agent" {
-        result.add_error("Agent name must be set to a meaningful value".to_string());
+        result.add_error_at(
+            "beltic/placeholder",
+            "/agentName",
+            "Agent name must be set to a meaningful value".to_string(),
+        );
     }
 
     if manifest.agent_description.starts_with("TODO") || manifest.agent_description.len() < 50 {
-        result.add_error(
+        result.add_error_at(
+            "beltic/placeholder",
+            "/agentDescription",
             "Agent description must be at least 50 characters and not a placeholder".to_string(),
         );
     }
@@ -83,14 +206,20 @@ fn check_no_todos(manifest: &AgentManifest, result: &mut ValidationResult) {
     if manifest.incident_response_contact.starts_with("TODO")
         || manifest.incident_response_contact == "security@example.com"
     {
-        result.add_error(
+        result.add_error_at(
+            "beltic/placeholder",
+            "/incidentResponseContact",
             "Incident response contact must be a valid email address for your organization"
                 .to_string(),
         );
     }
 
     if manifest.developer_credential_id == Uuid::nil() {
-        result.add_warning("Developer credential ID is not set. You'll need to obtain this from the Beltic platform".to_string());
+        result.add_warning_at(
+            "beltic/missing-developer-credential",
+            "/developerCredentialId",
+            "Developer credential ID is not set. You'll need to obtain this from the Beltic platform".to_string(),
+        );
     }
 }
 
@@ -181,109 +310,161 @@ fn validate_field_formats(manifest: &AgentManifest, result: &mut ValidationResul
     // Validate semantic version
     let version_regex = Regex::new(r"^\d+\.\d+\.\d+(-[\w\.]+)?(\+[\w\.]+)?$").unwrap();
     if !version_regex.is_match(&manifest.agent_version) {
-        result.add_error(format!(
-            "Invalid version format: {}. Must be semantic version (e.g., 1.0.0)",
-            manifest.agent_version
-        ));
+        result.add_error_at(
+            "beltic/invalid-version",
+            "/agentVersion",
+            format!(
+                "Invalid version format: {}. Must be semantic version (e.g., 1.0.0)",
+                manifest.agent_version
+            ),
+        );
     }
 
     // Validate email
     if !manifest.incident_response_contact.contains('@')
         || !manifest.incident_response_contact.contains('.')
     {
-        result.add_error(format!(
-            "Invalid email address: {}",
-            manifest.incident_response_contact
-        ));
+        result.add_error_at(
+            "beltic/invalid-email",
+            "/incidentResponseContact",
+            format!(
+                "Invalid email address: {}",
+                manifest.incident_response_contact
+            ),
+        );
     }
 
     // Validate ISO date
     let date_regex = Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap();
     if !date_regex.is_match(&manifest.first_release_date) {
-        result.add_error(format!(
-            "Invalid date format: {}. Must be ISO date (YYYY-MM-DD)",
-            manifest.first_release_date
-        ));
+        result.add_error_at(
+            "beltic/invalid-date",
+            "/firstReleaseDate",
+            format!(
+                "Invalid date format: {}. Must be ISO date (YYYY-MM-DD)",
+                manifest.first_release_date
+            ),
+        );
     }
     if !date_regex.is_match(&manifest.system_config_last_updated) {
-        result.add_error(format!(
-            "Invalid date format for systemConfigLastUpdated: {}",
-            manifest.system_config_last_updated
-        ));
+        result.add_error_at(
+            "beltic/invalid-date",
+            "/systemConfigLastUpdated",
+            format!(
+                "Invalid date format for systemConfigLastUpdated: {}",
+                manifest.system_config_last_updated
+            ),
+        );
     }
 
     // Validate ISO duration
     let duration_regex = Regex::new(r"^P(T?\d+[YMDHMS])+$").unwrap();
     if !duration_regex.is_match(&manifest.data_retention_max_period) {
-        result.add_error(format!(
-            "Invalid ISO duration: {}. Must be ISO 8601 (e.g., P30D, PT4H)",
-            manifest.data_retention_max_period
-        ));
+        result.add_error_at(
+            "beltic/invalid-duration",
+            "/dataRetentionMaxPeriod",
+            format!(
+                "Invalid ISO duration: {}. Must be ISO 8601 (e.g., P30D, PT4H)",
+                manifest.data_retention_max_period
+            ),
+        );
     }
     if !duration_regex.is_match(&manifest.incident_response_slo) {
-        result.add_error(format!(
-            "Invalid ISO duration for SLO: {}",
-            manifest.incident_response_slo
-        ));
+        result.add_error_at(
+            "beltic/invalid-duration",
+            "/incidentResponseSlo",
+            format!(
+                "Invalid ISO duration for SLO: {}",
+                manifest.incident_response_slo
+            ),
+        );
     }
 
     // Validate fingerprint (64 hex chars)
     if manifest.system_config_fingerprint != "TODO: Will be generated" {
         let fingerprint = manifest.system_config_fingerprint.replace("sha256:", "");
         if fingerprint.len() != 64 || !fingerprint.chars().all(|c| c.is_ascii_hexdigit()) {
-            result.add_error(format!(
-                "Invalid fingerprint format. Must be 64 hex characters"
-            ));
+            result.add_error_at(
+                "beltic/invalid-fingerprint",
+                "/systemConfigFingerprint",
+                "Invalid fingerprint format. Must be 64 hex characters".to_string(),
+            );
         }
     }
 
     // Validate language codes (ISO 639-1)
-    for lang in &manifest.language_capabilities {
+    for (i, lang) in manifest.language_capabilities.iter().enumerate() {
         if lang.len() != 2 {
-            result.add_warning(format!(
-                "Language code '{}' should be ISO 639-1 (2 letters)",
-                lang
-            ));
+            result.add_warning_at(
+                "beltic/invalid-language-code",
+                &format!("/languageCapabilities/{}", i),
+                format!("Language code '{}' should be ISO 639-1 (2 letters)", lang),
+            );
         }
     }
 
     // Validate region codes (ISO 3166-1 alpha-2)
-    for region in &manifest.data_location_profile.storage_regions {
+    for (i, region) in manifest
+        .data_location_profile
+        .storage_regions
+        .iter()
+        .enumerate()
+    {
         if region.len() != 2 || !region.chars().all(|c| c.is_ascii_uppercase()) {
-            result.add_warning(format!(
-                "Region code '{}' should be ISO 3166-1 alpha-2 (e.g., US, CA)",
-                region
-            ));
+            result.add_warning_at(
+                "beltic/invalid-region-code",
+                &format!("/dataLocationProfile/storageRegions/{}", i),
+                format!(
+                    "Region code '{}' should be ISO 3166-1 alpha-2 (e.g., US, CA)",
+                    region
+                ),
+            );
         }
     }
 
     // Validate field lengths
     if manifest.agent_name.len() < 2 || manifest.agent_name.len() > 200 {
-        result.add_error(format!(
-            "Agent name must be 2-200 characters (current: {})",
-            manifest.agent_name.len()
-        ));
+        result.add_error_at(
+            "beltic/field-length",
+            "/agentName",
+            format!(
+                "Agent name must be 2-200 characters (current: {})",
+                manifest.agent_name.len()
+            ),
+        );
     }
 
     if manifest.agent_description.len() < 50 || manifest.agent_description.len() > 1000 {
-        result.add_error(format!(
-            "Agent description must be 50-1000 characters (current: {})",
-            manifest.agent_description.len()
-        ));
+        result.add_error_at(
+            "beltic/field-length",
+            "/agentDescription",
+            format!(
+                "Agent description must be 50-1000 characters (current: {})",
+                manifest.agent_description.len()
+            ),
+        );
     }
 
     if manifest.fail_safe_behavior.len() < 50 || manifest.fail_safe_behavior.len() > 800 {
-        result.add_error(format!(
-            "Fail-safe behavior must be 50-800 characters (current: {})",
-            manifest.fail_safe_behavior.len()
-        ));
+        result.add_error_at(
+            "beltic/field-length",
+            "/failSafeBehavior",
+            format!(
+                "Fail-safe behavior must be 50-800 characters (current: {})",
+                manifest.fail_safe_behavior.len()
+            ),
+        );
     }
 
     if manifest.monitoring_coverage.len() < 50 || manifest.monitoring_coverage.len() > 800 {
-        result.add_error(format!(
-            "Monitoring coverage must be 50-800 characters (current: {})",
-            manifest.monitoring_coverage.len()
-        ));
+        result.add_error_at(
+            "beltic/field-length",
+            "/monitoringCoverage",
+            format!(
+                "Monitoring coverage must be 50-800 characters (current: {})",
+                manifest.monitoring_coverage.len()
+            ),
+        );
     }
 }
 
@@ -367,6 +548,8 @@ fn validate_business_logic(manifest: &AgentManifest, result: &mut ValidationResu
         ));
     }
 
+    check_context_window_for_family(manifest, result);
+
     // Check age restrictions with data categories
     if manifest
         .data_categories_processed
@@ -381,11 +564,46 @@ fn validate_business_logic(manifest: &AgentManifest, result: &mut ValidationResu
     }
 }
 
+/// How far a context window may be from the family's usual one, either
+/// way, before it looks like a typo
+const CONTEXT_WINDOW_TOLERANCE: u32 = 4;
+
+/// Warn when the context window is far from the model family's usual one.
+/// Only a warning: custom deployments can configure other windows.
+fn check_context_window_for_family(manifest: &AgentManifest, result: &mut ValidationResult) {
+    let window = manifest.model_context_window;
+    let family_name = manifest.primary_model_family.trim();
+    if window == 0 || family_name.starts_with("TODO") {
+        return;
+    }
+    let family = ModelFamily::from_display_name(family_name);
+    if family == ModelFamily::Other {
+        return;
+    }
+
+    let expected = family.default_context_window();
+    if window > expected.saturating_mul(CONTEXT_WINDOW_TOLERANCE)
+        || window.saturating_mul(CONTEXT_WINDOW_TOLERANCE) < expected
+    {
+        result.add_warning_at(
+            RULE_CONTEXT_WINDOW,
+            "/modelContextWindow",
+            format!(
+                "Context window {} is far from the {} tokens expected for {}; check for a typo",
+                window, expected, family_name
+            ),
+        );
+    }
+}
+
 /// Validate safety metrics (will be set by Beltic, but check structure)
 fn validate_safety_metrics(_manifest: &AgentManifest, result: &mut ValidationResult) {
     // For now, just add a note that safety metrics will be evaluated
-    result
-        .add_warning("Safety metrics will be evaluated and set by the Beltic platform".to_string());
+    result.push_warning(
+        RULE_SAFETY_METRICS,
+        None,
+        "Safety metrics will be evaluated and set by the Beltic platform".to_string(),
+    );
 }
 
 /// Check if a string contains a valid UUID
@@ -454,3 +672,48 @@ pub fn format_validation_summary(result: &ValidationResult) -> String {
 
     summary
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::schema::{ArchitectureType, DeploymentType};
+    use crate::manifest::templates::{generate_complete_defaults, ManifestTemplates};
+
+    fn context_warnings(family: &str, window: u32) -> Vec<String> {
+        let mut manifest = generate_complete_defaults(
+            "Support Bot".to_string(),
+            "0.1.0".to_string(),
+            ArchitectureType::ToolUsing,
+            DeploymentType::Standalone,
+        );
+        manifest.primary_model_family = family.to_string();
+        manifest.model_context_window = window;
+        validate_manifest(&manifest)
+            .issues
+            .into_iter()
+            .filter(|issue| issue.rule_id == RULE_CONTEXT_WINDOW)
+            .map(|issue| issue.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_context_window_far_from_family_warns() {
+        let warnings = context_warnings("Claude-3 Opus", 2000);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("200000 tokens expected for Claude-3 Opus"));
+        assert_eq!(context_warnings("GPT-4 Turbo", 1_280_000).len(), 1);
+
+        // Within 4x either way, or an unknown family, is fine
+        assert!(context_warnings("Claude-3 Opus", 100_000).is_empty());
+        assert!(context_warnings("GPT-4o", 32_000).is_empty());
+        assert!(context_warnings("Custom Model", 2000).is_empty());
+
+        // Template defaults never trip the check
+        for (provider, _) in ManifestTemplates::model_providers() {
+            for family in ManifestTemplates::model_families(provider) {
+                let window = ManifestTemplates::default_context_window(&family);
+                assert!(context_warnings(&family, window).is_empty(), "{}", family);
+            }
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Informational output and `--quiet`
+//!
+//! Progress and status lines ("✓ Created ...", "[info] Using ...") go through
+//! [`status!`](crate::status) and [`estatus!`](crate::estatus), which print
+//! nothing once [`set_quiet`] has been called. Errors, warnings and output the
+//! user asked for (a token, a JSON result, a verification verdict) are printed
+//! directly and are never suppressed.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Quiet mode set via the `--quiet` flag
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable quiet mode
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether informational output is suppressed
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// `println!` for informational output; prints nothing with `--quiet`
+#[macro_export]
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if !$crate::output::is_quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// `eprintln!` for informational output; prints nothing with `--quiet`
+#[macro_export]
+macro_rules! estatus {
+    ($($arg:tt)*) => {
+        if !$crate::output::is_quiet() {
+            eprintln!($($arg)*);
+        }
+    };
+}
@@ -0,0 +1,600 @@
+//! Credential revocation status
+//!
+//! A credential points at its revocation list in one of two ways:
+//!
+//! - a `credentialStatus` entry (`BitstringStatusListEntry` or
+//!   `StatusList2021Entry`) naming a status list credential and the
+//!   credential's index in it (v2 schemas)
+//! - a `revocationListUrl` (v1 schemas), looked up by the credential's id
+//!
+//! Three list formats are understood: a JSON array of revoked ids (or of
+//! `{"id", "status"}` objects), an object with `revoked` and `suspended` id
+//! arrays, and a StatusList2021 / Bitstring Status List credential whose
+//! `credentialSubject.encodedList` is a gzip-compressed, base64url-encoded
+//! bitstring. Fetched lists are cached under the schema cache directory so
+//! they can be used again in offline mode.
+
+use std::fmt;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use chrono::Utc;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::schema::{cache_dir, is_offline};
+
+/// Whether a credential is still in good standing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevocationStatus {
+    Active,
+    Suspended,
+    Revoked,
+}
+
+impl RevocationStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RevocationStatus::Active => "active",
+            RevocationStatus::Suspended => "suspended",
+            RevocationStatus::Revoked => "revoked",
+        }
+    }
+}
+
+impl fmt::Display for RevocationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Where a credential's status is recorded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatusReference {
+    /// Bit `index` of the status list credential at `url`
+    Index {
+        url: String,
+        index: usize,
+        /// `revocation` or `suspension`
+        purpose: String,
+    },
+    /// One of `ids` listed in the revocation list at `url`
+    Id { url: String, ids: Vec<String> },
+}
+
+impl StatusReference {
+    /// Read the status reference from a credential. `jti` is the token's id,
+    /// also looked up in id-based lists.
+    pub fn from_credential(vc: &Value, jti: Option<&str>) -> Result<Self> {
+        if let Some(entry) = vc.get("credentialStatus").filter(|v| v.is_object()) {
+            let url = entry
+                .get("statusListCredential")
+                .and_then(Value::as_str)
+                .context("credentialStatus has no statusListCredential")?;
+            let index = match entry.get("statusListIndex") {
+                Some(Value::String(index)) => index.parse().ok(),
+                Some(Value::Number(index)) => index.as_u64().map(|i| i as usize),
+                _ => None,
+            }
+            .context("credentialStatus has no valid statusListIndex")?;
+            let purpose = entry
+                .get("statusPurpose")
+                .and_then(Value::as_str)
+                .unwrap_or("revocation");
+            return Ok(StatusReference::Index {
+                url: url.to_string(),
+                index,
+                purpose: purpose.to_string(),
+            });
+        }
+
+        let url = vc
+            .get("revocationListUrl")
+            .and_then(Value::as_str)
+            .context("credential has neither a credentialStatus entry nor a revocationListUrl")?;
+        let mut ids: Vec<String> = Vec::new();
+        let candidates = ["credentialId", "id"]
+            .iter()
+            .filter_map(|field| vc.get(*field).and_then(Value::as_str))
+            .chain(jti);
+        for id in candidates {
+            if !ids.iter().any(|known| known == id) {
+                ids.push(id.to_string());
+            }
+        }
+        if ids.is_empty() {
+            bail!("credential has no credentialId to look up in its revocation list");
+        }
+        Ok(StatusReference::Id {
+            url: url.to_string(),
+            ids,
+        })
+    }
+
+    /// URL of the list holding the credential's status
+    pub fn url(&self) -> &str {
+        match self {
+            StatusReference::Index { url, .. } | StatusReference::Id { url, .. } => url,
+        }
+    }
+}
+
+/// A parsed revocation list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevocationList {
+    /// Ids with a non-active status
+    Ids {
+        revoked: Vec<String>,
+        suspended: Vec<String>,
+    },
+    /// Decompressed status list bitstring; a set bit means the credential
+    /// at that index has the list's purpose applied
+    Bitstring { purpose: String, bits: Vec<u8> },
+}
+
+impl RevocationList {
+    /// Parse any of the supported list formats
+    pub fn parse(list: &Value) -> Result<Self> {
+        match list {
+            Value::Array(entries) => {
+                let mut revoked = Vec::new();
+                let mut suspended = Vec::new();
+                for entry in entries {
+                    match entry {
+                        Value::String(id) => revoked.push(id.clone()),
+                        Value::Object(_) => {
+                            let id = entry
+                                .get("id")
+                                .and_then(Value::as_str)
+                                .context("revocation list entry has no id")?;
+                            match entry.get("status").and_then(Value::as_str) {
+                                None | Some("revoked") => revoked.push(id.to_string()),
+                                Some("suspended") => suspended.push(id.to_string()),
+                                Some("active") => {}
+                                Some(other) => bail!(
+                                    "revocation list entry {} has unknown status '{}'",
+                                    id,
+                                    other
+                                ),
+                            }
+                        }
+                        _ => bail!("revocation list entries must be ids or objects"),
+                    }
+                }
+                Ok(RevocationList::Ids { revoked, suspended })
+            }
+            Value::Object(object) if object.contains_key("credentialSubject") => {
+                Self::parse_status_list(list)
+            }
+            Value::Object(object) => {
+                if !object.contains_key("revoked") && !object.contains_key("suspended") {
+                    bail!("revocation list object needs revoked or suspended ids, or a credentialSubject status list");
+                }
+                Ok(RevocationList::Ids {
+                    revoked: id_array(list, "revoked")?,
+                    suspended: id_array(list, "suspended")?,
+                })
+            }
+            _ => bail!("revocation list must be a JSON array or object"),
+        }
+    }
+
+    fn parse_status_list(list: &Value) -> Result<Self> {
+        let subject = &list["credentialSubject"];
+        let encoded = subject
+            .get("encodedList")
+            .and_then(Value::as_str)
+            .context("status list credential has no credentialSubject.encodedList")?;
+        let purpose = match subject.get("statusPurpose") {
+            Some(Value::String(purpose)) => purpose.clone(),
+            // Bitstring Status List allows several purposes; one is enough here
+            Some(Value::Array(purposes)) => purposes
+                .first()
+                .and_then(Value::as_str)
+                .unwrap_or("revocation")
+                .to_string(),
+            _ => "revocation".to_string(),
+        };
+        Ok(RevocationList::Bitstring {
+            purpose,
+            bits: decode_bitstring(encoded)?,
+        })
+    }
+
+    /// Status of the referenced credential according to this list
+    pub fn status_of(&self, reference: &StatusReference) -> Result<RevocationStatus> {
+        match (self, reference) {
+            (RevocationList::Ids { revoked, suspended }, StatusReference::Id { ids, .. }) => {
+                if ids.iter().any(|id| revoked.contains(id)) {
+                    Ok(RevocationStatus::Revoked)
+                } else if ids.iter().any(|id| suspended.contains(id)) {
+                    Ok(RevocationStatus::Suspended)
+                } else {
+                    Ok(RevocationStatus::Active)
+                }
+            }
+            (
+                RevocationList::Bitstring { purpose, bits },
+                StatusReference::Index {
+                    index,
+                    purpose: entry_purpose,
+                    ..
+                },
+            ) => {
+                if purpose != entry_purpose {
+                    bail!(
+                        "credential's status entry is for {}, but the status list is for {}",
+                        entry_purpose,
+                        purpose
+                    );
+                }
+                let byte = bits.get(index / 8).with_context(|| {
+                    format!(
+                        "status list index {} is past the end of the list ({} entries)",
+                        index,
+                        bits.len() * 8
+                    )
+                })?;
+                // Index 0 is the most significant bit of the first byte
+                if byte & (0x80 >> (index % 8)) == 0 {
+                    Ok(RevocationStatus::Active)
+                } else if purpose == "suspension" {
+                    Ok(RevocationStatus::Suspended)
+                } else {
+                    Ok(RevocationStatus::Revoked)
+                }
+            }
+            (RevocationList::Bitstring { .. }, StatusReference::Id { .. }) => bail!(
+                "revocation list is a status list bitstring, but the credential has no credentialStatus index into it"
+            ),
+            (RevocationList::Ids { .. }, StatusReference::Index { .. }) => bail!(
+                "credential has a status list index, but its list is not a status list credential"
+            ),
+        }
+    }
+}
+
+fn id_array(list: &Value, field: &str) -> Result<Vec<String>> {
+    match list.get(field) {
+        None => Ok(Vec::new()),
+        Some(Value::Array(ids)) => ids
+            .iter()
+            .map(|id| {
+                id.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| anyhow!("revocation list {} entries must be strings", field))
+            })
+            .collect(),
+        Some(_) => bail!("revocation list {} must be an array", field),
+    }
+}
+
+/// Decode a gzip-compressed, base64url bitstring, with or without the
+/// multibase `u` prefix Bitstring Status List uses
+fn decode_bitstring(encoded: &str) -> Result<Vec<u8>> {
+    let trimmed = encoded.trim();
+    let unprefixed = trimmed.strip_prefix('u').unwrap_or(trimmed);
+    let compressed = URL_SAFE_NO_PAD
+        .decode(unprefixed.trim_end_matches('='))
+        .or_else(|_| STANDARD.decode(trimmed))
+        .context("status list encodedList is not base64")?;
+    let mut bits = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut bits)
+        .context("status list encodedList is not gzip-compressed")?;
+    Ok(bits)
+}
+
+/// Bits in a new status list: the 16 KB minimum StatusList2021 recommends,
+/// so the list doesn't reveal how many credentials its issuer has
+pub const DEFAULT_LIST_SIZE: usize = 131_072;
+
+/// Gzip-compress and base64url-encode a status list bitstring, as in a
+/// StatusList2021 `encodedList`
+pub fn encode_bitstring(bits: &[u8]) -> Result<String> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(bits)
+        .and_then(|_| encoder.finish())
+        .map(|compressed| URL_SAFE_NO_PAD.encode(compressed))
+        .context("failed to compress status list")
+}
+
+/// Set (`true`) or clear bit `index` of a status list bitstring
+pub fn set_status_bit(bits: &mut [u8], index: usize, value: bool) -> Result<()> {
+    let len = bits.len() * 8;
+    let byte = bits.get_mut(index / 8).with_context(|| {
+        format!(
+            "index {} is past the end of the status list ({} entries)",
+            index, len
+        )
+    })?;
+    let mask = 0x80 >> (index % 8);
+    if value {
+        *byte |= mask;
+    } else {
+        *byte &= !mask;
+    }
+    Ok(())
+}
+
+/// A StatusList2021 credential publishing `bits`, to be hosted at `url`
+pub fn status_list_credential(
+    url: &str,
+    issuer: &str,
+    purpose: &str,
+    bits: &[u8],
+) -> Result<Value> {
+    Ok(json!({
+        "@context": [
+            "https://www.w3.org/2018/credentials/v1",
+            "https://w3id.org/vc/status-list/2021/v1"
+        ],
+        "id": url,
+        "type": ["VerifiableCredential", "StatusList2021Credential"],
+        "issuer": issuer,
+        "issuanceDate": Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        "credentialSubject": {
+            "id": format!("{}#list", url),
+            "type": "StatusList2021",
+            "statusPurpose": purpose,
+            "encodedList": encode_bitstring(bits)?
+        }
+    }))
+}
+
+/// Where a checked list came from, for display
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListSource {
+    File(PathBuf),
+    Fetched(String),
+    /// Read from the cache in offline mode; `age` is how old the copy is
+    Cached {
+        url: String,
+        age: Option<u64>,
+    },
+}
+
+impl fmt::Display for ListSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListSource::File(path) => write!(f, "{}", path.display()),
+            ListSource::Fetched(url) => f.write_str(url),
+            ListSource::Cached {
+                url,
+                age: Some(age),
+            } => write!(f, "{} (cached {}s ago)", url, age),
+            ListSource::Cached { url, age: None } => write!(f, "{} (cached)", url),
+        }
+    }
+}
+
+/// Result of a revocation check
+#[derive(Debug, Clone)]
+pub struct RevocationCheck {
+    pub status: RevocationStatus,
+    pub source: ListSource,
+}
+
+/// Check a credential's status.
+///
+/// The list is read from `local_list` when given. Otherwise it is fetched
+/// from the credential's list URL and cached; in offline mode the cached
+/// copy is used instead, however old.
+pub fn check_revocation(
+    vc: &Value,
+    jti: Option<&str>,
+    local_list: Option<&Path>,
+) -> Result<RevocationCheck> {
+    let reference = StatusReference::from_credential(vc, jti)?;
+    let (list, source) = match local_list {
+        Some(path) => (read_list_file(path)?, ListSource::File(path.to_path_buf())),
+        None => load_list(reference.url())?,
+    };
+    let status = RevocationList::parse(&list)
+        .with_context(|| format!("invalid revocation list {}", source))?
+        .status_of(&reference)?;
+    Ok(RevocationCheck { status, source })
+}
+
+fn read_list_file(path: &Path) -> Result<Value> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read revocation list {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("revocation list {} is not valid JSON", path.display()))
+}
+
+fn load_list(url: &str) -> Result<(Value, ListSource)> {
+    let cached = list_cache_path(url);
+    if is_offline() {
+        let path = cached
+            .filter(|path| path.exists())
+            .with_context(|| format!("offline mode and no cached revocation list for {}", url))?;
+        let age = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .map(|age| age.as_secs());
+        return Ok((
+            read_list_file(&path)?,
+            ListSource::Cached {
+                url: url.to_string(),
+                age,
+            },
+        ));
+    }
+
+    let list = fetch_list(url)?;
+    if let Some(path) = cached {
+        // A failed cache write only costs offline mode its copy
+        let _ = write_cached_list(&path, &list);
+    }
+    Ok((list, ListSource::Fetched(url.to_string())))
+}
+
+fn fetch_list(url: &str) -> Result<Value> {
+    let client = crate::http::client()?;
+    let response = crate::http::send_with_retry(client.get(url))
+        .map_err(|err| crate::http::request_error(err, "revocation list server"))
+        .with_context(|| format!("failed to fetch revocation list from {}", url))?;
+    if !response.status().is_success() {
+        bail!(
+            "failed to fetch revocation list from {}: HTTP {}",
+            url,
+            response.status()
+        );
+    }
+    response
+        .json()
+        .with_context(|| format!("revocation list at {} is not valid JSON", url))
+}
+
+/// Cache file for the list at `url`, named by the URL's SHA-256
+fn list_cache_path(url: &str) -> Option<PathBuf> {
+    Some(
+        cache_dir()?
+            .join("revocation")
+            .join(format!("{:x}.json", Sha256::digest(url.as_bytes()))),
+    )
+}
+
+fn write_cached_list(path: &Path, list: &Value) -> Result<()> {
+    let dir = path.parent().context("invalid cache path")?;
+    fs::create_dir_all(dir)
+        .with_context(|| format!("failed to create cache directory: {}", dir.display()))?;
+    fs::write(path, serde_json::to_string_pretty(list)?)
+        .with_context(|| format!("failed to write cache file: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded_list(bits: &[u8]) -> String {
+        // Multibase-prefixed, as Bitstring Status List writes it
+        format!("u{}", encode_bitstring(bits).unwrap())
+    }
+
+    #[test]
+    fn test_id_lists() {
+        let vc = json!({
+            "credentialId": "c-2",
+            "revocationListUrl": "https://example.com/revocation"
+        });
+        let reference = StatusReference::from_credential(&vc, Some("c-2")).unwrap();
+        assert_eq!(
+            reference,
+            StatusReference::Id {
+                url: "https://example.com/revocation".to_string(),
+                ids: vec!["c-2".to_string()],
+            }
+        );
+
+        let status = |list: Value| {
+            RevocationList::parse(&list)
+                .unwrap()
+                .status_of(&reference)
+                .unwrap()
+        };
+        assert_eq!(status(json!(["c-1", "c-2"])), RevocationStatus::Revoked);
+        assert_eq!(status(json!(["c-1"])), RevocationStatus::Active);
+        assert_eq!(
+            status(json!([{"id": "c-2", "status": "suspended"}])),
+            RevocationStatus::Suspended
+        );
+        assert_eq!(
+            status(json!({"revoked": [], "suspended": ["c-2"]})),
+            RevocationStatus::Suspended
+        );
+        assert!(RevocationList::parse(&json!({"ids": []})).is_err());
+    }
+
+    #[test]
+    fn test_status_list_bits() {
+        // Bits 1 and 10 set
+        let list = json!({
+            "type": ["VerifiableCredential", "StatusList2021Credential"],
+            "credentialSubject": {
+                "type": "StatusList2021",
+                "statusPurpose": "revocation",
+                "encodedList": encoded_list(&[0b0100_0000, 0b0010_0000])
+            }
+        });
+        let list = RevocationList::parse(&list).unwrap();
+        let entry = |index: usize| {
+            let vc = json!({"credentialStatus": {
+                "type": "BitstringStatusListEntry",
+                "statusPurpose": "revocation",
+                "statusListIndex": index.to_string(),
+                "statusListCredential": "https://example.com/status/1"
+            }});
+            StatusReference::from_credential(&vc, None).unwrap()
+        };
+
+        assert_eq!(
+            list.status_of(&entry(1)).unwrap(),
+            RevocationStatus::Revoked
+        );
+        assert_eq!(
+            list.status_of(&entry(10)).unwrap(),
+            RevocationStatus::Revoked
+        );
+        assert_eq!(list.status_of(&entry(0)).unwrap(), RevocationStatus::Active);
+        assert!(list.status_of(&entry(16)).is_err());
+    }
+
+    #[test]
+    fn test_suspension_list_reports_suspended() {
+        let list = RevocationList::parse(&json!({
+            "credentialSubject": {
+                "statusPurpose": "suspension",
+                "encodedList": encoded_list(&[0x80])
+            }
+        }))
+        .unwrap();
+        let reference = StatusReference::Index {
+            url: "https://example.com/status/1".to_string(),
+            index: 0,
+            purpose: "suspension".to_string(),
+        };
+        assert_eq!(
+            list.status_of(&reference).unwrap(),
+            RevocationStatus::Suspended
+        );
+
+        let revocation_entry = StatusReference::Index {
+            url: reference.url().to_string(),
+            index: 0,
+            purpose: "revocation".to_string(),
+        };
+        assert!(list.status_of(&revocation_entry).is_err());
+    }
+
+    #[test]
+    fn test_local_list_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("revoked.json");
+        fs::write(&path, r#"["c-9"]"#).unwrap();
+        let vc = json!({
+            "credentialId": "c-9",
+            "revocationListUrl": "https://unreachable.invalid/revocation"
+        });
+
+        let check = check_revocation(&vc, None, Some(&path)).unwrap();
+        assert_eq!(check.status, RevocationStatus::Revoked);
+        assert_eq!(check.source, ListSource::File(path));
+    }
+
+    #[test]
+    fn test_credential_without_status_reference() {
+        assert!(StatusReference::from_credential(&json!({"credentialId": "c-1"}), None).is_err());
+    }
+}
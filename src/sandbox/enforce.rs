@@ -0,0 +1,470 @@
+//! Network allowlist enforcement (Linux only)
+//!
+//! The agent runs in a fresh network namespace (`unshare --net`) whose only
+//! usable interface is loopback. A helper inside the namespace listens on a
+//! loopback port and forwards each connection over a Unix socket to an
+//! egress proxy in the host namespace, which only connects to domains the
+//! policy allows. The agent gets `HTTP(S)_PROXY` pointing at the helper; any
+//! other connection attempt fails because the namespace has no route out.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::os::unix::fs::DirBuilderExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use log::debug;
+
+use super::monitor::{Observation, Severity, Violation, ViolationType};
+use super::policy::{DomainAccess, NetworkPolicy};
+
+/// Hidden `sandbox` subcommand that runs inside the namespace
+pub const HELPER_SUBCOMMAND: &str = "netns-exec";
+
+/// Longest request head the egress proxy reads before giving up
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Egress proxy in the host namespace, listening on a Unix socket and
+/// recording what it allowed and blocked
+pub struct EgressProxy {
+    socket_path: PathBuf,
+    dir: PathBuf,
+    violations: Arc<Mutex<Vec<Violation>>>,
+    observations: Arc<Mutex<Vec<Observation>>>,
+}
+
+impl EgressProxy {
+    /// Start the proxy on a socket in a fresh temporary directory that only
+    /// the current user can enter
+    pub fn start(policy: NetworkPolicy) -> Result<Self> {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or_default();
+        let dir =
+            std::env::temp_dir().join(format!("beltic-sandbox-{}-{}", std::process::id(), nanos));
+        std::fs::DirBuilder::new()
+            .mode(0o700)
+            .create(&dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+        let socket_path = dir.join("egress.sock");
+        let listener = UnixListener::bind(&socket_path)
+            .with_context(|| format!("failed to bind {}", socket_path.display()))?;
+
+        let violations = Arc::new(Mutex::new(Vec::new()));
+        let observations = Arc::new(Mutex::new(Vec::new()));
+        let policy = Arc::new(policy);
+        {
+            let violations = Arc::clone(&violations);
+            let observations = Arc::clone(&observations);
+            thread::spawn(move || {
+                for conn in listener.incoming().map_while(Result::ok) {
+                    let policy = Arc::clone(&policy);
+                    let violations = Arc::clone(&violations);
+                    let observations = Arc::clone(&observations);
+                    thread::spawn(move || {
+                        if let Err(err) = handle_egress(conn, &policy, &violations, &observations) {
+                            debug!("egress proxy connection failed: {:#}", err);
+                        }
+                    });
+                }
+            });
+        }
+
+        Ok(Self {
+            socket_path,
+            dir,
+            violations,
+            observations,
+        })
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// Command line that runs `command` inside a new network namespace,
+    /// through this proxy
+    pub fn wrap_command(&self, command: &[String]) -> Result<Vec<String>> {
+        let exe = std::env::current_exe().context("failed to locate the beltic executable")?;
+        let mut wrapped = unshare_prefix();
+        wrapped.extend([
+            "--".to_string(),
+            exe.display().to_string(),
+            "sandbox".to_string(),
+            HELPER_SUBCOMMAND.to_string(),
+            "--socket".to_string(),
+            self.socket_path.display().to_string(),
+            "--".to_string(),
+        ]);
+        wrapped.extend(command.iter().cloned());
+        Ok(wrapped)
+    }
+
+    /// Violations recorded so far (blocked connections)
+    pub fn take_violations(&self) -> Vec<Violation> {
+        std::mem::take(&mut *self.violations.lock().unwrap())
+    }
+
+    /// Observations recorded so far (allowed connections)
+    pub fn take_observations(&self) -> Vec<Observation> {
+        std::mem::take(&mut *self.observations.lock().unwrap())
+    }
+}
+
+impl Drop for EgressProxy {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// `unshare` invocation for a new network namespace. Unprivileged users
+/// also need a user namespace to be allowed to create it.
+fn unshare_prefix() -> Vec<String> {
+    let mut prefix = vec!["unshare".to_string()];
+    if !is_root() {
+        prefix.extend(["--user".to_string(), "--map-root-user".to_string()]);
+    }
+    prefix.push("--net".to_string());
+    prefix
+}
+
+fn is_root() -> bool {
+    // SAFETY: geteuid has no preconditions
+    unsafe { libc::geteuid() == 0 }
+}
+
+/// Fail early, with the privileges needed, if namespaces are unavailable
+pub fn preflight() -> Result<()> {
+    let mut check = unshare_prefix();
+    check.extend(["--".to_string(), "true".to_string()]);
+    let status = std::process::Command::new(&check[0])
+        .args(&check[1..])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(_) => bail!(
+            "cannot create a network namespace: run as root, or enable unprivileged user \
+             namespaces (e.g. sysctl kernel.unprivileged_userns_clone=1)"
+        ),
+        Err(err) => bail!(
+            "cannot run `unshare` ({}): install util-linux to use --enforce-network",
+            err
+        ),
+    }
+}
+
+fn handle_egress(
+    mut conn: UnixStream,
+    policy: &NetworkPolicy,
+    violations: &Mutex<Vec<Violation>>,
+    observations: &Mutex<Vec<Observation>>,
+) -> Result<()> {
+    conn.set_read_timeout(Some(Duration::from_secs(30)))?;
+    let mut reader = BufReader::new(conn.try_clone()?);
+    let head = read_head(&mut reader)?;
+    let request_line = head.lines().next().unwrap_or_default().to_string();
+    let target = parse_target(&request_line)?;
+    let timestamp = chrono::Utc::now().to_rfc3339();
+
+    let (severity, description) = match policy.check_domain(&target.host) {
+        DomainAccess::Allowed => {
+            observations.lock().unwrap().push(Observation {
+                timestamp: timestamp.clone(),
+                observation_type: "network_access".to_string(),
+                description: format!("Network access to: {} (allowed by proxy)", target.host),
+            });
+            (None, "")
+        }
+        DomainAccess::Prohibited => (
+            Some(Severity::High),
+            "Blocked network access to prohibited domain",
+        ),
+        DomainAccess::NotAllowed => (
+            Some(Severity::Medium),
+            "Blocked network access to non-allowed domain",
+        ),
+    };
+    if let Some(severity) = severity {
+        violations.lock().unwrap().push(Violation {
+            timestamp,
+            violation_type: ViolationType::NetworkAccessDenied,
+            severity,
+            description: description.to_string(),
+            details: format!("Attempted access to: {}", target.host),
+        });
+        conn.write_all(
+            b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        )?;
+        return Ok(());
+    }
+
+    let mut upstream = match connect_upstream(&target) {
+        Ok(upstream) => upstream,
+        Err(err) => {
+            conn.write_all(b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\n\r\n")?;
+            return Err(err);
+        }
+    };
+    if target.tunnel {
+        conn.write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")?;
+    } else {
+        upstream.write_all(head.as_bytes())?;
+    }
+    // Bytes the client sent after the head are still in the reader's buffer
+    upstream.write_all(reader.buffer())?;
+    conn.set_read_timeout(None)?;
+
+    pipe(conn, upstream)
+}
+
+fn connect_upstream(target: &ProxyTarget) -> Result<TcpStream> {
+    let addr = (target.host.as_str(), target.port)
+        .to_socket_addrs()?
+        .next()
+        .with_context(|| format!("{} did not resolve", target.host))?;
+    Ok(TcpStream::connect_timeout(&addr, Duration::from_secs(10))?)
+}
+
+/// Read up to and including the blank line that ends an HTTP request head
+fn read_head(reader: &mut impl BufRead) -> Result<String> {
+    let mut head = String::new();
+    loop {
+        let read = reader.read_line(&mut head)?;
+        if read == 0 || head.ends_with("\r\n\r\n") || head.ends_with("\n\n") {
+            return Ok(head);
+        }
+        if head.len() > MAX_HEAD_BYTES {
+            bail!("request head exceeds {} bytes", MAX_HEAD_BYTES);
+        }
+    }
+}
+
+/// Where a proxied request goes
+#[derive(Debug, PartialEq, Eq)]
+struct ProxyTarget {
+    host: String,
+    port: u16,
+    /// CONNECT tunnel rather than a plain HTTP request
+    tunnel: bool,
+}
+
+fn parse_target(request_line: &str) -> Result<ProxyTarget> {
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let target = parts.next().context("malformed proxy request line")?;
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) = target
+            .rsplit_once(':')
+            .context("CONNECT target must be host:port")?;
+        return Ok(ProxyTarget {
+            host: host.trim_matches(['[', ']']).to_lowercase(),
+            port: port.parse().context("invalid CONNECT port")?,
+            tunnel: true,
+        });
+    }
+
+    let url = url::Url::parse(target)
+        .with_context(|| format!("proxy requests need an absolute URL, got {}", target))?;
+    Ok(ProxyTarget {
+        host: url
+            .host_str()
+            .context("proxy request URL has no host")?
+            .to_lowercase(),
+        port: url.port_or_known_default().unwrap_or(80),
+        tunnel: false,
+    })
+}
+
+/// Copy bytes both ways until either side closes
+fn pipe<A, B>(a: A, b: B) -> Result<()>
+where
+    A: Read + Write + TryCloneStream + Send + 'static,
+    B: Read + Write + TryCloneStream + Send + 'static,
+{
+    let mut a_read = a.try_clone_stream()?;
+    let mut b_write = b.try_clone_stream()?;
+    let forward = thread::spawn(move || {
+        let _ = io::copy(&mut a_read, &mut b_write);
+        b_write.shutdown_write();
+    });
+    let (mut b_read, mut a_write) = (b, a);
+    let _ = io::copy(&mut b_read, &mut a_write);
+    a_write.shutdown_write();
+    let _ = forward.join();
+    Ok(())
+}
+
+/// The stream operations [`pipe`] needs from TCP and Unix sockets
+trait TryCloneStream: Sized {
+    fn try_clone_stream(&self) -> io::Result<Self>;
+    fn shutdown_write(&self);
+}
+
+impl TryCloneStream for TcpStream {
+    fn try_clone_stream(&self) -> io::Result<Self> {
+        self.try_clone()
+    }
+
+    fn shutdown_write(&self) {
+        let _ = self.shutdown(Shutdown::Write);
+    }
+}
+
+impl TryCloneStream for UnixStream {
+    fn try_clone_stream(&self) -> io::Result<Self> {
+        self.try_clone()
+    }
+
+    fn shutdown_write(&self) {
+        let _ = self.shutdown(Shutdown::Write);
+    }
+}
+
+/// Inside the namespace: bring up loopback, forward a loopback port to the
+/// egress proxy socket, and run the agent with proxy variables pointing at it.
+/// Returns the agent's exit code.
+pub fn run_helper(socket: &Path, command: &[String]) -> Result<i32> {
+    use std::net::TcpListener;
+    use std::os::unix::process::{CommandExt, ExitStatusExt};
+
+    let Some((program, args)) = command.split_first() else {
+        bail!("Empty command provided");
+    };
+
+    loopback_up().context("failed to bring up loopback in the sandbox namespace")?;
+    let listener = TcpListener::bind("127.0.0.1:0").context("failed to bind proxy port")?;
+    let proxy_url = format!("http://{}", listener.local_addr()?);
+
+    let socket = socket.to_path_buf();
+    thread::spawn(move || {
+        for conn in listener.incoming().map_while(Result::ok) {
+            let socket = socket.clone();
+            thread::spawn(move || {
+                if let Ok(upstream) = UnixStream::connect(&socket) {
+                    let _ = pipe(conn, upstream);
+                }
+            });
+        }
+    });
+
+    let mut child = std::process::Command::new(program);
+    child.args(args);
+    for name in ["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY"] {
+        child.env(name, &proxy_url);
+        child.env(name.to_lowercase(), &proxy_url);
+    }
+    child.env_remove("NO_PROXY").env_remove("no_proxy");
+    // SAFETY: prctl is async-signal-safe; the agent dies with this helper
+    // (e.g. when the sandbox times out and kills it)
+    unsafe {
+        child.pre_exec(|| {
+            libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+            Ok(())
+        });
+    }
+
+    let status = child
+        .status()
+        .with_context(|| format!("Failed to execute command: {}", command.join(" ")))?;
+    Ok(status
+        .code()
+        .unwrap_or_else(|| 128 + status.signal().unwrap_or(0)))
+}
+
+/// Set IFF_UP on `lo`; a new network namespace starts with it down
+fn loopback_up() -> io::Result<()> {
+    // SAFETY: plain socket/ioctl calls on a zeroed ifreq with a valid name
+    unsafe {
+        let fd = libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0);
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut ifr: libc::ifreq = std::mem::zeroed();
+        for (dst, src) in ifr.ifr_name.iter_mut().zip(b"lo\0") {
+            *dst = *src as libc::c_char;
+        }
+        let mut result = libc::ioctl(fd, libc::SIOCGIFFLAGS as _, &mut ifr);
+        if result == 0 {
+            ifr.ifr_ifru.ifru_flags |= libc::IFF_UP as libc::c_short;
+            result = libc::ioctl(fd, libc::SIOCSIFFLAGS as _, &ifr);
+        }
+        let err = io::Error::last_os_error();
+        libc::close(fd);
+        if result < 0 {
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> NetworkPolicy {
+        NetworkPolicy {
+            allowed_domains: vec!["api.anthropic.com".to_string()],
+            prohibited_domains: vec!["pastebin.com".to_string()],
+            external_api_allowed: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_proxy_targets() {
+        assert_eq!(
+            parse_target("CONNECT api.anthropic.com:443 HTTP/1.1").unwrap(),
+            ProxyTarget {
+                host: "api.anthropic.com".to_string(),
+                port: 443,
+                tunnel: true,
+            }
+        );
+        assert_eq!(
+            parse_target("GET http://Example.com/path HTTP/1.1").unwrap(),
+            ProxyTarget {
+                host: "example.com".to_string(),
+                port: 80,
+                tunnel: false,
+            }
+        );
+        assert!(parse_target("GET /relative HTTP/1.1").is_err());
+    }
+
+    #[test]
+    fn test_proxy_blocks_disallowed_domains() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let proxy = EgressProxy::start(policy()).unwrap();
+        let dir = proxy.socket_path().parent().unwrap();
+        let mode = std::fs::metadata(dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        for (host, severity) in [
+            ("pastebin.com", Severity::High),
+            ("example.org", Severity::Medium),
+        ] {
+            let mut conn = UnixStream::connect(proxy.socket_path()).unwrap();
+            write!(
+                conn,
+                "CONNECT {}:443 HTTP/1.1\r\nHost: {}\r\n\r\n",
+                host, host
+            )
+            .unwrap();
+            let mut response = String::new();
+            conn.read_to_string(&mut response).unwrap();
+            assert!(response.starts_with("HTTP/1.1 403"), "{}", response);
+
+            let violations = proxy.take_violations();
+            assert_eq!(violations.len(), 1);
+            assert_eq!(violations[0].severity, severity);
+        }
+    }
+}
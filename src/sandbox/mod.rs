@@ -1,7 +1,9 @@
+#[cfg(target_os = "linux")]
+pub mod enforce;
 pub mod monitor;
 pub mod policy;
 pub mod report;
 
 pub use monitor::SandboxMonitor;
-pub use policy::{extract_policy, SandboxPolicy};
+pub use policy::{extract_policy, PolicySource, SandboxPolicy};
 pub use report::SandboxReport;
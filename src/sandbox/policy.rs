@@ -1,6 +1,9 @@
-use crate::manifest::schema::{AgentManifest, DataCategory, RiskCategory};
-use anyhow::Result;
+use crate::manifest::credential::AgentCredential;
+use crate::manifest::risk::{convert, label};
+use crate::manifest::schema::{AgentManifest, DataCategory, RiskCategory, Tool};
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// Security policy extracted from agent manifest
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -85,8 +88,123 @@ pub struct UseCasePolicy {
     pub prohibited: Vec<String>,
 }
 
-/// Extract sandbox policy from agent manifest
-pub fn extract_policy(manifest: &AgentManifest) -> Result<SandboxPolicy> {
+/// How a network policy treats a domain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DomainAccess {
+    /// On the prohibited list
+    Prohibited,
+    /// Declared, or any external API is permitted
+    Allowed,
+    /// Neither declared nor covered by external API access
+    NotAllowed,
+}
+
+impl NetworkPolicy {
+    pub fn check_domain(&self, domain: &str) -> DomainAccess {
+        let is_prohibited = self
+            .prohibited_domains
+            .iter()
+            .any(|prohibited| domain.contains(prohibited) || prohibited.contains(domain));
+        if is_prohibited {
+            return DomainAccess::Prohibited;
+        }
+
+        let is_allowed = self
+            .allowed_domains
+            .iter()
+            .any(|allowed| domain.ends_with(allowed) || allowed.ends_with(domain));
+        if is_allowed || self.external_api_allowed {
+            DomainAccess::Allowed
+        } else {
+            DomainAccess::NotAllowed
+        }
+    }
+}
+
+impl SandboxPolicy {
+    /// Whether a tool id or name is declared. An agent without declared
+    /// tools is not restricted.
+    pub fn allows_tool(&self, name: &str) -> bool {
+        self.tools.is_empty()
+            || self.tools.iter().any(|tool| {
+                tool.tool_id.eq_ignore_ascii_case(name) || tool.tool_name.eq_ignore_ascii_case(name)
+            })
+    }
+}
+
+/// Something a sandbox policy can be derived from
+pub trait PolicySource {
+    fn to_policy(&self) -> Result<SandboxPolicy>;
+}
+
+impl PolicySource for AgentManifest {
+    fn to_policy(&self) -> Result<SandboxPolicy> {
+        manifest_policy(self)
+    }
+}
+
+/// Credentials carry no fingerprint scope, so the filesystem policy uses the
+/// default paths.
+impl PolicySource for AgentCredential {
+    fn to_policy(&self) -> Result<SandboxPolicy> {
+        let mut manifest = AgentManifest::new_with_defaults();
+        manifest.agent_name = self.agent_name.clone();
+        manifest.agent_version = self.agent_version.clone();
+        manifest.primary_model_provider = label(&self.primary_model_provider);
+        manifest.tools_list = self.tools_list.as_ref().map(|tools| {
+            tools
+                .iter()
+                .map(|t| Tool {
+                    tool_id: t.tool_id.clone(),
+                    tool_name: t.tool_name.clone(),
+                    tool_description: t.tool_description.clone(),
+                    risk_category: convert(&t.risk_category).unwrap_or(RiskCategory::Data),
+                    risk_subcategory: label(&t.risk_subcategory),
+                    requires_auth: t.requires_auth,
+                    requires_human_approval: t.requires_human_approval,
+                    mitigations: t.mitigations.clone(),
+                })
+                .collect()
+        });
+        manifest.data_categories_processed = self
+            .data_categories_processed
+            .iter()
+            .filter_map(convert)
+            .collect();
+        manifest.data_retention_max_period = self.data_retention_max_period.clone();
+        manifest.human_oversight_mode = convert(&self.human_oversight_mode)
+            .context("unsupported humanOversightMode in credential")?;
+        manifest.approved_use_cases = self.approved_use_cases.clone();
+        manifest.prohibited_use_cases = self.prohibited_use_cases.clone();
+
+        manifest_policy(&manifest)
+    }
+}
+
+/// A JSON document: a JWT payload (its `vc` claim is used), a credential
+/// (it has a `credentialId`), or otherwise a manifest
+impl PolicySource for Value {
+    fn to_policy(&self) -> Result<SandboxPolicy> {
+        let value = self.get("vc").unwrap_or(self);
+        if value.get("credentialId").is_some() {
+            let credential: AgentCredential = serde_json::from_value(value.clone())
+                .context("document is not a valid agent credential")?;
+            credential.to_policy()
+        } else {
+            let manifest: AgentManifest =
+                serde_json::from_value(value.clone()).context("failed to parse manifest")?;
+            manifest.to_policy()
+        }
+    }
+}
+
+/// Extract sandbox policy from an agent manifest, an agent credential, or a
+/// credential's `vc` value
+pub fn extract_policy<S: PolicySource + ?Sized>(source: &S) -> Result<SandboxPolicy> {
+    source.to_policy()
+}
+
+fn manifest_policy(manifest: &AgentManifest) -> Result<SandboxPolicy> {
     // Extract filesystem policy from fingerprint metadata
     let filesystem = extract_filesystem_policy(manifest);
 
@@ -244,3 +362,30 @@ fn extract_data_restrictions(manifest: &AgentManifest) -> DataRestrictions {
         max_retention_period: manifest.data_retention_max_period.clone(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credential_policy_matches_declared_tools() {
+        let credential: AgentCredential =
+            serde_json::from_str(include_str!("../../tests/fixtures/agent-valid.json")).unwrap();
+        let policy = extract_policy(&credential).unwrap();
+
+        assert_eq!(policy.agent_name, credential.agent_name);
+        assert_eq!(
+            policy.tools.len(),
+            credential.tools_list.as_ref().map_or(0, Vec::len)
+        );
+        assert_eq!(
+            policy.data_restrictions.allowed_data_categories.len(),
+            credential.data_categories_processed.len()
+        );
+        // No fingerprint scope in a credential: default paths apply
+        assert!(policy
+            .filesystem
+            .allowed_read_paths
+            .contains(&"./src/**".to_string()));
+    }
+}
@@ -1,10 +1,18 @@
-use super::monitor::{Observation, Severity, Violation};
+use super::monitor::{Observation, Severity, Violation, ViolationType};
 use super::policy::SandboxPolicy;
+use crate::manifest::risk::label;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::fmt::Write;
 use std::fs;
 use std::path::Path;
 
+/// Exit code when violations reach the `--fail-on` threshold
+pub const EXIT_POLICY_VIOLATED: i32 = 1;
+
+/// Exit code when the agent itself exited non-zero without a policy violation
+pub const EXIT_AGENT_FAILED: i32 = 3;
+
 /// Complete sandbox execution report
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -30,11 +38,24 @@ pub struct SandboxReport {
 pub struct ReportSummary {
     pub agent_name: String,
     pub agent_version: String,
+    /// The agent's own exit code
     pub exit_code: i32,
     pub compliant: bool,
+    /// Lowest severity that counts as a policy violation
+    #[serde(default)]
+    pub fail_on: Severity,
+    /// Whether any violation is at or above `fail_on`
+    #[serde(default)]
+    pub policy_violated: bool,
+    /// Whether the agent exited non-zero
+    #[serde(default)]
+    pub agent_failed: bool,
     pub total_violations: usize,
     pub total_observations: usize,
     pub timestamp: String,
+    /// Wall-clock run time in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -73,9 +94,13 @@ impl SandboxReport {
             agent_version: policy.agent_version.clone(),
             exit_code,
             compliant,
+            fail_on: Severity::Low,
+            policy_violated: !violations.is_empty(),
+            agent_failed: exit_code != 0,
             total_violations: violations.len(),
             total_observations: observations.len(),
             timestamp: chrono::Utc::now().to_rfc3339(),
+            duration_secs: None,
         };
 
         Self {
@@ -87,6 +112,33 @@ impl SandboxReport {
         }
     }
 
+    /// Count only violations at or above `threshold` as a policy violation
+    pub fn with_fail_on(mut self, threshold: Severity) -> Self {
+        self.summary.fail_on = threshold;
+        self.summary.policy_violated = self.violations_at_or_above(threshold) > 0;
+        self
+    }
+
+    /// Number of violations at or above `threshold`
+    pub fn violations_at_or_above(&self, threshold: Severity) -> usize {
+        self.violations
+            .iter()
+            .filter(|v| v.severity >= threshold)
+            .count()
+    }
+
+    /// Process exit code for CI gating: a policy violation takes precedence
+    /// over the agent's own failure
+    pub fn exit_code(&self) -> i32 {
+        if self.summary.policy_violated {
+            EXIT_POLICY_VIOLATED
+        } else if self.summary.agent_failed {
+            EXIT_AGENT_FAILED
+        } else {
+            0
+        }
+    }
+
     /// Calculate risk assessment from violations
     fn calculate_risk(violations: &[Violation]) -> RiskAssessment {
         let mut critical = 0;
@@ -164,6 +216,157 @@ impl SandboxReport {
         Ok(())
     }
 
+    /// Save report as Markdown
+    pub fn save_markdown(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.to_markdown())?;
+        Ok(())
+    }
+
+    /// Render the report as Markdown for reviewers: severity counts and the
+    /// verdict first, then violations grouped by severity and type,
+    /// observations, resource usage and recommendations
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let summary = &self.summary;
+        let risk = &self.risk_assessment;
+
+        let _ = writeln!(
+            md,
+            "# Sandbox Report: {} v{}\n",
+            summary.agent_name, summary.agent_version
+        );
+        let verdict = if summary.compliant {
+            "COMPLIANT"
+        } else {
+            "NON-COMPLIANT"
+        };
+        let _ = writeln!(
+            md,
+            "**Verdict:** {} (risk {}, score {}/100)\n",
+            verdict,
+            label(&risk.risk_level),
+            risk.risk_score
+        );
+        let _ = writeln!(
+            md,
+            "- Policy: {} ({} violation(s) at or above {})",
+            if summary.policy_violated {
+                "violated"
+            } else {
+                "ok"
+            },
+            self.violations_at_or_above(summary.fail_on),
+            summary.fail_on.as_str()
+        );
+        let _ = writeln!(
+            md,
+            "- Agent: {} (exit code {})\n",
+            if summary.agent_failed {
+                "failed"
+            } else {
+                "succeeded"
+            },
+            summary.exit_code
+        );
+        md.push_str("| Critical | High | Medium | Low |\n|---:|---:|---:|---:|\n");
+        let _ = writeln!(
+            md,
+            "| {} | {} | {} | {} |\n",
+            risk.critical_violations,
+            risk.high_violations,
+            risk.medium_violations,
+            risk.low_violations
+        );
+
+        md.push_str("## Violations\n\n");
+        if self.violations.is_empty() {
+            md.push_str("None.\n\n");
+        } else {
+            md.push_str("| Severity | Type | Count | Details |\n|---|---|---:|---|\n");
+            for (severity, violation_type, group) in self.grouped_violations() {
+                let mut details: Vec<&str> = Vec::new();
+                for violation in &group {
+                    let detail = if violation.details.is_empty() {
+                        &violation.description
+                    } else {
+                        &violation.details
+                    };
+                    if !details.contains(&detail.as_str()) {
+                        details.push(detail);
+                    }
+                }
+                let _ = writeln!(
+                    md,
+                    "| {} | {} | {} | {} |",
+                    severity.as_str(),
+                    label(violation_type),
+                    group.len(),
+                    escape_cell(&details.join("; "))
+                );
+            }
+            md.push('\n');
+        }
+
+        md.push_str("## Observations\n\n");
+        if self.observations.is_empty() {
+            md.push_str("None.\n");
+        }
+        for observation in &self.observations {
+            let _ = writeln!(
+                md,
+                "- `{}` {}",
+                observation.observation_type, observation.description
+            );
+        }
+        md.push('\n');
+
+        md.push_str("## Resource usage\n\n");
+        let _ = writeln!(md, "- Agent exit code: {}", summary.exit_code);
+        if let Some(secs) = summary.duration_secs {
+            let _ = writeln!(md, "- Duration: {:.2}s", secs);
+        }
+        let network_accesses = self
+            .observations
+            .iter()
+            .filter(|o| o.observation_type == "network_access")
+            .count();
+        let _ = writeln!(md, "- Allowed network accesses: {}", network_accesses);
+        let _ = writeln!(md, "- Tools declared: {}", self.policy.tools.len());
+        let _ = writeln!(
+            md,
+            "- Allowed domains: {}\n",
+            self.policy.network.allowed_domains.len()
+        );
+
+        md.push_str("## Recommendations\n\n");
+        for recommendation in &risk.recommendations {
+            let _ = writeln!(md, "- {}", recommendation);
+        }
+        md
+    }
+
+    /// Violations grouped by severity (highest first) and type, in order of
+    /// first occurrence within a severity
+    fn grouped_violations(&self) -> Vec<(Severity, &ViolationType, Vec<&Violation>)> {
+        let mut groups: Vec<(Severity, &ViolationType, Vec<&Violation>)> = Vec::new();
+        for violation in &self.violations {
+            let key = label(&violation.violation_type);
+            match groups
+                .iter_mut()
+                .find(|(s, t, _)| *s == violation.severity && label(*t) == key)
+            {
+                Some((_, _, group)) => group.push(violation),
+                None => groups.push((
+                    violation.severity,
+                    &violation.violation_type,
+                    vec![violation],
+                )),
+            }
+        }
+        groups.sort_by_key(|(severity, _, _)| std::cmp::Reverse(*severity));
+        groups
+    }
+
     /// Print summary to terminal
     pub fn print_summary(&self) {
         use console::style;
@@ -179,7 +382,12 @@ impl SandboxReport {
             style("NON-COMPLIANT").red().bold()
         };
         println!("Status: {}", status_msg);
-        println!("Exit code: {}", self.summary.exit_code);
+        println!("Agent exit code: {}", self.summary.exit_code);
+        println!(
+            "Policy: {} at or above {}",
+            self.violations_at_or_above(self.summary.fail_on),
+            self.summary.fail_on.as_str()
+        );
 
         // Risk assessment
         if !self.summary.compliant {
@@ -228,3 +436,83 @@ impl SandboxReport {
         );
     }
 }
+
+/// Keep pipes and newlines from breaking a Markdown table row
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::schema::AgentManifest;
+    use crate::sandbox::extract_policy;
+
+    fn violation(violation_type: ViolationType, severity: Severity, details: &str) -> Violation {
+        Violation {
+            timestamp: "2025-01-01T00:00:00Z".to_string(),
+            violation_type,
+            severity,
+            description: "test".to_string(),
+            details: details.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_markdown_groups_violations_and_counts_severities() {
+        let policy = extract_policy(&AgentManifest::new_with_defaults()).unwrap();
+        let violations = vec![
+            violation(
+                ViolationType::NetworkAccessDenied,
+                Severity::Medium,
+                "a.com",
+            ),
+            violation(ViolationType::DataPolicyViolation, Severity::High, "pii"),
+            violation(ViolationType::NetworkAccessDenied, Severity::Medium, "b|c"),
+        ];
+        let report = SandboxReport::new(policy, violations, Vec::new(), 0);
+        let md = report.to_markdown();
+
+        assert!(md.contains("**Verdict:** NON-COMPLIANT"));
+        assert!(md.contains("| 0 | 1 | 2 | 0 |"));
+        let high = md
+            .find("| high | data_policy_violation | 1 | pii |")
+            .unwrap();
+        let medium = md
+            .find("| medium | network_access_denied | 2 | a.com; b\\|c |")
+            .unwrap();
+        assert!(high < medium);
+    }
+
+    #[test]
+    fn test_exit_code_separates_policy_and_agent_failure() {
+        let policy = extract_policy(&AgentManifest::new_with_defaults()).unwrap();
+        let medium = vec![violation(
+            ViolationType::NetworkAccessDenied,
+            Severity::Medium,
+            "a.com",
+        )];
+
+        let report = SandboxReport::new(policy.clone(), medium.clone(), Vec::new(), 0);
+        assert_eq!(report.exit_code(), EXIT_POLICY_VIOLATED);
+
+        let report = report.with_fail_on(Severity::High);
+        assert!(!report.summary.policy_violated);
+        assert_eq!(report.exit_code(), 0);
+
+        let report = SandboxReport::new(policy.clone(), medium, Vec::new(), 2)
+            .with_fail_on(Severity::Critical);
+        assert!(report.summary.agent_failed);
+        assert_eq!(report.exit_code(), EXIT_AGENT_FAILED);
+
+        let critical = vec![violation(
+            ViolationType::DataPolicyViolation,
+            Severity::Critical,
+            "key",
+        )];
+        let report =
+            SandboxReport::new(policy, critical, Vec::new(), 2).with_fail_on(Severity::Critical);
+        assert_eq!(report.exit_code(), EXIT_POLICY_VIOLATED);
+        assert_eq!(report.summary.exit_code, 2);
+    }
+}
@@ -2,14 +2,31 @@
 //!
 //! This module provides functionality to fetch JSON schemas from the beltic-spec
 //! repository and cache them locally for offline use.
+//!
+//! Cached schemas are keyed by version (`<cache>/<version>/<name>`), and the
+//! version used for validation can be pinned with `schema.version` in
+//! `.beltic.yaml`. The `ETag` and `Last-Modified` headers of each download
+//! are kept next to it, so refreshing an unchanged schema is a conditional
+//! request answered with `304 Not Modified`. Downloads and imports must hash
+//! to the SHA-256 listed for their type and version in `schemas/SHA256SUMS`,
+//! shipped with the CLI, so a tampered or truncated file never replaces the
+//! cache; versions without a listed checksum can't be downloaded.
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, SystemTime};
 
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::BelticError;
+use crate::manifest::config::BelticConfig;
 
 /// Base URL for the beltic-spec schemas on GitHub
 const GITHUB_RAW_BASE: &str =
@@ -18,6 +35,62 @@ const GITHUB_RAW_BASE: &str =
 /// Cache TTL: 24 hours
 const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
 
+/// SHA-256 of each published schema, in `sha256sum` format with paths
+/// relative to the schemas directory (see [`SchemaType::path`])
+const CHECKSUMS: &str = include_str!("../schemas/SHA256SUMS");
+
+/// Schema versions bundled with the CLI, oldest first
+pub const EMBEDDED_VERSIONS: &[&str] = &["v1", "v2"];
+
+/// Schema version used when no pin is configured
+pub const DEFAULT_SCHEMA_VERSION: &str = "v1";
+
+/// Environment variable that enables offline mode (`1` or `true`)
+pub const OFFLINE_ENV: &str = "BELTIC_OFFLINE";
+
+/// Offline mode set via the `--offline` flag
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable offline mode for the schema subsystem.
+///
+/// In offline mode schemas are never fetched from the network; reads are
+/// served from the cache (even if expired) or the embedded copy.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Whether offline mode is enabled via `--offline` or `BELTIC_OFFLINE`
+pub fn is_offline() -> bool {
+    if OFFLINE.load(Ordering::Relaxed) {
+        return true;
+    }
+    std::env::var(OFFLINE_ENV)
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Normalize a schema version such as `1`, `v1` or `V1` to `v1`
+pub fn normalize_version(version: &str) -> Result<String> {
+    let trimmed = version.trim();
+    let digits = trimmed
+        .strip_prefix('v')
+        .or_else(|| trimmed.strip_prefix('V'))
+        .unwrap_or(trimmed);
+
+    match digits.parse::<u32>() {
+        Ok(number) if digits.chars().all(|c| c.is_ascii_digit()) => Ok(format!("v{}", number)),
+        _ => anyhow::bail!(
+            "invalid schema version '{}': expected a version like 'v1'",
+            version
+        ),
+    }
+}
+
+/// Numeric part of a normalized version, for ordering
+fn version_number(version: &str) -> u32 {
+    version.trim_start_matches('v').parse().unwrap_or_default()
+}
+
 /// Schema type for fetching
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SchemaType {
@@ -26,33 +99,112 @@ pub enum SchemaType {
 }
 
 impl SchemaType {
-    /// Returns the relative path within the schemas directory
-    pub fn path(self) -> &'static str {
+    /// Every schema type, in display order
+    pub const ALL: [SchemaType; 2] = [SchemaType::Agent, SchemaType::Developer];
+
+    /// Short name (`agent` or `developer`), also the schema directory name
+    pub fn name(self) -> &'static str {
         match self {
-            SchemaType::Agent => "agent/v1/agent-credential-v1.schema.json",
-            SchemaType::Developer => "developer/v1/developer-credential-v1.schema.json",
+            SchemaType::Agent => "agent",
+            SchemaType::Developer => "developer",
         }
     }
 
+    /// Parse a short name such as `agent`, ignoring case
+    pub fn from_name(name: &str) -> Option<SchemaType> {
+        Self::ALL
+            .into_iter()
+            .find(|schema_type| schema_type.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Returns the relative path within the schemas directory
+    pub fn path(self, version: &str) -> String {
+        format!("{}/{}/{}", self.name(), version, self.cache_name(version))
+    }
+
     /// Returns the full URL for the schema
-    pub fn url(self) -> String {
-        format!("{}/{}", GITHUB_RAW_BASE, self.path())
+    pub fn url(self, version: &str) -> String {
+        format!("{}/{}", GITHUB_RAW_BASE, self.path(version))
     }
 
     /// Returns the cache file name
-    pub fn cache_name(self) -> &'static str {
-        match self {
-            SchemaType::Agent => "agent-credential-v1.schema.json",
-            SchemaType::Developer => "developer-credential-v1.schema.json",
+    pub fn cache_name(self, version: &str) -> String {
+        format!("{}-credential-{}.schema.json", self.name(), version)
+    }
+
+    /// Infer the schema type from a schema document's `$id` or `title`
+    pub fn detect(schema: &Value) -> Option<SchemaType> {
+        let id = schema.get("$id").and_then(Value::as_str).unwrap_or("");
+        let title = schema.get("title").and_then(Value::as_str).unwrap_or("");
+
+        if id.contains("/agent/") || title.starts_with("AgentCredential") {
+            Some(SchemaType::Agent)
+        } else if id.contains("/developer/") || title.starts_with("DeveloperCredential") {
+            Some(SchemaType::Developer)
+        } else {
+            None
+        }
+    }
+}
+
+/// Infer the schema version from a schema document's `$id` (e.g. `.../agent/v2/...`)
+pub fn detect_version(schema: &Value) -> Option<String> {
+    let id = schema.get("$id").and_then(Value::as_str)?;
+    id.split('/')
+        .filter(|segment| segment.starts_with('v'))
+        .find_map(|segment| normalize_version(segment).ok())
+}
+
+/// Schema version pinned in `config` (a `--config` path) or, without one,
+/// in the nearest `.beltic.yaml`, if any
+pub fn pinned_version(config: Option<&Path>) -> Option<String> {
+    let cwd = std::env::current_dir().ok()?;
+    let config = BelticConfig::load(config, &cwd).ok()??;
+    config
+        .schema_version()
+        .and_then(|v| normalize_version(v).ok())
+}
+
+/// Schema version used for validation: the pin, or the default
+pub fn active_version(config: Option<&Path>) -> String {
+    pinned_version(config).unwrap_or_else(|| DEFAULT_SCHEMA_VERSION.to_string())
+}
+
+/// Newest schema version available locally (embedded or cached)
+pub fn latest_available_version() -> String {
+    let mut versions: Vec<String> = EMBEDDED_VERSIONS.iter().map(|v| v.to_string()).collect();
+
+    if let Some(entries) = cache_dir().and_then(|dir| fs::read_dir(dir).ok()) {
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                if let Ok(version) = normalize_version(&entry.file_name().to_string_lossy()) {
+                    versions.push(version);
+                }
+            }
         }
     }
+
+    versions
+        .into_iter()
+        .max_by_key(|v| version_number(v))
+        .unwrap_or_else(|| DEFAULT_SCHEMA_VERSION.to_string())
 }
 
-/// Get the cache directory for beltic schemas
-fn cache_dir() -> Option<PathBuf> {
+/// Get the cache directory for beltic schemas (also holds fetched
+/// revocation lists)
+pub(crate) fn cache_dir() -> Option<PathBuf> {
     ProjectDirs::from("com", "beltic", "beltic-cli").map(|dirs| dirs.cache_dir().to_path_buf())
 }
 
+/// Get the cache file path for a schema version
+fn cache_path(schema_type: SchemaType, version: &str) -> Option<PathBuf> {
+    Some(
+        cache_dir()?
+            .join(version)
+            .join(schema_type.cache_name(version)),
+    )
+}
+
 /// Check if cached schema is still valid (within TTL)
 fn is_cache_valid(path: &PathBuf) -> bool {
     if !path.exists() {
@@ -71,49 +223,180 @@ fn is_cache_valid(path: &PathBuf) -> bool {
 }
 
 /// Read schema from cache
-fn read_cached_schema(schema_type: SchemaType) -> Option<Value> {
-    let cache_dir = cache_dir()?;
-    let cache_path = cache_dir.join(schema_type.cache_name());
+fn read_cached_schema(schema_type: SchemaType, version: &str) -> Option<Value> {
+    let cache_path = cache_path(schema_type, version)?;
 
     if !is_cache_valid(&cache_path) {
         return None;
     }
 
-    let content = fs::read_to_string(&cache_path).ok()?;
+    read_schema_file(&cache_path)
+}
+
+/// Read schema from cache, ignoring the TTL
+fn read_stale_cached_schema(schema_type: SchemaType, version: &str) -> Option<Value> {
+    read_schema_file(&cache_path(schema_type, version)?)
+}
+
+fn read_schema_file(path: &Path) -> Option<Value> {
+    let content = fs::read_to_string(path).ok()?;
     serde_json::from_str(&content).ok()
 }
 
-/// Write schema to cache
-fn write_cached_schema(schema_type: SchemaType, schema: &Value) -> Result<()> {
-    let cache_dir = cache_dir().context("could not determine cache directory")?;
+/// Metadata kept next to a cached schema: the HTTP validators of its
+/// download, sent back as `If-None-Match` and `If-Modified-Since` on refresh,
+/// and the SHA-256 of the cached file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
+}
+
+impl CacheMetadata {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            sha256: None,
+        }
+    }
+}
 
-    fs::create_dir_all(&cache_dir)
+/// Metadata file for a cached schema (`<name>.schema.meta.json`)
+fn metadata_path(cache_path: &Path) -> PathBuf {
+    cache_path.with_extension("meta.json")
+}
+
+fn read_metadata(cache_path: &Path) -> Option<CacheMetadata> {
+    let content = fs::read_to_string(metadata_path(cache_path)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// SHA-256 a downloaded schema must have, from the checksum manifest shipped
+/// with the CLI. `None` for versions it doesn't list.
+pub fn pinned_checksum(schema_type: SchemaType, version: &str) -> Option<String> {
+    let path = schema_type.path(version);
+    CHECKSUMS.lines().find_map(|line| {
+        let (checksum, file) = line.split_once("  ")?;
+        (file.trim() == path).then(|| checksum.to_string())
+    })
+}
+
+/// [`pinned_checksum`], or an error: a schema that can't be verified is
+/// never cached
+fn required_checksum(schema_type: SchemaType, version: &str) -> Result<String> {
+    pinned_checksum(schema_type, version).ok_or_else(|| {
+        BelticError::Validation(format!(
+            "no pinned checksum for {}, so it can't be verified; upgrade beltic or import a copy with `beltic schema import --sha256`",
+            schema_type.cache_name(version)
+        ))
+        .into()
+    })
+}
+
+/// Fail unless `content` hashes to `expected`; `source` names it in the error
+fn verify_checksum(content: &str, expected: &str, source: &str) -> Result<()> {
+    let actual = sha256_hex(content.as_bytes());
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(BelticError::Validation(format!(
+            "checksum mismatch for schema from {}: expected SHA-256 {}, got {}; the cached copy was left unchanged",
+            source, expected, actual
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Write schema to cache as is, so its checksum is that of the source file
+fn write_cached_schema(schema_type: SchemaType, version: &str, content: &str) -> Result<()> {
+    let cache_path =
+        cache_path(schema_type, version).context("could not determine cache directory")?;
+    write_schema_file(&cache_path, content, CacheMetadata::default())
+}
+
+/// Write a schema file and its metadata, recording the file's checksum
+fn write_schema_file(path: &Path, content: &str, mut metadata: CacheMetadata) -> Result<()> {
+    let cache_dir = path.parent().context("invalid cache path")?;
+
+    fs::create_dir_all(cache_dir)
         .with_context(|| format!("failed to create cache directory: {}", cache_dir.display()))?;
 
-    let cache_path = cache_dir.join(schema_type.cache_name());
-    let content = serde_json::to_string_pretty(schema)?;
+    fs::write(path, content)
+        .with_context(|| format!("failed to write cache file: {}", path.display()))?;
 
-    fs::write(&cache_path, content)
-        .with_context(|| format!("failed to write cache file: {}", cache_path.display()))?;
+    metadata.sha256 = Some(sha256_hex(content.as_bytes()));
+    let meta_path = metadata_path(path);
+    fs::write(&meta_path, serde_json::to_string_pretty(&metadata)?)
+        .with_context(|| format!("failed to write cache file: {}", meta_path.display()))?;
 
     Ok(())
 }
 
-/// Fetch schema from GitHub
-fn fetch_schema_from_github(schema_type: SchemaType) -> Result<Value> {
-    let url = schema_type.url();
+/// A schema file as downloaded, ready to be cached
+#[derive(Debug)]
+struct Download {
+    content: String,
+    metadata: CacheMetadata,
+}
 
-    let client = reqwest::blocking::Client::builder()
-        .timeout(Duration::from_secs(30))
-        .build()
-        .context("failed to create HTTP client")?;
+/// Response to a schema download
+#[derive(Debug)]
+enum Fetched {
+    /// A new copy of the schema
+    Modified(Value, Download),
+    /// `304 Not Modified`: the copy matching the validators sent is current
+    NotModified,
+}
 
-    let response = client
-        .get(&url)
-        .header("User-Agent", "beltic-cli")
+/// Fetch the schema at `url`, conditionally if `validators` are given.
+///
+/// A download whose bytes don't hash to `expected_sha256` is rejected with
+/// [`BelticError::Validation`].
+fn fetch_schema(
+    url: &str,
+    validators: Option<&CacheMetadata>,
+    expected_sha256: &str,
+) -> Result<Fetched> {
+    if is_offline() {
+        anyhow::bail!("offline mode, cannot fetch schema from {}", url);
+    }
+
+    let client = crate::http::client()?;
+
+    let mut request = client.get(url);
+    if let Some(validators) = validators {
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = request
         .send()
+        .map_err(|e| crate::http::request_error(e, "GitHub"))
         .with_context(|| format!("failed to fetch schema from {}", url))?;
 
+    if response.status() == StatusCode::NOT_MODIFIED {
+        return Ok(Fetched::NotModified);
+    }
+
     if !response.status().is_success() {
         anyhow::bail!(
             "failed to fetch schema from {}: HTTP {}",
@@ -122,11 +405,72 @@ fn fetch_schema_from_github(schema_type: SchemaType) -> Result<Value> {
         );
     }
 
-    let schema: Value = response
-        .json()
+    let metadata = CacheMetadata::from_headers(response.headers());
+    let content = response
+        .text()
+        .with_context(|| format!("failed to read schema from {}", url))?;
+
+    verify_checksum(&content, expected_sha256, url)?;
+
+    let schema: Value = serde_json::from_str(&content)
         .with_context(|| format!("failed to parse schema from {}", url))?;
 
-    Ok(schema)
+    Ok(Fetched::Modified(schema, Download { content, metadata }))
+}
+
+/// Outcome of [`refresh_schema`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refreshed {
+    /// A new copy was downloaded and cached
+    Updated,
+    /// The server answered `304 Not Modified`; the cached copy was kept
+    UpToDate,
+}
+
+/// Fetch the schema at `url`, revalidating the copy cached at `path` with
+/// its stored validators.
+///
+/// Returns the current schema and, if it changed, the download to cache;
+/// `None` means the cached copy is still current.
+fn revalidate(url: &str, path: &Path, expected_sha256: &str) -> Result<(Value, Option<Download>)> {
+    let cached = read_schema_file(path);
+    let validators = cached.as_ref().and_then(|_| read_metadata(path));
+
+    match fetch_schema(url, validators.as_ref(), expected_sha256)? {
+        Fetched::NotModified => {
+            let schema = cached.with_context(|| {
+                format!("{} answered 304 Not Modified, but no schema is cached", url)
+            })?;
+            Ok((schema, None))
+        }
+        Fetched::Modified(schema, download) => Ok((schema, Some(download))),
+    }
+}
+
+/// Store the result of [`revalidate`]: write a changed schema, or restart
+/// the TTL of an unchanged one
+fn store_revalidated(path: &Path, download: Option<Download>) -> Result<Refreshed> {
+    match download {
+        Some(download) => {
+            write_schema_file(path, &download.content, download.metadata)?;
+            Ok(Refreshed::Updated)
+        }
+        None => {
+            fs::File::options()
+                .write(true)
+                .open(path)
+                .and_then(|file| file.set_modified(SystemTime::now()))
+                .with_context(|| format!("failed to update cache file: {}", path.display()))?;
+            Ok(Refreshed::UpToDate)
+        }
+    }
+}
+
+/// Get the schema for the active (pinned or default) version.
+///
+/// See [`get_schema_version`] for the lookup strategy.
+pub fn get_schema(schema_type: SchemaType) -> Result<Value, BelticError> {
+    get_schema_version(schema_type, &active_version(None))
 }
 
 /// Get schema, preferring cache but fetching from GitHub if needed
@@ -136,68 +480,170 @@ fn fetch_schema_from_github(schema_type: SchemaType) -> Result<Value> {
 /// 2. Try to fetch from GitHub
 /// 3. If fetch fails but cache exists (even expired), use stale cache
 /// 4. If no cache and fetch fails, use embedded schema as fallback
-pub fn get_schema(schema_type: SchemaType) -> Result<Value> {
+///
+/// In offline mode step 2 is skipped.
+pub fn get_schema_version(schema_type: SchemaType, version: &str) -> Result<Value, BelticError> {
     // 1. Check valid cache
-    if let Some(cached) = read_cached_schema(schema_type) {
+    if let Some(cached) = read_cached_schema(schema_type, version) {
         return Ok(cached);
     }
 
-    // 2. Try to fetch from GitHub
-    match fetch_schema_from_github(schema_type) {
-        Ok(schema) => {
-            // Cache for future use (ignore cache write errors)
-            let _ = write_cached_schema(schema_type, &schema);
-            Ok(schema)
+    if is_offline() {
+        if let Some(stale) = read_stale_cached_schema(schema_type, version) {
+            return Ok(stale);
         }
+        return get_embedded_schema(schema_type, version).ok_or_else(|| {
+            BelticError::SchemaCache(format!(
+                "offline mode and no cached or embedded schema for {}",
+                schema_type.cache_name(version)
+            ))
+        });
+    }
+
+    // A version without a pinned checksum can't be fetched; an imported copy
+    // stays current
+    if pinned_checksum(schema_type, version).is_none() {
+        if let Some(imported) = read_stale_cached_schema(schema_type, version) {
+            return Ok(imported);
+        }
+    }
+
+    // 2. Try to fetch from GitHub (a 304 keeps the expired cache)
+    match fetch_into_cache(schema_type, version) {
+        Ok(schema) => Ok(schema),
         Err(fetch_err) => {
             // 3. Try stale cache if available
-            let cache_dir = cache_dir();
-            if let Some(dir) = cache_dir {
-                let cache_path = dir.join(schema_type.cache_name());
-                if cache_path.exists() {
-                    if let Ok(content) = fs::read_to_string(&cache_path) {
-                        if let Ok(schema) = serde_json::from_str(&content) {
-                            eprintln!(
-                                "[warn] Using stale cached schema for {} (fetch failed: {})",
-                                schema_type.cache_name(),
-                                fetch_err
-                            );
-                            return Ok(schema);
-                        }
-                    }
-                }
+            if let Some(schema) = read_stale_cached_schema(schema_type, version) {
+                eprintln!(
+                    "[warn] Using stale cached schema for {} (fetch failed: {})",
+                    schema_type.cache_name(version),
+                    fetch_err
+                );
+                return Ok(schema);
             }
 
             // 4. Fall back to embedded schema
+            let Some(embedded) = get_embedded_schema(schema_type, version) else {
+                return Err(BelticError::SchemaCache(format!(
+                    "no cached or embedded schema for {}: {:#}",
+                    schema_type.cache_name(version),
+                    fetch_err
+                )));
+            };
             eprintln!(
                 "[warn] Using embedded schema for {} (fetch failed: {})",
-                schema_type.cache_name(),
+                schema_type.cache_name(version),
                 fetch_err
             );
-            Ok(get_embedded_schema(schema_type))
+            Ok(embedded)
         }
     }
 }
 
+/// Fetch a schema for [`get_schema_version`], keeping it for future use
+fn fetch_into_cache(schema_type: SchemaType, version: &str) -> Result<Value> {
+    let cache_path =
+        cache_path(schema_type, version).context("could not determine cache directory")?;
+    let expected = required_checksum(schema_type, version)?;
+    let (schema, download) = revalidate(&schema_type.url(version), &cache_path, &expected)?;
+    // Ignore cache write errors
+    let _ = store_revalidated(&cache_path, download);
+    Ok(schema)
+}
+
 /// Get the embedded (compile-time) schema as fallback
-fn get_embedded_schema(schema_type: SchemaType) -> Value {
-    match schema_type {
-        SchemaType::Agent => serde_json::from_str(include_str!(
-            "../schemas/agent/v1/agent-credential-v1.schema.json"
-        ))
-        .expect("embedded agent schema should parse"),
-        SchemaType::Developer => serde_json::from_str(include_str!(
-            "../schemas/developer/v1/developer-credential-v1.schema.json"
-        ))
-        .expect("embedded developer schema should parse"),
-    }
+fn get_embedded_schema(schema_type: SchemaType, version: &str) -> Option<Value> {
+    let content = embedded_schema_source(schema_type, version)?;
+    Some(serde_json::from_str(content).expect("embedded schema should parse"))
 }
 
-/// Force refresh schema from GitHub, ignoring cache
-pub fn refresh_schema(schema_type: SchemaType) -> Result<Value> {
-    let schema = fetch_schema_from_github(schema_type)?;
-    write_cached_schema(schema_type, &schema)?;
-    Ok(schema)
+/// Source of a schema bundled with the CLI
+fn embedded_schema_source(schema_type: SchemaType, version: &str) -> Option<&'static str> {
+    let content = match (schema_type, version) {
+        (SchemaType::Agent, "v1") => {
+            include_str!("../schemas/agent/v1/agent-credential-v1.schema.json")
+        }
+        (SchemaType::Agent, "v2") => {
+            include_str!("../schemas/agent/v2/agent-credential-v2.schema.json")
+        }
+        (SchemaType::Developer, "v1") => {
+            include_str!("../schemas/developer/v1/developer-credential-v1.schema.json")
+        }
+        (SchemaType::Developer, "v2") => {
+            include_str!("../schemas/developer/v2/developer-credential-v2.schema.json")
+        }
+        _ => return None,
+    };
+    Some(content)
+}
+
+/// Refresh a schema from GitHub, ignoring the TTL.
+///
+/// The cached copy is kept if the server reports it unchanged. The download
+/// must match [`pinned_checksum`], or the cache is left as it was; versions
+/// without a pinned checksum aren't fetched at all.
+pub fn refresh_schema(schema_type: SchemaType, version: &str) -> Result<Refreshed> {
+    let cache_path =
+        cache_path(schema_type, version).context("could not determine cache directory")?;
+    let expected = required_checksum(schema_type, version)?;
+    let (_, download) = revalidate(&schema_type.url(version), &cache_path, &expected)?;
+    store_revalidated(&cache_path, download)
+}
+
+/// Import a schema from a local file into the cache.
+///
+/// The schema type and version are inferred from the document unless given
+/// explicitly; the version falls back to the active version of `config`
+/// (see [`active_version`]). The document must compile as a JSON Schema
+/// (Draft 2020-12) and hash to the [`pinned_checksum`] of its type and
+/// version, or to `sha256` for versions without one.
+pub fn import_schema(
+    path: &Path,
+    schema_type: Option<SchemaType>,
+    version: Option<&str>,
+    sha256: Option<&str>,
+    config: Option<&Path>,
+) -> Result<(SchemaType, String)> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read schema file: {}", path.display()))?;
+    let schema: Value = serde_json::from_str(&content)
+        .with_context(|| format!("failed to parse schema file: {}", path.display()))?;
+
+    jsonschema::JSONSchema::options()
+        .with_draft(jsonschema::Draft::Draft202012)
+        .compile(&schema)
+        .map_err(|e| anyhow::anyhow!("{} is not a valid JSON Schema: {}", path.display(), e))?;
+
+    let schema_type = match schema_type.or_else(|| SchemaType::detect(&schema)) {
+        Some(schema_type) => schema_type,
+        None => anyhow::bail!(
+            "could not determine schema type for {}; pass --agent or --developer",
+            path.display()
+        ),
+    };
+
+    let version = match version {
+        Some(version) => normalize_version(version)?,
+        None => detect_version(&schema).unwrap_or_else(|| active_version(config)),
+    };
+
+    let source = path.display().to_string();
+    match (pinned_checksum(schema_type, &version), sha256) {
+        (Some(pinned), given) => {
+            verify_checksum(&content, &pinned, &source)?;
+            if let Some(given) = given {
+                verify_checksum(&content, given, &source)?;
+            }
+        }
+        (None, Some(given)) => verify_checksum(&content, given, &source)?,
+        (None, None) => anyhow::bail!(
+            "no pinned checksum for {}; pass --sha256 with the checksum of the published file",
+            schema_type.cache_name(&version)
+        ),
+    }
+
+    write_cached_schema(schema_type, &version, &content)?;
+    Ok((schema_type, version))
 }
 
 /// Clear all cached schemas
@@ -211,10 +657,75 @@ pub fn clear_cache() -> Result<()> {
     Ok(())
 }
 
-/// Get cache status for a schema type
-pub fn cache_status(schema_type: SchemaType) -> Option<CacheStatus> {
-    let cache_dir = cache_dir()?;
-    let cache_path = cache_dir.join(schema_type.cache_name());
+/// A schema file in the cache
+#[derive(Debug)]
+pub struct CachedSchema {
+    pub schema_type: SchemaType,
+    pub version: String,
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+    pub size: u64,
+}
+
+/// Schemas in the cache, ordered by type then version. Reads only the cache
+/// directory, never the network.
+pub fn cached_schemas() -> Vec<CachedSchema> {
+    cache_dir()
+        .map(|dir| cached_schemas_in(&dir))
+        .unwrap_or_default()
+}
+
+fn cached_schemas_in(dir: &Path) -> Vec<CachedSchema> {
+    let mut versions: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            // Only version directories, not e.g. `revocation/`
+            normalize_version(&name).ok().filter(|v| *v == name)
+        })
+        .collect();
+    versions.sort_by_key(|v| version_number(v));
+
+    let mut schemas = Vec::new();
+    for schema_type in SchemaType::ALL {
+        for version in &versions {
+            let path = dir.join(version).join(schema_type.cache_name(version));
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            if metadata.is_file() {
+                schemas.push(CachedSchema {
+                    schema_type,
+                    version: version.clone(),
+                    modified: metadata.modified().ok(),
+                    size: metadata.len(),
+                    path,
+                });
+            }
+        }
+    }
+    schemas
+}
+
+/// The cached copy of a schema, however old; `None` if it isn't cached
+pub fn cached_schema(schema_type: SchemaType, version: &str) -> Result<Option<Value>> {
+    let path = cache_path(schema_type, version).context("could not determine cache directory")?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read cache file: {}", path.display()))?;
+    let schema = serde_json::from_str(&content)
+        .with_context(|| format!("cached schema is not valid JSON: {}", path.display()))?;
+    Ok(Some(schema))
+}
+
+/// Get cache status for a schema type and version
+pub fn cache_status(schema_type: SchemaType, version: &str) -> Option<CacheStatus> {
+    let cache_path = cache_path(schema_type, version)?;
 
     if !cache_path.exists() {
         return Some(CacheStatus {
@@ -222,6 +733,7 @@ pub fn cache_status(schema_type: SchemaType) -> Option<CacheStatus> {
             exists: false,
             valid: false,
             age: None,
+            sha256: None,
         });
     }
 
@@ -231,6 +743,7 @@ pub fn cache_status(schema_type: SchemaType) -> Option<CacheStatus> {
     let valid = age < CACHE_TTL;
 
     Some(CacheStatus {
+        sha256: read_metadata(&cache_path).and_then(|metadata| metadata.sha256),
         path: cache_path,
         exists: true,
         valid,
@@ -245,24 +758,267 @@ pub struct CacheStatus {
     pub exists: bool,
     pub valid: bool,
     pub age: Option<Duration>,
+    /// SHA-256 of the cached file, recorded when it was written
+    pub sha256: Option<String>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Mutex;
+    use std::thread;
+
+    /// Serializes tests that toggle or depend on offline mode
+    static OFFLINE_LOCK: Mutex<()> = Mutex::new(());
 
     #[test]
     fn test_schema_type_url() {
-        assert!(SchemaType::Agent.url().contains("agent-credential"));
-        assert!(SchemaType::Developer.url().contains("developer-credential"));
+        assert!(SchemaType::Agent.url("v1").contains("agent-credential"));
+        assert!(SchemaType::Developer
+            .url("v1")
+            .contains("developer-credential"));
+        assert!(SchemaType::Agent
+            .url("v2")
+            .ends_with("agent/v2/agent-credential-v2.schema.json"));
+    }
+
+    #[test]
+    fn test_normalize_version() {
+        assert_eq!(normalize_version("v1").unwrap(), "v1");
+        assert_eq!(normalize_version("2").unwrap(), "v2");
+        assert_eq!(normalize_version("V10").unwrap(), "v10");
+        assert!(normalize_version("latest").is_err());
+        assert!(normalize_version("v").is_err());
+    }
+
+    #[test]
+    fn test_detect_schema_type() {
+        let agent_schema = get_embedded_schema(SchemaType::Agent, "v1").unwrap();
+        assert_eq!(SchemaType::detect(&agent_schema), Some(SchemaType::Agent));
+
+        let developer_schema = get_embedded_schema(SchemaType::Developer, "v1").unwrap();
+        assert_eq!(
+            SchemaType::detect(&developer_schema),
+            Some(SchemaType::Developer)
+        );
+
+        assert_eq!(SchemaType::detect(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn test_schema_type_from_name() {
+        assert_eq!(SchemaType::from_name("agent"), Some(SchemaType::Agent));
+        assert_eq!(
+            SchemaType::from_name("Developer"),
+            Some(SchemaType::Developer)
+        );
+        assert_eq!(SchemaType::from_name("issuer"), None);
+    }
+
+    #[test]
+    fn test_cached_schemas_lists_version_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        for (schema_type, version) in [
+            (SchemaType::Developer, "v1"),
+            (SchemaType::Agent, "v2"),
+            (SchemaType::Agent, "v1"),
+        ] {
+            let path = dir
+                .path()
+                .join(version)
+                .join(schema_type.cache_name(version));
+            write_schema_file(&path, "{}", CacheMetadata::default()).unwrap();
+        }
+        fs::create_dir_all(dir.path().join("revocation")).unwrap();
+        fs::write(dir.path().join("revocation").join("list.json"), "{}").unwrap();
+
+        let listed: Vec<_> = cached_schemas_in(dir.path())
+            .into_iter()
+            .map(|cached| (cached.schema_type, cached.version, cached.size > 0))
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                (SchemaType::Agent, "v1".to_string(), true),
+                (SchemaType::Agent, "v2".to_string(), true),
+                (SchemaType::Developer, "v1".to_string(), true),
+            ]
+        );
+        assert!(cached_schemas_in(&dir.path().join("missing")).is_empty());
+    }
+
+    #[test]
+    fn test_detect_version() {
+        let agent_schema = get_embedded_schema(SchemaType::Agent, "v2").unwrap();
+        assert_eq!(detect_version(&agent_schema).as_deref(), Some("v2"));
+        assert_eq!(detect_version(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn test_offline_mode_blocks_fetch() {
+        let _lock = OFFLINE_LOCK.lock().unwrap();
+        set_offline(true);
+        let result = fetch_schema(&SchemaType::Agent.url("v1"), None, "");
+        set_offline(false);
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("offline mode"));
     }
 
     #[test]
     fn test_embedded_schema_loads() {
-        let agent_schema = get_embedded_schema(SchemaType::Agent);
-        assert!(agent_schema.get("$schema").is_some());
+        for version in EMBEDDED_VERSIONS {
+            let agent_schema = get_embedded_schema(SchemaType::Agent, version).unwrap();
+            assert!(agent_schema.get("$schema").is_some());
+
+            let developer_schema = get_embedded_schema(SchemaType::Developer, version).unwrap();
+            assert!(developer_schema.get("$schema").is_some());
+        }
+        assert!(get_embedded_schema(SchemaType::Agent, "v99").is_none());
+    }
+
+    #[test]
+    fn test_refresh_keeps_cache_on_304() {
+        let _lock = OFFLINE_LOCK.lock().unwrap();
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/agent-credential-v1.schema.json",
+            server.server_addr().to_ip().unwrap()
+        );
+        let handle = thread::spawn(move || {
+            let request = server.recv().unwrap();
+            assert!(request
+                .headers()
+                .iter()
+                .all(|h| !h.field.equiv("If-None-Match")));
+            let response = tiny_http::Response::from_string(r#"{"title":"v1"}"#)
+                .with_header("ETag: \"abc\"".parse::<tiny_http::Header>().unwrap())
+                .with_header(
+                    "Last-Modified: Wed, 01 Jan 2025 00:00:00 GMT"
+                        .parse::<tiny_http::Header>()
+                        .unwrap(),
+                );
+            request.respond(response).unwrap();
+
+            let request = server.recv().unwrap();
+            let header = |name: &'static str| {
+                request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv(name))
+                    .map(|h| h.value.to_string())
+            };
+            let conditional = (header("If-None-Match"), header("If-Modified-Since"));
+            request.respond(tiny_http::Response::empty(304)).unwrap();
+            conditional
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir
+            .path()
+            .join("v1")
+            .join("agent-credential-v1.schema.json");
+
+        let expected = sha256_hex(br#"{"title":"v1"}"#);
+        let (_, download) = revalidate(&url, &path, &expected).unwrap();
+        assert_eq!(
+            store_revalidated(&path, download).unwrap(),
+            Refreshed::Updated
+        );
+        let stored = read_metadata(&path).unwrap();
+        assert_eq!(stored.etag.as_deref(), Some("\"abc\""));
+
+        let (schema, download) = revalidate(&url, &path, &expected).unwrap();
+        assert!(download.is_none());
+        assert_eq!(schema, serde_json::json!({"title": "v1"}));
+        assert_eq!(
+            store_revalidated(&path, download).unwrap(),
+            Refreshed::UpToDate
+        );
+        assert_eq!(read_schema_file(&path).unwrap(), schema);
+        assert_eq!(read_metadata(&path).unwrap(), stored);
+
+        let (if_none_match, if_modified_since) = handle.join().unwrap();
+        assert_eq!(if_none_match.as_deref(), Some("\"abc\""));
+        assert_eq!(
+            if_modified_since.as_deref(),
+            Some("Wed, 01 Jan 2025 00:00:00 GMT")
+        );
+    }
+
+    #[test]
+    fn test_checksum_mismatch_leaves_cache_unchanged() {
+        let _lock = OFFLINE_LOCK.lock().unwrap();
+        let body = r#"{"title":"v1"}"#;
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/schema.json",
+            server.server_addr().to_ip().unwrap()
+        );
+        let handle = thread::spawn(move || {
+            for _ in 0..2 {
+                let request = server.recv().unwrap();
+                request
+                    .respond(tiny_http::Response::from_string(body))
+                    .unwrap();
+            }
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("v1").join("schema.json");
+        write_schema_file(&path, r#"{"title":"cached"}"#, CacheMetadata::default()).unwrap();
+
+        let wrong = sha256_hex(b"something else");
+        let err = revalidate(&url, &path, &wrong).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BelticError>(),
+            Some(BelticError::Validation(_))
+        ));
+        assert!(err.to_string().contains("checksum mismatch"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"title":"cached"}"#);
+
+        let expected = sha256_hex(body.as_bytes());
+        let (_, download) = revalidate(&url, &path, &expected).unwrap();
+        store_revalidated(&path, download).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), body);
+        assert_eq!(read_metadata(&path).unwrap().sha256, Some(expected));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_pinned_checksums_cover_embedded_versions() {
+        for version in EMBEDDED_VERSIONS {
+            for schema_type in SchemaType::ALL {
+                let embedded = embedded_schema_source(schema_type, version).unwrap();
+                assert_eq!(
+                    pinned_checksum(schema_type, version),
+                    Some(sha256_hex(embedded.as_bytes()))
+                );
+            }
+        }
+        assert_eq!(pinned_checksum(SchemaType::Agent, "v99"), None);
+        assert!(required_checksum(SchemaType::Agent, "v99")
+            .unwrap_err()
+            .to_string()
+            .contains("no pinned checksum"));
+    }
+
+    #[test]
+    fn test_import_verifies_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agent.json");
+        let schema = r#"{"$schema":"https://json-schema.org/draft/2020-12/schema","$id":"https://example.com/agent/v1/x","type":"object"}"#;
+        fs::write(&path, schema).unwrap();
+
+        // Not the published v1 schema
+        let err = import_schema(&path, None, None, None, None).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{}", err);
 
-        let developer_schema = get_embedded_schema(SchemaType::Developer);
-        assert!(developer_schema.get("$schema").is_some());
+        // Unlisted versions need the checksum given explicitly
+        let err = import_schema(&path, None, Some("v99"), None, None).unwrap_err();
+        assert!(err.to_string().contains("--sha256"), "{}", err);
+        let wrong = sha256_hex(b"something else");
+        let err = import_schema(&path, None, Some("v99"), Some(&wrong), None).unwrap_err();
+        assert!(err.to_string().contains("checksum mismatch"), "{}", err);
     }
 }
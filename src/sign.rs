@@ -0,0 +1,8 @@
+//! Signing credentials as compact JWS.
+//!
+//! [`build_claims`] turns a credential into JWT claims (`iss`, `sub`, `nbf`,
+//! `exp`, `jti`, `vc`), and [`sign_jws`] signs them with any [`Signer`]:
+//! a [`PemSigner`] for a key on disk, or a KMS-backed signer.
+
+pub use crate::credential::{build_claims, ClaimsOptions, AGENT_TYP, DEVELOPER_TYP};
+pub use crate::crypto::{sign_jws, PemSigner, SignatureAlg, Signer};
@@ -0,0 +1,12 @@
+//! Verifying compact JWS credentials.
+//!
+//! [`verify_jws`] checks the signature, `exp`/`nbf` and `aud` against a key
+//! file; the `_with_pem` and `_with_allowed_algs` variants take the key as
+//! text and can restrict the accepted algorithms. Failures are
+//! [`BelticError`](crate::error::BelticError)s, so a caller can tell a bad
+//! signature from an expired token or an unreadable key.
+
+pub use crate::crypto::{
+    decode_jws_header, verify_jws, verify_jws_signature, verify_jws_with_allowed_algs,
+    verify_jws_with_pem, JwsHeader, SignatureAlg, VerifiedToken,
+};
@@ -0,0 +1,144 @@
+//! `dev-init` output signs and verifies with schema validation, for every
+//! entity type
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+/// Entity types accepted by --entity-type, including the short aliases
+const ENTITY_TYPES: &[&str] = &[
+    "individual",
+    "sole_proprietorship",
+    "corporation",
+    "llc",
+    "partnership",
+    "nonprofit",
+    "government",
+    "other",
+];
+
+fn beltic(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_beltic"))
+        .arg("--offline")
+        .args(args)
+        .current_dir(dir)
+        // Only the embedded schemas, never a cached or pinned copy
+        .env("XDG_CACHE_HOME", dir.join("cache"))
+        .env("RUST_BACKTRACE", "0")
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("failed to run beltic")
+}
+
+fn assert_success(output: &Output) {
+    assert!(output.status.success(), "{:?}", output);
+}
+
+#[test]
+fn signed_developer_credential_passes_schema_validation() {
+    let dir = tempdir().unwrap();
+    assert_success(&beltic(
+        dir.path(),
+        &[
+            "keygen",
+            "--alg",
+            "EdDSA",
+            "--out",
+            "developer.pem",
+            "--pub",
+            "developer.pub.pem",
+            "--non-interactive",
+        ],
+    ));
+
+    for entity_type in ENTITY_TYPES {
+        let credential = format!("{}.json", entity_type);
+        let mut args = vec![
+            "dev-init",
+            "--non-interactive",
+            "--name",
+            "Acme",
+            "--email",
+            "dev@acme.example",
+            "--website",
+            "https://acme.example",
+            "--country",
+            "GB",
+            "--entity-type",
+            entity_type,
+            "--sign-with",
+            "developer.pem",
+            "--output",
+            &credential,
+        ];
+        if !matches!(*entity_type, "individual" | "sole_proprietorship") {
+            args.extend([
+                "--incorporation-date",
+                "2019-03-01",
+                "--registration-number",
+                "12345678",
+                "--street-address",
+                "1 High Street",
+                "--city",
+                "London",
+                "--postal-code",
+                "EC1A 1BB",
+            ]);
+        }
+        assert_success(&beltic(dir.path(), &args));
+
+        let output = beltic(dir.path(), &["validate", &credential]);
+        assert_success(&output);
+
+        let token = format!("{}.jwt", entity_type);
+        let output = beltic(
+            dir.path(),
+            &[
+                "verify",
+                "--token",
+                &token,
+                "--key",
+                "developer.pub.pem",
+                "--summary",
+                "--non-interactive",
+            ],
+        );
+        assert_success(&output);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("type=DeveloperCredential"), "{}", stdout);
+        assert!(stdout.contains("Schema:     ran, passed"), "{}", stdout);
+    }
+}
+
+#[test]
+fn organizations_need_registration_details() {
+    let dir = tempdir().unwrap();
+    let output = beltic(
+        dir.path(),
+        &[
+            "dev-init",
+            "--non-interactive",
+            "--name",
+            "Acme",
+            "--email",
+            "dev@acme.example",
+            "--website",
+            "https://acme.example",
+            "--entity-type",
+            "corporation",
+            "--city",
+            "London",
+        ],
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "--entity-type corporation requires --incorporation-date, --registration-number, --street-address, --postal-code"
+        ),
+        "{}",
+        stderr
+    );
+    assert!(!dir.path().join("developer-credential.json").exists());
+}
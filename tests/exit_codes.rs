@@ -0,0 +1,158 @@
+//! Exit codes of the `beltic` binary: 0 ok, 1 error, 2 usage or input,
+//! 3 check failed, 4 network
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::tempdir;
+
+const AGENT_FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/agent-valid.json"
+);
+
+fn beltic(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_beltic"))
+        .args(args)
+        .current_dir(dir)
+        .env("RUST_BACKTRACE", "0")
+        .env("NO_COLOR", "1")
+        .env("BELTIC_HTTP_MAX_ATTEMPTS", "1")
+        .output()
+        .expect("failed to run beltic")
+}
+
+fn code(output: &Output) -> i32 {
+    output.status.code().expect("terminated by a signal")
+}
+
+fn keygen(dir: &Path, name: &str) {
+    let output = beltic(
+        dir,
+        &[
+            "keygen",
+            "--alg",
+            "EdDSA",
+            "--out",
+            &format!("{}.pem", name),
+            "--pub",
+            &format!("{}.pub.pem", name),
+            "--non-interactive",
+        ],
+    );
+    assert_eq!(code(&output), 0, "{:?}", output);
+}
+
+#[test]
+fn usage_errors_exit_2() {
+    let dir = tempdir().unwrap();
+    assert_eq!(code(&beltic(dir.path(), &["--bogus"])), 2);
+
+    let output = beltic(
+        dir.path(),
+        &["--config", "missing.yaml", "schema", "status"],
+    );
+    assert_eq!(code(&output), 2);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(
+        stderr.matches("Config file not found").count(),
+        1,
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn diff_exits_3_on_differences() {
+    let dir = tempdir().unwrap();
+    let mut credential: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(AGENT_FIXTURE).unwrap()).unwrap();
+    fs::write(dir.path().join("old.json"), credential.to_string()).unwrap();
+    credential["agentVersion"] = "9.9.9".into();
+    fs::write(dir.path().join("new.json"), credential.to_string()).unwrap();
+    fs::write(dir.path().join("bad.json"), "{}").unwrap();
+
+    assert_eq!(
+        code(&beltic(dir.path(), &["diff", "old.json", "old.json"])),
+        0
+    );
+    assert_eq!(
+        code(&beltic(dir.path(), &["diff", "old.json", "new.json"])),
+        3
+    );
+    // Not a credential: a plain error, not a difference
+    assert_eq!(
+        code(&beltic(dir.path(), &["diff", "old.json", "bad.json"])),
+        1
+    );
+}
+
+#[test]
+fn verify_exits_3_on_bad_signature() {
+    let dir = tempdir().unwrap();
+    keygen(dir.path(), "signer");
+    keygen(dir.path(), "other");
+    fs::copy(AGENT_FIXTURE, dir.path().join("agent.json")).unwrap();
+
+    let output = beltic(
+        dir.path(),
+        &[
+            "--offline",
+            "sign",
+            "--key",
+            "signer.pem",
+            "--payload",
+            "agent.json",
+            "--out",
+            "agent.jwt",
+            "--kid",
+            "k1",
+            "--non-interactive",
+        ],
+    );
+    assert_eq!(code(&output), 0, "{:?}", output);
+
+    let verify = |key: &str| {
+        beltic(
+            dir.path(),
+            &[
+                "--offline",
+                "verify",
+                "--token",
+                "agent.jwt",
+                "--key",
+                key,
+                "--non-interactive",
+            ],
+        )
+    };
+    assert_eq!(code(&verify("signer.pub.pem")), 0);
+
+    let output = verify("other.pub.pem");
+    assert_eq!(code(&output), 3);
+    // The verdict is printed once, without a trailing "Error:"
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("INVALID:"), "{}", stderr);
+    assert!(!stderr.contains("Error:"), "{}", stderr);
+}
+
+#[test]
+fn network_failures_exit_4() {
+    let dir = tempdir().unwrap();
+    keygen(dir.path(), "signer");
+
+    // Port 9 (discard) is closed, so the connection is refused
+    let output = beltic(
+        dir.path(),
+        &[
+            "directory",
+            "diff",
+            "--local",
+            ".",
+            "--remote",
+            "http://127.0.0.1:9/.well-known/http-message-signatures-directory",
+        ],
+    );
+    assert_eq!(code(&output), 4, "{:?}", output);
+}
@@ -0,0 +1,104 @@
+//! Signing through cloud key services.
+//!
+//! Each test needs its provider's cargo feature and a real key, named by an
+//! environment variable; without the variable the test is skipped (it
+//! passes without contacting the provider):
+//!
+//! - `kms`: `BELTIC_TEST_AWS_KMS_KEY_ID`, an ECC_NIST_P256 key (AWS
+//!   credentials and region from the environment)
+//! - `azure-kv`: `BELTIC_TEST_AZURE_KEY_ID`, a P-256 key identifier URL
+//!   (`AZURE_ACCESS_TOKEN` or `az login`)
+//! - `gcp-kms`: `BELTIC_TEST_GCP_KEY_VERSION`, an EC_SIGN_P256_SHA256 key
+//!   version (`GOOGLE_OAUTH_ACCESS_TOKEN` or `gcloud auth login`)
+//!
+//! Run with e.g. `cargo test --features gcp-kms --test remote_signers`.
+#![cfg(any(feature = "kms", feature = "azure-kv", feature = "gcp-kms"))]
+
+use anyhow::{Context, Result};
+use beltic::credential::{build_claims, ClaimsOptions, CredentialKind, AGENT_TYP};
+use beltic::crypto::{sign_jws, verify_jws_with_pem, SignatureAlg, Signer};
+use serde_json::Value;
+
+/// The key named by `env`, or `None` (skipping the test) when it is unset
+fn test_key(env: &str) -> Option<String> {
+    match std::env::var(env) {
+        Ok(key) if !key.is_empty() => Some(key),
+        _ => {
+            eprintln!("skipping: {} is not set", env);
+            None
+        }
+    }
+}
+
+/// Sign an agent credential and verify it with the public key the signer
+/// reports
+fn sign_and_verify(signer: &dyn Signer) -> Result<()> {
+    let payload: Value = serde_json::from_str(include_str!("fixtures/agent-valid.json"))?;
+    let claims = build_claims(
+        &payload,
+        CredentialKind::Agent,
+        ClaimsOptions {
+            issuer: None,
+            subject: Some("did:web:agent.example.com"),
+            audience: &[],
+            valid_for: None,
+            jti: None,
+            not_before: None,
+        },
+    )?;
+
+    let token = sign_jws(&claims, signer, AGENT_TYP, Some("application/json"))?;
+    let public_pem = signer
+        .public_key_pem()?
+        .context("remote signer did not report its public key")?;
+    let verified = verify_jws_with_pem(&token, &public_pem, None)?;
+
+    assert_eq!(verified.alg, SignatureAlg::Es256);
+    assert_eq!(
+        verified.header.kid.as_deref(),
+        Some("did:web:beltic.test#kms")
+    );
+    Ok(())
+}
+
+#[cfg(feature = "kms")]
+#[test]
+fn aws_kms_signs_verifiable_tokens() -> Result<()> {
+    let Some(key_id) = test_key("BELTIC_TEST_AWS_KMS_KEY_ID") else {
+        return Ok(());
+    };
+    let signer = beltic::crypto::kms::KmsSigner::new(
+        &key_id,
+        SignatureAlg::Es256,
+        Some("did:web:beltic.test#kms".to_string()),
+    )?;
+    sign_and_verify(&signer)
+}
+
+#[cfg(feature = "azure-kv")]
+#[test]
+fn azure_key_vault_signs_verifiable_tokens() -> Result<()> {
+    let Some(key_id) = test_key("BELTIC_TEST_AZURE_KEY_ID") else {
+        return Ok(());
+    };
+    let signer = beltic::crypto::azure_kv::AzureKeyVaultSigner::new(
+        &key_id,
+        SignatureAlg::Es256,
+        Some("did:web:beltic.test#kms".to_string()),
+    )?;
+    sign_and_verify(&signer)
+}
+
+#[cfg(feature = "gcp-kms")]
+#[test]
+fn gcp_kms_signs_verifiable_tokens() -> Result<()> {
+    let Some(key_version) = test_key("BELTIC_TEST_GCP_KEY_VERSION") else {
+        return Ok(());
+    };
+    let signer = beltic::crypto::gcp_kms::GcpKmsSigner::new(
+        &key_version,
+        SignatureAlg::Es256,
+        Some("did:web:beltic.test#kms".to_string()),
+    )?;
+    sign_and_verify(&signer)
+}
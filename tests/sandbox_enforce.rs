@@ -0,0 +1,72 @@
+//! `sandbox run --enforce-network` end to end: the namespace helper brings
+//! up loopback and runs the agent through the egress proxy. Skipped where
+//! network namespaces can't be created.
+
+#![cfg(target_os = "linux")]
+
+use std::fs;
+use std::process::Command;
+
+use serde_json::Value;
+use tempfile::tempdir;
+
+const AGENT_FIXTURE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/agent-valid.json"
+);
+
+/// Connects to the proxy the helper listens on, asks it for a prohibited
+/// domain, then tries to bypass it
+const AGENT_SCRIPT: &str = r#"
+proxy=${HTTPS_PROXY#http://}
+exec 3<>"/dev/tcp/${proxy%:*}/${proxy##*:}" || exit 9
+printf 'CONNECT pastebin.com:443 HTTP/1.1\r\nHost: pastebin.com\r\n\r\n' >&3
+head -n 1 <&3
+if (exec 4<>/dev/tcp/192.0.2.1/80) 2>/dev/null; then echo direct: open; else echo direct: blocked; fi
+exit 7
+"#;
+
+#[test]
+fn test_enforce_network_routes_agent_through_proxy() {
+    if let Err(err) = beltic::sandbox::enforce::preflight() {
+        eprintln!("skipping: {:#}", err);
+        return;
+    }
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("agent.sh"), AGENT_SCRIPT).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_beltic"))
+        .args([
+            "sandbox",
+            "run",
+            "--policy",
+            AGENT_FIXTURE,
+            "--enforce-network",
+            "--report",
+            "report.json",
+            "--",
+            "bash",
+            "agent.sh",
+        ])
+        .current_dir(dir.path())
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("HTTP/1.1 403 Forbidden"), "{}", stdout);
+    assert!(stdout.contains("direct: blocked"), "{}", stdout);
+
+    let report: Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("report.json")).unwrap()).unwrap();
+    // The helper passes the agent's exit code through
+    assert_eq!(report["summary"]["exitCode"], 7);
+    let violations = report["violations"].as_array().unwrap();
+    assert!(
+        violations.iter().any(|violation| {
+            violation["violationType"] == "network_access_denied"
+                && violation["details"] == "Attempted access to: pastebin.com"
+        }),
+        "{:#}",
+        report["violations"]
+    );
+}